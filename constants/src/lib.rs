@@ -30,6 +30,16 @@ lazy_static! {
 // require a max string length at time of implementation.
 pub const DEFAULT_MAX_STRING_LENGTH: u16 = 4000;
 
+// Driver-specific statement attributes, numbered from SQL_DRIVER_STMT_ATTR_BASE. Their values are
+// wide strings attached to the command run when the statement is executed: the name or key
+// document of the index to hint, and a comment.
+pub const SQL_ATTR_MONGODB_HINT: i32 = 0x4000;
+pub const SQL_ATTR_MONGODB_COMMENT: i32 = 0x4001;
+// A read-only SQLULEN, the number of getMore commands run on the connection of the statement, each
+// fetching a batch of a result set cursor after the first. It is a test hook, only supported by a
+// driver built with the test_hooks feature.
+pub const SQL_ATTR_MONGODB_GET_MORE_COUNT: i32 = 0x4002;
+
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub struct OdbcState<'a> {
     pub odbc_2_state: &'a str,
@@ -80,6 +90,10 @@ pub const GENERAL_WARNING: OdbcState<'static> = OdbcState {
     odbc_2_state: "01000",
     odbc_3_state: "01000",
 };
+pub const INVALID_CONNECTION_STRING_ATTRIBUTE: OdbcState<'static> = OdbcState {
+    odbc_2_state: "01S00",
+    odbc_3_state: "01S00",
+};
 pub const RIGHT_TRUNCATED: OdbcState<'static> = OdbcState {
     odbc_2_state: "01004",
     odbc_3_state: "01004",
@@ -96,6 +110,10 @@ pub const UNABLE_TO_CONNECT: OdbcState<'static> = OdbcState {
     odbc_2_state: "08001",
     odbc_3_state: "08001",
 };
+pub const INVALID_USE_OF_NULL_POINTER: OdbcState<'static> = OdbcState {
+    odbc_2_state: "S1009",
+    odbc_3_state: "HY009",
+};
 pub const INVALID_DESCRIPTOR_INDEX: OdbcState<'static> = OdbcState {
     odbc_2_state: "S1002",
     odbc_3_state: "07009",
@@ -133,10 +151,26 @@ pub const INVALID_ATTRIBUTE_OR_OPTION_IDENTIFIER: OdbcState<'static> = OdbcState
     odbc_2_state: "S1092",
     odbc_3_state: "HY092",
 };
+pub const COLUMN_TYPE_OUT_OF_RANGE: OdbcState<'static> = OdbcState {
+    odbc_2_state: "S1097",
+    odbc_3_state: "HY097",
+};
+pub const UNIQUENESS_OPTION_OUT_OF_RANGE: OdbcState<'static> = OdbcState {
+    odbc_2_state: "S1100",
+    odbc_3_state: "HY100",
+};
+pub const ACCURACY_OPTION_OUT_OF_RANGE: OdbcState<'static> = OdbcState {
+    odbc_2_state: "S1101",
+    odbc_3_state: "HY101",
+};
 pub const FETCH_TYPE_OUT_OF_RANGE: OdbcState<'static> = OdbcState {
     odbc_2_state: "S1106",
     odbc_3_state: "HY106",
 };
+pub const INVALID_TRANSACTION_OPERATION_CODE: OdbcState<'static> = OdbcState {
+    odbc_2_state: "S1012",
+    odbc_3_state: "HY012",
+};
 pub const INDICATOR_VARIABLE_REQUIRED: OdbcState<'static> = OdbcState {
     odbc_2_state: "22002",
     odbc_3_state: "22002",
@@ -157,6 +191,22 @@ pub const CONNECTION_NOT_OPEN: OdbcState<'static> = OdbcState {
     odbc_2_state: "08003",
     odbc_3_state: "08003",
 };
+pub const COMMUNICATION_LINK_FAILURE: OdbcState<'static> = OdbcState {
+    odbc_2_state: "08S01",
+    odbc_3_state: "08S01",
+};
+pub const INVALID_AUTHORIZATION: OdbcState<'static> = OdbcState {
+    odbc_2_state: "28000",
+    odbc_3_state: "28000",
+};
+pub const INVALID_CATALOG_NAME: OdbcState<'static> = OdbcState {
+    odbc_2_state: "S0002",
+    odbc_3_state: "3D000",
+};
+pub const SYNTAX_ERROR: OdbcState<'static> = OdbcState {
+    odbc_2_state: "37000",
+    odbc_3_state: "42000",
+};
 
 pub const SQL_ALL_TABLE_TYPES: &str = "%";
 pub const SQL_ALL_CATALOGS: &str = "%";
@@ -166,11 +216,24 @@ pub const SQL_CB_NULL: u16 = 0x0000;
 pub const MAX_COLUMNS_U16_ZERO: u16 = 0x0000;
 pub const SQL_CL_START: u16 = 0x0001;
 pub const MAX_COLUMNS_U32_ZERO: u32 = 0x0;
+// MongoDB allows at most 32 fields in a compound index.
+pub const MAX_COLUMNS_IN_INDEX: u16 = 32;
+// The total size of an index entry, the index key limit of MongoDB.
+pub const MAX_INDEX_SIZE: u32 = 1024;
+// Database names are fewer than 64 bytes long.
+pub const MAX_CATALOG_NAME_LEN: u16 = 63;
+// Statements are sent to the server in a single command, which cannot exceed the maximum BSON
+// document size of 16 MiB. Literals are limited to 15 MiB, which leaves room for the rest of the
+// statement and command, and for the rows returning them.
+pub const MAX_LITERAL_LEN: u32 = 15 * 1024 * 1024;
 pub const SQL_OIC_CORE: u32 = 0x00000001;
 pub const SQL_SC_SQL92_ENTRY: u32 = 0x00000001;
 pub const COLUMN_ALIAS_INFO_Y: &str = "Y";
+pub const INTEGRITY_INFO_N: &str = "N";
+pub const ROW_UPDATES_INFO_N: &str = "N";
 pub const SQL_GB_GROUP_BY_CONTAINS_SELECT: u16 = 0x0002;
 pub const SQL_CB_PRESERVE: u16 = 2;
+pub const SQL_IC_SENSITIVE: u16 = 3;
 pub const SQL_CA1_NEXT: u32 = 0x00000001;
 pub const SQL_CA2_READ_ONLY_CONCURRENCY: u32 = 0x00000001;
 #[allow(unused)]
@@ -180,9 +243,28 @@ pub const MONGO_CA2_SUPPORT: u32 = SQL_CA2_CRC_EXACT | SQL_CA2_READ_ONLY_CONCURR
 pub const SQL_SO_FORWARD_ONLY: u32 = 0x00000001;
 pub const SQL_SO_STATIC: u32 = 0x00000010;
 pub const MONGO_SO_SUPPORT: u32 = SQL_SO_FORWARD_ONLY | SQL_SO_STATIC;
-pub const SQL_TXN_SERIALIZABLE: u32 = 0x00000008;
+// SQL_FETCH_DIRECTION bitmask
+pub const SQL_FD_FETCH_NEXT: u32 = 0x00000001;
+pub const SQL_FD_FETCH_FIRST: u32 = 0x00000002;
+pub const SQL_FD_FETCH_LAST: u32 = 0x00000004;
+pub const SQL_FD_FETCH_PRIOR: u32 = 0x00000008;
+pub const SQL_FD_FETCH_ABSOLUTE: u32 = 0x00000010;
+pub const SQL_FD_FETCH_RELATIVE: u32 = 0x00000020;
+// Cursors only move forward, so SQLFetchScroll supports SQL_FETCH_NEXT alone.
+pub const MONGO_FD_SUPPORT: u32 = SQL_FD_FETCH_NEXT;
+// The only transaction isolation level reported. Queries read with the "local" read concern, so
+// they can return data that is later rolled back.
+pub const SQL_TXN_READ_UNCOMMITTED: u32 = 0x00000001;
 pub const SQL_SCCO_READ_ONLY: u32 = 0x00000001;
 pub const SQL_LCK_NO_CHANGE: u32 = 0x00000001;
+// SQL_ATTR_APP_WCHAR_TYPE values. Wide character data is always UTF-16.
+pub const SQL_DD_CP_ANSI: u32 = 0;
+pub const SQL_DD_CP_UTF16: u32 = 1;
+pub const SQL_DD_CP_UTF8: u32 = 65001;
+
+// SQL_STANDARD_CLI_CONFORMANCE bitmask
+pub const SQL_SCC_XOPEN_CLI_VERSION1: u32 = 0x00000001;
+pub const SQL_SCC_ISO92_CLI: u32 = 0x00000002;
 
 // SQL_CONVERT_FUNCTIONS bitmask
 pub const SQL_FN_CVT_CAST: u32 = 0x00000002;
@@ -325,6 +407,11 @@ pub const SQL_FN_STR_POSITION: u32 = 0x00800000;
 pub const SQL_FN_TD_CURRENT_TIMESTAMP: u32 = 0x00080000;
 pub const SQL_FN_TD_EXTRACT: u32 = 0x00100000;
 
+// SQL_DATETIME_LITERALS bitmasks
+pub const SQL_DL_SQL92_DATE: u32 = 0x00000001;
+pub const SQL_DL_SQL92_TIME: u32 = 0x00000002;
+pub const SQL_DL_SQL92_TIMESTAMP: u32 = 0x00000004;
+
 // SQL_CATALOG_USAGE bitmasks
 pub const SQL_CU_DML_STATEMENTS: u32 = 0x00000001;
 
@@ -369,6 +456,8 @@ pub const SQL_AF_DISTINCT: u32 = 0x00000020;
 pub const SQL_AF_ALL: u32 = 0x00000040;
 
 pub const SQL_OJ_LEFT: u32 = 0x00000001;
+pub const SQL_OJ_RIGHT: u32 = 0x00000002;
+pub const SQL_OJ_FULL: u32 = 0x00000004;
 pub const SQL_OJ_NOT_ORDERED: u32 = 0x00000010;
 pub const SQL_OJ_INNER: u32 = 0x00000020;
 pub const SQL_OJ_ALL_COMPARISON_OPS: u32 = 0x00000040;
@@ -0,0 +1,244 @@
+use crate::{
+    col_metadata::MongoColMetadata,
+    lexer::{name_path, tokenize, Token},
+};
+use std::collections::HashMap;
+
+// Keywords that end the FROM clause, or follow a datasource without being its alias.
+const FROM_CLAUSE_END: [&str; 6] = ["where", "group", "having", "order", "limit", "offset"];
+const NOT_ALIASES: [&str; 8] = [
+    "on", "join", "inner", "left", "right", "outer", "cross", "natural",
+];
+
+// apply_base_names sets the base table and column names of the columns of a query's result set,
+// the collection and field each column is read from. sqlGetResultSchema only returns the
+// datasource of each column, so the provenance is taken from the outermost query block: a column
+// of a datasource is the field of the collection the datasource is, or is an alias of, and a
+// column of the select list is the field of the column reference it selects. Computed columns, and
+// columns of derived tables, have empty base names.
+pub(crate) fn apply_base_names(metadata: &mut [MongoColMetadata], query: &str) {
+    let tokens = tokenize(query);
+    let mut top_level = tokens.iter().enumerate().filter(|(_, t)| t.depth == 0);
+    let Some((select, _)) = top_level.clone().find(|(_, t)| t.is_word("select")) else {
+        return;
+    };
+    // The columns of a union come from several query blocks.
+    if top_level.clone().any(|(_, t)| t.is_word("union")) {
+        return;
+    }
+    let Some((from, _)) = top_level.find(|&(i, t)| i > select && t.is_word("from")) else {
+        return;
+    };
+    let from_end = (from + 1..tokens.len())
+        .find(|&i| tokens[i].depth == 0 && FROM_CLAUSE_END.iter().any(|&kw| tokens[i].is_word(kw)))
+        .unwrap_or(tokens.len());
+    let datasources = datasource_collections(&tokens[from + 1..from_end]);
+
+    let mut list_start = select + 1;
+    if tokens[list_start].is_word("distinct") || tokens[list_start].is_word("all") {
+        list_start += 1;
+    }
+    // The column references of the select list, by the name they are returned as.
+    let selected = tokens[list_start..from]
+        .split(|t| t.depth == 0 && t.is_punct(","))
+        .filter_map(|item| select_item_source(item, &datasources))
+        .collect::<HashMap<_, _>>();
+
+    for column in metadata.iter_mut() {
+        let base = if column.table_name.is_empty() {
+            selected.get(&column.col_name).cloned()
+        } else {
+            datasources
+                .get(&column.table_name)
+                .cloned()
+                .flatten()
+                .map(|collection| (collection, column.col_name.clone()))
+        };
+        if let Some((base_table_name, base_col_name)) = base {
+            column.base_table_name = base_table_name;
+            column.base_col_name = base_col_name;
+        }
+    }
+}
+
+// datasource_collections returns the collection of each datasource of a FROM clause, by the name
+// the datasource is referred to: its alias, or the collection name. Derived tables have no
+// collection.
+fn datasource_collections(tokens: &[Token]) -> HashMap<String, Option<String>> {
+    let mut datasources = HashMap::new();
+    let mut i = 0;
+    let mut expecting_datasource = true;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        if token.depth > 0 {
+            i += 1;
+            continue;
+        }
+        if !expecting_datasource {
+            expecting_datasource = token.is_punct(",") || token.is_word("join");
+            i += 1;
+            continue;
+        }
+        expecting_datasource = false;
+        let (collection, name_end) = if token.is_punct("(") {
+            let close = (i + 1..tokens.len())
+                .find(|&j| tokens[j].depth == 0 && tokens[j].is_punct(")"))
+                .unwrap_or(tokens.len() - 1);
+            (None, close + 1)
+        } else {
+            // a collection may be qualified by its database
+            let mut name_end = i + 1;
+            while name_end + 1 < tokens.len() && tokens[name_end].is_punct(".") {
+                name_end += 2;
+            }
+            match name_path(&tokens[i..name_end]) {
+                Some(path) => (path.last().cloned(), name_end),
+                None => {
+                    i += 1;
+                    continue;
+                }
+            }
+        };
+        let mut alias_start = name_end;
+        if tokens.get(alias_start).is_some_and(|t| t.is_word("as")) {
+            alias_start += 1;
+        }
+        let alias = tokens
+            .get(alias_start)
+            .filter(|t| !NOT_ALIASES.iter().any(|&kw| t.is_word(kw)))
+            .and_then(|t| name_path(std::slice::from_ref(t)))
+            .map(|mut path| path.remove(0));
+        i = alias_start + usize::from(alias.is_some());
+        if let Some(name) = alias.or_else(|| collection.clone()) {
+            datasources.insert(name, collection);
+        }
+    }
+    datasources
+}
+
+// select_item_source returns the name an expression of the select list is returned as, with the
+// collection and field it reads, if it is a column reference of a collection.
+fn select_item_source(
+    item: &[Token],
+    datasources: &HashMap<String, Option<String>>,
+) -> Option<(String, (String, String))> {
+    let (expression, alias) = match item {
+        [expression @ .., as_keyword, alias]
+            if !expression.is_empty() && as_keyword.is_word("as") =>
+        {
+            (
+                expression,
+                name_path(std::slice::from_ref(alias))?.remove(0),
+            )
+        }
+        _ => (item, name_path(item)?.pop()?),
+    };
+    let path = name_path(expression)?;
+    let (datasource, field) = match path.as_slice() {
+        // an unqualified field is read from the only datasource
+        [field] if datasources.len() == 1 => (datasources.keys().next()?, field),
+        [datasource, field] => (datasource, field),
+        _ => return None,
+    };
+    let collection = datasources.get(datasource)?.clone()?;
+    Some((alias, (collection, field.clone())))
+}
+
+#[cfg(test)]
+mod unit {
+    use super::apply_base_names;
+    use crate::{
+        col_metadata::MongoColMetadata,
+        json_schema::{simplified::Atomic, simplified::Schema, BsonTypeName},
+        TypeMode,
+    };
+    use definitions::Nullability;
+
+    fn base_names(query: &str, columns: &[(&str, &str)]) -> Vec<(String, String)> {
+        let mut metadata = columns
+            .iter()
+            .map(|(datasource, field)| {
+                MongoColMetadata::new(
+                    "test",
+                    datasource.to_string(),
+                    field.to_string(),
+                    Schema::Atomic(Atomic::Scalar(BsonTypeName::Int)),
+                    Nullability::SQL_NULLABLE,
+                    TypeMode::Standard,
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+        apply_base_names(&mut metadata, query);
+        metadata
+            .into_iter()
+            .map(|col| (col.base_table_name, col.base_col_name))
+            .collect()
+    }
+
+    fn base(table: &str, column: &str) -> (String, String) {
+        (table.to_string(), column.to_string())
+    }
+
+    #[test]
+    fn select_star_columns_are_fields_of_the_collection() {
+        assert_eq!(
+            vec![base("foo", "_id"), base("foo", "a")],
+            base_names("SELECT * FROM foo", &[("foo", "_id"), ("foo", "a")])
+        );
+        assert_eq!(
+            vec![base("foo", "a"), base("bar", "b")],
+            base_names(
+                "SELECT * FROM test.foo AS f JOIN bar ON f.a = bar.b",
+                &[("f", "a"), ("bar", "b")]
+            )
+        );
+    }
+
+    #[test]
+    fn aliased_columns_keep_their_origin() {
+        assert_eq!(
+            vec![base("foo", "a"), base("foo", "b"), base("bar", "c")],
+            base_names(
+                "SELECT f.a AS x, f.b, `bar`.c AS \"y\" FROM foo f INNER JOIN bar ON f.a = bar.a",
+                &[("", "x"), ("", "b"), ("", "y")]
+            )
+        );
+        assert_eq!(
+            vec![base("foo", "a")],
+            base_names("SELECT DISTINCT a AS x FROM foo WHERE a > 1", &[("", "x")])
+        );
+    }
+
+    #[test]
+    fn computed_columns_have_empty_base_names() {
+        assert_eq!(
+            vec![base("", ""), base("", ""), base("", "")],
+            base_names(
+                "SELECT a + 1 AS x, COUNT(*) AS n, 'a' AS s FROM foo",
+                &[("", "x"), ("", "n"), ("", "s")]
+            )
+        );
+        // an unqualified field is ambiguous with several datasources
+        assert_eq!(
+            vec![base("", "")],
+            base_names("SELECT a AS x FROM foo, bar", &[("", "x")])
+        );
+        // the columns of a derived table are computed by its query
+        assert_eq!(
+            vec![base("", ""), base("", "")],
+            base_names(
+                "SELECT d.a AS x, d.b FROM (SELECT a, b FROM foo) AS d",
+                &[("", "x"), ("d", "b")]
+            )
+        );
+        // the columns of a union come from several queries
+        assert_eq!(
+            vec![base("", "")],
+            base_names(
+                "SELECT a FROM foo UNION ALL SELECT a FROM bar",
+                &[("foo", "a")]
+            )
+        );
+    }
+}
@@ -2,11 +2,13 @@ use definitions::{SqlCode, SqlDataType};
 
 pub const MAX_STRING_SIZE: u16 = u16::MAX;
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum TypeMode {
     Standard,
     Simple,
+    // Standard types, except that arrays and documents are rendered as extended json strings.
+    NestedJson,
 }
 
 /// make_default_attr_func creates an anonymous function that takes a single
@@ -165,7 +167,7 @@ impl BsonTypeInfo {
         is_auto_unique_value: Some(false),
         is_unsigned: Some(false),
         num_prec_radix: Some(2),
-        decimal_digit: Some(0),
+        decimal_digit: None,
         column_size: make_default_attr_func!(Some(15)),
         simple_type_info: None,
     };
@@ -540,28 +542,30 @@ impl BsonTypeInfo {
         column_size: make_default_attr_func!(Some(10)),
         simple_type_info: None,
     };
+    // A BSON Timestamp is exposed as the datetime of its seconds component, so it has no
+    // fractional seconds.
     pub const TIMESTAMP: BsonTypeInfo = BsonTypeInfo {
         type_name: "timestamp",
-        sql_type: SqlDataType::SQL_UNKNOWN_TYPE,
-        non_concise_type: None,
+        sql_type: SqlDataType::SQL_TYPE_TIMESTAMP,
+        non_concise_type: Some(SqlDataType::SQL_DATETIME),
         searchable: SQL_PRED_BASIC,
         is_case_sensitive: false,
-        fixed_prec_scale: false,
+        fixed_prec_scale: true,
         scale: None,
-        length: make_default_attr_func!(None),
-        precision: None,
+        length: make_default_attr_func!(Some(19)),
+        precision: Some(0),
         char_octet_length: make_default_attr_func!(None),
-        transfer_octet_length: None,
-        display_size: make_default_attr_func!(None),
-        literal_prefix: None,
-        literal_suffix: None,
-        sql_code: None,
+        transfer_octet_length: Some(16),
+        display_size: make_default_attr_func!(Some(19)),
+        literal_prefix: Some("'"),
+        literal_suffix: Some("'"),
+        sql_code: Some(SqlCode::SQL_CODE_TIMESTAMP),
         is_auto_unique_value: None,
         is_unsigned: None,
         num_prec_radix: None,
-        decimal_digit: None,
-        column_size: make_default_attr_func!(None),
-        simple_type_info: new_simple_type_info!(68, 68 * 4, 68),
+        decimal_digit: Some(0),
+        column_size: make_default_attr_func!(Some(19)),
+        simple_type_info: None,
     };
     pub const LONG: BsonTypeInfo = BsonTypeInfo {
         type_name: "long",
@@ -598,30 +602,33 @@ impl BsonTypeInfo {
         precision: None,
         char_octet_length: make_default_attr_func!(None),
         transfer_octet_length: None,
-        display_size: make_default_attr_func!(None),
+        // 34 digits of precision, plus the sign and the decimal point.
+        display_size: make_default_attr_func!(Some(36)),
         literal_prefix: None,
         literal_suffix: None,
         sql_code: None,
         is_auto_unique_value: Some(false),
         is_unsigned: Some(false),
-        num_prec_radix: None,
+        num_prec_radix: Some(10),
         decimal_digit: None,
         column_size: make_default_attr_func!(None),
         simple_type_info: SimpleTypeInfo::default(),
     };
+    // MinKey and MaxKey are read as strings, the name of the sentinel value or its extended JSON
+    // with the canonical json mode.
     pub const MINKEY: BsonTypeInfo = BsonTypeInfo {
         type_name: "minKey",
-        sql_type: SqlDataType::SQL_UNKNOWN_TYPE,
+        sql_type: SqlDataType::SQL_WVARCHAR,
         non_concise_type: None,
         searchable: SQL_PRED_BASIC,
         is_case_sensitive: false,
         fixed_prec_scale: false,
         scale: None,
-        length: make_default_attr_func!(None),
+        length: make_default_attr_func!(Some(14)),
         precision: None,
-        char_octet_length: make_default_attr_func!(None),
-        transfer_octet_length: None,
-        display_size: make_default_attr_func!(None),
+        char_octet_length: make_default_attr_func!(Some(14 * 4)),
+        transfer_octet_length: Some(14 * 4),
+        display_size: make_default_attr_func!(Some(14)),
         literal_prefix: None,
         literal_suffix: None,
         sql_code: None,
@@ -629,22 +636,22 @@ impl BsonTypeInfo {
         is_unsigned: None,
         num_prec_radix: None,
         decimal_digit: None,
-        column_size: make_default_attr_func!(None),
+        column_size: make_default_attr_func!(Some(14)),
         simple_type_info: new_simple_type_info!(14, 14 * 4, 14),
     };
     pub const MAXKEY: BsonTypeInfo = BsonTypeInfo {
         type_name: "maxKey",
-        sql_type: SqlDataType::SQL_UNKNOWN_TYPE,
+        sql_type: SqlDataType::SQL_WVARCHAR,
         non_concise_type: None,
         searchable: SQL_PRED_BASIC,
         is_case_sensitive: false,
         fixed_prec_scale: false,
         scale: None,
-        length: make_default_attr_func!(None),
+        length: make_default_attr_func!(Some(14)),
         precision: None,
-        char_octet_length: make_default_attr_func!(None),
-        transfer_octet_length: None,
-        display_size: make_default_attr_func!(None),
+        char_octet_length: make_default_attr_func!(Some(14 * 4)),
+        transfer_octet_length: Some(14 * 4),
+        display_size: make_default_attr_func!(Some(14)),
         literal_prefix: None,
         literal_suffix: None,
         sql_code: None,
@@ -652,7 +659,7 @@ impl BsonTypeInfo {
         is_unsigned: None,
         num_prec_radix: None,
         decimal_digit: None,
-        column_size: make_default_attr_func!(None),
+        column_size: make_default_attr_func!(Some(14)),
         simple_type_info: new_simple_type_info!(14, 14 * 4, 14),
     };
     pub const BSON: BsonTypeInfo = BsonTypeInfo {
@@ -679,46 +686,56 @@ impl BsonTypeInfo {
         simple_type_info: SimpleTypeInfo::default(),
     };
 
+    // The SimpleTypeInfo to use in place of the standard type info for the given type mode, if
+    // any.
+    fn simple_type_info_for(&self, type_mode: TypeMode) -> Option<SimpleTypeInfo> {
+        match type_mode {
+            TypeMode::Standard => None,
+            TypeMode::Simple => self.simple_type_info.clone(),
+            TypeMode::NestedJson
+                if self.type_name == Self::ARRAY.type_name
+                    || self.type_name == Self::OBJECT.type_name =>
+            {
+                self.simple_type_info.clone()
+            }
+            TypeMode::NestedJson => None,
+        }
+    }
+
     pub fn sql_type(&self, type_mode: TypeMode) -> SqlDataType {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            self.simple_type_info.clone().unwrap().sql_type
-        } else {
-            self.sql_type
+        match self.simple_type_info_for(type_mode) {
+            Some(simple_type_info) => simple_type_info.sql_type,
+            None => self.sql_type,
         }
     }
 
     pub fn non_concise_type(&self, type_mode: TypeMode) -> SqlDataType {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            let simple_type_info = self.simple_type_info.clone().unwrap();
-            simple_type_info
+        match self.simple_type_info_for(type_mode) {
+            Some(simple_type_info) => simple_type_info
                 .non_concise_type
-                .unwrap_or(simple_type_info.sql_type)
-        } else {
-            self.non_concise_type.unwrap_or(self.sql_type)
+                .unwrap_or(simple_type_info.sql_type),
+            None => self.non_concise_type.unwrap_or(self.sql_type),
         }
     }
 
     pub fn precision(&self, type_mode: TypeMode) -> Option<u16> {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            None
-        } else {
-            self.precision
+        match self.simple_type_info_for(type_mode) {
+            Some(_) => None,
+            None => self.precision,
         }
     }
 
     pub fn length(&self, type_mode: TypeMode, max_string_length: Option<u16>) -> Option<u16> {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            (self.simple_type_info.clone().unwrap().length)(max_string_length)
-        } else {
-            (self.length)(max_string_length)
+        match self.simple_type_info_for(type_mode) {
+            Some(simple_type_info) => (simple_type_info.length)(max_string_length),
+            None => (self.length)(max_string_length),
         }
     }
 
     pub fn transfer_octet_length(&self, type_mode: TypeMode) -> Option<u16> {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            self.simple_type_info.clone().unwrap().transfer_octet_length
-        } else {
-            self.transfer_octet_length
+        match self.simple_type_info_for(type_mode) {
+            Some(simple_type_info) => simple_type_info.transfer_octet_length,
+            None => self.transfer_octet_length,
         }
     }
 
@@ -727,34 +744,37 @@ impl BsonTypeInfo {
         type_mode: TypeMode,
         max_string_length: Option<u16>,
     ) -> Option<u16> {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            (self.simple_type_info.clone().unwrap().length)(max_string_length)
-        } else {
-            (self.char_octet_length)(max_string_length)
+        match self.simple_type_info_for(type_mode) {
+            Some(simple_type_info) => (simple_type_info.length)(max_string_length),
+            None => (self.char_octet_length)(max_string_length),
         }
     }
 
     pub fn display_size(&self, type_mode: TypeMode, max_string_length: Option<u16>) -> Option<u16> {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            (self.simple_type_info.clone().unwrap().display_size)(max_string_length)
-        } else {
-            (self.display_size)(max_string_length)
+        match self.simple_type_info_for(type_mode) {
+            Some(simple_type_info) => (simple_type_info.display_size)(max_string_length),
+            None => (self.display_size)(max_string_length),
+        }
+    }
+
+    pub fn num_prec_radix(&self, type_mode: TypeMode) -> Option<u16> {
+        match self.simple_type_info_for(type_mode) {
+            Some(_) => None,
+            None => self.num_prec_radix,
         }
     }
 
     pub fn decimal_digit(&self, type_mode: TypeMode) -> Option<u16> {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            None
-        } else {
-            self.decimal_digit
+        match self.simple_type_info_for(type_mode) {
+            Some(_) => None,
+            None => self.decimal_digit,
         }
     }
 
     pub fn column_size(&self, type_mode: TypeMode, max_string_length: Option<u16>) -> Option<u16> {
-        if type_mode == TypeMode::Simple && self.simple_type_info.is_some() {
-            (self.simple_type_info.clone().unwrap().length)(max_string_length)
-        } else {
-            (self.column_size)(max_string_length)
+        match self.simple_type_info_for(type_mode) {
+            Some(simple_type_info) => (simple_type_info.length)(max_string_length),
+            None => (self.column_size)(max_string_length),
         }
     }
 }
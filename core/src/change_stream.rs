@@ -0,0 +1,272 @@
+use crate::{
+    col_metadata::MongoColMetadata,
+    conn::MongoConnection,
+    err::{Error, Result},
+    query::command_comment,
+    stmt::{CommandOptions, MongoStatement},
+    BsonTypeInfo,
+};
+use bson::{Bson, Document};
+use definitions::Nullability;
+use mongodb::{
+    change_stream::ChangeStream,
+    options::{ChangeStreamOptions, FullDocumentType},
+};
+use std::time::{Duration, Instant};
+
+// How long the server waits for a change event before answering a getMore without one. The
+// query timeout is checked between getMores, so this bounds how late a timeout is reported.
+const MAX_AWAIT_TIME: Duration = Duration::from_secs(1);
+
+// change_stream_target returns the collection of a `TAIL <collection>` statement, or None if the
+// statement is not one. The keyword is case-insensitive and the collection may be quoted.
+pub fn change_stream_target(statement: &str) -> Option<&str> {
+    let statement = statement.trim().trim_end_matches(';').trim_end();
+    let (keyword, collection) = statement.split_once(char::is_whitespace)?;
+    if !keyword.eq_ignore_ascii_case("tail") {
+        return None;
+    }
+    let collection = collection.trim_start();
+    let quoted = ['"', '`'].into_iter().find_map(|quote| {
+        collection
+            .strip_prefix(quote)
+            .and_then(|c| c.strip_suffix(quote))
+    });
+    match quoted {
+        Some(collection) => (!collection.is_empty()).then_some(collection),
+        None => (!collection.contains(char::is_whitespace)).then_some(collection),
+    }
+}
+
+// MongoChangeStream opens a change stream on a collection of the current database, and returns
+// every change event as a row with the columns operationType, clusterTime, documentKey and
+// fullDocument. The result set never ends: next blocks until the next event arrives, or fails
+// once the query timeout, if any, expires without one.
+#[derive(Debug)]
+pub struct MongoChangeStream {
+    current_db: String,
+    collection: String,
+    // The TAIL statement
+    statement: String,
+    // The query timeout in seconds, 0 means no timeout
+    query_timeout: u64,
+    resultset_metadata: Vec<MongoColMetadata>,
+    // The change stream, once executed
+    stream: Option<ChangeStream<Document>>,
+    // The current change event
+    current: Option<Document>,
+}
+
+impl MongoChangeStream {
+    pub fn prepare(
+        current_db: Option<String>,
+        statement: &str,
+        collection: &str,
+        query_timeout: u64,
+    ) -> Result<Self> {
+        let resultset_metadata = [
+            (
+                "operationType",
+                BsonTypeInfo::STRING,
+                Nullability::SQL_NO_NULLS,
+            ),
+            (
+                "clusterTime",
+                BsonTypeInfo::TIMESTAMP,
+                Nullability::SQL_NULLABLE,
+            ),
+            (
+                "documentKey",
+                BsonTypeInfo::OBJECT,
+                Nullability::SQL_NULLABLE,
+            ),
+            (
+                "fullDocument",
+                BsonTypeInfo::OBJECT,
+                Nullability::SQL_NULLABLE,
+            ),
+        ]
+        .into_iter()
+        .map(|(name, type_info, nullability)| {
+            MongoColMetadata::new_metadata_from_bson_type_info_default(
+                "",
+                collection.to_string(),
+                name.to_string(),
+                type_info,
+                None,
+                nullability,
+            )
+        })
+        .collect();
+        Ok(MongoChangeStream {
+            current_db: current_db.ok_or(Error::NoDatabase)?,
+            collection: collection.to_string(),
+            statement: statement.to_string(),
+            query_timeout,
+            resultset_metadata,
+            stream: None,
+            current: None,
+        })
+    }
+}
+
+impl MongoStatement for MongoChangeStream {
+    // Wait for the next change event. Events that arrived since the last call are returned
+    // immediately.
+    fn next(&mut self, connection: Option<&MongoConnection>) -> Result<(bool, Vec<Error>)> {
+        let stream = self.stream.as_mut().ok_or(Error::StatementNotExecuted)?;
+        let connection = connection.ok_or(Error::MissingConnection("change stream"))?;
+        let deadline = (self.query_timeout > 0)
+            .then(|| Instant::now() + Duration::from_secs(self.query_timeout));
+        let _guard = connection.runtime.enter();
+        let event = connection.runtime.block_on(async {
+            loop {
+                if let Some(event) = stream
+                    .next_if_any()
+                    .await
+                    .map_err(Error::QueryCursorUpdate)?
+                {
+                    return Ok(event);
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    return Err(Error::ChangeStreamTimeout(self.query_timeout));
+                }
+            }
+        })?;
+        self.current = Some(event);
+        Ok((true, vec![]))
+    }
+
+    // Get the BSON value for the cell at the given colIndex on the current event. Fields missing
+    // from the event, like the fullDocument of a delete, are NULL.
+    fn get_value(&self, col_index: u16, max_string_length: Option<u16>) -> Result<Option<Bson>> {
+        let current = self.current.as_ref().ok_or(Error::InvalidCursorState)?;
+        let md = self.get_col_metadata(col_index, max_string_length)?;
+        Ok(current.get(&md.col_name).cloned())
+    }
+
+    fn get_resultset_metadata(&self, _: Option<u16>) -> &Vec<MongoColMetadata> {
+        &self.resultset_metadata
+    }
+
+    // Open the change stream. Only the changes made after it is opened are returned.
+    fn execute(
+        &mut self,
+        connection: &MongoConnection,
+        stmt_id: Bson,
+        _: u32,
+        command_options: &CommandOptions,
+    ) -> Result<bool> {
+        let collection = connection
+            .client
+            .database(&self.current_db)
+            .collection::<Document>(&self.collection);
+        let options = ChangeStreamOptions::builder()
+            .full_document(Some(FullDocumentType::UpdateLookup))
+            .max_await_time(Some(MAX_AWAIT_TIME))
+            .comment(Some(command_comment(stmt_id, command_options)))
+            .build();
+        let _guard = connection.runtime.enter();
+        let stream = connection
+            .runtime
+            .block_on(async { collection.watch(None, options).await })
+            .map_err(Error::QueryExecutionFailed)?;
+        self.stream = Some(stream.with_type());
+        self.current = None;
+        Ok(true)
+    }
+
+    fn close_cursor(&mut self) {
+        self.current = None;
+        self.stream = None;
+    }
+
+    fn query(&self) -> Option<&str> {
+        Some(&self.statement)
+    }
+}
+
+#[cfg(test)]
+mod unit {
+    use super::{change_stream_target, MongoChangeStream};
+    use crate::{err::Error, stmt::MongoStatement};
+    use bson::{doc, Bson, Timestamp};
+
+    #[test]
+    fn tail_statements_are_recognized() {
+        for (statement, expected) in [
+            ("TAIL orders", "orders"),
+            ("  tail\t\"order items\" ;", "order items"),
+            ("Tail `orders`;", "orders"),
+        ] {
+            assert_eq!(
+                Some(expected),
+                change_stream_target(statement),
+                "{statement}"
+            );
+        }
+        for statement in [
+            "SELECT * FROM orders",
+            "TAIL",
+            "TAIL orders WHERE x = 1",
+            "TAILorders",
+            "SELECT tail FROM orders",
+        ] {
+            assert_eq!(None, change_stream_target(statement), "{statement}");
+        }
+    }
+
+    #[test]
+    fn change_events_are_returned_as_rows() {
+        let mut stream =
+            MongoChangeStream::prepare(Some("db".to_string()), "TAIL orders", "orders", 0).unwrap();
+        assert!(matches!(
+            stream.next(None),
+            Err(Error::StatementNotExecuted)
+        ));
+        assert_eq!(
+            vec![
+                "operationType",
+                "clusterTime",
+                "documentKey",
+                "fullDocument"
+            ],
+            stream
+                .get_resultset_metadata(None)
+                .iter()
+                .map(|col| col.col_name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            stream.get_value(1, None),
+            Err(Error::InvalidCursorState)
+        ));
+
+        stream.current = Some(doc! {
+            "_id": {"_data": "token"},
+            "operationType": "delete",
+            "clusterTime": Timestamp { time: 1, increment: 2 },
+            "documentKey": {"_id": 7},
+        });
+        assert_eq!(
+            Some(Bson::String("delete".to_string())),
+            stream.get_value(1, None).unwrap()
+        );
+        assert_eq!(
+            Some(Bson::Timestamp(Timestamp {
+                time: 1,
+                increment: 2
+            })),
+            stream.get_value(2, None).unwrap()
+        );
+        assert_eq!(
+            Some(Bson::Document(doc! {"_id": 7})),
+            stream.get_value(3, None).unwrap()
+        );
+        assert_eq!(None, stream.get_value(4, None).unwrap());
+        assert!(matches!(
+            stream.get_value(5, None),
+            Err(Error::ColIndexOutOfBounds(5))
+        ));
+    }
+}
@@ -1,12 +1,13 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, mem::size_of};
 
 use crate::{
     json_schema::{
         simplified::{Atomic, ObjectSchema, Schema},
         BsonTypeName,
     },
-    BsonTypeInfo, Error, Result, TypeMode,
+    BsonTypeInfo, Error, Result, TypeMode, MAX_STRING_SIZE,
 };
+use cstr::WideChar;
 use definitions::{Nullability, SqlCode, SqlDataType};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -65,9 +66,9 @@ impl MongoColMetadata {
         nullability: Nullability,
     ) -> MongoColMetadata {
         MongoColMetadata {
-            // For base_col_name, base_table_name, and catalog_name, we do
-            // not have this information in sqlGetResultSchema, so these will
-            // always be empty string for now.
+            // sqlGetResultSchema does not return base_col_name, base_table_name,
+            // or catalog_name. The base names of query columns are set from the
+            // query text after the schema is processed, see base_names.rs.
             base_col_name: "".to_string(),
             base_table_name: "".to_string(),
             case_sensitive: bson_type_info.is_case_sensitive,
@@ -94,7 +95,7 @@ impl MongoColMetadata {
             literal_suffix: bson_type_info.literal_suffix,
             col_name: field_name,
             nullability,
-            num_prec_radix: bson_type_info.num_prec_radix,
+            num_prec_radix: bson_type_info.num_prec_radix(type_mode),
             transfer_octet_length: bson_type_info.transfer_octet_length(type_mode),
             char_octet_length: bson_type_info.char_octet_length(type_mode, max_string_length),
             precision: bson_type_info.precision(type_mode),
@@ -150,6 +151,27 @@ impl MongoColMetadata {
             nullability,
         )
     }
+
+    // The maximum length in bytes of a value of this column, as reported by
+    // SQL_DESC_OCTET_LENGTH. Fixed-length types report their transfer octet length. Character
+    // and binary types report their maximum length, which is MAX_STRING_SIZE when no max string
+    // length is set. It never includes the null terminator.
+    // Values of any other type are returned as extended JSON wide-char strings.
+    pub fn octet_length(&self) -> usize {
+        if let Some(transfer_octet_length) = self.transfer_octet_length {
+            return usize::from(transfer_octet_length);
+        }
+        let length = usize::from(self.length.unwrap_or(MAX_STRING_SIZE));
+        match self.sql_type {
+            SqlDataType::SQL_CHAR
+            | SqlDataType::SQL_VARCHAR
+            | SqlDataType::SQL_LONGVARCHAR
+            | SqlDataType::SQL_BINARY
+            | SqlDataType::SQL_VARBINARY
+            | SqlDataType::SQL_LONGVARBINARY => length,
+            _ => length * size_of::<WideChar>(),
+        }
+    }
 }
 
 // Struct representing the response for a sqlGetResultSchema command.
@@ -453,6 +475,57 @@ mod unit {
             );
         }
 
+        #[test]
+        fn nested_json_renders_only_arrays_and_documents_as_strings() {
+            let input = SqlGetSchemaResponse {
+                ok: 1,
+                schema: VersionedJsonSchema {
+                    version: 1,
+                    json_schema: Schema {
+                        bson_type: Some(BsonType::Single(BsonTypeName::Object)),
+                        properties: Some(map! {
+                            "foo".to_string() => Schema {
+                                bson_type: Some(BsonType::Single(BsonTypeName::Object)),
+                                properties: Some(map! {
+                                    "arr".to_string() => Schema {
+                                        bson_type: Some(BsonType::Single(BsonTypeName::Array)),
+                                        ..Default::default()
+                                    },
+                                    "doc".to_string() => Schema {
+                                        bson_type: Some(BsonType::Single(BsonTypeName::Object)),
+                                        ..Default::default()
+                                    },
+                                    "int".to_string() => Schema {
+                                        bson_type: Some(BsonType::Single(BsonTypeName::Int)),
+                                        ..Default::default()
+                                    },
+                                }),
+                                ..Default::default()
+                            },
+                        }),
+                        ..Default::default()
+                    },
+                },
+                select_order: None,
+            };
+
+            let schema = input
+                .process_result_metadata("test_db", TypeMode::NestedJson, Some(50))
+                .unwrap();
+            let types = schema
+                .iter()
+                .map(|col| (col.col_name.as_str(), col.sql_type, col.column_size))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vec![
+                    ("arr", definitions::SqlDataType::SQL_WVARCHAR, Some(50)),
+                    ("doc", definitions::SqlDataType::SQL_WVARCHAR, Some(50)),
+                    ("int", definitions::SqlDataType::SQL_INTEGER, Some(10)),
+                ],
+                types
+            );
+        }
+
         #[test]
         fn null_columns_are_sql_wvarchar_with_simple_types() {
             let input = SqlGetSchemaResponse {
@@ -793,4 +866,81 @@ mod unit {
             }
         }
     }
+
+    mod octet_length {
+        use crate::{BsonTypeInfo, MongoColMetadata, TypeMode, MAX_STRING_SIZE};
+        use cstr::WideChar;
+        use definitions::Nullability;
+        use std::mem::size_of;
+
+        fn octet_length(
+            bson_type_info: BsonTypeInfo,
+            type_mode: TypeMode,
+            max_string_length: Option<u16>,
+        ) -> usize {
+            MongoColMetadata::new_metadata_from_bson_type_info(
+                "",
+                "".to_string(),
+                "a".to_string(),
+                bson_type_info,
+                type_mode,
+                max_string_length,
+                Nullability::SQL_NULLABLE,
+            )
+            .octet_length()
+        }
+
+        #[test]
+        fn fixed_length_types_use_transfer_octet_length() {
+            assert_eq!(4, octet_length(BsonTypeInfo::INT, TypeMode::Standard, None));
+            assert_eq!(
+                8,
+                octet_length(BsonTypeInfo::LONG, TypeMode::Standard, None)
+            );
+            assert_eq!(
+                16,
+                octet_length(BsonTypeInfo::DATE, TypeMode::Standard, None)
+            );
+        }
+
+        #[test]
+        fn string_without_max_length_is_not_zero() {
+            assert_eq!(
+                usize::from(MAX_STRING_SIZE) * size_of::<WideChar>(),
+                octet_length(BsonTypeInfo::STRING, TypeMode::Standard, None)
+            );
+        }
+
+        #[test]
+        fn string_with_max_length() {
+            assert_eq!(
+                100 * size_of::<WideChar>(),
+                octet_length(BsonTypeInfo::STRING, TypeMode::Standard, Some(100))
+            );
+        }
+
+        #[test]
+        fn narrow_char_and_binary_are_one_byte_per_unit() {
+            assert_eq!(
+                usize::from(MAX_STRING_SIZE),
+                octet_length(BsonTypeInfo::VARCHAR, TypeMode::Standard, None)
+            );
+            assert_eq!(
+                usize::from(MAX_STRING_SIZE),
+                octet_length(BsonTypeInfo::BINDATA, TypeMode::Standard, None)
+            );
+        }
+
+        #[test]
+        fn json_rendered_types_are_wide_chars() {
+            assert_eq!(
+                usize::from(MAX_STRING_SIZE) * size_of::<WideChar>(),
+                octet_length(BsonTypeInfo::OBJECT, TypeMode::Standard, None)
+            );
+            assert_eq!(
+                50 * size_of::<WideChar>(),
+                octet_length(BsonTypeInfo::ARRAY, TypeMode::Simple, Some(50))
+            );
+        }
+    }
 }
@@ -8,7 +8,7 @@ use crate::{
     util::{COLLECTION, TABLE, TIMESERIES},
     BsonTypeInfo, Error,
 };
-use bson::{doc, Bson};
+use bson::{doc, Bson, Document};
 use definitions::Nullability;
 use futures::future;
 use mongodb::{options::ListDatabasesOptions, results::CollectionType};
@@ -66,6 +66,8 @@ fn init_collections_metadata(max_string_length: Option<u16>) -> Vec<MongoColMeta
 pub(crate) struct MongoODBCCollectionSpecification {
     pub name: String,
     pub collection_type: CollectionType,
+    // The REMARKS of the collection; see collection_remarks.
+    pub remarks: String,
 }
 
 impl MongoODBCCollectionSpecification {
@@ -73,6 +75,86 @@ impl MongoODBCCollectionSpecification {
         Self {
             name,
             collection_type,
+            remarks: String::new(),
+        }
+    }
+
+    // Build the specification from a listCollections cursor document.
+    fn from_list_collections_doc(doc: &Document) -> Self {
+        let name = doc.get_str("name").unwrap().to_string();
+        let collection_type = match doc.get_str("type").unwrap() {
+            "collection" => CollectionType::Collection,
+            "view" => CollectionType::View,
+            _ => CollectionType::Collection,
+        };
+        Self {
+            name,
+            collection_type,
+            remarks: collection_remarks(doc.get_document("options").ok()),
+        }
+    }
+}
+
+// The collection options exposed in the REMARKS column of SQLTables: whether the collection is
+// capped along with its size limits, and the source collection and pipeline of a view. They are
+// rendered as a relaxed extended JSON document, or an empty string if none are set.
+fn collection_remarks(options: Option<&Document>) -> String {
+    let remarks = options
+        .map(|options| {
+            ["capped", "size", "max", "viewOn", "pipeline"]
+                .into_iter()
+                .filter_map(|key| options.get(key).map(|val| (key.to_string(), val.clone())))
+                .collect::<Document>()
+        })
+        .unwrap_or_default();
+    if remarks.is_empty() {
+        String::new()
+    } else {
+        Bson::Document(remarks).into_relaxed_extjson().to_string()
+    }
+}
+
+// List the collections of the given database. The full listCollections output is requested so
+// that the collection options are available for REMARKS. If the user is not allowed to list them,
+// fall back to the names of the collections the user is authorized on.
+async fn list_collections(
+    mongo_connection: &MongoConnection,
+    db_name: &str,
+) -> Vec<MongoODBCCollectionSpecification> {
+    let db = mongo_connection.client.database(db_name);
+    let response = match db
+        .run_command(
+            doc! { "listCollections": 1, "nameOnly": false, "authorizedCollections": true},
+            None,
+        )
+        .await
+    {
+        Ok(response) => Ok(response),
+        Err(_) => {
+            db.run_command(
+                doc! { "listCollections": 1, "nameOnly": true, "authorizedCollections": true},
+                None,
+            )
+            .await
+        }
+    };
+    match response
+        .as_ref()
+        .map(|response| response.get_document("cursor"))
+    {
+        Ok(Ok(cursor)) => cursor
+            .get_array("firstBatch")
+            .unwrap()
+            .iter()
+            .map(|val| {
+                MongoODBCCollectionSpecification::from_list_collections_doc(
+                    val.as_document().unwrap(),
+                )
+            })
+            .collect(),
+        _ => {
+            log::error!("Error getting collections for db {}", db_name);
+            vec![]
         }
     }
 }
@@ -129,26 +211,9 @@ impl MongoCollections {
                     .filter(|&db_name| is_match(db_name, db_name_filter, accept_search_patterns))
                     .map(|val| async move {
                         CollectionsForDb {
-                database_name: val.to_string(),
-                collection_list: mongo_connection.client.database(val.as_str()).run_command(
-                    doc! { "listCollections": 1, "nameOnly": true, "authorizedCollections": true},
-                    None,
-                ).await.unwrap().get_document("cursor").map(|doc| {
-                    doc.get_array("firstBatch").unwrap().iter().map(|val| {
-                        let doc = val.as_document().unwrap();
-                        let name = doc.get_str("name").unwrap().to_string();
-                        let collection_type = match doc.get_str("type").unwrap() {
-                            "collection" => CollectionType::Collection,
-                            "view" => CollectionType::View,
-                            _ => CollectionType::Collection
-                        };
-                        MongoODBCCollectionSpecification::new(name, collection_type)
-                    }).collect()
-                }).unwrap_or_else(|_| {
-                    log::error!("Error getting collections for db {}", val);
-                    vec![]
-                }),
-            }
+                            database_name: val.to_string(),
+                            collection_list: list_collections(mongo_connection, val).await,
+                        }
                     }),
             )
             .await
@@ -263,7 +328,7 @@ impl MongoStatement for MongoCollections {
         // 2-> Schema name; NULL as it is not applicable
         // 3 -> current_collection.name
         // 4 -> current_collection.collection_type
-        // 5 -> Remarks; NULL
+        // 5 -> current_collection.remarks
         let return_val = match col_index {
             1 => Bson::String(
                 self.collections_for_db_list
@@ -286,7 +351,7 @@ impl MongoStatement for MongoCollections {
                     _ => Bson::String(coll_type.to_uppercase()),
                 }
             }
-            5 => Bson::String("".to_string()),
+            5 => Bson::String(self.current_collection.as_ref().unwrap().remarks.clone()),
             _ => return Err(Error::ColIndexOutOfBounds(col_index)),
         };
         Ok(Some(return_val))
@@ -486,4 +551,55 @@ mod unit {
             assert!(filters.is_empty());
         }
     }
+
+    #[cfg(test)]
+    mod remarks {
+        use crate::collections::MongoODBCCollectionSpecification;
+        use bson::doc;
+        use mongodb::results::CollectionType;
+
+        #[test]
+        fn plain_collection_has_no_remarks() {
+            let spec = MongoODBCCollectionSpecification::from_list_collections_doc(
+                &doc! {"name": "foo", "type": "collection", "options": {}},
+            );
+            assert_eq!(CollectionType::Collection, spec.collection_type);
+            assert_eq!("", spec.remarks);
+        }
+
+        #[test]
+        fn name_only_listing_has_no_remarks() {
+            let spec = MongoODBCCollectionSpecification::from_list_collections_doc(
+                &doc! {"name": "foo", "type": "collection"},
+            );
+            assert_eq!("", spec.remarks);
+        }
+
+        #[test]
+        fn capped_collection() {
+            let spec = MongoODBCCollectionSpecification::from_list_collections_doc(&doc! {
+                "name": "log",
+                "type": "collection",
+                "options": {"capped": true, "size": 4096, "max": 100, "validationLevel": "off"},
+                "info": {"readOnly": false},
+            });
+            assert_eq!(CollectionType::Collection, spec.collection_type);
+            assert_eq!(r#"{"capped":true,"size":4096,"max":100}"#, spec.remarks);
+        }
+
+        #[test]
+        fn view() {
+            let spec = MongoODBCCollectionSpecification::from_list_collections_doc(&doc! {
+                "name": "baz",
+                "type": "view",
+                "options": {"viewOn": "foo", "pipeline": [{"$match": {"a": 1}}]},
+                "info": {"readOnly": true},
+            });
+            assert_eq!(CollectionType::View, spec.collection_type);
+            assert_eq!(
+                r#"{"viewOn":"foo","pipeline":[{"$match":{"a":1}}]}"#,
+                spec.remarks
+            );
+        }
+    }
 }
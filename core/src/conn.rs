@@ -1,10 +1,22 @@
 use crate::odbc_uri::UserOptions;
 use crate::{err::Result, Error};
-use crate::{MongoQuery, TypeMode};
-use bson::{doc, Bson, UuidRepresentation};
-use mongodb::Client;
+use crate::{MongoQuery, ResultCache, TypeMode};
+use bson::{doc, Bson, Document, UuidRepresentation};
+use mongodb::{
+    event::command::{
+        CommandEventHandler, CommandFailedEvent, CommandStartedEvent, CommandSucceededEvent,
+    },
+    options::{Collation, ListDatabasesOptions},
+    Client,
+};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+#[cfg(feature = "test_hooks")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 #[derive(Debug)]
 #[repr(C)]
@@ -18,11 +30,116 @@ pub struct MongoConnection {
     pub operation_timeout: Option<Duration>,
     /// The UuidRepresentation to use for this connection.
     pub uuid_repr: Option<UuidRepresentation>,
+    /// Whether a query that fails with a network error is run again once. Comes from the
+    /// retryReads attribute, and defaults to true like in the mongo rust driver.
+    pub retry_reads: bool,
+    /// The results of the recent queries on this connection. Only set when RESULT_CACHE_TTL_MS
+    /// is.
+    pub result_cache: Option<ResultCache>,
+    /// The collation of the string comparisons and sorts of queries. Comes from the collation
+    /// attribute, the default collation of each collection applies when it is not set.
+    pub collation: Option<Collation>,
+    /// Whether every query runs as the $sql aggregation, even when it could run as a find command.
+    /// Comes from the FORCE_AGGREGATE attribute, and is true unless it is 0.
+    pub force_aggregate: bool,
+    /// The features of the server the driver depends on. Comes from the server version, read when
+    /// connecting.
+    pub capabilities: ServerCapabilities,
+    /// The number of documents of each batch of the query cursors. Comes from the batchSize
+    /// attribute, where 0 leaves it to the server. When it is not set, the rowset size is the
+    /// batch size of large rowsets.
+    pub batch_size: Option<u32>,
+    /// The number of getMore commands run on the connection, each fetching a batch of a cursor
+    /// after the first. Only counted with the test_hooks feature, for the tests of the batch size.
+    #[cfg(feature = "test_hooks")]
+    pub get_more_count: Arc<AtomicU64>,
+    /// The warnings returned by the server in the replies to the commands run on the connection,
+    /// such as the use of deprecated features, until they are reported to the application.
+    pub server_warnings: Arc<Mutex<ServerWarnings>>,
+    /// The default collation of each collection queried on the connection, by database and
+    /// collection name, so that they are only listed once. None when the collection has none.
+    pub collection_collations: Mutex<HashMap<(String, String), Option<Document>>>,
 
     /// the tokio runtime
     pub runtime: tokio::runtime::Runtime,
 }
 
+/// The features the driver depends on that older servers do not have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    /// The server version, as reported by buildInfo.
+    pub version: Option<String>,
+    /// Whether the server supports the Decimal128 type, from MongoDB 3.4.
+    pub decimal128: bool,
+    /// Whether the server supports the $densify stage, from MongoDB 5.1.
+    pub densify: bool,
+    /// Whether the server supports the $fill stage, from MongoDB 5.3.
+    pub fill: bool,
+}
+
+// Every feature is assumed to be available when the server version is unknown.
+impl Default for ServerCapabilities {
+    fn default() -> Self {
+        ServerCapabilities {
+            version: None,
+            decimal128: true,
+            densify: true,
+            fill: true,
+        }
+    }
+}
+
+impl ServerCapabilities {
+    /// Returns the capabilities of a server of the given version, and its major, minor, and patch
+    /// numbers as in the versionArray of buildInfo.
+    pub fn from_version(version: &str, version_array: &[i32]) -> Self {
+        let at_least = |major: i32, minor: i32| {
+            let (server_major, server_minor) = (
+                version_array.first().copied().unwrap_or_default(),
+                version_array.get(1).copied().unwrap_or_default(),
+            );
+            (server_major, server_minor) >= (major, minor)
+        };
+        ServerCapabilities {
+            version: Some(version.to_string()),
+            decimal128: at_least(3, 4),
+            densify: at_least(5, 1),
+            fill: at_least(5, 3),
+        }
+    }
+
+    /// Returns a description of each feature the server does not support, and of how the driver
+    /// does without it.
+    pub fn unsupported_features(&self) -> Vec<String> {
+        let version = self.version.as_deref().unwrap_or("unknown");
+        let mut unsupported = vec![];
+        if !self.decimal128 {
+            unsupported.push(format!(
+                "server version {version} does not support Decimal128, the decimal type is not reported by SQLGetTypeInfo"
+            ));
+        }
+        unsupported
+    }
+}
+
+// server_selection_timeout returns the server selection timeout used to connect, the smaller of
+// the serverSelectionTimeoutMS option and the login timeout, so that connecting to an unreachable
+// server does not outlast SQL_ATTR_LOGIN_TIMEOUT. A login timeout of 0 means no timeout.
+fn server_selection_timeout(
+    server_selection_timeout: Option<Duration>,
+    login_timeout: Option<u32>,
+) -> Option<Duration> {
+    let login_timeout = login_timeout
+        .filter(|&to| to > 0)
+        .map(|to| Duration::from_secs(u64::from(to)));
+    match (server_selection_timeout, login_timeout) {
+        (Some(server_selection_timeout), Some(login_timeout)) => {
+            Some(server_selection_timeout.min(login_timeout))
+        }
+        (server_selection_timeout, login_timeout) => server_selection_timeout.or(login_timeout),
+    }
+}
+
 impl MongoConnection {
     /// Creates a new MongoConnection with the given settings and runs a command to make
     /// sure that the MongoConnection is valid.
@@ -51,16 +168,39 @@ impl MongoConnection {
         });
         user_options.client_options.connect_timeout =
             login_timeout.map(|to| Duration::new(u64::from(to), 0));
+        user_options.client_options.server_selection_timeout = server_selection_timeout(
+            user_options.client_options.server_selection_timeout,
+            login_timeout,
+        );
+        let retry_reads = user_options.client_options.retry_reads.unwrap_or(true);
+        #[cfg(feature = "test_hooks")]
+        let get_more_count = Arc::new(AtomicU64::new(0));
+        let server_warnings = Arc::new(Mutex::new(ServerWarnings::default()));
+        user_options.client_options.command_event_handler = Some(Arc::new(CommandMonitor {
+            #[cfg(feature = "test_hooks")]
+            get_more_count: get_more_count.clone(),
+            server_warnings: server_warnings.clone(),
+        }));
         let guard = runtime.enter();
         let client = runtime.block_on(async {
             Client::with_options(user_options.client_options).map_err(Error::InvalidClientOptions)
         })?;
         drop(guard);
         let uuid_repr = user_options.uuid_representation;
-        let connection = MongoConnection {
+        let mut connection = MongoConnection {
             client,
             operation_timeout: operation_timeout.map(|to| Duration::new(u64::from(to), 0)),
             uuid_repr,
+            retry_reads,
+            result_cache: None,
+            collation: None,
+            force_aggregate: true,
+            capabilities: ServerCapabilities::default(),
+            batch_size: None,
+            #[cfg(feature = "test_hooks")]
+            get_more_count,
+            server_warnings,
+            collection_collations: Mutex::new(HashMap::new()),
             runtime,
         };
         // Verify that the connection is working and the user has access to the default DB
@@ -72,10 +212,63 @@ impl MongoConnection {
             "select 1",
             type_mode,
             max_string_length,
+            false,
         )?;
+        connection.capabilities = connection.server_capabilities();
         Ok(connection)
     }
 
+    // server_capabilities returns the capabilities of the server version. The capabilities are
+    // only a hint, so the defaults are returned if the version cannot be read.
+    fn server_capabilities(&self) -> ServerCapabilities {
+        let _guard = self.runtime.enter();
+        let build_info = self.runtime.block_on(async {
+            self.client
+                .database("admin")
+                .run_command(doc! {"buildInfo": 1}, None)
+                .await
+        });
+        let version = build_info.as_ref().ok().and_then(|build_info| {
+            let version = build_info.get_str("version").ok()?;
+            let version_array = build_info
+                .get_array("versionArray")
+                .ok()?
+                .iter()
+                .filter_map(Bson::as_i32)
+                .collect::<Vec<_>>();
+            Some((version.to_string(), version_array))
+        });
+        match version {
+            Some((version, version_array)) => {
+                ServerCapabilities::from_version(&version, &version_array)
+            }
+            None => {
+                log::warn!("Failed to read the server version: {build_info:?}");
+                ServerCapabilities::default()
+            }
+        }
+    }
+
+    /// Returns the number of getMore commands run on the connection.
+    #[cfg(feature = "test_hooks")]
+    pub fn get_more_count(&self) -> u64 {
+        self.get_more_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the warnings the server returned to the commands of the statement since they were
+    /// last taken, in the order the commands completed. The statement id is the one in the
+    /// comment of its commands, and None takes the warnings of the commands run outside of a
+    /// statement execution, like connecting or getting the schema of a prepared query.
+    pub fn take_server_warnings(&self, statement_id: Option<&Bson>) -> Vec<String> {
+        self.server_warnings.lock().unwrap().take(statement_id)
+    }
+
+    /// Discards the warnings the server returned to the commands of the statement since they
+    /// were last taken.
+    pub fn clear_server_warnings(&self, statement_id: Option<&Bson>) {
+        self.server_warnings.lock().unwrap().take(statement_id);
+    }
+
     pub fn shutdown(self) -> Result<()> {
         self.runtime
             .block_on(async { self.client.shutdown().await });
@@ -83,6 +276,23 @@ impl MongoConnection {
         Ok(())
     }
 
+    /// Checks whether the given database exists and the user is authorized to access it.
+    pub fn database_exists(&self, database: &str) -> Result<bool> {
+        let _guard = self.runtime.enter();
+        let database_names = self.runtime.block_on(async {
+            self.client
+                .list_database_names(
+                    None,
+                    ListDatabasesOptions::builder()
+                        .authorized_databases(true)
+                        .build(),
+                )
+                .await
+                .map_err(Error::DatabaseListing)
+        })?;
+        Ok(database_names.iter().any(|name| name == database))
+    }
+
     /// Gets the ADF version the client is connected to.
     pub fn get_adf_version(&self) -> Result<String> {
         let _guard = self.runtime.enter();
@@ -103,10 +313,14 @@ impl MongoConnection {
         let _guard = self.runtime.enter();
         // because there are so many awaits in this function, the bulk of the function is wrapped in a block_on
         self.runtime.block_on(async {
-            // use $currentOp and match the comment field to identify any queries issued by the current statement
+            // use $currentOp and match the comment field to identify any queries issued by the current statement.
+            // When the application set a comment, the statement id is embedded in the comment document.
             let current_ops_pipeline = vec![
                 doc! {"$currentOp": {}},
-                doc! {"$match": {"command.comment": statement_id}},
+                doc! {"$match": {"$or": [
+                    {"command.comment": &statement_id},
+                    {"command.comment.statementId": &statement_id},
+                ]}},
             ];
             let admin_db = self.client.database("admin");
             let mut cursor = admin_db
@@ -132,6 +346,107 @@ impl MongoConnection {
     }
 }
 
+// ServerWarnings holds the warnings the server returned to the commands of a client, by the
+// statement id in the comment of the commands (see query::command_comment), so that each statement
+// only reports the warnings of its own commands.
+#[derive(Debug, Default)]
+pub struct ServerWarnings {
+    // The statement id of each command in flight, by request id.
+    in_flight: HashMap<i32, Option<Bson>>,
+    // The warnings and the statement id of the command they were returned to, in the order the
+    // commands completed.
+    warnings: Vec<(Option<Bson>, String)>,
+}
+
+impl ServerWarnings {
+    // push records a warning returned to a command of the statement.
+    pub fn push(&mut self, statement_id: Option<Bson>, warning: String) {
+        self.warnings.push((statement_id, warning));
+    }
+
+    // take removes and returns the warnings returned to the commands of the statement.
+    fn take(&mut self, statement_id: Option<&Bson>) -> Vec<String> {
+        let (taken, kept) = std::mem::take(&mut self.warnings)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(id, _)| id.as_ref() == statement_id);
+        self.warnings = kept;
+        taken.into_iter().map(|(_, warning)| warning).collect()
+    }
+}
+
+// CommandMonitor collects the warnings the server returns in the replies to the commands of a
+// client, and counts its getMore commands with the test_hooks feature. It is installed on every
+// client since any command may return warnings. With a command event handler, the mongo driver
+// builds an event holding a copy of each command and of each reply, and the monitor takes a lock
+// to track the statement id of each command in flight. Only the replies with warnings are read
+// further, and this stays small next to the round trip of each command.
+struct CommandMonitor {
+    #[cfg(feature = "test_hooks")]
+    get_more_count: Arc<AtomicU64>,
+    server_warnings: Arc<Mutex<ServerWarnings>>,
+}
+
+impl CommandEventHandler for CommandMonitor {
+    fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        #[cfg(feature = "test_hooks")]
+        if event.command_name == "getMore" {
+            self.get_more_count.fetch_add(1, Ordering::Relaxed);
+        }
+        self.server_warnings
+            .lock()
+            .unwrap()
+            .in_flight
+            .insert(event.request_id, command_statement_id(&event.command));
+    }
+
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        let mut server_warnings = self.server_warnings.lock().unwrap();
+        let statement_id = server_warnings
+            .in_flight
+            .remove(&event.request_id)
+            .flatten();
+        for warning in reply_warnings(&event.reply) {
+            log::warn!("{} returned a warning: {warning}", event.command_name);
+            server_warnings.push(statement_id.clone(), warning);
+        }
+    }
+
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        self.server_warnings
+            .lock()
+            .unwrap()
+            .in_flight
+            .remove(&event.request_id);
+    }
+}
+
+// command_statement_id returns the statement id of a command, its comment or the statementId of
+// its comment when the application set a comment too. Commands run outside of a statement
+// execution have no comment.
+fn command_statement_id(command: &Document) -> Option<Bson> {
+    match command.get("comment")? {
+        Bson::Document(comment) => comment.get("statementId").cloned(),
+        comment => Some(comment.clone()),
+    }
+}
+
+// reply_warnings returns the warnings of a command reply, the messages of its warnings array.
+// A warning that is not a string is returned as its extended JSON.
+fn reply_warnings(reply: &Document) -> Vec<String> {
+    reply
+        .get_array("warnings")
+        .map(|warnings| {
+            warnings
+                .iter()
+                .map(|warning| match warning {
+                    Bson::String(message) => message.clone(),
+                    other => other.clone().into_relaxed_extjson().to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 // Struct representing the response for a buildInfo command.
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 struct BuildInfoResult {
@@ -151,3 +466,96 @@ struct DataLakeBuildInfo {
     pub git_version: String,
     pub date: String,
 }
+
+#[cfg(test)]
+mod unit {
+    use super::{
+        command_statement_id, reply_warnings, server_selection_timeout, ServerCapabilities,
+        ServerWarnings,
+    };
+    use bson::{doc, Bson};
+    use std::time::Duration;
+
+    #[test]
+    fn warnings_are_read_from_the_reply() {
+        assert_eq!(
+            vec![
+                "$foo is deprecated".to_string(),
+                r#"{"code":1,"msg":"bar"}"#.to_string()
+            ],
+            reply_warnings(&doc! {
+                "ok": 1,
+                "warnings": ["$foo is deprecated", {"code": 1, "msg": "bar"}],
+            })
+        );
+        assert!(reply_warnings(&doc! {"ok": 1, "cursor": {"id": 0_i64}}).is_empty());
+        assert!(reply_warnings(&doc! {"ok": 1, "warnings": "not an array"}).is_empty());
+    }
+
+    #[test]
+    fn warnings_are_kept_by_statement_id() {
+        let statement_id = Bson::String("stmt".to_string());
+        assert_eq!(
+            Some(statement_id.clone()),
+            command_statement_id(&doc! {"aggregate": 1, "comment": "stmt"})
+        );
+        assert_eq!(
+            Some(statement_id.clone()),
+            command_statement_id(
+                &doc! {"getMore": 1_i64, "comment": {"statementId": "stmt", "comment": "app"}}
+            )
+        );
+        assert_eq!(None, command_statement_id(&doc! {"sqlGetResultSchema": 1}));
+
+        let mut warnings = ServerWarnings::default();
+        warnings.push(Some(statement_id.clone()), "$foo is deprecated".to_string());
+        warnings.push(None, "$bar is deprecated".to_string());
+        warnings.push(Some(statement_id.clone()), "$baz is deprecated".to_string());
+        assert_eq!(
+            vec!["$foo is deprecated", "$baz is deprecated"],
+            warnings.take(Some(&statement_id))
+        );
+        assert!(warnings.take(Some(&statement_id)).is_empty());
+        assert_eq!(vec!["$bar is deprecated"], warnings.take(None));
+    }
+
+    #[test]
+    fn decimal128_requires_server_3_4() {
+        let capabilities = ServerCapabilities::from_version("3.2.22", &[3, 2, 22, 0]);
+        assert!(!capabilities.decimal128);
+        assert_eq!(
+            vec!["server version 3.2.22 does not support Decimal128, the decimal type is not reported by SQLGetTypeInfo"],
+            capabilities.unsupported_features()
+        );
+        for (version, version_array) in [("3.4.0", [3, 4, 0, 0]), ("4.0.1", [4, 0, 1, 0])] {
+            let capabilities = ServerCapabilities::from_version(version, &version_array);
+            assert!(capabilities.decimal128);
+            assert!(capabilities.unsupported_features().is_empty());
+        }
+    }
+
+    #[test]
+    fn server_selection_timeout_is_bounded_by_login_timeout() {
+        let ms = Duration::from_millis;
+        assert_eq!(
+            Some(ms(5000)),
+            server_selection_timeout(Some(ms(5000)), Some(10))
+        );
+        assert_eq!(
+            Some(ms(2000)),
+            server_selection_timeout(Some(ms(5000)), Some(2))
+        );
+        assert_eq!(
+            Some(ms(5000)),
+            server_selection_timeout(Some(ms(5000)), None)
+        );
+        assert_eq!(Some(ms(3000)), server_selection_timeout(None, Some(3)));
+        // A login timeout of 0 disables the timeout.
+        assert_eq!(
+            Some(ms(5000)),
+            server_selection_timeout(Some(ms(5000)), Some(0))
+        );
+        assert_eq!(None, server_selection_timeout(None, Some(0)));
+        assert_eq!(None, server_selection_timeout(None, None));
+    }
+}
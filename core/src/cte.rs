@@ -0,0 +1,211 @@
+use crate::{
+    err::{Error, Result},
+    lexer::{name_path, tokenize, Token, TokenKind},
+};
+
+// The keywords that may follow a table reference, which are not the alias of the table.
+const CLAUSE_KEYWORDS: [&str; 15] = [
+    "where", "group", "having", "order", "limit", "offset", "union", "join", "inner", "left",
+    "right", "cross", "natural", "on", "full",
+];
+
+// inline_ctes rewrites a query starting with a WITH clause by replacing each table reference to
+// one of its common table expressions with the query of the expression as a derived table, since
+// MongoSQL has no WITH clause. An expression may refer to the expressions defined before it.
+// Recursive expressions, and expressions naming their columns, are not supported. Queries without
+// a WITH clause are returned unchanged.
+pub fn inline_ctes(query: &str) -> Result<String> {
+    let tokens = tokenize(query);
+    if !tokens.first().is_some_and(|t| t.is_word("with")) {
+        return Ok(query.to_string());
+    }
+    let unsupported = |reason: &str| Err(Error::UnsupportedCte(reason.to_string()));
+    if tokens.get(1).is_some_and(|t| t.is_word("recursive")) {
+        return unsupported("recursive common table expressions are not supported");
+    }
+
+    // The name and the inlined query of each expression.
+    let mut ctes: Vec<(String, String)> = vec![];
+    let mut next = 1;
+    loop {
+        let Some(name) = tokens
+            .get(next)
+            .and_then(|t| name_path(std::slice::from_ref(t)))
+        else {
+            return unsupported("expected the name of a common table expression after WITH");
+        };
+        if tokens.get(next + 1).is_some_and(|t| t.is_punct("(")) {
+            return unsupported("the columns of a common table expression cannot be listed");
+        }
+        let open = next + 2;
+        if !tokens.get(next + 1).is_some_and(|t| t.is_word("as"))
+            || !tokens.get(open).is_some_and(|t| t.is_punct("("))
+        {
+            return unsupported("expected WITH name AS (query)");
+        }
+        let Some(close) =
+            (open + 1..tokens.len()).find(|&i| tokens[i].depth == 0 && tokens[i].is_punct(")"))
+        else {
+            return unsupported("the query of a common table expression is not closed");
+        };
+        let body = &query[tokens[open].end..tokens[close].start];
+        ctes.push((name[0].clone(), replace_references(body, &ctes)));
+        next = close + 1;
+        if !tokens.get(next).is_some_and(|t| t.is_punct(",")) {
+            break;
+        }
+        next += 1;
+    }
+    let Some(main) = tokens.get(next) else {
+        return unsupported("a WITH clause must be followed by a query");
+    };
+    Ok(replace_references(&query[main.start..], &ctes))
+}
+
+// replace_references replaces the table references of a query to the given common table
+// expressions by their queries.
+fn replace_references(query: &str, ctes: &[(String, String)]) -> String {
+    let tokens = tokenize(query);
+    let mut inlined = String::with_capacity(query.len());
+    let mut copied = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if !matches!(token.kind, TokenKind::Word | TokenKind::Identifier)
+            || !is_table_reference(&tokens, i)
+        {
+            continue;
+        }
+        let Some((_, cte)) = name_path(std::slice::from_ref(token))
+            .and_then(|path| ctes.iter().find(|(name, _)| *name == path[0]))
+        else {
+            continue;
+        };
+        inlined.push_str(&query[copied..token.start]);
+        inlined.push_str(&format!("({cte})"));
+        // A reference without an alias is named after the expression.
+        if !has_alias(&tokens, i) {
+            inlined.push_str(&format!(" AS {}", &query[token.start..token.end]));
+        }
+        copied = token.end;
+    }
+    inlined.push_str(&query[copied..]);
+    inlined
+}
+
+// is_table_reference returns whether the name at index i is an unqualified table of a FROM clause:
+// it follows FROM, JOIN, or a comma of the FROM clause, and is not followed by a dot.
+fn is_table_reference(tokens: &[Token], i: usize) -> bool {
+    if i == 0 || tokens.get(i + 1).is_some_and(|t| t.is_punct(".")) {
+        return false;
+    }
+    let previous = &tokens[i - 1];
+    if previous.is_word("join") {
+        return true;
+    }
+    if !previous.is_word("from") && !previous.is_punct(",") {
+        return false;
+    }
+    // The last clause keyword of the query block must be FROM, or a JOIN of the FROM clause.
+    // FROM is also a keyword of the arguments of functions such as EXTRACT, which are not query
+    // blocks.
+    let mut block = tokens[..i]
+        .iter()
+        .rev()
+        .take_while(|t| t.depth >= previous.depth);
+    let Some(clause) = block.find(|t| {
+        t.depth == previous.depth
+            && (t.is_word("from")
+                || t.is_word("select")
+                || CLAUSE_KEYWORDS.iter().any(|k| t.is_word(k)))
+    }) else {
+        return false;
+    };
+    ["from", "join", "on"].iter().any(|k| clause.is_word(k))
+        && block.any(|t| t.depth == previous.depth && t.is_word("select"))
+}
+
+// has_alias returns whether the table reference at index i is followed by an alias.
+fn has_alias(tokens: &[Token], i: usize) -> bool {
+    tokens.get(i + 1).is_some_and(|t| {
+        t.is_word("as")
+            || t.kind == TokenKind::Identifier
+            || t.kind == TokenKind::Word && !CLAUSE_KEYWORDS.iter().any(|k| t.is_word(k))
+    })
+}
+
+#[cfg(test)]
+mod unit {
+    use super::inline_ctes;
+
+    #[test]
+    fn query_without_with_is_unchanged() {
+        for query in [
+            "SELECT * FROM foo",
+            "SELECT with FROM foo",
+            "SELECT * FROM (SELECT a FROM foo) AS t",
+        ] {
+            assert_eq!(query, inline_ctes(query).unwrap());
+        }
+    }
+
+    #[test]
+    fn references_are_replaced_by_the_query_of_the_expression() {
+        assert_eq!(
+            "SELECT t.a FROM (SELECT a FROM foo WHERE a > 1) AS t WHERE t.a < 10",
+            inline_ctes(
+                "WITH t AS (SELECT a FROM foo WHERE a > 1) SELECT t.a FROM t WHERE t.a < 10"
+            )
+            .unwrap()
+        );
+        // Aliased references keep their alias, and names that are not table references are
+        // left as is.
+        assert_eq!(
+            "SELECT x.a, u.b FROM (SELECT a FROM foo) AS x JOIN (SELECT b FROM bar) u ON x.a = u.b, \
+             db.t WHERE a IN (SELECT a FROM (SELECT a FROM foo) AS t)",
+            inline_ctes(
+                "with t as (SELECT a FROM foo), `u` AS (SELECT b FROM bar) \
+                 SELECT x.a, u.b FROM t AS x JOIN u u ON x.a = u.b, db.t \
+                 WHERE a IN (SELECT a FROM t)"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn names_that_are_not_tables_are_left_as_is() {
+        assert_eq!(
+            "SELECT EXTRACT(YEAR FROM t), a, t FROM foo JOIN (SELECT a FROM bar) AS t ON a = t, \
+             (SELECT a FROM bar) AS t",
+            inline_ctes(
+                "WITH t AS (SELECT a FROM bar) \
+                 SELECT EXTRACT(YEAR FROM t), a, t FROM foo JOIN t ON a = t, t"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn expressions_may_refer_to_the_previous_ones() {
+        assert_eq!(
+            "SELECT * FROM (SELECT a FROM (SELECT a FROM foo) AS t WHERE a > 1) AS u, \
+             (SELECT a FROM foo) AS t",
+            inline_ctes(
+                "WITH t AS (SELECT a FROM foo), u AS (SELECT a FROM t WHERE a > 1) \
+                 SELECT * FROM u, t"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn unsupported_expressions_are_errors() {
+        for query in [
+            "WITH RECURSIVE t AS (SELECT 1 AS n UNION ALL SELECT n + 1 FROM t) SELECT * FROM t",
+            "WITH t(a) AS (SELECT a FROM foo) SELECT * FROM t",
+            "WITH t AS SELECT a FROM foo",
+            "WITH t AS (SELECT a FROM foo",
+            "WITH t AS (SELECT a FROM foo)",
+        ] {
+            assert!(inline_ctes(query).is_err(), "{query} should be an error");
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use crate::err::{Error, Result};
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+// The format of the timestamps the literals are translated to. BSON dates have a millisecond
+// precision and are always UTC.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+// translate_datetime_literals rewrites the datetime literals of a query into MongoSQL, which only
+// builds timestamps with CAST. Both the ODBC escape clauses `{d 'YYYY-MM-DD'}`,
+// `{t 'HH:MM:SS'}` and `{ts 'YYYY-MM-DD HH:MM:SS[.fff]'}` and the SQL-92 literals
+// `DATE '...'`, `TIME '...'` and `TIMESTAMP '...'` are translated to
+// `CAST('YYYY-MM-DDTHH:MM:SS.fffZ' AS TIMESTAMP)`. Literals are local times of the session time
+// zone, UTC when it is not set, so that a datetime filters on the value it is returned as. A date
+// is midnight, a time is on the current date, as when a time is converted to a timestamp. String
+// literals, quoted identifiers and comments are copied as is, as are the other escape clauses.
+pub fn translate_datetime_literals(query: &str, time_zone: Option<FixedOffset>) -> Result<String> {
+    let offset = time_zone.unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let mut translated = String::with_capacity(query.len());
+    let mut chars = query.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                let mut end = query.len();
+                // a doubled quote is an escaped quote and does not end the literal
+                while let Some((i, q)) = chars.next() {
+                    if q == c && chars.next_if(|&(_, n)| n == c).is_none() {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                translated.push_str(&query[start..end]);
+            }
+            '-' if chars.next_if(|&(_, n)| n == '-').is_some() => {
+                let end = chars
+                    .by_ref()
+                    .find(|&(_, n)| n == '\n')
+                    .map_or(query.len(), |(i, _)| i + 1);
+                translated.push_str(&query[start..end]);
+            }
+            '/' if chars.next_if(|&(_, n)| n == '*').is_some() => {
+                let mut end = query.len();
+                let mut previous = ' ';
+                for (i, n) in chars.by_ref() {
+                    if previous == '*' && n == '/' {
+                        end = i + 1;
+                        break;
+                    }
+                    previous = n;
+                }
+                translated.push_str(&query[start..end]);
+            }
+            '{' => match escape_clause(&query[start + 1..]) {
+                Some((kind, value, len)) => {
+                    translated.push_str(&timestamp_cast(kind, value, offset)?);
+                    // skip the rest of the escape clause, up to and including the closing brace
+                    while chars.next_if(|&(i, _)| i <= start + len).is_some() {}
+                }
+                None => translated.push(c),
+            },
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, n)) = chars.next_if(|&(_, n)| n.is_alphanumeric() || n == '_') {
+                    end = i + n.len_utf8();
+                }
+                let word = &query[start..end];
+                match sql92_literal(word, &query[end..]) {
+                    Some((kind, value, len)) => {
+                        translated.push_str(&timestamp_cast(kind, value, offset)?);
+                        while chars.next_if(|&(i, _)| i < end + len).is_some() {}
+                    }
+                    None => translated.push_str(word),
+                }
+            }
+            _ => translated.push(c),
+        }
+    }
+    Ok(translated)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LiteralKind {
+    Date,
+    Time,
+    Timestamp,
+}
+
+// escape_clause parses a datetime escape clause, without its opening brace, and returns its kind,
+// its value and the length of the clause up to and including the closing brace. Other escape
+// clauses return None.
+fn escape_clause(s: &str) -> Option<(LiteralKind, &str, usize)> {
+    let rest = s.trim_start();
+    let keyword_len = rest.find(|c: char| !c.is_ascii_alphabetic())?;
+    let kind = match rest[..keyword_len].to_ascii_lowercase().as_str() {
+        "d" => LiteralKind::Date,
+        "t" => LiteralKind::Time,
+        "ts" => LiteralKind::Timestamp,
+        _ => return None,
+    };
+    let (value, value_len) = quoted_value(&rest[keyword_len..])?;
+    let after_value = &rest[keyword_len + value_len..];
+    let closing = after_value.trim_start();
+    closing
+        .starts_with('}')
+        .then(|| (kind, value, s.len() - closing.len() + 1))
+}
+
+// sql92_literal parses the value of a `DATE '...'`, `TIME '...'` or `TIMESTAMP '...'` literal
+// following word, and returns its kind, its value and the length of the value. Any other word
+// returns None.
+fn sql92_literal<'a>(word: &str, rest: &'a str) -> Option<(LiteralKind, &'a str, usize)> {
+    let kind = match word.to_ascii_lowercase().as_str() {
+        "date" => LiteralKind::Date,
+        "time" => LiteralKind::Time,
+        "timestamp" => LiteralKind::Timestamp,
+        _ => return None,
+    };
+    let (value, len) = quoted_value(rest)?;
+    Some((kind, value, len))
+}
+
+// quoted_value returns the single quoted value at the start of s, after any whitespace, and the
+// length up to and including the closing quote.
+fn quoted_value(s: &str) -> Option<(&str, usize)> {
+    let trimmed = s.trim_start();
+    let value = trimmed.strip_prefix('\'')?;
+    let end = value.find('\'')?;
+    Some((&value[..end], s.len() - trimmed.len() + end + 2))
+}
+
+fn timestamp_cast(kind: LiteralKind, value: &str, offset: FixedOffset) -> Result<String> {
+    let invalid = || Error::InvalidDatetimeLiteral(value.to_string());
+    let timestamp = match kind {
+        LiteralKind::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| invalid())?
+            .and_time(NaiveTime::MIN),
+        LiteralKind::Time => Utc::now()
+            .with_timezone(&offset)
+            .date_naive()
+            .and_time(NaiveTime::parse_from_str(value, "%H:%M:%S%.f").map_err(|_| invalid())?),
+        LiteralKind::Timestamp => {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").map_err(|_| invalid())?
+        }
+    };
+    let utc = timestamp - Duration::seconds(i64::from(offset.local_minus_utc()));
+    Ok(format!(
+        "CAST('{}' AS TIMESTAMP)",
+        utc.format(TIMESTAMP_FORMAT)
+    ))
+}
+
+#[cfg(test)]
+mod unit {
+    use super::translate_datetime_literals;
+    use chrono::{FixedOffset, Utc};
+
+    #[test]
+    fn date_escape_is_midnight_utc() {
+        assert_eq!(
+            "SELECT * FROM foo WHERE d = CAST('2020-01-31T00:00:00.000Z' AS TIMESTAMP)",
+            translate_datetime_literals("SELECT * FROM foo WHERE d = {d '2020-01-31'}", None)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn time_escape_is_on_the_current_date() {
+        let today = || Utc::now().date_naive().format("%Y-%m-%d").to_string();
+        let before = today();
+        let translated =
+            translate_datetime_literals("SELECT * FROM foo WHERE t < {t '13:14:15'}", None)
+                .unwrap();
+        // The date may change while the query is translated.
+        assert!([before, today()].iter().any(|date| translated
+            == format!("SELECT * FROM foo WHERE t < CAST('{date}T13:14:15.000Z' AS TIMESTAMP)")));
+    }
+
+    #[test]
+    fn timestamp_escape_keeps_milliseconds() {
+        assert_eq!(
+            "SELECT * FROM foo WHERE ts >= CAST('2020-01-31T13:14:15.000Z' AS TIMESTAMP) \
+             AND ts < CAST('2020-01-31T13:14:15.678Z' AS TIMESTAMP)",
+            translate_datetime_literals(
+                "SELECT * FROM foo WHERE ts >= {ts '2020-01-31 13:14:15'} \
+                 AND ts < { TS '2020-01-31 13:14:15.678' }",
+                None
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn sql92_literals_are_translated() {
+        assert_eq!(
+            "SELECT CAST('2020-01-31T00:00:00.000Z' AS TIMESTAMP), \
+             CAST('2020-01-31T13:14:15.100Z' AS TIMESTAMP) FROM foo",
+            translate_datetime_literals(
+                "SELECT DATE '2020-01-31', timestamp '2020-01-31 13:14:15.1' FROM foo",
+                None
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn literals_are_local_times_of_the_session_time_zone() {
+        let eastern = FixedOffset::west_opt(5 * 3600);
+        assert_eq!(
+            "SELECT * FROM foo WHERE d = CAST('2020-01-31T05:00:00.000Z' AS TIMESTAMP) \
+             AND ts < CAST('2020-02-01T02:14:15.000Z' AS TIMESTAMP)",
+            translate_datetime_literals(
+                "SELECT * FROM foo WHERE d = {d '2020-01-31'} AND ts < {ts '2020-01-31 21:14:15'}",
+                eastern
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn other_text_is_unchanged() {
+        for query in [
+            "SELECT {fn CURRENT_TIMESTAMP()} FROM foo",
+            "SELECT '{d ''2020-01-31''}' FROM foo",
+            "SELECT `{d '2020-01-31'}` FROM foo",
+            "SELECT a FROM foo -- {d '2020-01-31'}",
+            "SELECT a /* {d '2020-01-31'} */ FROM foo",
+            "SELECT CAST(a AS TIMESTAMP), date FROM foo WHERE b = 'DATE'",
+            "SELECT mydate, 'é' FROM foo WHERE {",
+        ] {
+            assert_eq!(query, translate_datetime_literals(query, None).unwrap());
+        }
+    }
+
+    #[test]
+    fn invalid_literal_is_an_error() {
+        for query in [
+            "SELECT * FROM foo WHERE d = {d '2020-02-30'}",
+            "SELECT * FROM foo WHERE t = {t '25:00:00'}",
+            "SELECT * FROM foo WHERE ts = {ts '2020-01-31'}",
+            "SELECT * FROM foo WHERE ts = TIMESTAMP 'now'",
+        ] {
+            assert!(
+                translate_datetime_literals(query, None).is_err(),
+                "{query} should be invalid"
+            );
+        }
+    }
+}
@@ -0,0 +1,625 @@
+use crate::{
+    col_metadata::MongoColMetadata,
+    conn::ServerCapabilities,
+    err::{Error, Result},
+    lexer::{name_path, tokenize, Token, TokenKind},
+};
+use bson::{doc, Bson, Document};
+
+// The interval units of DENSIFY, which are also the units of $densify.
+const UNITS: [&str; 9] = [
+    "millisecond",
+    "second",
+    "minute",
+    "hour",
+    "day",
+    "week",
+    "month",
+    "quarter",
+    "year",
+];
+const FILL_METHODS: [&str; 2] = ["linear", "locf"];
+
+// The fields of the documents the gaps are filled in: the row of the result set, and the values of
+// the densified and filled columns read from it.
+const ROW: &str = "row";
+const KEY: &str = "key";
+const FILL_PREFIX: &str = "fill";
+const SORT_PREFIX: &str = "sort";
+
+// A DENSIFY clause, which fills the gaps of a time series. `DENSIFY(column, INTERVAL 'n' UNIT)`
+// adds a row for every missing step of the column between its first and last values, and each
+// following `FILL(column, LINEAR | LOCF)` fills the column in the rows added, and in the rows where
+// it is null, by linear interpolation or with the last value observed. The ORDER BY, LIMIT and
+// OFFSET clauses of the query apply to the rows once the gaps are filled.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Densify {
+    column: Vec<String>,
+    step: i64,
+    unit: &'static str,
+    fills: Vec<(Vec<String>, &'static str)>,
+    // The columns of the ORDER BY clause, with 1 for ascending and -1 for descending.
+    order_by: Vec<(Vec<String>, i32)>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+// split_densify splits the DENSIFY clause, and the FILL clauses following it, from the end of a
+// query, since MongoSQL has no gap filling and the driver appends the stages filling the gaps to
+// the $sql aggregation. The ORDER BY, LIMIT and OFFSET clauses of the query are split along, as
+// they apply to the rows added too. A query without a DENSIFY clause is returned as is.
+pub(crate) fn split_densify(query: &str) -> Result<(&str, Option<Densify>)> {
+    let tokens = tokenize(query);
+    let Some(start) = (0..tokens.len()).find(|&i| {
+        tokens[i].depth == 0
+            && tokens[i].is_word("densify")
+            && tokens.get(i + 1).is_some_and(|t| t.is_punct("("))
+    }) else {
+        return Ok((query, None));
+    };
+    let invalid = |reason: String| Err(Error::InvalidDensify(reason));
+
+    let (args, mut next) = call_args(&tokens, start)?;
+    let (column, step, unit) = match args.as_slice() {
+        [column, [interval, step, unit]]
+            if interval.is_word("interval") && step.kind == TokenKind::Literal =>
+        {
+            let step = step.text.trim_matches('\'').trim();
+            let Some(step) = step.parse::<i64>().ok().filter(|&step| step > 0) else {
+                return invalid(format!("the step '{step}' is not a positive integer"));
+            };
+            let Some(unit) = UNITS
+                .into_iter()
+                .find(|u| unit.text.eq_ignore_ascii_case(u))
+            else {
+                return invalid(format!("unknown interval unit {}", unit.text));
+            };
+            (column_path(column)?, step, unit)
+        }
+        _ => return invalid(
+            "expected DENSIFY(column, INTERVAL 'n' UNIT), such as DENSIFY(ts, INTERVAL '1' HOUR)"
+                .to_string(),
+        ),
+    };
+
+    let mut fills = vec![];
+    while let Some(token) = tokens.get(next) {
+        if !token.is_word("fill") || !tokens.get(next + 1).is_some_and(|t| t.is_punct("(")) {
+            return invalid(format!("unexpected {} after DENSIFY", token.text));
+        }
+        let (args, after) = call_args(&tokens, next)?;
+        let method = match args.as_slice() {
+            [_, [method]] => FILL_METHODS.into_iter().find(|m| method.is_word(m)),
+            _ => None,
+        };
+        let Some(method) = method else {
+            return invalid("expected FILL(column, LINEAR | LOCF)".to_string());
+        };
+        fills.push((column_path(args[0])?, method));
+        next = after;
+    }
+
+    let (end, order_by, limit, offset) = split_order_by(&tokens[..start])?;
+    Ok((
+        query[..tokens.get(end).map_or(query.len(), |t| t.start)].trim_end(),
+        Some(Densify {
+            column,
+            step,
+            unit,
+            fills,
+            order_by,
+            limit,
+            offset,
+        }),
+    ))
+}
+
+type OrderBy = Vec<(Vec<String>, i32)>;
+
+// split_order_by returns the index of the first token of the ORDER BY, LIMIT and OFFSET clauses
+// ending the query, along with the columns of the ORDER BY clause and the LIMIT and OFFSET.
+fn split_order_by(tokens: &[Token]) -> Result<(usize, OrderBy, Option<i64>, Option<i64>)> {
+    let invalid = |reason: String| Err(Error::InvalidDensify(reason));
+    let Some(start) = (0..tokens.len()).find(|&i| {
+        tokens[i].depth == 0
+            && ((tokens[i].is_word("order") && tokens.get(i + 1).is_some_and(|t| t.is_word("by")))
+                || tokens[i].is_word("limit")
+                || tokens[i].is_word("offset"))
+    }) else {
+        return Ok((tokens.len(), vec![], None, None));
+    };
+    let mut next = start;
+    let mut order_by = vec![];
+    if tokens[next].is_word("order") {
+        next += 2;
+        loop {
+            let key_end = (next..tokens.len())
+                .find(|&i| {
+                    tokens[i].depth == 0
+                        && (tokens[i].is_punct(",")
+                            || ["asc", "desc", "limit", "offset"]
+                                .into_iter()
+                                .any(|word| tokens[i].is_word(word)))
+                })
+                .unwrap_or(tokens.len());
+            let column = name_path(&tokens[next..key_end])
+                .filter(|path| path.len() <= 2)
+                .ok_or_else(|| {
+                    let text = tokens[next..key_end]
+                        .iter()
+                        .map(|t| t.text)
+                        .collect::<Vec<_>>()
+                        .join("");
+                    Error::InvalidDensify(format!(
+                        "ORDER BY {text} is not a column, which DENSIFY requires"
+                    ))
+                })?;
+            next = key_end;
+            let direction = match tokens.get(next) {
+                Some(t) if t.is_word("desc") => -1,
+                _ => 1,
+            };
+            if tokens
+                .get(next)
+                .is_some_and(|t| t.is_word("asc") || t.is_word("desc"))
+            {
+                next += 1;
+            }
+            order_by.push((column, direction));
+            if !tokens.get(next).is_some_and(|t| t.is_punct(",")) {
+                break;
+            }
+            next += 1;
+        }
+    }
+    let count = |clause: &str, next: &mut usize| -> Result<Option<i64>> {
+        if !tokens.get(*next).is_some_and(|t| t.is_word(clause)) {
+            return Ok(None);
+        }
+        let count = tokens
+            .get(*next + 1)
+            .and_then(|t| t.text.parse::<i64>().ok());
+        let Some(count) = count.filter(|&count| count >= 0) else {
+            return Err(Error::InvalidDensify(format!(
+                "expected a number of rows after {}",
+                clause.to_uppercase()
+            )));
+        };
+        *next += 2;
+        Ok(Some(count))
+    };
+    let limit = count("limit", &mut next)?;
+    let offset = count("offset", &mut next)?;
+    if let Some(token) = tokens.get(next) {
+        return invalid(format!("unexpected {} before DENSIFY", token.text));
+    }
+    Ok((start, order_by, limit, offset))
+}
+
+// call_args returns the arguments of the call whose name is the token at start, and the index of
+// the token following the call.
+fn call_args<'a, 'b>(
+    tokens: &'b [Token<'a>],
+    start: usize,
+) -> Result<(Vec<&'b [Token<'a>]>, usize)> {
+    let open = &tokens[start + 1];
+    let Some(close) = (start + 2..tokens.len())
+        .find(|&i| tokens[i].depth == open.depth && tokens[i].is_punct(")"))
+    else {
+        return Err(Error::InvalidDensify(format!(
+            "missing closing parenthesis of {}",
+            tokens[start].text
+        )));
+    };
+    let args = tokens[start + 2..close]
+        .split(|t| t.depth == open.depth + 1 && t.is_punct(","))
+        .collect();
+    Ok((args, close + 1))
+}
+
+fn column_path(tokens: &[Token]) -> Result<Vec<String>> {
+    name_path(tokens)
+        .filter(|path| path.len() <= 2)
+        .ok_or_else(|| {
+            let text = tokens.iter().map(|t| t.text).collect::<Vec<_>>().join("");
+            Error::InvalidDensify(format!("{text} is not a column"))
+        })
+}
+
+impl Densify {
+    // check_capabilities returns an error when the server does not have the stages filling the
+    // gaps.
+    pub(crate) fn check_capabilities(&self, capabilities: &ServerCapabilities) -> Result<()> {
+        let version = || capabilities.version.clone().unwrap_or_default();
+        if !capabilities.densify {
+            return Err(Error::UnsupportedServerFeature(
+                version(),
+                "$densify, which DENSIFY requires",
+            ));
+        }
+        if !self.fills.is_empty() && !capabilities.fill {
+            return Err(Error::UnsupportedServerFeature(
+                version(),
+                "$fill, which FILL requires",
+            ));
+        }
+        Ok(())
+    }
+
+    // stages returns the stages appended to the $sql aggregation. The rows of the result set are
+    // documents of the columns of each datasource, and the datasource of the columns of the select
+    // list is the empty string, which field paths cannot name. So the values of the columns are
+    // first read with $getField next to the row, the gaps are filled and the rows sorted on them,
+    // and they are then set back in the row, which is empty in the rows added.
+    pub(crate) fn stages(&self, metadata: &[MongoColMetadata]) -> Result<Vec<Document>> {
+        let key = resolve(&self.column, metadata)?;
+        let fills = self
+            .fills
+            .iter()
+            .enumerate()
+            .map(|(i, (column, method))| {
+                Ok((
+                    resolve(column, metadata)?,
+                    format!("{FILL_PREFIX}{i}"),
+                    *method,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut values = doc! {ROW: "$$ROOT", KEY: get_field("$$ROOT", &key)};
+        for (column, field, _) in &fills {
+            values.insert(field, get_field("$$ROOT", column));
+        }
+        // The sort keys are the filled values of the densified and filled columns, and the values
+        // of the other columns, which are null in the rows added.
+        let mut sort = Document::new();
+        for (i, (column, direction)) in self.order_by.iter().enumerate() {
+            let column = resolve(column, metadata)?;
+            let filled = fills.iter().find(|(filled, _, _)| *filled == column);
+            let field = if column == key {
+                KEY.to_string()
+            } else if let Some((_, field, _)) = filled {
+                field.clone()
+            } else {
+                let field = format!("{SORT_PREFIX}{i}");
+                values.insert(&field, get_field("$$ROOT", &column));
+                field
+            };
+            sort.insert(field, *direction);
+        }
+        let mut stages = vec![
+            doc! {"$replaceWith": values},
+            doc! {"$densify": {
+                "field": KEY,
+                "range": {"step": self.step, "unit": self.unit, "bounds": "full"},
+            }},
+        ];
+        if !fills.is_empty() {
+            let output = fills
+                .iter()
+                .map(|(_, field, method)| (field.clone(), Bson::from(doc! {"method": *method})))
+                .collect::<Document>();
+            stages.push(doc! {"$fill": {"sortBy": {KEY: 1}, "output": output}});
+        }
+        if !sort.is_empty() {
+            stages.push(doc! {"$sort": sort});
+        }
+        if let Some(offset) = self.offset {
+            stages.push(doc! {"$skip": offset});
+        }
+        if let Some(limit) = self.limit {
+            stages.push(doc! {"$limit": limit});
+        }
+
+        let set_back = std::iter::once((&key, format!("${KEY}"))).chain(
+            fills
+                .iter()
+                .map(|(column, field, _)| (column, format!("${field}"))),
+        );
+        let mut datasources: Vec<(&str, Vec<(&str, String)>)> = vec![];
+        for ((datasource, column), value) in set_back {
+            match datasources.iter_mut().find(|(name, _)| name == datasource) {
+                Some((_, columns)) => columns.push((column, value)),
+                None => datasources.push((datasource, vec![(column, value)])),
+            }
+        }
+        let row = datasources.into_iter().fold(
+            Bson::from(doc! {"$ifNull": [format!("${ROW}"), {}]}),
+            |row, (datasource, columns)| {
+                let columns = columns.into_iter().fold(
+                    Bson::from(
+                        doc! {"$ifNull": [get_field_of(format!("${ROW}").into(), datasource), {}]},
+                    ),
+                    |input, (column, value)| set_field(input, column, value.into()),
+                );
+                set_field(row, datasource, columns)
+            },
+        );
+        stages.push(doc! {"$replaceWith": row});
+        Ok(stages)
+    }
+}
+
+// resolve returns the datasource and name of the column of the result set a DENSIFY or FILL
+// column refers to. An unqualified name must be the name of a single column.
+fn resolve(path: &[String], metadata: &[MongoColMetadata]) -> Result<(String, String)> {
+    let matches = metadata
+        .iter()
+        .filter(|col| match path {
+            [name] => &col.col_name == name,
+            [datasource, name] => &col.table_name == datasource && &col.col_name == name,
+            _ => false,
+        })
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [col] => Ok((col.table_name.clone(), col.col_name.clone())),
+        [] => Err(Error::InvalidDensify(format!(
+            "{} is not a column of the result set",
+            path.join(".")
+        ))),
+        _ => Err(Error::InvalidDensify(format!(
+            "{} is ambiguous, qualify it with its datasource",
+            path.join(".")
+        ))),
+    }
+}
+
+// get_field returns the expression reading a column of a row. The names are literals, so that
+// names starting with $ or containing dots are read as is.
+fn get_field(row: impl Into<Bson>, (datasource, column): &(String, String)) -> Bson {
+    let datasource = get_field_of(row.into(), datasource);
+    get_field_of(datasource, column)
+}
+
+fn get_field_of(input: Bson, field: &str) -> Bson {
+    doc! {"$getField": {"field": {"$literal": field}, "input": input}}.into()
+}
+
+fn set_field(input: Bson, field: &str, value: Bson) -> Bson {
+    doc! {"$setField": {"field": {"$literal": field}, "input": input, "value": value}}.into()
+}
+
+#[cfg(test)]
+mod unit {
+    use super::{split_densify, Densify};
+    use crate::{
+        col_metadata::MongoColMetadata,
+        conn::ServerCapabilities,
+        json_schema::{simplified::Atomic, simplified::Schema, BsonTypeName},
+        TypeMode,
+    };
+    use bson::doc;
+    use definitions::Nullability;
+
+    fn metadata(columns: &[(&str, &str)]) -> Vec<MongoColMetadata> {
+        columns
+            .iter()
+            .map(|(datasource, column)| {
+                MongoColMetadata::new(
+                    "test",
+                    datasource.to_string(),
+                    column.to_string(),
+                    Schema::Atomic(Atomic::Scalar(BsonTypeName::Int)),
+                    Nullability::SQL_NULLABLE,
+                    TypeMode::Standard,
+                    None,
+                )
+            })
+            .collect()
+    }
+
+    fn path(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn query_without_densify_is_unchanged() {
+        for query in [
+            "SELECT ts, v FROM readings ORDER BY ts",
+            "SELECT densify FROM foo",
+            "SELECT 'DENSIFY(ts, INTERVAL ''1'' HOUR)' FROM foo",
+            "SELECT * FROM (SELECT a FROM foo) AS densify",
+        ] {
+            assert_eq!((query, None), split_densify(query).unwrap());
+        }
+    }
+
+    #[test]
+    fn densify_and_fill_clauses_are_split_from_the_query() {
+        assert_eq!(
+            (
+                "SELECT ts, v FROM readings WHERE v > 0",
+                Some(Densify {
+                    column: path(&["ts"]),
+                    step: 15,
+                    unit: "minute",
+                    fills: vec![],
+                    order_by: vec![],
+                    limit: None,
+                    offset: None,
+                })
+            ),
+            split_densify(
+                "SELECT ts, v FROM readings WHERE v > 0 densify(ts, interval ' 15 ' Minute)"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            (
+                "SELECT * FROM readings AS r",
+                Some(Densify {
+                    column: path(&["r", "ts"]),
+                    step: 1,
+                    unit: "hour",
+                    fills: vec![(path(&["v"]), "linear"), (path(&["r", "w"]), "locf")],
+                    order_by: vec![],
+                    limit: None,
+                    offset: None,
+                })
+            ),
+            split_densify(
+                "SELECT * FROM readings AS r \
+                 DENSIFY(r.ts, INTERVAL '1' HOUR) FILL(v, LINEAR) FILL(`r`.w, locf)"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn order_by_limit_and_offset_apply_after_the_gaps_are_filled() {
+        let (sql, densify) = split_densify(
+            "SELECT ts, v FROM readings AS r WHERE v > 0 \
+             ORDER BY r.ts DESC, v ASC LIMIT 5 OFFSET 2 DENSIFY(ts, INTERVAL '1' HOUR)",
+        )
+        .unwrap();
+        let densify = densify.unwrap();
+        assert_eq!("SELECT ts, v FROM readings AS r WHERE v > 0", sql);
+        assert_eq!(
+            vec![(path(&["r", "ts"]), -1), (path(&["v"]), 1)],
+            densify.order_by
+        );
+        assert_eq!((Some(5), Some(2)), (densify.limit, densify.offset));
+
+        // The clauses of a subquery are its own.
+        let query = "SELECT * FROM (SELECT ts FROM r ORDER BY ts LIMIT 3) AS s";
+        let densified = format!("{query} DENSIFY(ts, INTERVAL '1' HOUR)");
+        let (sql, densify) = split_densify(&densified).unwrap();
+        assert_eq!(query, sql);
+        assert!(densify.unwrap().order_by.is_empty());
+    }
+
+    #[test]
+    fn invalid_clauses_are_errors() {
+        for query in [
+            "SELECT ts FROM foo DENSIFY(ts)",
+            "SELECT ts FROM foo DENSIFY(ts, 1)",
+            "SELECT ts FROM foo DENSIFY(ts, INTERVAL '0' HOUR)",
+            "SELECT ts FROM foo DENSIFY(ts, INTERVAL 'one' HOUR)",
+            "SELECT ts FROM foo DENSIFY(ts, INTERVAL '1' FORTNIGHT)",
+            "SELECT ts FROM foo DENSIFY(ts + 1, INTERVAL '1' HOUR)",
+            "SELECT ts FROM foo DENSIFY(ts, INTERVAL '1' HOUR",
+            "SELECT ts FROM foo DENSIFY(ts, INTERVAL '1' HOUR) LIMIT 10",
+            "SELECT ts FROM foo DENSIFY(ts, INTERVAL '1' HOUR) FILL(v, AVERAGE)",
+            "SELECT ts FROM foo ORDER BY ts + 1 DENSIFY(ts, INTERVAL '1' HOUR)",
+            "SELECT ts FROM foo LIMIT ten DENSIFY(ts, INTERVAL '1' HOUR)",
+            "SELECT ts FROM foo LIMIT 1, 2 DENSIFY(ts, INTERVAL '1' HOUR)",
+        ] {
+            assert!(split_densify(query).is_err(), "{query} should be invalid");
+        }
+    }
+
+    #[test]
+    fn stages_fill_the_gaps_of_the_columns() {
+        let (_, densify) = split_densify(
+            "SELECT ts, v FROM readings DENSIFY(ts, INTERVAL '1' HOUR) FILL(v, LINEAR)",
+        )
+        .unwrap();
+        let get = |datasource: &str, column: &str, input: &str| {
+            doc! {"$getField": {
+                "field": {"$literal": column},
+                "input": {"$getField": {"field": {"$literal": datasource}, "input": input}},
+            }}
+        };
+        assert_eq!(
+            vec![
+                doc! {"$replaceWith": {
+                    "row": "$$ROOT",
+                    "key": get("", "ts", "$$ROOT"),
+                    "fill0": get("", "v", "$$ROOT"),
+                }},
+                doc! {"$densify": {
+                    "field": "key",
+                    "range": {"step": 1_i64, "unit": "hour", "bounds": "full"},
+                }},
+                doc! {"$fill": {"sortBy": {"key": 1}, "output": {"fill0": {"method": "linear"}}}},
+                doc! {"$replaceWith": {"$setField": {
+                    "field": {"$literal": ""},
+                    "input": {"$ifNull": ["$row", {}]},
+                    "value": {"$setField": {
+                        "field": {"$literal": "v"},
+                        "input": {"$setField": {
+                            "field": {"$literal": "ts"},
+                            "input": {"$ifNull": [
+                                {"$getField": {"field": {"$literal": ""}, "input": "$row"}},
+                                {},
+                            ]},
+                            "value": "$key",
+                        }},
+                        "value": "$fill0",
+                    }},
+                }}},
+            ],
+            densify
+                .unwrap()
+                .stages(&metadata(&[("", "ts"), ("", "v")]))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn columns_must_be_in_the_result_set() {
+        let stages = |query: &str| {
+            split_densify(query).unwrap().1.unwrap().stages(&metadata(&[
+                ("a", "ts"),
+                ("b", "ts"),
+                ("a", "v"),
+            ]))
+        };
+        assert!(stages("SELECT * FROM a, b DENSIFY(a.ts, INTERVAL '1' DAY) FILL(v, LOCF)").is_ok());
+        // ts is a column of both datasources
+        assert!(stages("SELECT * FROM a, b DENSIFY(ts, INTERVAL '1' DAY)").is_err());
+        assert!(
+            stages("SELECT * FROM a, b DENSIFY(a.ts, INTERVAL '1' DAY) FILL(w, LOCF)").is_err()
+        );
+    }
+
+    #[test]
+    fn rows_are_sorted_and_limited_after_the_gaps_are_filled() {
+        let (_, densify) = split_densify(
+            "SELECT ts, v, w FROM readings ORDER BY ts DESC, w LIMIT 2 OFFSET 1 \
+             DENSIFY(ts, INTERVAL '1' HOUR) FILL(v, LINEAR)",
+        )
+        .unwrap();
+        let stages = densify
+            .unwrap()
+            .stages(&metadata(&[("", "ts"), ("", "v"), ("", "w")]))
+            .unwrap();
+        assert!(stages[0]
+            .get_document("$replaceWith")
+            .unwrap()
+            .contains_key("sort1"));
+        assert_eq!(
+            &[
+                doc! {"$sort": {"key": -1, "sort1": 1}},
+                doc! {"$skip": 1_i64},
+                doc! {"$limit": 2_i64},
+            ],
+            &stages[3..6]
+        );
+    }
+
+    #[test]
+    fn densify_and_fill_require_server_support() {
+        let (_, densify) =
+            split_densify("SELECT ts FROM foo DENSIFY(ts, INTERVAL '1' HOUR)").unwrap();
+        let (_, densify_and_fill) =
+            split_densify("SELECT ts FROM foo DENSIFY(ts, INTERVAL '1' HOUR) FILL(v, LOCF)")
+                .unwrap();
+        let (densify, densify_and_fill) = (densify.unwrap(), densify_and_fill.unwrap());
+        let capabilities = |version: &str, version_array: [i32; 2]| {
+            ServerCapabilities::from_version(version, &version_array)
+        };
+        assert!(densify
+            .check_capabilities(&capabilities("5.0", [5, 0]))
+            .is_err());
+        assert!(densify
+            .check_capabilities(&capabilities("5.1", [5, 1]))
+            .is_ok());
+        assert!(densify_and_fill
+            .check_capabilities(&capabilities("5.1", [5, 1]))
+            .is_err());
+        assert!(densify_and_fill
+            .check_capabilities(&capabilities("5.3", [5, 3]))
+            .is_ok());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::lexer::{tokenize, Token, TokenKind};
+use std::ops::Range;
+
+// The keywords that may precede a parenthesized expression, which are not function names.
+const KEYWORDS: [&str; 16] = [
+    "select", "where", "having", "on", "and", "or", "not", "when", "then", "else", "in", "by",
+    "exists", "as", "from", "is",
+];
+
+// translate_distinct_from rewrites the null-safe comparisons of a query, `a IS NOT DISTINCT FROM b`
+// and `a <=> b`, into `COALESCE(a = b, a IS NULL AND b IS NULL)`, and `a IS DISTINCT FROM b` into
+// its negation, since MongoSQL has neither. A comparison is true when both operands are equal or
+// both are NULL or MISSING, and false otherwise, never NULL. A comparison with the NULL literal is
+// an IS [NOT] NULL test. The operands must be columns, literals, function calls or parenthesized
+// expressions, any other comparison is left as is for the server to report.
+pub fn translate_distinct_from(query: &str) -> String {
+    let tokens = tokenize(query);
+    let mut translated = String::with_capacity(query.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let Some((operator_end, distinct)) = null_safe_operator(&tokens, i) else {
+            i += 1;
+            continue;
+        };
+        let (Some(left), Some(right)) = (
+            left_operand(&tokens, i),
+            right_operand(&tokens, operator_end),
+        ) else {
+            i = operator_end;
+            continue;
+        };
+        // An operand next to an operator binding tighter than the comparison is only a part of the
+        // operand of the comparison.
+        let separated_before = left.start == 0 || {
+            let previous = &tokens[left.start - 1];
+            previous.kind == TokenKind::Word || previous.is_punct("(") || previous.is_punct(",")
+        };
+        let separated_after = tokens.get(right.end).is_none_or(|next| {
+            matches!(next.kind, TokenKind::Word | TokenKind::Identifier)
+                || next.is_punct(")")
+                || next.is_punct(",")
+        });
+        if !separated_before || !separated_after || tokens[left.start].start < copied {
+            i = operator_end;
+            continue;
+        }
+        let text = |range: &Range<usize>| {
+            translate_distinct_from(&query[tokens[range.start].start..tokens[range.end - 1].end])
+        };
+        let is_null =
+            |range: &Range<usize>| range.len() == 1 && tokens[range.start].is_word("null");
+        let (a, b) = (text(&left), text(&right));
+        let comparison = match (is_null(&left), is_null(&right)) {
+            (true, true) => "TRUE".to_string(),
+            (true, false) => format!("{b} IS NULL"),
+            (false, true) => format!("{a} IS NULL"),
+            (false, false) => format!("COALESCE({a} = {b}, {a} IS NULL AND {b} IS NULL)"),
+        };
+        translated.push_str(&query[copied..tokens[left.start].start]);
+        if distinct {
+            translated.push_str(&format!("(NOT {comparison})"));
+        } else {
+            translated.push_str(&format!("({comparison})"));
+        }
+        copied = tokens[right.end - 1].end;
+        i = right.end;
+    }
+    translated.push_str(&query[copied..]);
+    translated
+}
+
+// null_safe_operator returns the index of the token following the null-safe comparison operator
+// starting at index i, and whether it is IS DISTINCT FROM, if there is one.
+fn null_safe_operator(tokens: &[Token], i: usize) -> Option<(usize, bool)> {
+    let is_word = |j: usize, word: &str| tokens.get(j).is_some_and(|t| t.is_word(word));
+    if is_word(i, "is") {
+        let negated = is_word(i + 1, "not");
+        let distinct = i + 1 + usize::from(negated);
+        return (is_word(distinct, "distinct") && is_word(distinct + 1, "from"))
+            .then_some((distinct + 2, !negated));
+    }
+    let adjacent_punct = |j: usize, punct: &str| {
+        tokens
+            .get(j)
+            .is_some_and(|t| t.is_punct(punct) && (j == i || tokens[j - 1].end == t.start))
+    };
+    (adjacent_punct(i, "<") && adjacent_punct(i + 1, "=") && adjacent_punct(i + 2, ">"))
+        .then_some((i + 3, false))
+}
+
+// left_operand returns the range of the tokens of the operand ending before index end.
+fn left_operand(tokens: &[Token], end: usize) -> Option<Range<usize>> {
+    let last = tokens.get(end.checked_sub(1)?)?;
+    let start = if last.is_punct(")") {
+        let open = (0..end - 1)
+            .rev()
+            .find(|&j| tokens[j].depth == last.depth && tokens[j].is_punct("("))?;
+        match open.checked_sub(1).map(|j| &tokens[j]) {
+            Some(name) if is_function_name(name) => open - 1,
+            _ => open,
+        }
+    } else if is_name(last) {
+        let mut start = end - 1;
+        while start >= 2 && tokens[start - 1].is_punct(".") && is_name(&tokens[start - 2]) {
+            start -= 2;
+        }
+        start
+    } else if last.kind == TokenKind::Literal {
+        end - 1
+    } else {
+        return None;
+    };
+    Some(start..end)
+}
+
+// right_operand returns the range of the tokens of the operand starting at index start.
+fn right_operand(tokens: &[Token], start: usize) -> Option<Range<usize>> {
+    let first = tokens.get(start)?;
+    let close = |open: usize| {
+        (open + 1..tokens.len())
+            .find(|&j| tokens[j].depth == tokens[open].depth && tokens[j].is_punct(")"))
+    };
+    let end = if first.is_punct("(") {
+        close(start)? + 1
+    } else if is_function_name(first) && tokens.get(start + 1).is_some_and(|t| t.is_punct("(")) {
+        close(start + 1)? + 1
+    } else if first.is_punct("-") && tokens.get(start + 1).is_some_and(is_number) {
+        start + 2
+    } else if is_name(first) {
+        let mut end = start + 1;
+        while tokens.get(end).is_some_and(|t| t.is_punct("."))
+            && tokens.get(end + 1).is_some_and(is_name)
+        {
+            end += 2;
+        }
+        end
+    } else if first.kind == TokenKind::Literal {
+        start + 1
+    } else {
+        return None;
+    };
+    Some(start..end)
+}
+
+// is_name returns whether a token is a name, a number or a keyword literal such as NULL.
+fn is_name(token: &Token) -> bool {
+    token.kind == TokenKind::Identifier
+        || token.kind == TokenKind::Word && !KEYWORDS.iter().any(|k| token.is_word(k))
+}
+
+fn is_function_name(token: &Token) -> bool {
+    token.kind == TokenKind::Word && !KEYWORDS.iter().any(|k| token.is_word(k))
+}
+
+fn is_number(token: &Token) -> bool {
+    token.kind == TokenKind::Word && token.text.starts_with(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod unit {
+    use super::translate_distinct_from;
+
+    #[test]
+    fn null_safe_comparisons_are_translated() {
+        assert_eq!(
+            "SELECT * FROM foo WHERE (COALESCE(foo.a = b, foo.a IS NULL AND b IS NULL)) \
+             AND (NOT COALESCE(c = 'x', c IS NULL AND 'x' IS NULL)) \
+             AND (COALESCE(d = -1, d IS NULL AND -1 IS NULL))",
+            translate_distinct_from(
+                "SELECT * FROM foo WHERE foo.a IS NOT DISTINCT FROM b \
+                 AND c is distinct from 'x' AND d <=> -1"
+            )
+        );
+    }
+
+    #[test]
+    fn operands_may_be_calls_and_parenthesized_expressions() {
+        assert_eq!(
+            "SELECT (NOT COALESCE(LOWER(a) = (b || c), LOWER(a) IS NULL AND (b || c) IS NULL)) \
+             AS x FROM foo",
+            translate_distinct_from("SELECT LOWER(a) IS DISTINCT FROM (b || c) AS x FROM foo")
+        );
+    }
+
+    #[test]
+    fn comparisons_with_null_are_null_tests() {
+        assert_eq!(
+            "SELECT * FROM foo WHERE (a IS NULL) AND (NOT b IS NULL)",
+            translate_distinct_from(
+                "SELECT * FROM foo WHERE a IS NOT DISTINCT FROM NULL \
+                 AND NULL IS DISTINCT FROM b"
+            )
+        );
+    }
+
+    #[test]
+    fn other_queries_are_unchanged() {
+        for query in [
+            "SELECT * FROM foo WHERE a IS NOT NULL",
+            "SELECT DISTINCT a FROM foo",
+            "SELECT * FROM foo WHERE a <= 1 AND b > 2",
+            "SELECT 'a IS DISTINCT FROM b' FROM foo",
+            // The operands are parts of arithmetic expressions.
+            "SELECT * FROM foo WHERE a IS DISTINCT FROM b + 1",
+            "SELECT * FROM foo WHERE a * 2 <=> b",
+        ] {
+            assert_eq!(query, translate_distinct_from(query));
+        }
+    }
+}
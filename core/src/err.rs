@@ -1,11 +1,20 @@
 use constants::{
-    OdbcState, FUNCTION_SEQUENCE_ERROR, GENERAL_ERROR, INVALID_CURSOR_STATE,
-    INVALID_DESCRIPTOR_INDEX, NO_DSN_OR_DRIVER, OPERATION_CANCELLED, TIMEOUT_EXPIRED,
-    UNABLE_TO_CONNECT,
+    OdbcState, COMMUNICATION_LINK_FAILURE, FUNCTION_SEQUENCE_ERROR, GENERAL_ERROR,
+    INVALID_ATTR_VALUE, INVALID_AUTHORIZATION, INVALID_CURSOR_STATE, INVALID_DATETIME_FORMAT,
+    INVALID_DESCRIPTOR_INDEX, NOT_IMPLEMENTED, NO_DSN_OR_DRIVER, OPERATION_CANCELLED, SYNTAX_ERROR,
+    TIMEOUT_EXPIRED, UNABLE_TO_CONNECT,
 };
 use mongodb::error::{BulkWriteFailure, ErrorKind, WriteFailure};
 use thiserror::Error;
 
+// Server error codes for failed authentication or authorization:
+// Unauthorized and AuthenticationFailed.
+const AUTH_ERROR_CODES: [i32; 2] = [13, 18];
+// Server error codes reported when the server cannot be reached or is going away:
+// HostUnreachable, HostNotFound, ShutdownInProgress, InterruptedAtShutdown,
+// InterruptedDueToReplStateChange and SocketException.
+const NETWORK_ERROR_CODES: [i32; 6] = [6, 7, 91, 11600, 11602, 9001];
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Error, Debug, Clone)]
@@ -20,6 +29,8 @@ pub enum Error {
     DatabaseVersionRetreival(mongodb::error::Error),
     #[error("Getting database metadata failed with error: {0}")]
     DatabaseVersionDeserialization(bson::de::Error),
+    #[error("Listing databases failed with error: {0}")]
+    DatabaseListing(mongodb::error::Error),
     #[error("Setting connection options failed with error: {0}")]
     InvalidClientOptions(mongodb::error::Error),
     #[error("Invalid cursor state: cursor not advanced")]
@@ -52,22 +63,52 @@ pub enum Error {
     UnsupportedOperation(&'static str),
     #[error("Statement not executed")]
     StatementNotExecuted,
+    #[error("No index matches the hint {0}")]
+    InvalidHint(String),
+    #[error("Invalid session variable value: {0}")]
+    InvalidSessionVariable(String),
+    #[error("No change event arrived within the query timeout of {0} seconds")]
+    ChangeStreamTimeout(u64),
+    #[error("Invalid datetime literal '{0}'")]
+    InvalidDatetimeLiteral(String),
+    #[error("Unsupported grouping: {0}")]
+    UnsupportedGrouping(String),
+    #[error("Invalid DENSIFY clause: {0}")]
+    InvalidDensify(String),
+    #[error("Unsupported common table expression: {0}")]
+    UnsupportedCte(String),
+    #[error("Server version {0} does not support {1}")]
+    UnsupportedServerFeature(String, &'static str),
 }
 
 impl Error {
     pub fn get_sql_state(&self) -> OdbcState {
         match self {
             Error::CollectionCursorUpdate(err)
+            | Error::DatabaseListing(err)
             | Error::DatabaseVersionRetreival(err)
             | Error::InvalidClientOptions(err)
             | Error::QueryCursorUpdate(err)
-            | Error::QueryExecutionFailed(err) => {
-                if matches!(err.kind.as_ref(), ErrorKind::Io(ref io_err) if io_err.kind() == std::io::ErrorKind::TimedOut)
+            | Error::QueryExecutionFailed(err) => match err.kind.as_ref() {
+                ErrorKind::Io(ref io_err) if io_err.kind() == std::io::ErrorKind::TimedOut => {
+                    TIMEOUT_EXPIRED
+                }
+                ErrorKind::Authentication { .. } => INVALID_AUTHORIZATION,
+                ErrorKind::Command(command_error)
+                    if AUTH_ERROR_CODES.contains(&command_error.code) =>
                 {
-                    return TIMEOUT_EXPIRED;
+                    INVALID_AUTHORIZATION
                 }
-                GENERAL_ERROR
-            }
+                ErrorKind::Io(_)
+                | ErrorKind::ConnectionPoolCleared { .. }
+                | ErrorKind::ServerSelection { .. } => COMMUNICATION_LINK_FAILURE,
+                ErrorKind::Command(command_error)
+                    if NETWORK_ERROR_CODES.contains(&command_error.code) =>
+                {
+                    COMMUNICATION_LINK_FAILURE
+                }
+                _ => GENERAL_ERROR,
+            },
             Error::InvalidUriFormat(_) => UNABLE_TO_CONNECT,
             Error::MongoParseConnectionString(_) => UNABLE_TO_CONNECT,
             Error::NoDatabase => NO_DSN_OR_DRIVER,
@@ -81,9 +122,17 @@ impl Error {
             | Error::QueryDeserialization(_)
             | Error::UnknownColumn(_)
             | Error::ValueAccess(_, _)
-            | Error::UnsupportedOperation(_) => GENERAL_ERROR,
+            | Error::UnsupportedOperation(_)
+            | Error::InvalidHint(_) => GENERAL_ERROR,
             Error::StatementNotExecuted => FUNCTION_SEQUENCE_ERROR,
             Error::QueryCancelled => OPERATION_CANCELLED,
+            Error::InvalidSessionVariable(_) => INVALID_ATTR_VALUE,
+            Error::ChangeStreamTimeout(_) => TIMEOUT_EXPIRED,
+            Error::InvalidDatetimeLiteral(_) => INVALID_DATETIME_FORMAT,
+            Error::UnsupportedGrouping(_) | Error::InvalidDensify(_) | Error::UnsupportedCte(_) => {
+                SYNTAX_ERROR
+            }
+            Error::UnsupportedServerFeature(_, _) => NOT_IMPLEMENTED,
         }
     }
 
@@ -91,6 +140,7 @@ impl Error {
         // using `match` instead of `if let` in case we add future variants
         match self {
             Error::CollectionCursorUpdate(m)
+            | Error::DatabaseListing(m)
             | Error::DatabaseVersionRetreival(m)
             | Error::InvalidClientOptions(m)
             | Error::QueryCursorUpdate(m)
@@ -122,7 +172,76 @@ impl Error {
             | Error::UnknownColumn(_)
             | Error::ValueAccess(_, _)
             | Error::UnsupportedOperation(_)
-            | Error::StatementNotExecuted => 0,
+            | Error::StatementNotExecuted
+            | Error::InvalidHint(_)
+            | Error::InvalidSessionVariable(_)
+            | Error::ChangeStreamTimeout(_)
+            | Error::InvalidDatetimeLiteral(_)
+            | Error::UnsupportedGrouping(_)
+            | Error::InvalidDensify(_)
+            | Error::UnsupportedCte(_)
+            | Error::UnsupportedServerFeature(_, _) => 0,
         }
     }
 }
+
+#[cfg(test)]
+mod unit {
+    use super::Error;
+    use bson::doc;
+    use constants::{
+        COMMUNICATION_LINK_FAILURE, GENERAL_ERROR, INVALID_AUTHORIZATION, TIMEOUT_EXPIRED,
+    };
+    use mongodb::error::{CommandError, ErrorKind};
+
+    fn command_error(code: i32, code_name: &str) -> mongodb::error::Error {
+        let command_error: CommandError = bson::from_document(doc! {
+            "code": code,
+            "codeName": code_name,
+            "errmsg": "simulated server error",
+        })
+        .unwrap();
+        ErrorKind::Command(command_error).into()
+    }
+
+    #[test]
+    fn unauthorized() {
+        let err = Error::QueryExecutionFailed(command_error(13, "Unauthorized"));
+        assert_eq!(13, err.code());
+        assert_eq!(INVALID_AUTHORIZATION, err.get_sql_state());
+    }
+
+    #[test]
+    fn authentication_failed() {
+        let err = Error::QueryExecutionFailed(command_error(18, "AuthenticationFailed"));
+        assert_eq!(18, err.code());
+        assert_eq!(INVALID_AUTHORIZATION, err.get_sql_state());
+    }
+
+    #[test]
+    fn interrupted_at_shutdown() {
+        let err = Error::QueryCursorUpdate(command_error(11600, "InterruptedAtShutdown"));
+        assert_eq!(11600, err.code());
+        assert_eq!(COMMUNICATION_LINK_FAILURE, err.get_sql_state());
+    }
+
+    #[test]
+    fn io_errors() {
+        let err = Error::QueryExecutionFailed(
+            std::io::Error::from(std::io::ErrorKind::ConnectionReset).into(),
+        );
+        assert_eq!(0, err.code());
+        assert_eq!(COMMUNICATION_LINK_FAILURE, err.get_sql_state());
+
+        let err =
+            Error::QueryExecutionFailed(std::io::Error::from(std::io::ErrorKind::TimedOut).into());
+        assert_eq!(TIMEOUT_EXPIRED, err.get_sql_state());
+    }
+
+    #[test]
+    fn other_command_errors() {
+        let err = Error::QueryExecutionFailed(command_error(2, "BadValue"));
+        assert_eq!(2, err.code());
+        assert_eq!(GENERAL_ERROR, err.get_sql_state());
+    }
+}
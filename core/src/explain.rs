@@ -0,0 +1,243 @@
+use crate::{
+    col_metadata::MongoColMetadata,
+    conn::MongoConnection,
+    err::{Error, Result},
+    query::command_comment,
+    stmt::{CommandOptions, MongoStatement},
+    BsonTypeInfo,
+};
+use bson::{doc, Bson, Document};
+use definitions::Nullability;
+use once_cell::sync::OnceCell;
+
+static EXPLAIN_METADATA: OnceCell<Vec<MongoColMetadata>> = OnceCell::new();
+
+// The executionStats fields returned by EXPLAIN ANALYZE, in column order.
+const EXECUTION_STATS: [&str; 3] = ["nReturned", "totalDocsExamined", "executionTimeMillis"];
+
+// explain_analyze_target returns the query of an `EXPLAIN ANALYZE <query>` statement, or None if
+// the statement is not one. The keywords are case-insensitive.
+pub fn explain_analyze_target(statement: &str) -> Option<&str> {
+    let rest = strip_keyword(statement.trim_start(), "explain")?;
+    let query = strip_keyword(rest, "analyze")?.trim();
+    (!query.is_empty()).then_some(query)
+}
+
+fn strip_keyword<'a>(s: &'a str, keyword: &str) -> Option<&'a str> {
+    let (word, rest) = s.split_at(s.find(char::is_whitespace)?);
+    word.eq_ignore_ascii_case(keyword)
+        .then(|| rest.trim_start())
+}
+
+// MongoExplain runs the $sql aggregation of a query with the executionStats explain verbosity,
+// and returns the execution statistics as a single row result set with the columns
+// nReturned, totalDocsExamined and executionTimeMillis.
+#[derive(Debug)]
+pub struct MongoExplain {
+    current_db: String,
+    // The EXPLAIN ANALYZE statement
+    statement: String,
+    // The explained query
+    query: String,
+    // The execution statistics, once executed
+    stats: Option<Document>,
+    // Whether the row has been returned by next
+    current: bool,
+}
+
+impl MongoExplain {
+    pub fn prepare(current_db: Option<String>, statement: &str, query: &str) -> Result<Self> {
+        Ok(MongoExplain {
+            current_db: current_db.ok_or(Error::NoDatabase)?,
+            statement: statement.to_string(),
+            query: query.to_string(),
+            stats: None,
+            current: false,
+        })
+    }
+}
+
+// execution_stats returns the first executionStats document of an explain output. Depending on
+// the topology, it is either at the top level or nested in the stages or shards of the plan.
+fn execution_stats(explain: &Document) -> Option<&Document> {
+    if let Ok(stats) = explain.get_document("executionStats") {
+        return Some(stats);
+    }
+    explain.values().find_map(|value| match value {
+        Bson::Document(d) => execution_stats(d),
+        Bson::Array(a) => a
+            .iter()
+            .find_map(|v| v.as_document().and_then(execution_stats)),
+        _ => None,
+    })
+}
+
+impl MongoStatement for MongoExplain {
+    // The result set has a single row, available once executed.
+    fn next(&mut self, _: Option<&MongoConnection>) -> Result<(bool, Vec<Error>)> {
+        if self.stats.is_none() {
+            return Err(Error::StatementNotExecuted);
+        }
+        let has_row = !self.current;
+        self.current = true;
+        Ok((has_row, vec![]))
+    }
+
+    // Get the BSON value for the cell at the given colIndex on the current row.
+    // Statistics missing from the explain output are NULL.
+    fn get_value(&self, col_index: u16, _: Option<u16>) -> Result<Option<Bson>> {
+        let stats = self
+            .stats
+            .as_ref()
+            .filter(|_| self.current)
+            .ok_or(Error::InvalidCursorState)?;
+        let name = EXECUTION_STATS
+            .get(usize::from(col_index).wrapping_sub(1))
+            .ok_or(Error::ColIndexOutOfBounds(col_index))?;
+        Ok(Some(match stats.get(name) {
+            Some(Bson::Int32(n)) => Bson::Int64(i64::from(*n)),
+            Some(Bson::Int64(n)) => Bson::Int64(*n),
+            _ => Bson::Null,
+        }))
+    }
+
+    fn get_resultset_metadata(&self, max_string_length: Option<u16>) -> &Vec<MongoColMetadata> {
+        EXPLAIN_METADATA.get_or_init(|| {
+            EXECUTION_STATS
+                .iter()
+                .map(|name| {
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        name.to_string(),
+                        BsonTypeInfo::LONG,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    // Run the $sql aggregation of the query with the executionStats verbosity.
+    fn execute(
+        &mut self,
+        connection: &MongoConnection,
+        stmt_id: Bson,
+        _: u32,
+        command_options: &CommandOptions,
+    ) -> Result<bool> {
+        let db = connection.client.database(&self.current_db);
+        let mut aggregate = doc! {
+            "aggregate": 1,
+            "pipeline": [{"$sql": {"statement": &self.query}}],
+            "cursor": {},
+        };
+        if let Some(hint) = command_options.hint.as_ref() {
+            aggregate.insert("hint", bson::to_bson(hint).unwrap_or_default());
+        }
+        if let Some(collation) = connection.collation.as_ref() {
+            aggregate.insert("collation", bson::to_bson(collation).unwrap_or_default());
+        }
+        let explain_cmd = doc! {
+            "explain": aggregate,
+            "verbosity": "executionStats",
+            "comment": command_comment(stmt_id, command_options),
+        };
+        let _guard = connection.runtime.enter();
+        let explain = connection
+            .runtime
+            .block_on(async { db.run_command(explain_cmd, None).await })
+            .map_err(Error::QueryExecutionFailed)?;
+        self.stats = Some(execution_stats(&explain).cloned().unwrap_or_default());
+        self.current = false;
+        Ok(true)
+    }
+
+    fn close_cursor(&mut self) {
+        self.current = true;
+    }
+
+    fn query(&self) -> Option<&str> {
+        Some(&self.statement)
+    }
+}
+
+#[cfg(test)]
+mod unit {
+    use super::{execution_stats, explain_analyze_target, MongoExplain};
+    use crate::{err::Error, stmt::MongoStatement};
+    use bson::{doc, Bson};
+
+    #[test]
+    fn explain_analyze_statements_are_recognized() {
+        assert_eq!(
+            Some("SELECT * FROM foo"),
+            explain_analyze_target("EXPLAIN ANALYZE SELECT * FROM foo")
+        );
+        assert_eq!(
+            Some("SELECT 1"),
+            explain_analyze_target("  explain\n\tAnalyze  SELECT 1 ")
+        );
+        for statement in [
+            "SELECT * FROM foo",
+            "EXPLAIN SELECT * FROM foo",
+            "EXPLAIN ANALYZE",
+            "EXPLAINANALYZE SELECT 1",
+            "SELECT explain analyze FROM foo",
+        ] {
+            assert_eq!(None, explain_analyze_target(statement), "{statement}");
+        }
+    }
+
+    #[test]
+    fn execution_stats_are_found_in_nested_stages() {
+        let explain = doc! {
+            "stages": [
+                {"$cursor": {
+                    "queryPlanner": {},
+                    "executionStats": {"nReturned": 2, "totalDocsExamined": 5i64, "executionTimeMillis": 1},
+                }},
+                {"$project": {}},
+            ],
+            "ok": 1,
+        };
+        assert_eq!(
+            Some(&doc! {"nReturned": 2, "totalDocsExamined": 5i64, "executionTimeMillis": 1}),
+            execution_stats(&explain)
+        );
+        assert_eq!(None, execution_stats(&doc! {"ok": 1}));
+    }
+
+    #[test]
+    fn explain_returns_a_single_row_of_stats() {
+        let mut explain = MongoExplain::prepare(
+            Some("db".to_string()),
+            "EXPLAIN ANALYZE SELECT 1",
+            "SELECT 1",
+        )
+        .unwrap();
+        assert!(matches!(
+            explain.next(None),
+            Err(Error::StatementNotExecuted)
+        ));
+        explain.stats = Some(doc! {"nReturned": 2, "totalDocsExamined": 5i64});
+        assert!(explain.next(None).unwrap().0);
+        assert_eq!(Some(Bson::Int64(2)), explain.get_value(1, None).unwrap());
+        assert_eq!(Some(Bson::Int64(5)), explain.get_value(2, None).unwrap());
+        assert_eq!(Some(Bson::Null), explain.get_value(3, None).unwrap());
+        assert!(matches!(
+            explain.get_value(4, None),
+            Err(Error::ColIndexOutOfBounds(4))
+        ));
+        assert!(!explain.next(None).unwrap().0);
+        assert_eq!(
+            vec!["nReturned", "totalDocsExamined", "executionTimeMillis"],
+            explain
+                .get_resultset_metadata(None)
+                .iter()
+                .map(|col| col.col_name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -422,6 +422,65 @@ mod unit {
                 .nullability
         );
     }
+
+    #[test]
+    fn numeric_columns_decimal_digits_and_radix() {
+        use crate::{
+            col_metadata::MongoColMetadata,
+            fields::MongoFields,
+            json_schema::{
+                simplified::{Atomic, Schema},
+                BsonTypeName,
+            },
+            stmt::MongoStatement,
+            TypeMode,
+        };
+        use bson::Bson;
+        use definitions::Nullability;
+
+        let numeric_column = |bson_type, type_mode| {
+            let mut fields = MongoFields::empty();
+            fields.current_col_metadata = vec![MongoColMetadata::new(
+                "db",
+                "foo".to_string(),
+                "a".to_string(),
+                Schema::Atomic(Atomic::Scalar(bson_type)),
+                Nullability::SQL_NULLABLE,
+                type_mode,
+                None,
+            )];
+            fields.current_field_for_collection = 0;
+            // DECIMAL_DIGITS and NUM_PREC_RADIX
+            (
+                fields.get_value(9, None).unwrap().unwrap(),
+                fields.get_value(10, None).unwrap().unwrap(),
+            )
+        };
+        // Integers have no digits to the right of the decimal point.
+        assert_eq!(
+            (Bson::Int32(0), Bson::Int32(10)),
+            numeric_column(BsonTypeName::Int, TypeMode::Standard)
+        );
+        assert_eq!(
+            (Bson::Int32(0), Bson::Int32(10)),
+            numeric_column(BsonTypeName::Long, TypeMode::Standard)
+        );
+        // The scale of a binary floating point number is not fixed.
+        assert_eq!(
+            (Bson::Null, Bson::Int32(2)),
+            numeric_column(BsonTypeName::Double, TypeMode::Standard)
+        );
+        // Neither is the scale of a decimal128, whose exponent varies with each value.
+        assert_eq!(
+            (Bson::Null, Bson::Int32(10)),
+            numeric_column(BsonTypeName::Decimal, TypeMode::Standard)
+        );
+        // Simple types expose decimal128 as a string.
+        assert_eq!(
+            (Bson::Null, Bson::Null),
+            numeric_column(BsonTypeName::Decimal, TypeMode::Simple)
+        );
+    }
 }
 
 #[derive(Debug)]
@@ -726,14 +785,13 @@ impl MongoStatement for MongoFields {
                 Some(dec_dg) => Bson::Int32(i32::from(dec_dg)),
             },
             // NUM_PREC_RADIX
-            10 => match get_meta_data()?.sql_type {
-                // For numeric data types. 10 means that the values in COLUMN_SIZE and DECIMAL_DIGITS
-                // give the maximum number of digits and maximum number of digits to the right of the
-                // decimal point allowed for the column respectively.
-                SqlDataType::SQL_INTEGER | SqlDataType::SQL_DOUBLE | SqlDataType::SQL_DECIMAL => {
-                    Bson::Int32(10)
-                }
-                _ => Bson::Null,
+            10 => match get_meta_data()?.num_prec_radix {
+                // For numeric data types. 2 means that COLUMN_SIZE gives the number of bits of
+                // an approximate type, 10 that COLUMN_SIZE and DECIMAL_DIGITS give the number of
+                // digits of an exact type, and of digits to the right of the decimal point.
+                // NULL is returned for data types where NUM_PREC_RADIX is not applicable.
+                None => Bson::Null,
+                Some(radix) => Bson::Int32(i32::from(radix)),
             },
             // NULLABLE
             11 => Bson::Int32(get_meta_data()?.nullability as i32),
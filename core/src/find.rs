@@ -0,0 +1,529 @@
+use crate::{
+    col_metadata::MongoColMetadata,
+    lexer::{name_path, tokenize, Token, TokenKind},
+};
+use bson::{doc, Bson, Document};
+
+// A query run with the find command rather than the $sql aggregation. Only the queries of a single
+// collection that filter, project, sort and limit its documents are, as find reads the documents
+// of the collection directly where the aggregation is translated and planned by the server.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FindQuery {
+    // The database of the collection, when the query qualifies it.
+    pub(crate) database: Option<String>,
+    pub(crate) collection: String,
+    pub(crate) filter: Document,
+    pub(crate) projection: Document,
+    pub(crate) sort: Option<Document>,
+    pub(crate) skip: Option<u64>,
+    pub(crate) limit: Option<i64>,
+    // The datasource, name and field of each column of the result set.
+    columns: Vec<(String, String, String)>,
+}
+
+impl FindQuery {
+    // row returns the row of the result set of a document of the collection, in the shape of the
+    // rows of the $sql aggregation: a document of the columns of each datasource.
+    pub(crate) fn row(&self, document: &Document) -> Document {
+        let mut row = Document::new();
+        for (datasource, name, field) in &self.columns {
+            if !row.contains_key(datasource) {
+                row.insert(datasource.clone(), Document::new());
+            }
+            if let (Some(value), Ok(columns)) =
+                (document.get(field), row.get_document_mut(datasource))
+            {
+                columns.insert(name.clone(), value.clone());
+            }
+        }
+        row
+    }
+}
+
+// find_query returns the find command of a query, if the query is of the form
+// `SELECT <columns> FROM <collection> [WHERE <conditions>] [ORDER BY <columns>] [LIMIT n]
+// [OFFSET m]`, where every column of the result set is a field of the collection, and every
+// condition compares a column to a literal of its type or tests it for NULL. Any other query
+// returns None and runs as the $sql aggregation.
+// The filtered and sorted columns must have a single scalar type: find matches the elements of
+// arrays, `{field: null}` matches arrays holding a null, and find compares values of different
+// types by their type order, none of which the $sql aggregation does.
+pub(crate) fn find_query(query: &str, metadata: &[MongoColMetadata]) -> Option<FindQuery> {
+    let tokens = tokenize(query);
+    // Function calls, subqueries and lists are not part of the grammar.
+    if tokens.iter().any(|t| t.depth > 0 || t.is_punct("(")) {
+        return None;
+    }
+    let from = tokens.iter().position(|t| t.is_word("from"))?;
+    if !tokens.first()?.is_word("select")
+        || tokens[1..from]
+            .iter()
+            .any(|t| t.is_word("distinct") || t.is_word("value") || t.is_word("values"))
+    {
+        return None;
+    }
+    let mut parser = Parser {
+        tokens: &tokens,
+        position: from + 1,
+    };
+
+    let path = parser.name_path()?;
+    let (database, collection) = match path.as_slice() {
+        [collection] => (None, collection.clone()),
+        [database, collection] => (Some(database.clone()), collection.clone()),
+        _ => return None,
+    };
+    parser.word("as");
+    let datasource = match parser.peek() {
+        Some(t)
+            if t.kind == TokenKind::Identifier || (t.kind == TokenKind::Word && !is_keyword(t)) =>
+        {
+            parser.name_path()?.pop()?
+        }
+        _ => collection.clone(),
+    };
+
+    // Every column must be read as is from a field of the collection.
+    let mut columns = Vec::with_capacity(metadata.len());
+    for column in metadata {
+        if column.base_table_name != collection || !is_plain_field(&column.base_col_name) {
+            return None;
+        }
+        columns.push((
+            column.table_name.clone(),
+            column.col_name.clone(),
+            column.base_col_name.clone(),
+        ));
+    }
+    // field returns the field of a column of the result set and its type.
+    let field = |path: Vec<String>| -> Option<(String, ScalarType)> {
+        let field = match path.as_slice() {
+            [field] => field,
+            [qualifier, field] if *qualifier == datasource => field,
+            _ => return None,
+        };
+        let column = metadata
+            .iter()
+            .find(|column| &column.base_col_name == field)?;
+        Some((field.clone(), ScalarType::of(column)?))
+    };
+
+    let mut filter = Document::new();
+    if parser.word("where") {
+        let mut conditions = vec![];
+        loop {
+            let (field, field_type) = field(parser.name_path()?)?;
+            let condition = if parser.word("is") {
+                let negated = parser.word("not");
+                if !parser.word("null") {
+                    return None;
+                }
+                // NULL is both a null and a missing field, as for the `{field: null}` filter.
+                if negated {
+                    doc! {field: {"$ne": Bson::Null}}
+                } else {
+                    doc! {field: Bson::Null}
+                }
+            } else {
+                let operator = parser.comparison()?;
+                let value = parser.literal()?;
+                if ScalarType::of_literal(&value)? != field_type {
+                    return None;
+                }
+                match operator {
+                    "$eq" => doc! {field: {"$eq": value}},
+                    // A comparison with NULL or MISSING is not true, so neither satisfies <>.
+                    "$ne" => doc! {field: {"$nin": [value, Bson::Null]}},
+                    operator => doc! {field: {operator: value}},
+                }
+            };
+            conditions.push(Bson::Document(condition));
+            if !parser.word("and") {
+                break;
+            }
+        }
+        filter = match conditions.len() {
+            1 => conditions.pop()?.as_document()?.clone(),
+            _ => doc! {"$and": conditions},
+        };
+    }
+
+    let mut sort = None;
+    if parser.word("order") {
+        if !parser.word("by") {
+            return None;
+        }
+        let mut keys = Document::new();
+        loop {
+            let path = parser.name_path()?;
+            // An unqualified name is a column of the result set before it is a field.
+            let key = match path.as_slice() {
+                [name] => match metadata.iter().find(|column| &column.col_name == name) {
+                    Some(column) => {
+                        ScalarType::of(column)?;
+                        column.base_col_name.clone()
+                    }
+                    None => field(path)?.0,
+                },
+                _ => field(path)?.0,
+            };
+            let direction = if parser.word("desc") {
+                -1
+            } else {
+                parser.word("asc");
+                1
+            };
+            keys.insert(key, direction);
+            if !parser.punct(",") {
+                break;
+            }
+        }
+        sort = Some(keys);
+    }
+
+    let limit = if parser.word("limit") {
+        Some(i64::try_from(parser.integer()?).ok()?)
+    } else {
+        None
+    };
+    let skip = if parser.word("offset") {
+        Some(parser.integer()?)
+    } else {
+        None
+    };
+    if parser.peek().is_some() {
+        return None;
+    }
+
+    let mut projection = Document::new();
+    if !columns.iter().any(|(_, _, field)| field == "_id") {
+        projection.insert("_id", 0);
+    }
+    for (_, _, field) in &columns {
+        projection.insert(field.clone(), 1);
+    }
+    Some(FindQuery {
+        database,
+        collection,
+        filter,
+        projection,
+        sort,
+        skip,
+        limit,
+        columns,
+    })
+}
+
+// The types of the columns a find filter or sort applies to, and of the literals they are compared
+// with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ScalarType {
+    Number,
+    String,
+    Bool,
+}
+
+impl ScalarType {
+    // of returns the type of a column, if it has a single scalar type find compares as the $sql
+    // aggregation does.
+    fn of(column: &MongoColMetadata) -> Option<Self> {
+        match column.type_name.as_str() {
+            "int" | "long" | "double" | "decimal" => Some(ScalarType::Number),
+            "string" => Some(ScalarType::String),
+            "bool" => Some(ScalarType::Bool),
+            _ => None,
+        }
+    }
+
+    fn of_literal(value: &Bson) -> Option<Self> {
+        match value {
+            Bson::Int32(_) | Bson::Int64(_) | Bson::Double(_) => Some(ScalarType::Number),
+            Bson::String(_) => Some(ScalarType::String),
+            Bson::Boolean(_) => Some(ScalarType::Bool),
+            _ => None,
+        }
+    }
+}
+
+// Fields with a dot or a dollar prefix are paths and expressions in find filters and projections.
+fn is_plain_field(field: &str) -> bool {
+    !field.is_empty() && !field.contains('.') && !field.starts_with('$')
+}
+
+fn is_keyword(token: &Token) -> bool {
+    ["where", "order", "limit", "offset", "join", "group"]
+        .iter()
+        .any(|&keyword| token.is_word(keyword))
+}
+
+struct Parser<'a, 'q> {
+    tokens: &'a [Token<'q>],
+    position: usize,
+}
+
+impl<'q> Parser<'_, 'q> {
+    fn peek(&self) -> Option<&Token<'q>> {
+        self.tokens.get(self.position)
+    }
+
+    // word consumes the next token if it is the given keyword.
+    fn word(&mut self, word: &str) -> bool {
+        let matches = self.peek().is_some_and(|t| t.is_word(word));
+        self.position += usize::from(matches);
+        matches
+    }
+
+    fn punct(&mut self, punct: &str) -> bool {
+        let matches = self.peek().is_some_and(|t| t.is_punct(punct));
+        self.position += usize::from(matches);
+        matches
+    }
+
+    // name_path consumes a possibly qualified name.
+    fn name_path(&mut self) -> Option<Vec<String>> {
+        let mut end = self.position + 1;
+        while end + 1 < self.tokens.len() && self.tokens[end].is_punct(".") {
+            end += 2;
+        }
+        let path = name_path(self.tokens.get(self.position..end)?)?;
+        self.position = end;
+        Some(path)
+    }
+
+    // comparison consumes a comparison operator, and returns the matching query operator.
+    fn comparison(&mut self) -> Option<&'static str> {
+        // The characters of an operator are punctuation tokens of their own.
+        let mut operator = String::new();
+        let mut end = None;
+        while let Some(t) = self.tokens.get(self.position + operator.len()) {
+            if t.kind != TokenKind::Punct
+                || !"<>=!".contains(t.text)
+                || end.is_some_and(|end| end != t.start)
+            {
+                break;
+            }
+            operator.push_str(t.text);
+            end = Some(t.end);
+        }
+        self.position += operator.len();
+        match operator.as_str() {
+            "=" => Some("$eq"),
+            "<>" | "!=" => Some("$ne"),
+            "<" => Some("$lt"),
+            "<=" => Some("$lte"),
+            ">" => Some("$gt"),
+            ">=" => Some("$gte"),
+            _ => None,
+        }
+    }
+
+    // literal consumes a number, string or boolean literal. NULL is not a literal a comparison can
+    // be true for.
+    fn literal(&mut self) -> Option<Bson> {
+        let negative = self.punct("-");
+        let token = self.peek()?;
+        let value = match token.kind {
+            TokenKind::Literal if !negative => {
+                let text = token.text.strip_prefix('\'')?.strip_suffix('\'')?;
+                Bson::String(text.replace("''", "'"))
+            }
+            TokenKind::Word if !negative && token.is_word("true") => Bson::Boolean(true),
+            TokenKind::Word if !negative && token.is_word("false") => Bson::Boolean(false),
+            TokenKind::Word if token.text.starts_with(|c: char| c.is_ascii_digit()) => {
+                let mut number = token.text.to_string();
+                // the fractional part is a token of its own
+                if let (Some(dot), Some(fraction)) = (
+                    self.tokens.get(self.position + 1),
+                    self.tokens.get(self.position + 2),
+                ) {
+                    if dot.is_punct(".") && dot.start == token.end && fraction.start == dot.end {
+                        number = format!("{number}.{}", fraction.text);
+                        self.position += 2;
+                    }
+                }
+                if negative {
+                    number.insert(0, '-');
+                }
+                match number.parse::<i32>() {
+                    Ok(i) => Bson::Int32(i),
+                    Err(_) => match number.parse::<i64>() {
+                        Ok(i) => Bson::Int64(i),
+                        Err(_) => Bson::Double(number.parse::<f64>().ok()?),
+                    },
+                }
+            }
+            _ => return None,
+        };
+        self.position += 1;
+        Some(value)
+    }
+
+    fn integer(&mut self) -> Option<u64> {
+        let value = self.peek()?.text.parse().ok()?;
+        self.position += 1;
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod unit {
+    use super::{find_query, FindQuery};
+    use crate::{
+        base_names::apply_base_names,
+        col_metadata::MongoColMetadata,
+        json_schema::{simplified::Atomic, simplified::Schema, BsonTypeName},
+        TypeMode,
+    };
+    use bson::{doc, Bson};
+    use definitions::Nullability;
+
+    // find_query_of returns the find command of a query whose result set has the given
+    // (datasource, column) int columns.
+    fn find_query_of(query: &str, columns: &[(&str, &str)]) -> Option<FindQuery> {
+        let columns = columns
+            .iter()
+            .map(|&(datasource, column)| (datasource, column, BsonTypeName::Int))
+            .collect::<Vec<_>>();
+        find_query_of_types(query, &columns)
+    }
+
+    // find_query_of_types returns the find command of a query whose result set has the given
+    // (datasource, column, type) columns.
+    fn find_query_of_types(
+        query: &str,
+        columns: &[(&str, &str, BsonTypeName)],
+    ) -> Option<FindQuery> {
+        let mut metadata = columns
+            .iter()
+            .map(|(datasource, column, bson_type)| {
+                MongoColMetadata::new(
+                    "test",
+                    datasource.to_string(),
+                    column.to_string(),
+                    Schema::Atomic(Atomic::Scalar(*bson_type)),
+                    Nullability::SQL_NULLABLE,
+                    TypeMode::Standard,
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+        apply_base_names(&mut metadata, query);
+        find_query(query, &metadata)
+    }
+
+    #[test]
+    fn simple_query_runs_as_find() {
+        let find = find_query_of_types(
+            "SELECT a AS x, f.b, c, d FROM test.foo AS f \
+             WHERE a >= 1 AND f.b <> 'it''s' AND c IS NOT NULL AND d < -2.5 \
+             ORDER BY x DESC, b LIMIT 10 OFFSET 5",
+            &[
+                ("", "x", BsonTypeName::Int),
+                ("f", "b", BsonTypeName::String),
+                ("f", "c", BsonTypeName::Bool),
+                ("f", "d", BsonTypeName::Double),
+            ],
+        )
+        .unwrap();
+        assert_eq!(Some("test".to_string()), find.database);
+        assert_eq!("foo", find.collection);
+        assert_eq!(
+            doc! {"$and": [
+                {"a": {"$gte": 1}},
+                {"b": {"$nin": ["it's", Bson::Null]}},
+                {"c": {"$ne": Bson::Null}},
+                {"d": {"$lt": -2.5}},
+            ]},
+            find.filter
+        );
+        assert_eq!(
+            doc! {"_id": 0, "a": 1, "b": 1, "c": 1, "d": 1},
+            find.projection
+        );
+        assert_eq!(Some(doc! {"a": -1, "b": 1}), find.sort);
+        assert_eq!((Some(5), Some(10)), (find.skip, find.limit));
+    }
+
+    #[test]
+    fn select_star_runs_as_find() {
+        let find = find_query_of_types(
+            "SELECT * FROM foo WHERE a = TRUE",
+            &[
+                ("foo", "_id", BsonTypeName::ObjectId),
+                ("foo", "a", BsonTypeName::Bool),
+            ],
+        )
+        .unwrap();
+        assert_eq!(None, find.database);
+        assert_eq!(doc! {"a": {"$eq": true}}, find.filter);
+        assert_eq!(doc! {"_id": 1, "a": 1}, find.projection);
+        assert_eq!((None, None, None), (find.sort, find.skip, find.limit));
+    }
+
+    #[test]
+    fn rows_have_the_shape_of_the_aggregation_rows() {
+        let find = find_query_of("SELECT a AS x, b FROM foo", &[("", "x"), ("foo", "b")]).unwrap();
+        assert_eq!(
+            doc! {"": {"x": 1}, "foo": {}},
+            find.row(&doc! {"_id": 0, "a": 1, "c": 2})
+        );
+    }
+
+    #[test]
+    fn other_queries_run_as_aggregation() {
+        for (query, columns) in [
+            (
+                "SELECT a, COUNT(*) AS n FROM foo GROUP BY a",
+                &[("", "a"), ("", "n")][..],
+            ),
+            ("SELECT a FROM foo GROUP BY a", &[("", "a")]),
+            ("SELECT a + 1 AS x FROM foo", &[("", "x")]),
+            ("SELECT DISTINCT a FROM foo", &[("", "a")]),
+            (
+                "SELECT * FROM foo JOIN bar ON foo.a = bar.a",
+                &[("foo", "a")],
+            ),
+            ("SELECT * FROM foo, bar", &[("foo", "a")]),
+            ("SELECT * FROM foo WHERE a = 1 OR a = 2", &[("foo", "a")]),
+            ("SELECT * FROM foo WHERE a = b", &[("foo", "a")]),
+            ("SELECT * FROM foo WHERE a = NULL", &[("foo", "a")]),
+            ("SELECT * FROM foo WHERE a IN (1, 2)", &[("foo", "a")]),
+            ("SELECT * FROM (SELECT * FROM foo) AS f", &[("f", "a")]),
+            (
+                "SELECT * FROM foo UNION ALL SELECT * FROM bar",
+                &[("foo", "a")],
+            ),
+            ("SELECT `a.b` FROM foo", &[("", "a.b")]),
+            ("SELECT 1", &[("", "_1")]),
+        ] {
+            assert_eq!(None, find_query_of(query, columns), "{query}");
+        }
+    }
+
+    #[test]
+    fn columns_find_compares_differently_run_as_aggregation() {
+        for (query, bson_type) in [
+            // find matches the elements of arrays, and sorts arrays by their smallest element
+            ("SELECT a FROM foo WHERE a = 1", BsonTypeName::Array),
+            ("SELECT a FROM foo WHERE a IS NULL", BsonTypeName::Array),
+            ("SELECT a FROM foo ORDER BY a", BsonTypeName::Array),
+            // find compares values of different types by their type order
+            ("SELECT a FROM foo WHERE a > 1", BsonTypeName::Any),
+            ("SELECT a FROM foo ORDER BY a", BsonTypeName::Any),
+            ("SELECT a FROM foo WHERE a > '1'", BsonTypeName::Int),
+            ("SELECT a FROM foo WHERE a = 1", BsonTypeName::String),
+            ("SELECT a FROM foo WHERE a = 1", BsonTypeName::Object),
+        ] {
+            assert_eq!(
+                None,
+                find_query_of_types(query, &[("foo", "a", bson_type)]),
+                "{query}"
+            );
+        }
+        // The type of a field that is not a column of the result set is unknown.
+        assert_eq!(
+            None,
+            find_query_of("SELECT a FROM foo WHERE b = 1", &[("foo", "a")])
+        );
+    }
+}
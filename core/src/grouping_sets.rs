@@ -0,0 +1,316 @@
+use crate::{
+    err::{Error, Result},
+    lexer::{name_path, tokenize, Token},
+};
+
+// The name of the column telling the grouping sets of a ROLLUP or CUBE apart.
+pub const GROUPING_ID_COLUMN: &str = "grouping_id";
+
+// Every combination of the columns of a CUBE is a grouping set, the union grows exponentially.
+const MAX_CUBE_COLUMNS: usize = 8;
+
+// expand_grouping_sets rewrites a query grouped by ROLLUP or CUBE into the UNION ALL of the query
+// grouped by each of its grouping sets, since MongoSQL only groups by columns. The grouping
+// columns a grouping set leaves out are NULL, and the grouping_id column tells the grouping sets
+// apart: as with GROUPING_ID, the bit of each column left out is set, the first column being the
+// most significant bit. HAVING applies to each grouping set, ORDER BY, LIMIT and OFFSET to the
+// union. Queries without ROLLUP or CUBE are returned unchanged.
+pub fn expand_grouping_sets(query: &str) -> Result<String> {
+    let tokens = tokenize(query);
+    let groupings = (0..tokens.len().saturating_sub(3))
+        .filter(|&i| {
+            tokens[i].is_word("group")
+                && tokens[i + 1].is_word("by")
+                && ((tokens[i + 2].is_word("rollup") || tokens[i + 2].is_word("cube"))
+                    && tokens[i + 3].is_punct("(")
+                    || tokens[i + 2].is_word("grouping") && tokens[i + 3].is_word("sets"))
+        })
+        .collect::<Vec<_>>();
+    let Some(&group) = groupings.first() else {
+        return Ok(query.to_string());
+    };
+    let unsupported = |reason: &str| Err(Error::UnsupportedGrouping(reason.to_string()));
+    if tokens[group + 2].is_word("grouping") {
+        return unsupported("GROUPING SETS is not supported, use ROLLUP or CUBE");
+    }
+    if groupings.len() > 1 {
+        return unsupported("only one ROLLUP or CUBE is supported per query");
+    }
+    if tokens[group].depth > 0 {
+        return unsupported("ROLLUP and CUBE are only supported in the outermost query");
+    }
+    let top_level = || tokens.iter().enumerate().filter(|(_, t)| t.depth == 0);
+    if top_level().any(|(_, t)| t.is_word("union")) {
+        return unsupported("ROLLUP and CUBE cannot be combined with UNION");
+    }
+    let cube = tokens[group + 2].is_word("cube");
+
+    // The grouping columns, between the parentheses following ROLLUP or CUBE.
+    let open = group + 3;
+    let Some(close) =
+        (open + 1..tokens.len()).find(|&i| tokens[i].depth == 0 && tokens[i].is_punct(")"))
+    else {
+        return unsupported("the grouping columns are not closed by a parenthesis");
+    };
+    if tokens[open + 1..close].iter().any(|t| t.depth > 1) {
+        return unsupported("ROLLUP and CUBE only group by columns");
+    }
+    let columns = tokens[open + 1..close]
+        .split(|t| t.is_punct(","))
+        .collect::<Vec<_>>();
+    if columns.iter().any(|column| column_name(column).is_none()) {
+        return unsupported("ROLLUP and CUBE only group by columns");
+    }
+    if tokens.get(close + 1).is_some_and(|t| t.is_punct(",")) {
+        return unsupported("ROLLUP and CUBE cannot be combined with other grouping columns");
+    }
+
+    // The select list, between SELECT and FROM.
+    let Some((select, _)) = top_level().find(|(_, t)| t.is_word("select")) else {
+        return unsupported("ROLLUP and CUBE are only supported in SELECT queries");
+    };
+    let Some((from, _)) = top_level().find(|&(i, t)| i > select && t.is_word("from")) else {
+        return unsupported("a query grouped by ROLLUP or CUBE must have a FROM clause");
+    };
+    let mut list_start = select + 1;
+    if tokens[list_start].is_word("distinct") || tokens[list_start].is_word("all") {
+        list_start += 1;
+    }
+    let items = tokens[list_start..from]
+        .split(|t| t.depth == 0 && t.is_punct(","))
+        .map(|item| SelectItem::new(query, item))
+        .collect::<Result<Vec<_>>>()?;
+
+    // HAVING applies to each grouping set, the ORDER BY, LIMIT and OFFSET that follow to the union.
+    let order_start = top_level()
+        .find(|&(i, t)| {
+            i > close
+                && (t.is_word("order") && tokens.get(i + 1).is_some_and(|t| t.is_word("by"))
+                    || t.is_word("limit")
+                    || t.is_word("offset"))
+        })
+        .map_or(query.len(), |(_, t)| t.start);
+    let having = query[tokens[close].end..order_start].trim();
+    let order = query[order_start..].trim();
+
+    if cube && columns.len() > MAX_CUBE_COLUMNS {
+        return unsupported(&format!("CUBE is limited to {MAX_CUBE_COLUMNS} columns"));
+    }
+    let set_count = if cube {
+        1 << columns.len()
+    } else {
+        columns.len() + 1
+    };
+    let branches = (0..set_count)
+        .map(|set| {
+            // a ROLLUP leaves out its last columns one by one
+            let grouping_id = if cube { set } else { (1 << set) - 1 };
+            let left_out = |i: usize| grouping_id & (1 << (columns.len() - 1 - i)) != 0;
+            let select_list = items
+                .iter()
+                .map(|item| match &item.alias {
+                    Some(alias)
+                        if (0..columns.len())
+                            .any(|i| left_out(i) && item.column == column_name(columns[i])) =>
+                    {
+                        format!("NULL AS {alias}")
+                    }
+                    _ => item.text.to_string(),
+                })
+                .chain(std::iter::once(format!(
+                    "{grouping_id} AS {GROUPING_ID_COLUMN}"
+                )))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let grouped_by = (0..columns.len())
+                .filter(|&i| !left_out(i))
+                .map(|i| &query[columns[i][0].start..columns[i][columns[i].len() - 1].end])
+                .collect::<Vec<_>>();
+            let mut branch = format!(
+                "{} {select_list} {}",
+                &query[tokens[select].start..tokens[list_start].start].trim_end(),
+                query[tokens[from].start..tokens[group].start].trim_end()
+            );
+            if !grouped_by.is_empty() {
+                branch.push_str(&format!(" GROUP BY {}", grouped_by.join(", ")));
+            }
+            if !having.is_empty() {
+                branch.push_str(&format!(" {having}"));
+            }
+            branch
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    let expanded = if order.is_empty() {
+        branches
+    } else {
+        format!("SELECT * FROM ({branches}) AS grouping_sets {order}")
+    };
+    Ok(format!("{}{expanded}", &query[..tokens[select].start]))
+}
+
+// An expression of the select list, with the name of the column it is, if it is one, and the name
+// it is returned as.
+struct SelectItem<'a> {
+    text: &'a str,
+    column: Option<String>,
+    alias: Option<&'a str>,
+}
+
+impl<'a> SelectItem<'a> {
+    fn new(query: &'a str, tokens: &[Token<'a>]) -> Result<Self> {
+        let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+            return Err(Error::UnsupportedGrouping(
+                "the select list has an empty expression".to_string(),
+            ));
+        };
+        if last.is_punct("*") {
+            return Err(Error::UnsupportedGrouping(
+                "SELECT * cannot be grouped by ROLLUP or CUBE, list the columns instead"
+                    .to_string(),
+            ));
+        }
+        let (expression, alias) = match tokens {
+            [expression @ .., as_keyword, alias]
+                if !expression.is_empty() && as_keyword.is_word("as") =>
+            {
+                (expression, Some(alias.text))
+            }
+            _ => (tokens, None),
+        };
+        let column = column_name(expression);
+        Ok(SelectItem {
+            text: &query[first.start..last.end],
+            alias: alias.or_else(|| {
+                column
+                    .is_some()
+                    .then_some(expression[expression.len() - 1].text)
+            }),
+            column,
+        })
+    }
+}
+
+// column_name returns the name of the column a possibly qualified column reference such as
+// `foo.a` refers to, or None if the tokens are another expression. Names are case sensitive, as
+// the fields of documents are.
+fn column_name(tokens: &[Token]) -> Option<String> {
+    name_path(tokens).map(|path| path[path.len() - 1].clone())
+}
+
+#[cfg(test)]
+mod unit {
+    use super::expand_grouping_sets;
+
+    #[test]
+    fn query_without_rollup_is_unchanged() {
+        for query in [
+            "SELECT a, SUM(b) AS s FROM foo GROUP BY a",
+            "SELECT rollup FROM foo GROUP BY rollup",
+            "SELECT 'GROUP BY ROLLUP(a)' AS a FROM foo",
+        ] {
+            assert_eq!(query, expand_grouping_sets(query).unwrap());
+        }
+    }
+
+    #[test]
+    fn rollup_is_the_union_of_its_grouping_sets() {
+        assert_eq!(
+            "SELECT a, b, SUM(c) AS s, 0 AS grouping_id FROM foo WHERE c > 0 GROUP BY a, b \
+             UNION ALL SELECT a, NULL AS b, SUM(c) AS s, 1 AS grouping_id FROM foo WHERE c > 0 \
+             GROUP BY a \
+             UNION ALL SELECT NULL AS a, NULL AS b, SUM(c) AS s, 3 AS grouping_id FROM foo \
+             WHERE c > 0",
+            expand_grouping_sets(
+                "SELECT a, b, SUM(c) AS s FROM foo WHERE c > 0 GROUP BY ROLLUP(a, b)"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn cube_groups_by_every_combination_of_columns() {
+        assert_eq!(
+            "SELECT foo.a, b AS x, COUNT(*) AS n, 0 AS grouping_id FROM foo GROUP BY foo.a, b \
+             UNION ALL SELECT foo.a, NULL AS x, COUNT(*) AS n, 1 AS grouping_id FROM foo \
+             GROUP BY foo.a \
+             UNION ALL SELECT NULL AS a, b AS x, COUNT(*) AS n, 2 AS grouping_id FROM foo \
+             GROUP BY b \
+             UNION ALL SELECT NULL AS a, NULL AS x, COUNT(*) AS n, 3 AS grouping_id FROM foo",
+            expand_grouping_sets(
+                "SELECT foo.a, b AS x, COUNT(*) AS n FROM foo GROUP BY CUBE(foo.a, b)"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn columns_differing_in_case_are_different_columns() {
+        assert_eq!(
+            "SELECT a, A, 0 AS grouping_id FROM foo GROUP BY a \
+             UNION ALL SELECT NULL AS a, A, 1 AS grouping_id FROM foo",
+            expand_grouping_sets("SELECT a, A FROM foo GROUP BY ROLLUP(a)").unwrap()
+        );
+    }
+
+    #[test]
+    fn having_applies_to_each_grouping_set_and_order_by_to_the_union() {
+        assert_eq!(
+            "SELECT * FROM (\
+             SELECT DISTINCT a, COUNT(*) AS n, 0 AS grouping_id FROM foo GROUP BY a \
+             HAVING COUNT(*) > 1 \
+             UNION ALL SELECT DISTINCT NULL AS a, COUNT(*) AS n, 1 AS grouping_id FROM foo \
+             HAVING COUNT(*) > 1\
+             ) AS grouping_sets ORDER BY grouping_id, a LIMIT 10",
+            expand_grouping_sets(
+                "SELECT DISTINCT a, COUNT(*) AS n FROM foo GROUP BY ROLLUP (a) \
+                 HAVING COUNT(*) > 1 ORDER BY grouping_id, a LIMIT 10"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn unsupported_grouping_is_an_error() {
+        for (query, reason) in [
+            (
+                "SELECT a FROM foo GROUP BY GROUPING SETS ((a), ())",
+                "GROUPING SETS is not supported",
+            ),
+            (
+                "SELECT a FROM foo GROUP BY ROLLUP(a) UNION ALL SELECT a FROM bar GROUP BY CUBE(a)",
+                "only one ROLLUP or CUBE",
+            ),
+            (
+                "SELECT a, b FROM foo GROUP BY ROLLUP(a), CUBE(b)",
+                "cannot be combined with other grouping columns",
+            ),
+            (
+                "SELECT a, b FROM foo GROUP BY ROLLUP(a), b",
+                "cannot be combined with other grouping columns",
+            ),
+            (
+                "SELECT * FROM (SELECT a FROM foo GROUP BY ROLLUP(a)) AS bar",
+                "only supported in the outermost query",
+            ),
+            (
+                "SELECT a FROM foo GROUP BY ROLLUP(a) UNION ALL SELECT a FROM bar",
+                "cannot be combined with UNION",
+            ),
+            (
+                "SELECT a FROM foo GROUP BY ROLLUP((a, b))",
+                "only group by columns",
+            ),
+            (
+                "SELECT a FROM foo GROUP BY ROLLUP(a + 1)",
+                "only group by columns",
+            ),
+            ("SELECT * FROM foo GROUP BY ROLLUP(a)", "SELECT *"),
+        ] {
+            match expand_grouping_sets(query) {
+                Err(e) => assert!(e.to_string().contains(reason), "{query}: {e}"),
+                Ok(expanded) => panic!("{query} should be unsupported, expanded to {expanded}"),
+            }
+        }
+    }
+}
@@ -345,17 +345,21 @@ pub mod simplified {
         fn from(v: Schema) -> Self {
             match v {
                 Schema::Atomic(a) => a.into(),
-                Schema::AnyOf(b) => (b.len() == 2)
-                    .then(|| {
-                        let atomics = b
-                            .into_iter()
-                            .filter(|a| !matches!(a, Atomic::Scalar(BsonTypeName::Null)))
-                            .collect::<Vec<Atomic>>();
-                        (atomics.len() == 1)
-                            .then(|| atomics.first().unwrap().to_owned().into())
-                            .unwrap_or(BsonTypeInfo::BSON)
-                    })
-                    .unwrap_or(BsonTypeInfo::BSON),
+                Schema::AnyOf(b) => {
+                    let atomics = b
+                        .into_iter()
+                        .filter(|a| !matches!(a, Atomic::Scalar(BsonTypeName::Null)))
+                        .collect::<Vec<Atomic>>();
+                    match atomics.as_slice() {
+                        [atomic] => atomic.to_owned().into(),
+                        // A field holding both int and long values widens to long, which can
+                        // represent all of them.
+                        [Atomic::Scalar(BsonTypeName::Int), Atomic::Scalar(BsonTypeName::Long)] => {
+                            BsonTypeInfo::LONG
+                        }
+                        _ => BsonTypeInfo::BSON,
+                    }
+                }
             }
         }
     }
@@ -926,5 +930,30 @@ mod unit {
 
             assert_eq!(BsonTypeInfo::BSON, BsonTypeInfo::from(input));
         }
+
+        #[test]
+        fn any_of_int_and_long_widens_to_long() {
+            for types in [
+                vec![BsonTypeName::Int, BsonTypeName::Long],
+                vec![BsonTypeName::Long, BsonTypeName::Null, BsonTypeName::Int],
+            ] {
+                let input_schema = json_schema::Schema {
+                    any_of: Some(
+                        types
+                            .into_iter()
+                            .map(|t| json_schema::Schema {
+                                bson_type: Some(BsonType::Single(t)),
+                                ..Default::default()
+                            })
+                            .collect(),
+                    ),
+                    ..Default::default()
+                };
+
+                let input = simplified::Schema::try_from(input_schema).unwrap();
+
+                assert_eq!(BsonTypeInfo::LONG, BsonTypeInfo::from(input));
+            }
+        }
     }
 }
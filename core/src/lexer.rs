@@ -0,0 +1,120 @@
+// A minimal lexer of MongoSQL queries, for the few rewrites and analyses the driver makes before
+// sending a query to the server, which translates it.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TokenKind {
+    Word,
+    // A quoted identifier.
+    Identifier,
+    // A string literal.
+    Literal,
+    Punct,
+}
+
+// A token of a query, with its byte range and the number of parentheses it is nested in. The
+// parentheses themselves are at the depth of the expression they are in.
+#[derive(Debug)]
+pub(crate) struct Token<'a> {
+    pub(crate) kind: TokenKind,
+    pub(crate) text: &'a str,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) depth: usize,
+}
+
+impl Token<'_> {
+    pub(crate) fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+
+    pub(crate) fn is_punct(&self, punct: &str) -> bool {
+        self.kind == TokenKind::Punct && self.text == punct
+    }
+}
+
+// tokenize splits a query into words, quoted identifiers, string literals and punctuation.
+// Whitespace and comments are skipped.
+pub(crate) fn tokenize(query: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut chars = query.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let (kind, end) = match c {
+            '\'' | '"' | '`' => {
+                let mut end = query.len();
+                // a doubled quote is an escaped quote and does not end the literal
+                while let Some((i, q)) = chars.next() {
+                    if q == c && chars.next_if(|&(_, n)| n == c).is_none() {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                let kind = if c == '\'' {
+                    TokenKind::Literal
+                } else {
+                    TokenKind::Identifier
+                };
+                (kind, end)
+            }
+            '-' if chars.next_if(|&(_, n)| n == '-').is_some() => {
+                chars.by_ref().find(|&(_, n)| n == '\n');
+                continue;
+            }
+            '/' if chars.next_if(|&(_, n)| n == '*').is_some() => {
+                let mut previous = ' ';
+                for (_, n) in chars.by_ref() {
+                    if previous == '*' && n == '/' {
+                        break;
+                    }
+                    previous = n;
+                }
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, n)) = chars.next_if(|&(_, n)| n.is_alphanumeric() || n == '_') {
+                    end = i + n.len_utf8();
+                }
+                (TokenKind::Word, end)
+            }
+            c => (TokenKind::Punct, start + c.len_utf8()),
+        };
+        if c == ')' {
+            depth = usize::saturating_sub(depth, 1);
+        }
+        tokens.push(Token {
+            kind,
+            text: &query[start..end],
+            start,
+            end,
+            depth,
+        });
+        if c == '(' {
+            depth += 1;
+        }
+    }
+    tokens
+}
+
+// name_path returns the unquoted parts of a possibly qualified name such as `foo.a`, or None if
+// the tokens are not a name.
+pub(crate) fn name_path(tokens: &[Token]) -> Option<Vec<String>> {
+    if tokens.len() % 2 != 1 || tokens.iter().skip(1).step_by(2).any(|t| !t.is_punct(".")) {
+        return None;
+    }
+    tokens
+        .iter()
+        .step_by(2)
+        .map(|t| match t.kind {
+            TokenKind::Word => Some(t.text.to_string()),
+            TokenKind::Identifier => {
+                // a doubled quote is an escaped quote
+                let quote = &t.text[..1];
+                let name = t.text[1..].strip_suffix(quote).unwrap_or(&t.text[1..]);
+                Some(name.replace(&quote.repeat(2), quote))
+            }
+            _ => None,
+        })
+        .collect()
+}
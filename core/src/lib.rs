@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+mod base_names;
 mod bson_type_info;
 use bson_type_info::BsonTypeInfo;
 pub use bson_type_info::TypeMode;
@@ -8,23 +9,38 @@ pub use bson_type_info::SQL_SEARCHABLE;
 mod collections;
 pub use collections::MongoCollections;
 mod conn;
-pub use conn::MongoConnection;
+pub use conn::{MongoConnection, ServerCapabilities, ServerWarnings};
+mod cte;
+pub use cte::inline_ctes;
 mod databases;
 pub use databases::MongoDatabases;
+mod distinct_from;
+pub use distinct_from::translate_distinct_from;
+mod datetime_literal;
+pub use datetime_literal::translate_datetime_literals;
+mod densify;
 mod table_types;
 pub use table_types::MongoTableTypes;
 mod err;
 pub use err::{Error, Result};
 mod fields;
 pub use fields::MongoFields;
+mod find;
+mod grouping_sets;
+mod lexer;
+pub use grouping_sets::{expand_grouping_sets, GROUPING_ID_COLUMN};
 pub mod col_metadata;
 pub mod json_schema;
 pub use col_metadata::MongoColMetadata;
 mod query;
-pub use query::MongoQuery;
+mod result_cache;
+pub use query::{has_unordered_limit, MongoQuery, ROW_AS_JSON_COLUMN};
+pub use result_cache::{ResultCache, ResultKey};
 pub mod mock_query;
+mod objectid_timestamp;
+pub use objectid_timestamp::translate_objectid_timestamp;
 mod stmt;
-pub use stmt::MongoStatement;
+pub use stmt::{CommandOptions, MongoStatement};
 pub mod odbc_uri;
 mod primary_keys;
 mod type_info;
@@ -33,4 +49,18 @@ pub mod util;
 pub use primary_keys::MongoPrimaryKeys;
 mod foreign_keys;
 pub use foreign_keys::MongoForeignKeys;
+mod procedures;
+pub use procedures::MongoProcedures;
+mod procedure_columns;
+pub use procedure_columns::MongoProcedureColumns;
+mod special_columns;
+pub use special_columns::MongoSpecialColumns;
+mod change_stream;
+pub use change_stream::{change_stream_target, MongoChangeStream};
+mod explain;
+pub use explain::{explain_analyze_target, MongoExplain};
+mod session;
+pub use session::{MongoSet, SessionVariable};
+mod statistics;
+pub use statistics::MongoStatistics;
 pub mod oidc_auth;
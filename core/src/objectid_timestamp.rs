@@ -0,0 +1,87 @@
+use crate::lexer::tokenize;
+
+const OBJECTID_TIMESTAMP: &str = "objectid_timestamp";
+
+// translate_objectid_timestamp rewrites the `OBJECTID_TIMESTAMP(expr)` calls of a query into
+// `CAST(expr AS TIMESTAMP)`. MongoSQL translates the cast to $convert, which reads the creation
+// time embedded in an ObjectId, so the function may be used wherever an expression may, in the
+// select list as in predicates. A call that does not have exactly one argument is left as is for
+// the server to report.
+pub fn translate_objectid_timestamp(query: &str) -> String {
+    let tokens = tokenize(query);
+    let mut translated = String::with_capacity(query.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i + 1 < tokens.len() {
+        let (name, open) = (&tokens[i], &tokens[i + 1]);
+        if !name.is_word(OBJECTID_TIMESTAMP) || !open.is_punct("(") {
+            i += 1;
+            continue;
+        }
+        let Some(close) = (i + 2..tokens.len())
+            .find(|&j| tokens[j].depth == open.depth && tokens[j].is_punct(")"))
+        else {
+            break;
+        };
+        let arguments = &tokens[i + 2..close];
+        let single_argument = !arguments.is_empty()
+            && !arguments
+                .iter()
+                .any(|t| t.depth == open.depth + 1 && t.is_punct(","));
+        if !single_argument {
+            i += 1;
+            continue;
+        }
+        let argument = &query[arguments[0].start..arguments[arguments.len() - 1].end];
+        translated.push_str(&query[copied..name.start]);
+        translated.push_str(&format!(
+            "CAST({} AS TIMESTAMP)",
+            translate_objectid_timestamp(argument)
+        ));
+        copied = tokens[close].end;
+        i = close + 1;
+    }
+    translated.push_str(&query[copied..]);
+    translated
+}
+
+#[cfg(test)]
+mod unit {
+    use super::translate_objectid_timestamp;
+
+    #[test]
+    fn calls_are_translated_to_timestamp_casts() {
+        assert_eq!(
+            "SELECT CAST(_id AS TIMESTAMP) AS created FROM foo \
+             WHERE CAST(foo._id AS TIMESTAMP) >= CAST('2020-01-31T00:00:00.000Z' AS TIMESTAMP)",
+            translate_objectid_timestamp(
+                "SELECT OBJECTID_TIMESTAMP(_id) AS created FROM foo \
+                 WHERE ObjectId_Timestamp( foo._id ) >= CAST('2020-01-31T00:00:00.000Z' AS TIMESTAMP)"
+            )
+        );
+    }
+
+    #[test]
+    fn nested_calls_are_translated() {
+        assert_eq!(
+            "SELECT EXTRACT(YEAR FROM CAST(COALESCE(a, CAST(b AS TIMESTAMP)) AS TIMESTAMP)) FROM foo",
+            translate_objectid_timestamp(
+                "SELECT EXTRACT(YEAR FROM objectid_timestamp(COALESCE(a, objectid_timestamp(b)))) FROM foo"
+            )
+        );
+    }
+
+    #[test]
+    fn other_text_is_unchanged() {
+        for query in [
+            "SELECT objectid_timestamp FROM foo",
+            "SELECT 'OBJECTID_TIMESTAMP(_id)', `objectid_timestamp`(_id) FROM foo",
+            "SELECT a FROM foo -- OBJECTID_TIMESTAMP(_id)",
+            "SELECT OBJECTID_TIMESTAMP() FROM foo",
+            "SELECT OBJECTID_TIMESTAMP(a, b) FROM foo",
+            "SELECT OBJECTID_TIMESTAMP(a FROM foo",
+        ] {
+            assert_eq!(query, translate_objectid_timestamp(query));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{col_metadata::MongoColMetadata, stmt::EmptyStatement, BsonTypeInfo};
+use definitions::Nullability;
+use once_cell::sync::OnceCell;
+
+static PROCEDURE_COLUMNS_METADATA: OnceCell<Vec<MongoColMetadata>> = OnceCell::new();
+
+pub struct MongoProcedureColumns {}
+
+impl MongoProcedureColumns {
+    pub fn empty(max_string_length: Option<u16>) -> EmptyStatement {
+        EmptyStatement {
+            resultset_metadata: PROCEDURE_COLUMNS_METADATA.get_or_init(|| {
+                vec![
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "PROCEDURE_CAT".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "PROCEDURE_SCHEM".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "PROCEDURE_NAME".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "COLUMN_NAME".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "COLUMN_TYPE".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "DATA_TYPE".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "TYPE_NAME".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "COLUMN_SIZE".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "BUFFER_LENGTH".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "DECIMAL_DIGITS".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "NUM_PREC_RADIX".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "NULLABLE".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "REMARKS".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "COLUMN_DEF".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "SQL_DATA_TYPE".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "SQL_DATETIME_SUB".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "CHAR_OCTET_LENGTH".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "ORDINAL_POSITION".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "IS_NULLABLE".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                ]
+            }),
+        }
+    }
+}
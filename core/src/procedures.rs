@@ -0,0 +1,82 @@
+use crate::{col_metadata::MongoColMetadata, stmt::EmptyStatement, BsonTypeInfo};
+use definitions::Nullability;
+use once_cell::sync::OnceCell;
+
+static PROCEDURES_METADATA: OnceCell<Vec<MongoColMetadata>> = OnceCell::new();
+
+pub struct MongoProcedures {}
+
+impl MongoProcedures {
+    pub fn empty(max_string_length: Option<u16>) -> EmptyStatement {
+        EmptyStatement {
+            resultset_metadata: PROCEDURES_METADATA.get_or_init(|| {
+                vec![
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "PROCEDURE_CAT".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "PROCEDURE_SCHEM".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "PROCEDURE_NAME".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NO_NULLS,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "NUM_INPUT_PARAMS".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "NUM_OUTPUT_PARAMS".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "NUM_RESULT_SETS".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "REMARKS".to_string(),
+                        BsonTypeInfo::STRING,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                    MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        "PROCEDURE_TYPE".to_string(),
+                        BsonTypeInfo::INT,
+                        max_string_length,
+                        Nullability::SQL_NULLABLE,
+                    ),
+                ]
+            }),
+        }
+    }
+}
@@ -1,19 +1,35 @@
 use crate::{
+    base_names::apply_base_names,
     col_metadata::{MongoColMetadata, SqlGetSchemaResponse},
     conn::MongoConnection,
+    densify::split_densify,
     err::Result,
-    stmt::MongoStatement,
-    Error, TypeMode,
+    find::{find_query, FindQuery},
+    result_cache::{CachedResult, ResultKey, MAX_CACHED_RESULT_BYTES, MAX_CACHED_ROWS},
+    stmt::{CommandOptions, MongoStatement},
+    util::is_case_insensitive_collation,
+    BsonTypeInfo, Error, TypeMode,
 };
 use bson::{doc, document::ValueAccessError, Bson, Document};
+use definitions::{Nullability, SqlDataType};
 use mongodb::{
     error::{CommandError, ErrorKind},
-    options::AggregateOptions,
-    Cursor,
+    options::{AggregateOptions, Collation, FindOptions, Hint},
+    Collection, Cursor, Database,
+};
+use once_cell::sync::OnceCell;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    sync::Arc,
+    time::Duration,
 };
-use std::time::Duration;
 
 const BATCH_SIZE_REPLACEMENT_THRESHOLD: u32 = 100;
+// The name of the only column of a query run with ROW_AS_JSON=1.
+pub const ROW_AS_JSON_COLUMN: &str = "json";
+
+static ROW_AS_JSON_METADATA: OnceCell<Vec<MongoColMetadata>> = OnceCell::new();
 
 #[derive(Debug)]
 pub struct MongoQuery {
@@ -23,16 +39,32 @@ pub struct MongoQuery {
     resultset_metadata: Vec<MongoColMetadata>,
     // The current deserialized "row".
     current: Option<Document>,
+    // The rows of the cached result the query is answered from.
+    buffered_rows: Option<VecDeque<Document>>,
+    // The rows read from the cursor so far and their size in bytes, while the result is small
+    // enough to be cached once the cursor is exhausted.
+    rows_to_cache: Option<(Vec<Document>, usize)>,
+    // The cached result the query was prepared from, if any.
+    cached_result: Option<Arc<CachedResult>>,
     // The current database
     pub current_db: Option<String>,
     // The query
     pub query: String,
     // The query timeout
     pub query_timeout: Option<u32>,
+    // The type mode and maximum string length the result set metadata is built with.
+    type_mode: TypeMode,
+    max_string_length: Option<u16>,
+    // Whether every row is returned as a single JSON column instead of one column per field.
+    row_as_json: bool,
+    // The find command the query runs as, if it does not need the $sql aggregation.
+    find: Option<FindQuery>,
 }
 
 impl MongoQuery {
-    // Create a MongoQuery with only the resultset_metadata.
+    // Create a MongoQuery with only the resultset_metadata. With row_as_json, every row is returned
+    // as a single ROW_AS_JSON_COLUMN column holding the whole row document, rather than one column
+    // per field of the result set.
     pub fn prepare(
         client: &MongoConnection,
         current_db: Option<String>,
@@ -40,41 +72,459 @@ impl MongoQuery {
         query: &str,
         type_mode: TypeMode,
         max_string_length: Option<u16>,
+        row_as_json: bool,
     ) -> Result<Self> {
         let current_db = current_db.ok_or(Error::NoDatabase)?;
+        // The DENSIFY clause is not MongoSQL, the server only translates the rest of the query.
+        let (sql, densify) = split_densify(query)?;
+        if let Some(densify) = densify.as_ref() {
+            densify.check_capabilities(&client.capabilities)?;
+        }
+        let result_key = ResultKey {
+            db: current_db.clone(),
+            query: query.to_string(),
+            type_mode,
+            max_string_length,
+            row_as_json,
+        };
+        if let Some(cached_result) = client
+            .result_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&result_key))
+        {
+            return Ok(Self {
+                resultset_cursor: None,
+                resultset_metadata: cached_result.metadata.clone(),
+                current: None,
+                buffered_rows: None,
+                rows_to_cache: None,
+                cached_result: Some(cached_result),
+                current_db: Some(current_db),
+                query: query.to_string(),
+                query_timeout,
+                type_mode,
+                max_string_length,
+                row_as_json,
+                find: None,
+            });
+        }
         let db = client.client.database(&current_db);
 
         // 1. Run the sqlGetResultSchema command to get the result set
         // metadata. Column metadata is sorted alphabetically by table
         // and column name.
         let get_result_schema_cmd =
-            doc! {"sqlGetResultSchema": 1, "query": query, "schemaVersion": 1};
+            doc! {"sqlGetResultSchema": 1, "query": sql, "schemaVersion": 1};
 
         let guard = client.runtime.enter();
         let schema_response = client.runtime.block_on(async {
-            db.run_command(get_result_schema_cmd, None)
-                .await
-                .map_err(Error::QueryExecutionFailed)
+            with_command_retry(client.retry_reads, || {
+                db.run_command(get_result_schema_cmd.clone(), None)
+            })
+            .await
+            .map_err(Error::QueryExecutionFailed)
         })?;
         drop(guard);
         let get_result_schema_response: SqlGetSchemaResponse =
             bson::from_document(schema_response).map_err(Error::QueryDeserialization)?;
 
-        let metadata = get_result_schema_response.process_result_metadata(
+        let mut metadata = get_result_schema_response.process_result_metadata(
             &current_db,
             type_mode,
             max_string_length,
         )?;
+        apply_base_names(&mut metadata, sql);
+        // The DENSIFY stages are built when the query runs on the server, which a cached result
+        // does not. The clause is only checked against the result set here.
+        if let Some(densify) = densify.as_ref() {
+            densify.stages(&metadata)?;
+        }
+        // Gaps are only filled by the aggregation.
+        let find = (!client.force_aggregate && densify.is_none())
+            .then(|| find_query(sql, &metadata))
+            .flatten();
+        match client.collation.as_ref() {
+            // The collation the query runs with overrides the default collations of the
+            // collections.
+            Some(collation) => apply_query_collation(&mut metadata, collation),
+            None => {
+                let collations = cached_collection_collations(client, &db, &metadata);
+                apply_collection_collations(&mut metadata, &collations);
+            }
+        }
 
         Ok(Self {
             resultset_cursor: None,
             resultset_metadata: metadata,
             current: None,
+            buffered_rows: None,
+            rows_to_cache: None,
+            cached_result: None,
             current_db: Some(current_db),
             query: query.to_string(),
             query_timeout,
+            type_mode,
+            max_string_length,
+            row_as_json,
+            find,
+        })
+    }
+
+    // read_collections returns the collections the query reads: the collection of its find
+    // command, or else the base tables of its result set columns.
+    fn read_collections(
+        &self,
+        connection: &MongoConnection,
+        current_db: &str,
+    ) -> Vec<Collection<Document>> {
+        match self.find.as_ref() {
+            Some(find) => vec![connection
+                .client
+                .database(find.database.as_deref().unwrap_or(current_db))
+                .collection(&find.collection)],
+            None => {
+                let db = connection.client.database(current_db);
+                self.resultset_metadata
+                    .iter()
+                    .map(|col| col.base_table_name.as_str())
+                    .filter(|name| !name.is_empty())
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .map(|name| db.collection(name))
+                    .collect()
+            }
+        }
+    }
+
+    // result_key returns the key of the result of the query on the database in the result cache.
+    fn result_key(&self, current_db: &str) -> ResultKey {
+        ResultKey {
+            db: current_db.to_string(),
+            query: self.query.clone(),
+            type_mode: self.type_mode,
+            max_string_length: self.max_string_length,
+            row_as_json: self.row_as_json,
+        }
+    }
+
+    // result_row returns the row of the result set of a document of the cursor.
+    fn result_row(&self, document: Document) -> Document {
+        match self.find.as_ref() {
+            Some(find) => find.row(&document),
+            None => document,
+        }
+    }
+}
+
+// row_document merges the datasources of a result row into a single document, so that a row of
+// `SELECT * FROM foo` is the foo document itself.
+fn row_document(row: &Document) -> Document {
+    let mut document = Document::new();
+    for (datasource, fields) in row {
+        match fields {
+            Bson::Document(fields) => document.extend(fields.clone()),
+            other => {
+                document.insert(datasource, other.clone());
+            }
+        }
+    }
+    document
+}
+
+// has_unordered_limit returns whether the query applies a LIMIT without an ORDER BY in the same
+// query block (the top level or a parenthesized subquery). MongoDB does not guarantee any order
+// without a sort, so such a query returns an arbitrary set of rows. String literals, quoted
+// identifiers and comments are skipped. This is a lexical check only, the query is translated by
+// the server.
+pub fn has_unordered_limit(query: &str) -> bool {
+    // (has ORDER BY, has LIMIT) for each open query block
+    let mut blocks = vec![(false, false)];
+    let mut unordered_limit = false;
+    let mut previous_word = String::new();
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                // a doubled quote is an escaped quote and does not end the literal
+                while let Some(q) = chars.next() {
+                    if q == c && chars.next_if_eq(&c).is_none() {
+                        break;
+                    }
+                }
+                previous_word.clear();
+            }
+            '-' if chars.next_if_eq(&'-').is_some() => {
+                chars.by_ref().find(|&n| n == '\n');
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                for n in chars.by_ref() {
+                    if previous == '*' && n == '/' {
+                        break;
+                    }
+                    previous = n;
+                }
+            }
+            '(' => {
+                blocks.push((false, false));
+                previous_word.clear();
+            }
+            ')' => {
+                if blocks.len() > 1 {
+                    let (ordered, limited) = blocks.pop().unwrap();
+                    unordered_limit |= limited && !ordered;
+                }
+                previous_word.clear();
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(n) = chars.next_if(|n| n.is_alphanumeric() || *n == '_') {
+                    word.push(n);
+                }
+                let block = blocks.last_mut().unwrap();
+                if word.eq_ignore_ascii_case("by") && previous_word.eq_ignore_ascii_case("order") {
+                    block.0 = true;
+                } else if word.eq_ignore_ascii_case("limit") {
+                    block.1 = true;
+                }
+                previous_word = word;
+            }
+            _ => {}
+        }
+    }
+    blocks
+        .into_iter()
+        .fold(unordered_limit, |acc, (ordered, limited)| {
+            acc || (limited && !ordered)
         })
+}
+
+// cached_collection_collations returns the default collation of each collection the
+// case-sensitive columns of the result set come from. The collations are read from the server the
+// first time a collection is queried on the connection only.
+fn cached_collection_collations(
+    client: &MongoConnection,
+    db: &Database,
+    metadata: &[MongoColMetadata],
+) -> HashMap<String, Document> {
+    let table_names = metadata
+        .iter()
+        .filter(|col| col.case_sensitive)
+        .map(|col| (db.name().to_string(), col.table_name.clone()))
+        .collect::<HashSet<_>>();
+    let missing = {
+        let cache = client.collection_collations.lock().unwrap();
+        table_names
+            .iter()
+            .filter(|name| !cache.contains_key(*name))
+            .map(|(_, table_name)| table_name.clone())
+            .collect::<Vec<_>>()
+    };
+    if !missing.is_empty() {
+        if let Some(mut collations) = client
+            .runtime
+            .block_on(collection_collations(db, missing.clone()))
+        {
+            let mut cache = client.collection_collations.lock().unwrap();
+            for table_name in missing {
+                let collation = collations.remove(&table_name);
+                cache.insert((db.name().to_string(), table_name), collation);
+            }
+        }
     }
+    let cache = client.collection_collations.lock().unwrap();
+    table_names
+        .into_iter()
+        .filter_map(|name| Some((name.1.clone(), cache.get(&name)?.clone()?)))
+        .collect()
+}
+
+// collection_collations returns the default collation of the collections with the given names
+// that have one. Datasources that are not collections of the current database, for instance
+// aliases or derived tables, are simply not found. Failing to list the collections is not an
+// error, the columns just keep the default binary collation.
+async fn collection_collations(
+    db: &Database,
+    table_names: Vec<String>,
+) -> Option<HashMap<String, Document>> {
+    let filter = doc! {"name": {"$in": table_names}};
+    let response = match db
+        .run_command(
+            doc! {"listCollections": 1, "filter": filter, "authorizedCollections": true},
+            None,
+        )
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            log::warn!("Unable to get the collations of the queried collections: {e}");
+            return None;
+        }
+    };
+    Some(
+        response
+            .get_document("cursor")
+            .and_then(|cursor| cursor.get_array("firstBatch"))
+            .map(|batch| {
+                batch
+                    .iter()
+                    .filter_map(Bson::as_document)
+                    .filter_map(|coll| {
+                        let name = coll.get_str("name").ok()?;
+                        let collation =
+                            coll.get_document("options").ok()?.get_document("collation");
+                        Some((name.to_string(), collation.ok()?.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    )
+}
+
+// apply_collection_collations marks the columns of collections with a case-insensitive default
+// collation as not case-sensitive, since their string comparisons ignore case.
+fn apply_collection_collations(
+    metadata: &mut [MongoColMetadata],
+    collations: &HashMap<String, Document>,
+) {
+    for col in metadata.iter_mut() {
+        if collations
+            .get(&col.table_name)
+            .is_some_and(is_case_insensitive_collation)
+        {
+            col.case_sensitive = false;
+        }
+    }
+}
+
+// apply_query_collation marks every column as not case-sensitive when the collation the query runs
+// with ignores case.
+fn apply_query_collation(metadata: &mut [MongoColMetadata], collation: &Collation) {
+    if bson::to_document(collation).is_ok_and(|collation| is_case_insensitive_collation(&collation))
+    {
+        for col in metadata.iter_mut() {
+            col.case_sensitive = false;
+        }
+    }
+}
+
+// with_command_retry runs the given command, and runs it one more time if retry_reads is set and
+// the first attempt failed with a network error, so that a momentary network blip does not surface
+// to the application. The driver clears the connection pool after a network error, so the second
+// attempt runs on a new connection. Any other error, or a second network error, is returned as is.
+// Only sqlGetResultSchema goes through it: the driver already retries aggregate and find under
+// retryReads, but not commands sent with run_command.
+async fn with_command_retry<T, F, Fut>(
+    retry_reads: bool,
+    mut command: F,
+) -> mongodb::error::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = mongodb::error::Result<T>>,
+{
+    match command().await {
+        Err(e) if retry_reads && is_network_error(&e) => {
+            log::warn!("Retrying command after network error: {e}");
+            command().await
+        }
+        res => res,
+    }
+}
+
+fn is_network_error(e: &mongodb::error::Error) -> bool {
+    matches!(
+        *e.kind,
+        ErrorKind::Io(_) | ErrorKind::ConnectionPoolCleared { .. }
+    )
+}
+
+// command_comment returns the comment of the commands run by a statement. The statement id is
+// always part of it, so that SQLCancel can find the operations of the statement.
+pub(crate) fn command_comment(stmt_id: Bson, command_options: &CommandOptions) -> Bson {
+    match command_options.comment.as_ref() {
+        Some(comment) => doc! {"statementId": stmt_id, "comment": comment}.into(),
+        None => stmt_id,
+    }
+}
+
+// aggregate_options builds the options of the $sql aggregation.
+fn aggregate_options(
+    stmt_id: Bson,
+    query_timeout: Option<u32>,
+    rowset_size: u32,
+    batch_size: Option<u32>,
+    collation: Option<Collation>,
+    command_options: &CommandOptions,
+) -> AggregateOptions {
+    // If the query timeout is 0, it means "no timeout"
+    let max_time = query_timeout
+        .filter(|&timeout| timeout > 0)
+        .map(|timeout| Duration::from_millis(u64::from(timeout)));
+    // A batch size of 0 leaves it to the server. If no batch size is set and rowset_size is large,
+    // then update the batch_size to be rowset_size for better efficiency.
+    let batch_size = match batch_size {
+        Some(0) => None,
+        Some(batch_size) => Some(batch_size),
+        None => (rowset_size > BATCH_SIZE_REPLACEMENT_THRESHOLD).then_some(rowset_size),
+    };
+    AggregateOptions::builder()
+        .comment_bson(Some(command_comment(stmt_id, command_options)))
+        .max_time(max_time)
+        .batch_size(batch_size)
+        .hint(command_options.hint.clone())
+        .collation(collation)
+        .build()
+}
+
+// hint_is_unknown returns whether the indexes of the collections were listed and none of them
+// matches the hint, either by name or by key specification. A query whose collections are
+// unknown, or that reads a collection whose indexes cannot be listed like a view, leaves the hint
+// to the server.
+async fn hint_is_unknown(collections: &[Collection<Document>], hint: &Hint) -> Result<bool> {
+    for collection in collections {
+        let Ok(mut indexes) = collection.list_indexes(None).await else {
+            return Ok(false);
+        };
+        while indexes.advance().await.map_err(Error::QueryCursorUpdate)? {
+            let index = indexes
+                .deserialize_current()
+                .map_err(Error::QueryCursorUpdate)?;
+            let matches = match hint {
+                Hint::Name(name) => index
+                    .options
+                    .and_then(|options| options.name)
+                    .is_some_and(|index_name| &index_name == name),
+                Hint::Keys(keys) => &index.keys == keys,
+                _ => false,
+            };
+            if matches {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(!collections.is_empty())
+}
+
+// find_options builds the options of the find command of a query, which runs with the options
+// of the $sql aggregation it replaces.
+fn find_options(find: &FindQuery, aggregate_options: AggregateOptions) -> FindOptions {
+    FindOptions::builder()
+        .comment_bson(aggregate_options.comment_bson)
+        .max_time(aggregate_options.max_time)
+        .batch_size(aggregate_options.batch_size)
+        .hint(aggregate_options.hint)
+        // Without a collation, find compares strings with the default collation of the collection,
+        // which the $sql aggregation does not.
+        .collation(
+            aggregate_options
+                .collation
+                .unwrap_or_else(|| Collation::builder().locale("simple").build()),
+        )
+        .projection(find.projection.clone())
+        .sort(find.sort.clone())
+        .skip(find.skip)
+        .limit(find.limit)
+        .build()
 }
 
 impl MongoStatement for MongoQuery {
@@ -82,6 +532,16 @@ impl MongoStatement for MongoQuery {
     // Return true if moving was successful, false otherwise.
     // This method deserializes the current row and stores it in self.
     fn next(&mut self, connection: Option<&MongoConnection>) -> Result<(bool, Vec<Error>)> {
+        if let Some(rows) = self.buffered_rows.as_mut() {
+            if let Some(row) = rows.pop_front() {
+                self.current = Some(row);
+                return Ok((true, vec![]));
+            }
+            if self.resultset_cursor.is_none() {
+                self.current = None;
+                return Ok((false, vec![]));
+            }
+        }
         let guard = connection.unwrap().runtime.enter();
         let res = self
             .resultset_cursor
@@ -97,15 +557,36 @@ impl MongoStatement for MongoQuery {
         // Calling Cursor::deserialize_current after Cursor::advance does not return true or without
         // calling Cursor::advance at all may result in a panic
         if res {
-            self.current = Some(
-                self.resultset_cursor
-                    .as_ref()
-                    .unwrap()
-                    .deserialize_current()
-                    .map_err(Error::QueryCursorUpdate)?,
-            );
+            let cursor = self.resultset_cursor.as_ref().unwrap();
+            let size = cursor.current().as_bytes().len();
+            let document = cursor
+                .deserialize_current()
+                .map_err(Error::QueryCursorUpdate)?;
+            let row = self.result_row(document);
+            if let Some((rows, rows_size)) = self.rows_to_cache.as_mut() {
+                *rows_size += size;
+                if rows.len() < MAX_CACHED_ROWS && *rows_size <= MAX_CACHED_RESULT_BYTES {
+                    rows.push(row.clone());
+                } else {
+                    // The result is too large to be cached, it is only streamed.
+                    self.rows_to_cache = None;
+                }
+            }
+            self.current = Some(row);
         } else {
             self.current = None;
+            // The whole result was read, it can be cached.
+            if let (Some((rows, _)), Some(cache), Some(current_db)) = (
+                self.rows_to_cache.take(),
+                connection.unwrap().result_cache.as_ref(),
+                self.current_db.as_ref(),
+            ) {
+                cache.insert(
+                    self.result_key(current_db),
+                    self.resultset_metadata.clone(),
+                    rows,
+                );
+            }
         }
 
         Ok((res, vec![]))
@@ -115,6 +596,12 @@ impl MongoStatement for MongoQuery {
     // Fails if the first row as not been retrieved (next must be called at least once before getValue).
     fn get_value(&self, col_index: u16, max_string_length: Option<u16>) -> Result<Option<Bson>> {
         let current = self.current.as_ref().ok_or(Error::InvalidCursorState)?;
+        if self.row_as_json {
+            return match col_index {
+                1 => Ok(Some(Bson::Document(row_document(current)))),
+                _ => Err(Error::ColIndexOutOfBounds(col_index)),
+            };
+        }
         let md = self
             .get_col_metadata(col_index, max_string_length)
             .map_err(|_| Error::ColIndexOutOfBounds(col_index))?;
@@ -126,6 +613,22 @@ impl MongoStatement for MongoQuery {
     }
 
     fn get_resultset_metadata(&self, _: Option<u16>) -> &Vec<MongoColMetadata> {
+        if self.row_as_json {
+            return ROW_AS_JSON_METADATA.get_or_init(|| {
+                vec![MongoColMetadata {
+                    sql_type: SqlDataType::SQL_WLONGVARCHAR,
+                    non_concise_type: SqlDataType::SQL_WLONGVARCHAR,
+                    ..MongoColMetadata::new_metadata_from_bson_type_info_default(
+                        "",
+                        "".to_string(),
+                        ROW_AS_JSON_COLUMN.to_string(),
+                        BsonTypeInfo::OBJECT,
+                        None,
+                        Nullability::SQL_NO_NULLS,
+                    )
+                }]
+            });
+        }
         &self.resultset_metadata
     }
 
@@ -137,31 +640,56 @@ impl MongoStatement for MongoQuery {
         connection: &MongoConnection,
         stmt_id: Bson,
         rowset_size: u32,
+        command_options: &CommandOptions,
     ) -> Result<bool> {
         let current_db = self.current_db.as_ref().ok_or(Error::NoDatabase)?;
+        // A hint or a comment is meant for the server, so a statement that sets one always runs
+        // there and its result is not cached.
+        let use_cache = command_options.hint.is_none() && command_options.comment.is_none();
+        let prepared_result = self.cached_result.take();
+        let cached_result = match connection.result_cache.as_ref() {
+            Some(cache) if use_cache => {
+                prepared_result.or_else(|| cache.get(&self.result_key(current_db)))
+            }
+            _ => None,
+        };
+        if let Some(cached_result) = cached_result {
+            self.buffered_rows = Some(cached_result.rows.iter().cloned().collect());
+            self.resultset_cursor = None;
+            return Ok(true);
+        }
         let db = connection.client.database(current_db);
+        let _guard = connection.runtime.enter();
 
-        // 2. Run the $sql aggregation to get the result set cursor.
-        let pipeline = vec![doc! {"$sql": {
-            "statement": &self.query,
-        }}];
-
-        let opt = AggregateOptions::builder().comment_bson(Some(stmt_id));
-
-        let mut max_time = None;
-        // If the query timeout is 0, it means "no timeout"
-        if self.query_timeout.is_some_and(|timeout| timeout > 0) {
-            max_time = Some(Duration::from_millis(u64::from(
-                self.query_timeout.unwrap(),
-            )))
+        if let Some(hint) = command_options.hint.as_ref() {
+            let collections = self.read_collections(connection, current_db);
+            if connection
+                .runtime
+                .block_on(hint_is_unknown(&collections, hint))?
+            {
+                return Err(Error::InvalidHint(
+                    bson::to_bson(hint).unwrap_or_default().to_string(),
+                ));
+            }
         }
 
-        let mut batch_size = None;
-        // If rowset_size is large, then update the batch_size to be rowset_size for better efficiency.
-        if rowset_size > BATCH_SIZE_REPLACEMENT_THRESHOLD {
-            batch_size = Some(rowset_size)
+        // 2. Run the $sql aggregation, or the find command the query is equivalent to, to get the
+        // result set cursor.
+        let (sql, densify) = split_densify(&self.query)?;
+        let mut pipeline = vec![doc! {"$sql": {
+            "statement": sql,
+        }}];
+        if let Some(densify) = densify {
+            pipeline.extend(densify.stages(&self.resultset_metadata)?);
         }
-        let options = opt.max_time(max_time).batch_size(batch_size).build();
+        let options = aggregate_options(
+            stmt_id,
+            self.query_timeout,
+            rowset_size,
+            connection.batch_size,
+            connection.collation.clone(),
+            command_options,
+        );
 
         // handle an error coming back from execution; if it was cancelled, throw a specific error to
         // denote this to the program, otherwise return a generic query execution error
@@ -173,12 +701,31 @@ impl MongoStatement for MongoQuery {
             _ => Error::QueryExecutionFailed(e),
         };
 
-        let _guard = connection.runtime.enter();
-        let cursor: Cursor<Document> = connection.runtime.block_on(async {
-            db.aggregate(pipeline, options)
-                .await
-                .map_err(map_query_error)
-        })?;
+        let cursor: Cursor<Document> = match self.find.as_ref() {
+            Some(find) => {
+                let collection = connection
+                    .client
+                    .database(find.database.as_deref().unwrap_or(current_db))
+                    .collection::<Document>(&find.collection);
+                let options = find_options(find, options);
+                connection.runtime.block_on(async {
+                    collection
+                        .find(find.filter.clone(), options)
+                        .await
+                        .map_err(map_query_error)
+                })?
+            }
+            None => connection.runtime.block_on(async {
+                db.aggregate(pipeline, options)
+                    .await
+                    .map_err(map_query_error)
+            })?,
+        };
+        self.buffered_rows = None;
+        // 3. Stream the result, and record its rows on the way so that it is cached when it is
+        // small enough.
+        self.rows_to_cache =
+            (use_cache && connection.result_cache.is_some()).then(|| (Vec::new(), 0));
         self.resultset_cursor = Some(cursor);
         Ok(true)
     }
@@ -186,6 +733,581 @@ impl MongoStatement for MongoQuery {
     // Close the cursor by setting the current value and cursor to None.
     fn close_cursor(&mut self) {
         self.current = None;
+        self.buffered_rows = None;
+        self.rows_to_cache = None;
         self.resultset_cursor = None;
     }
+
+    fn query(&self) -> Option<&str> {
+        Some(&self.query)
+    }
+}
+
+#[cfg(test)]
+mod unit {
+    use super::{
+        aggregate_options, apply_collection_collations, apply_query_collation,
+        cached_collection_collations, find_options, has_unordered_limit, with_command_retry,
+        MongoQuery, ROW_AS_JSON_COLUMN,
+    };
+    use crate::{
+        base_names::apply_base_names,
+        col_metadata::MongoColMetadata,
+        find::find_query,
+        json_schema::{simplified::Atomic, simplified::Schema, BsonTypeName},
+        stmt::{CommandOptions, MongoStatement},
+        BsonTypeInfo, MongoConnection, ResultCache, ResultKey, ServerCapabilities, TypeMode,
+    };
+    use bson::{doc, Bson};
+    use definitions::{Nullability, SqlDataType};
+    use mongodb::{
+        error::{Error, ErrorKind},
+        options::{Collation, CollationStrength, Hint},
+        Client,
+    };
+    use std::{
+        cell::Cell,
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    fn network_error() -> Error {
+        ErrorKind::Io(Arc::new(std::io::ErrorKind::ConnectionReset.into())).into()
+    }
+
+    fn custom_error() -> Error {
+        Error::custom("not a network error")
+    }
+
+    // result_key returns the result cache key of a statement run on the db database with the
+    // standard type mode and no maximum string length.
+    fn result_key(query: &str, row_as_json: bool) -> ResultKey {
+        ResultKey {
+            db: "db".to_string(),
+            query: query.to_string(),
+            type_mode: TypeMode::Standard,
+            max_string_length: None,
+            row_as_json,
+        }
+    }
+
+    // Runs a command failing with the given errors before succeeding, and returns the result
+    // along with the number of attempts.
+    async fn run_with_errors(retry_reads: bool, errors: Vec<Error>) -> (Result<i32, Error>, usize) {
+        let attempts = Cell::new(0);
+        let errors = std::cell::RefCell::new(errors.into_iter());
+        let res = with_command_retry(retry_reads, || {
+            attempts.set(attempts.get() + 1);
+            let next = errors.borrow_mut().next();
+            async move { next.map_or(Ok(1), Err) }
+        })
+        .await;
+        (res, attempts.get())
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn one_time_network_error_is_retried() {
+        let (res, attempts) = run_with_errors(true, vec![network_error()]).await;
+        assert_eq!(1, res.unwrap());
+        assert_eq!(2, attempts);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn network_error_is_not_retried_without_retry_reads() {
+        let (res, attempts) = run_with_errors(false, vec![network_error()]).await;
+        assert!(res.is_err());
+        assert_eq!(1, attempts);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn retry_is_bounded() {
+        let (res, attempts) = run_with_errors(true, vec![network_error(), network_error()]).await;
+        assert!(matches!(*res.unwrap_err().kind, ErrorKind::Io(_)));
+        assert_eq!(2, attempts);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn non_network_error_is_not_retried() {
+        let (res, attempts) = run_with_errors(true, vec![custom_error()]).await;
+        assert!(matches!(*res.unwrap_err().kind, ErrorKind::Custom(_)));
+        assert_eq!(1, attempts);
+    }
+
+    // The connection points at a server that does not exist, so the query can only succeed if
+    // both its preparation and its execution are answered by the result cache.
+    #[test]
+    fn cached_result_does_not_hit_the_server() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let client = runtime.block_on(async {
+            Client::with_uri_str("mongodb://localhost:1/?serverSelectionTimeoutMS=1")
+                .await
+                .unwrap()
+        });
+        let cache = ResultCache::new(Duration::from_secs(60));
+        let metadata = vec![MongoColMetadata::new_metadata_from_bson_type_info_default(
+            "db",
+            "foo".to_string(),
+            "a".to_string(),
+            BsonTypeInfo::INT,
+            None,
+            Nullability::SQL_NULLABLE,
+        )];
+        cache.insert(
+            result_key("select a from foo", false),
+            metadata,
+            vec![doc! {"foo": {"a": 1}}, doc! {"foo": {"a": 2}}],
+        );
+        let connection = MongoConnection {
+            client,
+            operation_timeout: None,
+            uuid_repr: None,
+            retry_reads: true,
+            result_cache: Some(cache),
+            collation: None,
+            force_aggregate: false,
+            capabilities: ServerCapabilities::default(),
+            batch_size: None,
+            #[cfg(feature = "test_hooks")]
+            get_more_count: Default::default(),
+            server_warnings: Default::default(),
+            collection_collations: Default::default(),
+            runtime,
+        };
+
+        for run in 1..=2 {
+            let mut query = MongoQuery::prepare(
+                &connection,
+                Some("db".to_string()),
+                None,
+                "select a from foo",
+                TypeMode::Standard,
+                None,
+                false,
+            )
+            .unwrap();
+            assert!(query
+                .execute(&connection, Bson::Null, 1, &CommandOptions::default())
+                .unwrap());
+            let mut values = vec![];
+            while query.next(Some(&connection)).unwrap().0 {
+                values.push(query.get_value(1, None).unwrap().unwrap());
+            }
+            assert_eq!(vec![Bson::Int32(1), Bson::Int32(2)], values);
+            assert_eq!(run, connection.result_cache.as_ref().unwrap().hits());
+        }
+
+        // A different statement is not cached and goes to the server, which is unreachable.
+        assert!(MongoQuery::prepare(
+            &connection,
+            Some("db".to_string()),
+            None,
+            "select b from foo",
+            TypeMode::Standard,
+            None,
+            false,
+        )
+        .is_err());
+        // So does the same statement with another type mode, whose metadata differs.
+        assert!(MongoQuery::prepare(
+            &connection,
+            Some("db".to_string()),
+            None,
+            "select a from foo",
+            TypeMode::Simple,
+            None,
+            false,
+        )
+        .is_err());
+        // A statement with a comment always runs on the server.
+        let mut query = MongoQuery::prepare(
+            &connection,
+            Some("db".to_string()),
+            None,
+            "select a from foo",
+            TypeMode::Standard,
+            None,
+            false,
+        )
+        .unwrap();
+        let command_options = CommandOptions {
+            comment: Some("report".to_string()),
+            ..Default::default()
+        };
+        assert!(query
+            .execute(&connection, Bson::Null, 1, &command_options)
+            .is_err());
+    }
+
+    // With ROW_AS_JSON, the result set has a single column holding every row as a document. The
+    // rows come from the result cache, so no server is needed.
+    #[test]
+    fn row_as_json_returns_whole_row_document() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let client = runtime.block_on(async {
+            Client::with_uri_str("mongodb://localhost:1/?serverSelectionTimeoutMS=1")
+                .await
+                .unwrap()
+        });
+        let cache = ResultCache::new(Duration::from_secs(60));
+        let metadata = ["_id", "a"]
+            .into_iter()
+            .map(|name| {
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "db",
+                    "foo".to_string(),
+                    name.to_string(),
+                    BsonTypeInfo::INT,
+                    None,
+                    Nullability::SQL_NULLABLE,
+                )
+            })
+            .collect();
+        cache.insert(
+            result_key("select * from foo", true),
+            metadata,
+            vec![
+                doc! {"foo": {"_id": 0, "a": {"b": [1, 2]}}},
+                doc! {"foo": {"_id": 1}, "": {"c": "x"}},
+            ],
+        );
+        let connection = MongoConnection {
+            client,
+            operation_timeout: None,
+            uuid_repr: None,
+            retry_reads: true,
+            result_cache: Some(cache),
+            collation: None,
+            force_aggregate: false,
+            capabilities: ServerCapabilities::default(),
+            batch_size: None,
+            #[cfg(feature = "test_hooks")]
+            get_more_count: Default::default(),
+            server_warnings: Default::default(),
+            collection_collations: Default::default(),
+            runtime,
+        };
+
+        let mut query = MongoQuery::prepare(
+            &connection,
+            Some("db".to_string()),
+            None,
+            "select * from foo",
+            TypeMode::Standard,
+            None,
+            true,
+        )
+        .unwrap();
+        let metadata = query.get_resultset_metadata(None);
+        assert_eq!(1, metadata.len());
+        assert_eq!(ROW_AS_JSON_COLUMN, metadata[0].col_name);
+        assert_eq!(SqlDataType::SQL_WLONGVARCHAR, metadata[0].sql_type);
+
+        assert!(query
+            .execute(&connection, Bson::Null, 1, &CommandOptions::default())
+            .unwrap());
+        let mut rows = vec![];
+        while query.next(Some(&connection)).unwrap().0 {
+            assert!(query.get_value(2, None).is_err());
+            rows.push(query.get_value(1, None).unwrap().unwrap());
+        }
+        assert_eq!(
+            vec![
+                Bson::Document(doc! {"_id": 0, "a": {"b": [1, 2]}}),
+                Bson::Document(doc! {"_id": 1, "c": "x"}),
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn string_columns_follow_collection_collation() {
+        let col = |table: &str, name: &str, type_info| {
+            MongoColMetadata::new_metadata_from_bson_type_info_default(
+                "db",
+                table.to_string(),
+                name.to_string(),
+                type_info,
+                None,
+                Nullability::SQL_NULLABLE,
+            )
+        };
+        let mut metadata = vec![
+            col("binary", "s", BsonTypeInfo::STRING),
+            col("insensitive", "s", BsonTypeInfo::STRING),
+            col("insensitive", "i", BsonTypeInfo::INT),
+        ];
+        let collations = [
+            ("binary".to_string(), doc! {"locale": "simple"}),
+            (
+                "insensitive".to_string(),
+                doc! {"locale": "en", "strength": 2},
+            ),
+        ]
+        .into_iter()
+        .collect();
+        apply_collection_collations(&mut metadata, &collations);
+        assert!(metadata[0].case_sensitive);
+        assert!(!metadata[1].case_sensitive);
+        assert!(!metadata[2].case_sensitive);
+    }
+
+    // The connection points at a server that does not exist, so the collations can only be
+    // found in the cache of the connection.
+    #[test]
+    fn collection_collations_are_cached() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let client = runtime.block_on(async {
+            Client::with_uri_str("mongodb://localhost:1/?serverSelectionTimeoutMS=1")
+                .await
+                .unwrap()
+        });
+        let connection = MongoConnection {
+            client,
+            operation_timeout: None,
+            uuid_repr: None,
+            retry_reads: true,
+            result_cache: None,
+            collation: None,
+            force_aggregate: true,
+            capabilities: ServerCapabilities::default(),
+            batch_size: None,
+            #[cfg(feature = "test_hooks")]
+            get_more_count: Default::default(),
+            server_warnings: Default::default(),
+            collection_collations: Mutex::new(
+                [
+                    (("db".to_string(), "binary".to_string()), None),
+                    (
+                        ("db".to_string(), "insensitive".to_string()),
+                        Some(doc! {"locale": "en", "strength": 2}),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            runtime,
+        };
+        let metadata = ["binary", "insensitive"].map(|table| {
+            MongoColMetadata::new_metadata_from_bson_type_info_default(
+                "db",
+                table.to_string(),
+                "s".to_string(),
+                BsonTypeInfo::STRING,
+                None,
+                Nullability::SQL_NULLABLE,
+            )
+        });
+        let db = connection.client.database("db");
+        assert_eq!(
+            [(
+                "insensitive".to_string(),
+                doc! {"locale": "en", "strength": 2}
+            )]
+            .into_iter()
+            .collect::<HashMap<_, _>>(),
+            cached_collection_collations(&connection, &db, &metadata)
+        );
+    }
+
+    #[test]
+    fn string_columns_follow_query_collation() {
+        let mut metadata = vec![MongoColMetadata::new_metadata_from_bson_type_info_default(
+            "db",
+            "foo".to_string(),
+            "s".to_string(),
+            BsonTypeInfo::STRING,
+            None,
+            Nullability::SQL_NULLABLE,
+        )];
+        apply_query_collation(
+            &mut metadata,
+            &Collation::builder()
+                .locale("en")
+                .strength(CollationStrength::Tertiary)
+                .build(),
+        );
+        assert!(metadata[0].case_sensitive);
+        apply_query_collation(
+            &mut metadata,
+            &Collation::builder()
+                .locale("en")
+                .strength(CollationStrength::Primary)
+                .build(),
+        );
+        assert!(!metadata[0].case_sensitive);
+    }
+
+    #[test]
+    fn limit_without_order_by_is_unordered() {
+        assert!(has_unordered_limit("SELECT * FROM foo LIMIT 10"));
+        assert!(has_unordered_limit("select * from foo limit 10"));
+        assert!(has_unordered_limit(
+            "SELECT * FROM (SELECT * FROM foo LIMIT 10) AS f ORDER BY a"
+        ));
+    }
+
+    #[test]
+    fn limit_with_order_by_is_ordered() {
+        assert!(!has_unordered_limit(
+            "SELECT * FROM foo ORDER BY a LIMIT 10"
+        ));
+        assert!(!has_unordered_limit(
+            "SELECT * FROM foo ORDER\n  BY a DESC LIMIT 10 OFFSET 5"
+        ));
+        assert!(!has_unordered_limit("SELECT * FROM foo"));
+    }
+
+    #[test]
+    fn limit_in_literals_identifiers_and_comments_is_ignored() {
+        assert!(!has_unordered_limit("SELECT 'LIMIT' FROM foo"));
+        assert!(!has_unordered_limit("SELECT 'it''s a LIMIT' FROM foo"));
+        assert!(!has_unordered_limit("SELECT `limit` FROM foo"));
+        assert!(!has_unordered_limit("SELECT \"limit\" FROM foo"));
+        assert!(!has_unordered_limit("SELECT a FROM foo -- LIMIT 10"));
+        assert!(!has_unordered_limit("SELECT a /* LIMIT 10 */ FROM foo"));
+        assert!(!has_unordered_limit("SELECT a FROM foo AS limited"));
+    }
+
+    #[test]
+    fn literal_of_the_maximum_length_is_skipped() {
+        let literal = "LIMIT ".repeat(constants::MAX_LITERAL_LEN as usize / 6);
+        assert!(!has_unordered_limit(&format!(
+            "SELECT '{literal}' AS s FROM foo"
+        )));
+        assert!(has_unordered_limit(&format!(
+            "SELECT '{literal}' AS s FROM foo LIMIT 1"
+        )));
+    }
+
+    #[test]
+    fn order_by_in_a_subquery_does_not_order_the_outer_limit() {
+        assert!(has_unordered_limit(
+            "SELECT * FROM (SELECT * FROM foo ORDER BY a) AS f LIMIT 10"
+        ));
+    }
+
+    #[test]
+    fn statement_id_is_the_default_comment() {
+        let options = aggregate_options(
+            Bson::Int32(7),
+            Some(0),
+            1,
+            None,
+            None,
+            &CommandOptions::default(),
+        );
+        assert_eq!(Some(Bson::Int32(7)), options.comment_bson);
+        assert_eq!(None, options.hint);
+        assert_eq!(None, options.max_time);
+        assert_eq!(None, options.batch_size);
+    }
+
+    #[test]
+    fn batch_size_takes_precedence_over_the_rowset_size() {
+        let batch_size = |rowset_size, batch_size| {
+            aggregate_options(
+                Bson::Int32(7),
+                None,
+                rowset_size,
+                batch_size,
+                None,
+                &CommandOptions::default(),
+            )
+            .batch_size
+        };
+        assert_eq!(None, batch_size(100, None));
+        assert_eq!(Some(500), batch_size(500, None));
+        assert_eq!(Some(2), batch_size(500, Some(2)));
+        assert_eq!(Some(2), batch_size(1, Some(2)));
+        // 0 leaves the batch size to the server
+        assert_eq!(None, batch_size(500, Some(0)));
+    }
+
+    #[test]
+    fn collation_is_attached_to_the_aggregation() {
+        let collation = Collation::builder()
+            .locale("en")
+            .strength(CollationStrength::Primary)
+            .build();
+        let options = aggregate_options(
+            Bson::Int32(7),
+            None,
+            1,
+            None,
+            Some(collation),
+            &CommandOptions::default(),
+        );
+        assert_eq!(
+            Some(doc! {"locale": "en", "strength": 1}),
+            options
+                .collation
+                .map(|collation| bson::to_document(&collation).unwrap())
+        );
+    }
+
+    #[test]
+    fn hint_and_comment_are_attached_to_the_aggregation() {
+        let command_options = CommandOptions {
+            hint: Some(Hint::Keys(doc! {"a": 1})),
+            comment: Some("dashboard".to_string()),
+        };
+        let options = aggregate_options(
+            Bson::Int32(7),
+            Some(1000),
+            500,
+            None,
+            None,
+            &command_options,
+        );
+        assert_eq!(
+            Some(Bson::Document(
+                doc! {"statementId": 7, "comment": "dashboard"}
+            )),
+            options.comment_bson
+        );
+        assert_eq!(Some(Hint::Keys(doc! {"a": 1})), options.hint);
+        assert_eq!(Some(Duration::from_millis(1000)), options.max_time);
+        assert_eq!(Some(500), options.batch_size);
+    }
+
+    #[test]
+    fn hint_and_comment_are_attached_to_the_find_command() {
+        let query = "SELECT a FROM foo WHERE a > 1";
+        let mut metadata = vec![MongoColMetadata::new(
+            "db",
+            "foo".to_string(),
+            "a".to_string(),
+            Schema::Atomic(Atomic::Scalar(BsonTypeName::Int)),
+            Nullability::SQL_NULLABLE,
+            TypeMode::Standard,
+            None,
+        )];
+        apply_base_names(&mut metadata, query);
+        let find = find_query(query, &metadata).unwrap();
+        let command_options = CommandOptions {
+            hint: Some(Hint::Name("a_1".to_string())),
+            comment: Some("dashboard".to_string()),
+        };
+        let options = find_options(
+            &find,
+            aggregate_options(Bson::Int32(7), None, 1, None, None, &command_options),
+        );
+        assert_eq!(Some(Hint::Name("a_1".to_string())), options.hint);
+        assert_eq!(
+            Some(Bson::Document(
+                doc! {"statementId": 7, "comment": "dashboard"}
+            )),
+            options.comment_bson
+        );
+    }
 }
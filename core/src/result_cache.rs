@@ -0,0 +1,250 @@
+use crate::{col_metadata::MongoColMetadata, TypeMode};
+use bson::Document;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// The maximum number of results kept by a connection. When full, the oldest result is evicted.
+pub const MAX_CACHED_RESULTS: usize = 64;
+// The maximum size in bytes of the results kept by a connection. When full, the oldest results are
+// evicted.
+pub const MAX_CACHED_BYTES: usize = 64 * 1024 * 1024;
+// Results with more rows or bytes than these are not cached.
+pub const MAX_CACHED_ROWS: usize = 10_000;
+pub const MAX_CACHED_RESULT_BYTES: usize = 16 * 1024 * 1024;
+
+// A materialized query result.
+#[derive(Debug)]
+pub struct CachedResult {
+    pub metadata: Vec<MongoColMetadata>,
+    pub rows: Vec<Document>,
+    // The size in bytes of the rows.
+    size: usize,
+    inserted_at: Instant,
+    // The insertion order, used to evict the oldest result.
+    sequence: usize,
+}
+
+// ResultKey identifies a cached result. Statements are keyed by their exact text since the driver
+// does not support parameters, along with the database they run on and the settings that change
+// their result set metadata or how their rows are returned.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ResultKey {
+    pub db: String,
+    pub query: String,
+    pub type_mode: TypeMode,
+    pub max_string_length: Option<u16>,
+    pub row_as_json: bool,
+}
+
+// ResultCache keeps the results of the queries run on a connection for RESULT_CACHE_TTL_MS
+// milliseconds, so that running the same statement with the same key again within the TTL is
+// answered without going to the server. A result is cached once all of its rows were fetched.
+#[derive(Debug)]
+pub struct ResultCache {
+    ttl: Duration,
+    results: Mutex<HashMap<ResultKey, Arc<CachedResult>>>,
+    hits: AtomicUsize,
+    inserts: AtomicUsize,
+}
+
+impl ResultCache {
+    pub fn new(ttl: Duration) -> Self {
+        ResultCache {
+            ttl,
+            results: Mutex::new(HashMap::new()),
+            hits: AtomicUsize::new(0),
+            inserts: AtomicUsize::new(0),
+        }
+    }
+
+    // get returns the cached result of the key, if it has not expired.
+    pub fn get(&self, key: &ResultKey) -> Option<Arc<CachedResult>> {
+        let mut results = self.results.lock().unwrap();
+        match results.get(key) {
+            Some(result) if result.inserted_at.elapsed() < self.ttl => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(result.clone())
+            }
+            Some(_) => {
+                results.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    // insert caches the result of the key. Results with more than MAX_CACHED_ROWS rows or
+    // MAX_CACHED_RESULT_BYTES bytes are ignored.
+    pub fn insert(&self, key: ResultKey, metadata: Vec<MongoColMetadata>, rows: Vec<Document>) {
+        if rows.len() > MAX_CACHED_ROWS {
+            return;
+        }
+        let size = rows.iter().map(document_size).sum::<usize>();
+        if size > MAX_CACHED_RESULT_BYTES {
+            return;
+        }
+        let mut results = self.results.lock().unwrap();
+        results.retain(|_, result| result.inserted_at.elapsed() < self.ttl);
+        results.remove(&key);
+        let mut cached_size = results.values().map(|result| result.size).sum::<usize>();
+        while results.len() >= MAX_CACHED_RESULTS || cached_size + size > MAX_CACHED_BYTES {
+            let Some(oldest) = results
+                .iter()
+                .min_by_key(|(_, result)| result.sequence)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            cached_size -= results.remove(&oldest).map_or(0, |result| result.size);
+        }
+        results.insert(
+            key,
+            Arc::new(CachedResult {
+                metadata,
+                rows,
+                size,
+                inserted_at: Instant::now(),
+                sequence: self.inserts.fetch_add(1, Ordering::Relaxed),
+            }),
+        );
+    }
+
+    // hits returns the number of queries answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.results.lock().unwrap().len()
+    }
+}
+
+// document_size returns the size in bytes of the BSON encoding of a document.
+fn document_size(document: &Document) -> usize {
+    bson::to_vec(document).map_or(0, |bytes| bytes.len())
+}
+
+#[cfg(test)]
+mod unit {
+    use super::{
+        ResultCache, ResultKey, MAX_CACHED_BYTES, MAX_CACHED_RESULTS, MAX_CACHED_RESULT_BYTES,
+        MAX_CACHED_ROWS,
+    };
+    use crate::TypeMode;
+    use bson::{doc, Document};
+    use std::time::Duration;
+
+    fn key(db: &str, query: &str) -> ResultKey {
+        ResultKey {
+            db: db.to_string(),
+            query: query.to_string(),
+            type_mode: TypeMode::Standard,
+            max_string_length: None,
+            row_as_json: false,
+        }
+    }
+
+    #[test]
+    fn result_is_returned_within_ttl() {
+        let cache = ResultCache::new(Duration::from_secs(60));
+        assert!(cache.get(&key("db", "select 1")).is_none());
+        cache.insert(key("db", "select 1"), vec![], vec![doc! {"": {"_1": 1}}]);
+        let result = cache.get(&key("db", "select 1")).unwrap();
+        assert_eq!(vec![doc! {"": {"_1": 1}}], result.rows);
+        assert_eq!(1, cache.hits());
+    }
+
+    #[test]
+    fn result_is_keyed_by_database_and_exact_statement() {
+        let cache = ResultCache::new(Duration::from_secs(60));
+        cache.insert(key("db", "select 1"), vec![], vec![]);
+        assert!(cache.get(&key("other", "select 1")).is_none());
+        assert!(cache.get(&key("db", "SELECT 1")).is_none());
+        assert_eq!(0, cache.hits());
+    }
+
+    #[test]
+    fn result_is_keyed_by_result_settings() {
+        let cache = ResultCache::new(Duration::from_secs(60));
+        cache.insert(key("db", "select 1"), vec![], vec![]);
+        for other in [
+            ResultKey {
+                type_mode: TypeMode::Simple,
+                ..key("db", "select 1")
+            },
+            ResultKey {
+                max_string_length: Some(4000),
+                ..key("db", "select 1")
+            },
+            ResultKey {
+                row_as_json: true,
+                ..key("db", "select 1")
+            },
+        ] {
+            assert!(cache.get(&other).is_none(), "{other:?}");
+        }
+        assert_eq!(0, cache.hits());
+    }
+
+    #[test]
+    fn result_expires_after_ttl() {
+        let cache = ResultCache::new(Duration::from_millis(1));
+        cache.insert(key("db", "select 1"), vec![], vec![]);
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get(&key("db", "select 1")).is_none());
+        assert_eq!(0, cache.len());
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let cache = ResultCache::new(Duration::from_secs(60));
+        for i in 0..=MAX_CACHED_RESULTS {
+            cache.insert(key("db", &format!("select {i}")), vec![], vec![]);
+        }
+        assert_eq!(MAX_CACHED_RESULTS, cache.len());
+        // The oldest result was evicted.
+        assert!(cache.get(&key("db", "select 0")).is_none());
+        assert!(cache
+            .get(&key("db", &format!("select {MAX_CACHED_RESULTS}")))
+            .is_some());
+
+        cache.insert(
+            key("db", "select big"),
+            vec![],
+            vec![Document::new(); MAX_CACHED_ROWS + 1],
+        );
+        assert!(cache.get(&key("db", "select big")).is_none());
+    }
+
+    #[test]
+    fn cache_is_bounded_by_size() {
+        // Each result is a single row of nearly the largest size cached, so that only
+        // MAX_CACHED_BYTES / MAX_CACHED_RESULT_BYTES results fit.
+        let row = doc! {"s": "x".repeat(MAX_CACHED_RESULT_BYTES - 1024)};
+        let fitting = MAX_CACHED_BYTES / MAX_CACHED_RESULT_BYTES;
+        let cache = ResultCache::new(Duration::from_secs(60));
+        for i in 0..=fitting {
+            cache.insert(key("db", &format!("select {i}")), vec![], vec![row.clone()]);
+        }
+        assert_eq!(fitting, cache.len());
+        // The oldest result was evicted.
+        assert!(cache.get(&key("db", "select 0")).is_none());
+        assert!(cache
+            .get(&key("db", &format!("select {fitting}")))
+            .is_some());
+
+        cache.insert(
+            key("db", "select big"),
+            vec![],
+            vec![doc! {"s": "x".repeat(MAX_CACHED_RESULT_BYTES)}],
+        );
+        assert!(cache.get(&key("db", "select big")).is_none());
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    col_metadata::MongoColMetadata,
+    conn::MongoConnection,
+    err::{Error, Result},
+    stmt::{CommandOptions, MongoStatement},
+};
+use bson::Bson;
+use chrono::FixedOffset;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+// A SET statement has no result set.
+static SET_METADATA: Vec<MongoColMetadata> = Vec::new();
+
+lazy_static! {
+    // A fixed offset from UTC, such as +05:30, -0800 or UTC+1.
+    static ref UTC_OFFSET: Regex =
+        Regex::new(r"^(?i:utc|gmt)?([+-])(\d{1,2})(?::?(\d{2}))?$").unwrap();
+}
+
+// SessionVariable is a session variable assigned by a SET statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionVariable {
+    // The time zone datetimes are returned in.
+    TimeZone(FixedOffset),
+    // The client character set. Strings are always UTF-8, so it has no effect.
+    Names(String),
+    // A variable the driver does not know, by name.
+    Unknown(String),
+}
+
+// MongoSet is a `SET [SESSION | LOCAL] <variable> [= | TO] <value>` statement. Tools issue these
+// when a session starts. Nothing is sent to the server; the driver applies the variables it
+// knows when the statement is executed.
+#[derive(Debug, Clone)]
+pub struct MongoSet {
+    statement: String,
+    pub variable: SessionVariable,
+}
+
+impl MongoSet {
+    // parse returns the SET statement, or None if the statement is not one. The keywords and
+    // variable names are case-insensitive. A TIMEZONE that is neither UTC nor a fixed offset
+    // from UTC is an error.
+    pub fn parse(statement: &str) -> Option<Result<Self>> {
+        let rest = strip_keyword(statement.trim().trim_end_matches(';'), "set")?;
+        let rest = strip_keyword(rest, "session")
+            .or_else(|| strip_keyword(rest, "local"))
+            .unwrap_or(rest);
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let (name, value) = rest.split_at(name_end);
+        let name = name.to_uppercase();
+        let name = name
+            .trim_start_matches("@@")
+            .trim_start_matches("SESSION.")
+            .to_string();
+        if name.is_empty() {
+            return None;
+        }
+        // `TIME ZONE` is the standard spelling of the variable.
+        let (name, value) = match (name.as_str(), strip_keyword(value.trim_start(), "zone")) {
+            ("TIME", Some(value)) => ("TIMEZONE".to_string(), value),
+            _ => (name, value),
+        };
+        let value = value.trim_start();
+        let value = value
+            .strip_prefix('=')
+            .or_else(|| strip_keyword(value, "to"))
+            .unwrap_or(value)
+            .trim()
+            .trim_matches(|c| c == '\'' || c == '"');
+        let variable = match name.as_str() {
+            "TIMEZONE" | "TIME_ZONE" => match parse_time_zone(value) {
+                Ok(offset) => SessionVariable::TimeZone(offset),
+                Err(e) => return Some(Err(e)),
+            },
+            "NAMES" => SessionVariable::Names(value.to_string()),
+            _ => SessionVariable::Unknown(name),
+        };
+        Some(Ok(MongoSet {
+            statement: statement.to_string(),
+            variable,
+        }))
+    }
+}
+
+fn strip_keyword<'a>(s: &'a str, keyword: &str) -> Option<&'a str> {
+    let end = s.find(char::is_whitespace).unwrap_or(s.len());
+    let (word, rest) = s.split_at(end);
+    word.eq_ignore_ascii_case(keyword)
+        .then(|| rest.trim_start())
+}
+
+// parse_time_zone returns the offset of a UTC or fixed offset time zone. LOCAL and DEFAULT
+// restore the default time zone, which is UTC.
+fn parse_time_zone(value: &str) -> Result<FixedOffset> {
+    if ["utc", "gmt", "z", "local", "default"]
+        .iter()
+        .any(|tz| value.eq_ignore_ascii_case(tz))
+    {
+        return Ok(FixedOffset::east_opt(0).unwrap());
+    }
+    UTC_OFFSET
+        .captures(value)
+        .and_then(|offset| {
+            let hours: i32 = offset[2].parse().ok()?;
+            let minutes: i32 = offset.get(3).map_or(Some(0), |m| m.as_str().parse().ok())?;
+            let seconds = (hours * 60 + minutes) * 60;
+            match &offset[1] {
+                "-" => FixedOffset::west_opt(seconds),
+                _ => FixedOffset::east_opt(seconds),
+            }
+        })
+        .ok_or_else(|| {
+            Error::InvalidSessionVariable(format!(
+                "TIMEZONE must be UTC or a fixed offset from UTC such as +05:30, found '{value}'"
+            ))
+        })
+}
+
+impl MongoStatement for MongoSet {
+    fn next(&mut self, _: Option<&MongoConnection>) -> Result<(bool, Vec<Error>)> {
+        Ok((false, vec![]))
+    }
+
+    fn get_value(&self, _: u16, _: Option<u16>) -> Result<Option<Bson>> {
+        Err(Error::InvalidCursorState)
+    }
+
+    fn get_resultset_metadata(&self, _: Option<u16>) -> &Vec<MongoColMetadata> {
+        &SET_METADATA
+    }
+
+    // There is nothing to run on the server.
+    fn execute(
+        &mut self,
+        _: &MongoConnection,
+        _: Bson,
+        _: u32,
+        _: &CommandOptions,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn query(&self) -> Option<&str> {
+        Some(&self.statement)
+    }
+}
+
+#[cfg(test)]
+mod unit {
+    use super::{MongoSet, SessionVariable};
+    use crate::err::Error;
+    use chrono::FixedOffset;
+
+    fn variable(statement: &str) -> SessionVariable {
+        MongoSet::parse(statement).unwrap().unwrap().variable
+    }
+
+    #[test]
+    fn time_zone_statements_are_recognized() {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let india = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let pacific = FixedOffset::west_opt(8 * 3600).unwrap();
+        for (statement, expected) in [
+            ("SET TIMEZONE = 'UTC'", utc),
+            ("set timezone to '+05:30'", india),
+            ("SET TIME ZONE '-08:00';", pacific),
+            ("SET SESSION time_zone = '-0800'", pacific),
+            ("SET @@session.time_zone='+05:30'", india),
+            ("SET TIMEZONE TO UTC+5:30", india),
+            ("SET TIME ZONE LOCAL", utc),
+        ] {
+            assert_eq!(
+                SessionVariable::TimeZone(expected),
+                variable(statement),
+                "{statement}"
+            );
+        }
+    }
+
+    #[test]
+    fn names_and_unknown_variables_are_recognized() {
+        assert_eq!(
+            SessionVariable::Names("utf8".to_string()),
+            variable("SET NAMES utf8")
+        );
+        assert_eq!(
+            SessionVariable::Unknown("ANSI_NULLS".to_string()),
+            variable("set ansi_nulls on")
+        );
+        assert_eq!(
+            SessionVariable::Unknown("SEARCH_PATH".to_string()),
+            variable("SET search_path TO public")
+        );
+    }
+
+    #[test]
+    fn other_statements_are_not_set_statements() {
+        for statement in [
+            "SELECT * FROM foo",
+            "SET",
+            "SETTINGS x = 1",
+            "SELECT set FROM foo",
+        ] {
+            assert!(MongoSet::parse(statement).is_none(), "{statement}");
+        }
+    }
+
+    #[test]
+    fn named_time_zones_are_errors() {
+        for statement in [
+            "SET TIMEZONE = 'America/New_York'",
+            "SET TIMEZONE = '+25:00'",
+        ] {
+            assert!(
+                matches!(
+                    MongoSet::parse(statement),
+                    Some(Err(Error::InvalidSessionVariable(_)))
+                ),
+                "{statement}"
+            );
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    col_metadata::{MongoColMetadata, SqlGetSchemaResponse},
+    conn::MongoConnection,
+    err::{Error, Result},
+    fields::MongoFields,
+    stmt::MongoStatement,
+    BsonTypeInfo, TypeMode,
+};
+use bson::{doc, Bson};
+use definitions::{Nullability, SQL_NO_TOTAL, SQL_PC_NOT_PSEUDO, SQL_SCOPE_SESSION};
+use once_cell::sync::OnceCell;
+
+static SPECIAL_COLUMNS_METADATA: OnceCell<Vec<MongoColMetadata>> = OnceCell::new();
+
+// The _id field is the only unique row identifier for a MongoDB document.
+const ROW_ID_FIELD: &str = "_id";
+
+#[derive(Debug)]
+pub struct MongoSpecialColumns {
+    // The metadata of the _id field, if the collection has one.
+    row_id: Option<MongoColMetadata>,
+    // Whether the cursor is positioned on the _id row.
+    on_row: bool,
+    // Whether the _id row has already been returned.
+    exhausted: bool,
+    max_string_length: Option<u16>,
+    /// Whether the DATA_TYPE should map to odbc 3 types or not
+    odbc_3_types: bool,
+}
+
+// Statement related to a SQLSpecialColumns call.
+// The Resultset columns are hard-coded and follow the ODBC resultset for SQLSpecialColumns :
+// SCOPE, COLUMN_NAME, DATA_TYPE, TYPE_NAME, COLUMN_SIZE, BUFFER_LENGTH, DECIMAL_DIGITS,
+// PSEUDO_COLUMN.
+impl MongoSpecialColumns {
+    // Create a new MongoStatement describing the best row identifier (SQL_BEST_ROWID) of the
+    // given collection, which is always its _id field.
+    // The collection schema is retrieved with sqlGetSchema; if the collection has no _id field
+    // in its schema, the result set is empty.
+    pub fn best_rowid(
+        mongo_connection: &MongoConnection,
+        db_name: &str,
+        collection_name: &str,
+        type_mode: TypeMode,
+        max_string_length: Option<u16>,
+        odbc_3_types: bool,
+    ) -> Result<Self> {
+        let _guard = mongo_connection.runtime.enter();
+        let response = mongo_connection.runtime.block_on(async {
+            mongo_connection
+                .client
+                .database(db_name)
+                .run_command(doc! {"sqlGetSchema": collection_name}, None)
+                .await
+                .map_err(Error::QueryExecutionFailed)
+        })?;
+        let schema_response: SqlGetSchemaResponse = bson::from_document(response)
+            .map_err(|e| Error::CollectionDeserialization(collection_name.to_string(), e))?;
+        let row_id = schema_response
+            .process_collection_metadata(db_name, collection_name, type_mode, max_string_length)?
+            .into_iter()
+            .find(|col_metadata| col_metadata.col_name == ROW_ID_FIELD);
+        Ok(MongoSpecialColumns {
+            row_id,
+            on_row: false,
+            exhausted: false,
+            max_string_length,
+            odbc_3_types,
+        })
+    }
+
+    // A MongoSpecialColumns with no rows. This is what is returned for SQL_ROWVER since there are
+    // no columns automatically updated when a document is modified.
+    pub fn empty(max_string_length: Option<u16>) -> MongoSpecialColumns {
+        MongoSpecialColumns {
+            row_id: None,
+            on_row: false,
+            exhausted: true,
+            max_string_length,
+            odbc_3_types: true,
+        }
+    }
+}
+
+impl MongoStatement for MongoSpecialColumns {
+    // There is at most one row, describing the _id field.
+    fn next(&mut self, _: Option<&MongoConnection>) -> Result<(bool, Vec<Error>)> {
+        self.on_row = !self.exhausted && self.row_id.is_some();
+        self.exhausted = true;
+        Ok((self.on_row, vec![]))
+    }
+
+    // Get the BSON value for the cell at the given colIndex on the current row.
+    // Fails if the first row as not been retrieved (next must be called at least once before getValue).
+    fn get_value(&self, col_index: u16, _: Option<u16>) -> Result<Option<Bson>> {
+        let row_id = match (self.on_row, self.row_id.as_ref()) {
+            (true, Some(row_id)) => row_id,
+            _ => return Err(Error::InvalidCursorState),
+        };
+        Ok(Some(match col_index {
+            // SCOPE
+            // The _id of a document is valid for as long as the document exists.
+            1 => Bson::Int32(i32::from(SQL_SCOPE_SESSION)),
+            // COLUMN_NAME
+            2 => Bson::String(row_id.col_name.clone()),
+            // DATA_TYPE
+            3 => Bson::Int32(MongoFields::map_type_for_odbc_version(
+                self.odbc_3_types,
+                row_id.sql_type,
+            ) as i32),
+            // TYPE_NAME
+            4 => Bson::String(row_id.type_name.clone()),
+            // COLUMN_SIZE
+            5 => Bson::Int32(match row_id.column_size {
+                None => SQL_NO_TOTAL,
+                Some(col_size) => i32::from(col_size),
+            }),
+            // BUFFER_LENGTH = Transfer octet length
+            6 => Bson::Int32(match row_id.transfer_octet_length {
+                None => SQL_NO_TOTAL,
+                Some(l) => i32::from(l),
+            }),
+            // DECIMAL_DIGITS
+            7 => match row_id.decimal_digits {
+                None => Bson::Null,
+                Some(dec_dg) => Bson::Int32(i32::from(dec_dg)),
+            },
+            // PSEUDO_COLUMN
+            8 => Bson::Int32(i32::from(SQL_PC_NOT_PSEUDO)),
+            _ => return Err(Error::ColIndexOutOfBounds(col_index)),
+        }))
+    }
+
+    fn get_resultset_metadata(&self, _: Option<u16>) -> &Vec<MongoColMetadata> {
+        SPECIAL_COLUMNS_METADATA.get_or_init(|| {
+            vec![
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "SCOPE".to_string(),
+                    BsonTypeInfo::INT,
+                    self.max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "COLUMN_NAME".to_string(),
+                    BsonTypeInfo::STRING,
+                    self.max_string_length,
+                    Nullability::SQL_NO_NULLS,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "DATA_TYPE".to_string(),
+                    BsonTypeInfo::INT,
+                    self.max_string_length,
+                    Nullability::SQL_NO_NULLS,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "TYPE_NAME".to_string(),
+                    BsonTypeInfo::STRING,
+                    self.max_string_length,
+                    Nullability::SQL_NO_NULLS,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "COLUMN_SIZE".to_string(),
+                    BsonTypeInfo::INT,
+                    self.max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "BUFFER_LENGTH".to_string(),
+                    BsonTypeInfo::INT,
+                    self.max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "DECIMAL_DIGITS".to_string(),
+                    BsonTypeInfo::INT,
+                    self.max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "PSEUDO_COLUMN".to_string(),
+                    BsonTypeInfo::INT,
+                    self.max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+            ]
+        })
+    }
+}
+
+#[cfg(test)]
+mod unit {
+    use crate::{special_columns::MongoSpecialColumns, stmt::MongoStatement};
+    use definitions::Nullability;
+
+    #[test]
+    fn metadata_column_names_and_types() {
+        let special_columns = MongoSpecialColumns::empty(None);
+        let metadata = special_columns.get_resultset_metadata(None);
+        assert_eq!(8, metadata.len());
+        let expected = [
+            ("SCOPE", "int", Nullability::SQL_NULLABLE),
+            ("COLUMN_NAME", "string", Nullability::SQL_NO_NULLS),
+            ("DATA_TYPE", "int", Nullability::SQL_NO_NULLS),
+            ("TYPE_NAME", "string", Nullability::SQL_NO_NULLS),
+            ("COLUMN_SIZE", "int", Nullability::SQL_NULLABLE),
+            ("BUFFER_LENGTH", "int", Nullability::SQL_NULLABLE),
+            ("DECIMAL_DIGITS", "int", Nullability::SQL_NULLABLE),
+            ("PSEUDO_COLUMN", "int", Nullability::SQL_NULLABLE),
+        ];
+        for (col_metadata, (name, type_name, nullability)) in metadata.iter().zip(expected) {
+            assert_eq!(name, col_metadata.col_name);
+            assert_eq!(type_name, col_metadata.type_name);
+            assert_eq!(nullability, col_metadata.nullability);
+        }
+    }
+
+    #[test]
+    fn empty_has_no_rows() {
+        let mut special_columns = MongoSpecialColumns::empty(None);
+        assert!(!special_columns.next(None).unwrap().0);
+        assert!(special_columns.get_value(1, None).is_err());
+    }
+}
@@ -0,0 +1,460 @@
+use crate::{
+    col_metadata::MongoColMetadata,
+    conn::MongoConnection,
+    err::{Error, Result},
+    stmt::MongoStatement,
+    BsonTypeInfo,
+};
+use bson::{Bson, Document};
+use definitions::{Nullability, SQL_INDEX_HASHED, SQL_INDEX_OTHER, SQL_TABLE_STAT};
+use mongodb::IndexModel;
+use once_cell::sync::OnceCell;
+
+static STATISTICS_METADATA: OnceCell<Vec<MongoColMetadata>> = OnceCell::new();
+
+// The index MongoDB creates on _id for every collection. It is always unique even though its
+// specification does not say so.
+const ID_INDEX_NAME: &str = "_id_";
+
+// One row of the SQLStatistics result set, describing one key of an index.
+#[derive(Debug)]
+struct IndexColumn {
+    non_unique: bool,
+    index_name: String,
+    index_type: i16,
+    ordinal_position: i32,
+    column_name: String,
+    asc_or_desc: Option<&'static str>,
+    filter_condition: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct MongoStatistics {
+    db_name: String,
+    collection_name: String,
+    // The number of documents of the collection, reported in the SQL_TABLE_STAT row.
+    cardinality: Option<i32>,
+    index_columns: Vec<IndexColumn>,
+    // The current row. The SQL_TABLE_STAT row, if any, comes before the rows of the index keys.
+    current_row: Option<usize>,
+}
+
+// Statement related to a SQLStatistics call.
+// The Resultset columns are hard-coded and follow the ODBC resultset for SQLStatistics :
+// TABLE_CAT, TABLE_SCHEM, TABLE_NAME, NON_UNIQUE, INDEX_QUALIFIER, INDEX_NAME, TYPE,
+// ORDINAL_POSITION, COLUMN_NAME, ASC_OR_DESC, CARDINALITY, PAGES, FILTER_CONDITION.
+impl MongoStatistics {
+    // Create a new MongoStatement listing the indexes of the given collection, one row per index
+    // key, after a SQL_TABLE_STAT row with the number of documents of the collection. Compound
+    // indexes produce one row per key, in the order of the index specification.
+    // If unique_only is true, only the unique indexes are listed. If exact_cardinality is true,
+    // the documents are counted, otherwise the count is estimated from the collection metadata.
+    pub fn list_indexes(
+        mongo_connection: &MongoConnection,
+        db_name: &str,
+        collection_name: &str,
+        unique_only: bool,
+        exact_cardinality: bool,
+    ) -> Result<Self> {
+        let _guard = mongo_connection.runtime.enter();
+        let collection = mongo_connection
+            .client
+            .database(db_name)
+            .collection::<Document>(collection_name);
+        let (cardinality, indexes) = mongo_connection.runtime.block_on(async {
+            let count = if exact_cardinality {
+                collection.count_documents(None, None).await
+            } else {
+                collection.estimated_document_count(None).await
+            }
+            .map_err(Error::QueryExecutionFailed)?;
+            let mut cursor = collection
+                .list_indexes(None)
+                .await
+                .map_err(Error::QueryExecutionFailed)?;
+            let mut indexes = vec![];
+            while cursor.advance().await.map_err(Error::QueryCursorUpdate)? {
+                indexes.push(
+                    cursor
+                        .deserialize_current()
+                        .map_err(Error::QueryCursorUpdate)?,
+                );
+            }
+            Ok::<(u64, Vec<IndexModel>), Error>((count, indexes))
+        })?;
+        let mut index_columns: Vec<IndexColumn> = indexes
+            .iter()
+            .flat_map(MongoStatistics::index_columns)
+            .filter(|index_column| !unique_only || !index_column.non_unique)
+            .collect();
+        // The result set is ordered by NON_UNIQUE, TYPE, INDEX_QUALIFIER, INDEX_NAME, and
+        // ORDINAL_POSITION.
+        index_columns.sort_by(|a, b| {
+            (
+                a.non_unique,
+                a.index_type,
+                &a.index_name,
+                a.ordinal_position,
+            )
+                .cmp(&(
+                    b.non_unique,
+                    b.index_type,
+                    &b.index_name,
+                    b.ordinal_position,
+                ))
+        });
+        Ok(MongoStatistics {
+            db_name: db_name.to_string(),
+            collection_name: collection_name.to_string(),
+            // CARDINALITY is an SQLINTEGER
+            cardinality: Some(i32::try_from(cardinality).unwrap_or(i32::MAX)),
+            index_columns,
+            current_row: None,
+        })
+    }
+
+    // A MongoStatistics with no rows.
+    pub fn empty() -> MongoStatistics {
+        MongoStatistics {
+            db_name: "".to_string(),
+            collection_name: "".to_string(),
+            cardinality: None,
+            index_columns: vec![],
+            current_row: None,
+        }
+    }
+
+    // Turns an index specification into one IndexColumn per key.
+    fn index_columns(index: &IndexModel) -> Vec<IndexColumn> {
+        let options = index.options.as_ref();
+        let index_name = options
+            .and_then(|options| options.name.clone())
+            .unwrap_or_default();
+        let non_unique = !(index_name == ID_INDEX_NAME
+            || options.and_then(|options| options.unique).unwrap_or(false));
+        let index_type = if index
+            .keys
+            .values()
+            .any(|key_type| key_type.as_str() == Some("hashed"))
+        {
+            SQL_INDEX_HASHED
+        } else {
+            SQL_INDEX_OTHER
+        };
+        let filter_condition = options
+            .and_then(|options| options.partial_filter_expression.clone())
+            .map(|filter| Bson::Document(filter).into_relaxed_extjson().to_string());
+        index
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(position, (column_name, key_type))| IndexColumn {
+                non_unique,
+                index_name: index_name.clone(),
+                index_type,
+                ordinal_position: i32::try_from(position + 1)
+                    .expect("index has more keys than i32::MAX"),
+                column_name: column_name.clone(),
+                asc_or_desc: MongoStatistics::sort_order(key_type),
+                filter_condition: filter_condition.clone(),
+            })
+            .collect()
+    }
+
+    // The sort order of an index key: "A" for ascending and "D" for descending keys. Text,
+    // geospatial, and hashed keys have no sort order.
+    fn sort_order(key_type: &Bson) -> Option<&'static str> {
+        let direction = match key_type {
+            Bson::Int32(direction) => i64::from(*direction).signum(),
+            Bson::Int64(direction) => direction.signum(),
+            Bson::Double(direction) if *direction > 0.0 => 1,
+            Bson::Double(direction) if *direction < 0.0 => -1,
+            _ => 0,
+        };
+        match direction {
+            1 => Some("A"),
+            -1 => Some("D"),
+            _ => None,
+        }
+    }
+}
+
+impl MongoStatement for MongoStatistics {
+    // Move the cursor to the next row.
+    // Return true if moving was successful, false otherwise.
+    fn next(&mut self, _: Option<&MongoConnection>) -> Result<(bool, Vec<Error>)> {
+        let next_row = self.current_row.map_or(0, |current| current + 1);
+        self.current_row = Some(next_row);
+        let row_count = usize::from(self.cardinality.is_some()) + self.index_columns.len();
+        Ok((next_row < row_count, vec![]))
+    }
+
+    // Get the BSON value for the cell at the given colIndex on the current row.
+    // Fails if the first row as not been retrieved (next must be called at least once before getValue).
+    fn get_value(&self, col_index: u16, _: Option<u16>) -> Result<Option<Bson>> {
+        let current = self.current_row.ok_or(Error::InvalidCursorState)?;
+        // The index key of the current row, or None for the SQL_TABLE_STAT row.
+        let index_column = match (self.cardinality, current) {
+            (Some(_), 0) => None,
+            (Some(_), current) => Some(current - 1),
+            (None, current) => Some(current),
+        }
+        .map(|index| {
+            self.index_columns
+                .get(index)
+                .ok_or(Error::InvalidCursorState)
+        })
+        .transpose()?;
+        Ok(Some(match col_index {
+            // TABLE_CAT
+            1 => Bson::String(self.db_name.clone()),
+            // TABLE_SCHEM
+            2 => Bson::Null,
+            // TABLE_NAME
+            3 => Bson::String(self.collection_name.clone()),
+            // NON_UNIQUE
+            4 => index_column.map_or(Bson::Null, |index_column| {
+                Bson::Int32(i32::from(index_column.non_unique))
+            }),
+            // INDEX_QUALIFIER
+            5 => Bson::Null,
+            // INDEX_NAME
+            6 => index_column.map_or(Bson::Null, |index_column| {
+                Bson::String(index_column.index_name.clone())
+            }),
+            // TYPE
+            7 => Bson::Int32(i32::from(
+                index_column.map_or(SQL_TABLE_STAT, |index_column| index_column.index_type),
+            )),
+            // ORDINAL_POSITION
+            8 => index_column.map_or(Bson::Null, |index_column| {
+                Bson::Int32(index_column.ordinal_position)
+            }),
+            // COLUMN_NAME
+            9 => index_column.map_or(Bson::Null, |index_column| {
+                Bson::String(index_column.column_name.clone())
+            }),
+            // ASC_OR_DESC
+            10 => index_column
+                .and_then(|index_column| index_column.asc_or_desc)
+                .map_or(Bson::Null, |asc_or_desc| {
+                    Bson::String(asc_or_desc.to_string())
+                }),
+            // CARDINALITY, only known for the table
+            11 => match index_column {
+                Some(_) => Bson::Null,
+                None => self.cardinality.map_or(Bson::Null, Bson::Int32),
+            },
+            // PAGES
+            12 => Bson::Null,
+            // FILTER_CONDITION
+            13 => index_column
+                .and_then(|index_column| index_column.filter_condition.clone())
+                .map_or(Bson::Null, Bson::String),
+            _ => return Err(Error::ColIndexOutOfBounds(col_index)),
+        }))
+    }
+
+    fn get_resultset_metadata(&self, max_string_length: Option<u16>) -> &Vec<MongoColMetadata> {
+        STATISTICS_METADATA.get_or_init(|| {
+            vec![
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "TABLE_CAT".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "TABLE_SCHEM".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "TABLE_NAME".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NO_NULLS,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "NON_UNIQUE".to_string(),
+                    BsonTypeInfo::INT,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "INDEX_QUALIFIER".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "INDEX_NAME".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "TYPE".to_string(),
+                    BsonTypeInfo::INT,
+                    max_string_length,
+                    Nullability::SQL_NO_NULLS,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "ORDINAL_POSITION".to_string(),
+                    BsonTypeInfo::INT,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "COLUMN_NAME".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "ASC_OR_DESC".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "CARDINALITY".to_string(),
+                    BsonTypeInfo::INT,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "PAGES".to_string(),
+                    BsonTypeInfo::INT,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+                MongoColMetadata::new_metadata_from_bson_type_info_default(
+                    "",
+                    "".to_string(),
+                    "FILTER_CONDITION".to_string(),
+                    BsonTypeInfo::STRING,
+                    max_string_length,
+                    Nullability::SQL_NULLABLE,
+                ),
+            ]
+        })
+    }
+}
+
+#[cfg(test)]
+mod unit {
+    use crate::{statistics::MongoStatistics, stmt::MongoStatement};
+    use bson::{doc, Bson};
+    use definitions::{SQL_INDEX_HASHED, SQL_INDEX_OTHER, SQL_TABLE_STAT};
+    use mongodb::{options::IndexOptions, IndexModel};
+
+    fn index(keys: bson::Document, name: &str, unique: Option<bool>) -> IndexModel {
+        IndexModel::builder()
+            .keys(keys)
+            .options(
+                IndexOptions::builder()
+                    .name(name.to_string())
+                    .unique(unique)
+                    .build(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn compound_index_has_one_row_per_key_in_order() {
+        let columns = MongoStatistics::index_columns(&index(
+            doc! {"b": 1, "a": -1, "c": "text"},
+            "b_1_a_-1_c_text",
+            None,
+        ));
+        assert_eq!(3, columns.len());
+        let keys: Vec<(&str, i32, Option<&str>)> = columns
+            .iter()
+            .map(|c| (c.column_name.as_str(), c.ordinal_position, c.asc_or_desc))
+            .collect();
+        assert_eq!(
+            vec![("b", 1, Some("A")), ("a", 2, Some("D")), ("c", 3, None)],
+            keys
+        );
+        assert!(columns.iter().all(|c| c.non_unique));
+        assert!(columns.iter().all(|c| c.index_type == SQL_INDEX_OTHER));
+    }
+
+    #[test]
+    fn unique_and_id_indexes_are_unique() {
+        let unique = MongoStatistics::index_columns(&index(doc! {"a": 1}, "a_1", Some(true)));
+        assert!(!unique[0].non_unique);
+        let id = MongoStatistics::index_columns(&index(doc! {"_id": 1}, "_id_", None));
+        assert!(!id[0].non_unique);
+    }
+
+    #[test]
+    fn hashed_index_type() {
+        let hashed = MongoStatistics::index_columns(&index(doc! {"a": "hashed"}, "a_hashed", None));
+        assert_eq!(SQL_INDEX_HASHED, hashed[0].index_type);
+        assert_eq!(None, hashed[0].asc_or_desc);
+    }
+
+    #[test]
+    fn table_statistics_row_comes_first_with_the_cardinality() {
+        let mut statistics = MongoStatistics {
+            db_name: "db".to_string(),
+            collection_name: "foo".to_string(),
+            cardinality: Some(3),
+            index_columns: MongoStatistics::index_columns(&index(doc! {"a": 1}, "a_1", None)),
+            current_row: None,
+        };
+        let mut rows = vec![];
+        while statistics.next(None).unwrap().0 {
+            rows.push(
+                [4, 6, 7, 9, 11]
+                    .map(|col_index| statistics.get_value(col_index, None).unwrap().unwrap()),
+            );
+        }
+        assert_eq!(
+            vec![
+                [
+                    Bson::Null,
+                    Bson::Null,
+                    Bson::Int32(i32::from(SQL_TABLE_STAT)),
+                    Bson::Null,
+                    Bson::Int32(3),
+                ],
+                [
+                    Bson::Int32(1),
+                    Bson::String("a_1".to_string()),
+                    Bson::Int32(i32::from(SQL_INDEX_OTHER)),
+                    Bson::String("a".to_string()),
+                    Bson::Null,
+                ],
+            ],
+            rows
+        );
+    }
+}
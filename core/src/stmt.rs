@@ -3,8 +3,19 @@ use crate::{
     MongoColMetadata, MongoConnection,
 };
 use bson::Bson;
+use mongodb::options::Hint;
 use std::fmt::Debug;
 
+// CommandOptions are attached to the command run when executing a statement. They are set with
+// the driver-specific statement attributes.
+#[derive(Clone, Debug, Default)]
+pub struct CommandOptions {
+    // The index the command must use.
+    pub hint: Option<Hint>,
+    // A comment identifying the command in the server logs, profiler and currentOp output.
+    pub comment: Option<String>,
+}
+
 pub trait MongoStatement: Debug {
     // Move the cursor to the next item.
     // Return true if moving was successful, false otherwise.
@@ -28,18 +39,24 @@ pub trait MongoStatement: Debug {
             .ok_or(Error::ColIndexOutOfBounds(col_index))
     }
     // Executes a prepared statement.
-    // Only MongoQuery supports this workflow. The other statements don't.
+    // Only MongoQuery and MongoExplain support this workflow. The other statements don't.
     fn execute(
         &mut self,
         _connection: &MongoConnection,
         _stmt_id: Bson,
         _rowset_size: u32,
+        _options: &CommandOptions,
     ) -> Result<bool> {
         Err(Error::UnsupportedOperation("execute"))
     }
     // Closes the cursor.
-    // Only MongoQuery supports this workflow. The other statements don't.
+    // Only MongoQuery and MongoExplain support this workflow. The other statements don't.
     fn close_cursor(&mut self) {}
+    // Returns the SQL text of the statement.
+    // Only MongoQuery and MongoExplain have one. The other statements don't.
+    fn query(&self) -> Option<&str> {
+        None
+    }
 }
 
 #[derive(Debug)]
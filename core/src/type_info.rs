@@ -1,6 +1,10 @@
 use crate::{
-    bson_type_info::SQL_PRED_BASIC, col_metadata::MongoColMetadata, conn::MongoConnection,
-    err::Result, stmt::MongoStatement, BsonTypeInfo, Error, TypeMode,
+    bson_type_info::SQL_PRED_BASIC,
+    col_metadata::MongoColMetadata,
+    conn::{MongoConnection, ServerCapabilities},
+    err::Result,
+    stmt::MongoStatement,
+    BsonTypeInfo, Error, TypeMode,
 };
 use bson::Bson;
 use definitions::{Nullability, SqlCode, SqlDataType};
@@ -34,10 +38,18 @@ const LEGACY_DATE: BsonTypeInfo = BsonTypeInfo {
     simple_type_info: None,
 };
 
+// the ODBC 2 counterpart of BsonTypeInfo::TIMESTAMP, returned alongside it like LEGACY_DATE.
+const LEGACY_TIMESTAMP: BsonTypeInfo = BsonTypeInfo {
+    sql_type: SqlDataType::SQL_TIMESTAMP,
+    ..BsonTypeInfo::TIMESTAMP
+};
+
 // order of array is by SqlDataType, since that is the ordering of the
 // SQLGetTypeInfo result set according to the spec
-const DATA_TYPES: [BsonTypeInfo; 23] = [
+const DATA_TYPES: [BsonTypeInfo; 24] = [
     BsonTypeInfo::STRING,              // SqlDataType(-9)
+    BsonTypeInfo::MAXKEY,              // SqlDataType(-9)
+    BsonTypeInfo::MINKEY,              // SqlDataType(-9)
     BsonTypeInfo::BOOL,                // SqlDataType(-7)
     BsonTypeInfo::LONG,                // SqlDataType(-5)
     BsonTypeInfo::BINDATA,             // SqlDataType(-2)
@@ -47,19 +59,18 @@ const DATA_TYPES: [BsonTypeInfo; 23] = [
     BsonTypeInfo::DECIMAL,             // SqlDataType(0)
     BsonTypeInfo::JAVASCRIPT,          // SqlDataType(0)
     BsonTypeInfo::JAVASCRIPTWITHSCOPE, // SqlDataType(0)
-    BsonTypeInfo::MAXKEY,              // SqlDataType(0)
-    BsonTypeInfo::MINKEY,              // SqlDataType(0)
     BsonTypeInfo::NULL,                // SqlDataType(0)
     BsonTypeInfo::OBJECT,              // SqlDataType(0)
     BsonTypeInfo::OBJECTID,            // SqlDataType(0)
     BsonTypeInfo::SYMBOL,              // SqlDataType(0)
-    BsonTypeInfo::TIMESTAMP,           // SqlDataType(0)
     BsonTypeInfo::UNDEFINED,           // SqlDataType(0)
     BsonTypeInfo::INT,                 // SqlDataType(4)
     BsonTypeInfo::DOUBLE,              // SqlDataType(8)
     LEGACY_DATE,                       // SqlDataType(11)
+    LEGACY_TIMESTAMP,                  // SqlDataType(11)
     BsonTypeInfo::VARCHAR,             // SqlDataType(12)
     BsonTypeInfo::DATE,                // SqlDataType(93)
+    BsonTypeInfo::TIMESTAMP,           // SqlDataType(93)
 ];
 
 static TYPES_INFO_METADATA: OnceCell<Vec<MongoColMetadata>> = OnceCell::new();
@@ -69,28 +80,47 @@ pub struct MongoTypesInfo {
     current_type_index: usize,
     sql_data_type: SqlDataType,
     type_mode: TypeMode,
+    capabilities: ServerCapabilities,
 }
 
 impl MongoTypesInfo {
-    pub fn new(sql_data_type: SqlDataType, type_mode: TypeMode) -> MongoTypesInfo {
+    // Create a MongoTypesInfo listing the types of the given sql type the server supports.
+    pub fn new(
+        sql_data_type: SqlDataType,
+        type_mode: TypeMode,
+        capabilities: ServerCapabilities,
+    ) -> MongoTypesInfo {
         MongoTypesInfo {
             current_type_index: 0,
             sql_data_type,
             type_mode,
+            capabilities,
         }
     }
+
+    // is_supported returns whether the server supports the type. The column metadata of result
+    // sets needs no such check: it is built from the schema the server returns, and a server
+    // without Decimal128 cannot store or return a decimal value, so it never reports a decimal
+    // column.
+    fn is_supported(&self, type_info: &BsonTypeInfo) -> bool {
+        type_info.type_name != BsonTypeInfo::DECIMAL.type_name || self.capabilities.decimal128
+    }
 }
 
 impl MongoStatement for MongoTypesInfo {
     // iterate through the list, searching for the next "valid" data type.
-    // a type is valid if its sql type matches the desired sql type, or if we are getting all types.
+    // a type is valid if the server supports it, and its sql type matches the desired sql type or
+    // we are getting all types.
     fn next(&mut self, _: Option<&MongoConnection>) -> Result<(bool, Vec<Error>)> {
         loop {
             self.current_type_index += 1;
-            if self.current_type_index > DATA_TYPES.len()
-                || DATA_TYPES[self.current_type_index - 1].sql_type(self.type_mode)
-                    == self.sql_data_type
-                || self.sql_data_type == SqlDataType::SQL_UNKNOWN_TYPE
+            if self.current_type_index > DATA_TYPES.len() {
+                break;
+            }
+            let type_info = &DATA_TYPES[self.current_type_index - 1];
+            if self.is_supported(type_info)
+                && (type_info.sql_type(self.type_mode) == self.sql_data_type
+                    || self.sql_data_type == SqlDataType::SQL_UNKNOWN_TYPE)
             {
                 break;
             }
@@ -335,3 +365,34 @@ impl MongoStatement for MongoTypesInfo {
         })
     }
 }
+
+#[cfg(test)]
+mod unit {
+    use super::MongoTypesInfo;
+    use crate::{conn::ServerCapabilities, stmt::MongoStatement, TypeMode};
+    use bson::Bson;
+    use definitions::SqlDataType;
+
+    fn type_names(capabilities: ServerCapabilities) -> Vec<Bson> {
+        let mut types_info = MongoTypesInfo::new(
+            SqlDataType::SQL_UNKNOWN_TYPE,
+            TypeMode::Standard,
+            capabilities,
+        );
+        let mut type_names = vec![];
+        while types_info.next(None).unwrap().0 {
+            type_names.push(types_info.get_value(1, None).unwrap().unwrap());
+        }
+        type_names
+    }
+
+    #[test]
+    fn decimal_is_only_listed_when_the_server_supports_it() {
+        let decimal = Bson::String("decimal".to_string());
+        let types_3_2 = type_names(ServerCapabilities::from_version("3.2.0", &[3, 2, 0, 0]));
+        assert!(!types_3_2.contains(&decimal));
+        let types_4_0 = type_names(ServerCapabilities::from_version("4.0.0", &[4, 0, 0, 0]));
+        assert!(types_4_0.contains(&decimal));
+        assert_eq!(types_4_0.len(), types_3_2.len() + 1);
+    }
+}
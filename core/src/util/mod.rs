@@ -1,4 +1,4 @@
-use bson::doc;
+use bson::{doc, Bson, Document};
 use constants::SQL_ALL_TABLE_TYPES;
 mod test_connection;
 use fancy_regex::Regex as FancyRegex;
@@ -25,7 +25,6 @@ lazy_static! {
 }
 
 // Converts SQL pattern characters (% and _) into proper regex patterns.
-// SQL-1308: Handle SQL_ATTR_METADATA_ID
 // Returns regex for a filter
 pub(crate) fn to_name_regex(filter: &str) -> Option<Regex> {
     match filter {
@@ -78,6 +77,19 @@ pub(crate) fn table_type_filter_to_vec(table_type: &str) -> Option<Vec<Collectio
     };
 }
 
+// Whether string comparisons under the given collation ignore case. Strength 1 and 2 only compare
+// base characters (and accents), unless caseLevel adds case back to the comparison. A collation
+// without a strength uses the default strength of 3, which is case-sensitive.
+pub(crate) fn is_case_insensitive_collation(collation: &Document) -> bool {
+    let ignores_case = match collation.get("strength") {
+        Some(Bson::Int32(s)) => *s <= 2,
+        Some(Bson::Int64(s)) => *s <= 2,
+        Some(Bson::Double(s)) => *s <= 2.0,
+        _ => false,
+    };
+    ignores_case && !collation.get_bool("caseLevel").unwrap_or(false)
+}
+
 #[macro_export]
 macro_rules! map {
         ($($key:expr => $val:expr),* $(,)?) => {
@@ -100,6 +112,34 @@ macro_rules! set {
         };
     }
 
+#[cfg(test)]
+mod collation {
+    use super::is_case_insensitive_collation;
+    use bson::doc;
+
+    #[test]
+    fn binary_and_default_strength_are_case_sensitive() {
+        assert!(!is_case_insensitive_collation(&doc! {}));
+        assert!(!is_case_insensitive_collation(&doc! {"locale": "simple"}));
+        assert!(!is_case_insensitive_collation(
+            &doc! {"locale": "en", "strength": 3}
+        ));
+    }
+
+    #[test]
+    fn primary_and_secondary_strength_are_case_insensitive() {
+        assert!(is_case_insensitive_collation(
+            &doc! {"locale": "en", "strength": 1}
+        ));
+        assert!(is_case_insensitive_collation(
+            &doc! {"locale": "en", "strength": 2i64}
+        ));
+        assert!(!is_case_insensitive_collation(
+            &doc! {"locale": "en", "strength": 2, "caseLevel": true}
+        ));
+    }
+}
+
 #[cfg(test)]
 mod filtering {
     use super::{is_match, to_name_regex};
@@ -161,4 +201,14 @@ mod filtering {
         assert!(is_match("conversion%2022", r"conversion\%2022", true));
         assert!(!is_match("conversions2022", r"conversion\%2022", true));
     }
+
+    #[test]
+    fn test_regex_metacharacters_in_pattern_are_literal() {
+        assert!(is_match("a.b[1]", "a.b[%", true));
+        assert!(!is_match("axb[1]", "a.b[%", true));
+        assert!(!is_match("a.b1]", "a.b[%", true));
+        assert!(is_match("a.b(c)+", "a.b(_)+", true));
+        assert!(!is_match("a.b(c)", "a.b(_)+", true));
+        assert!(is_match("a*b?{2}|^$", "a*b?{2}|^$", true));
+    }
 }
@@ -1,6 +1,6 @@
 #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 use crate::{odbc_uri::ODBCUri, MongoConnection, TypeMode};
-use cstr::{input_text_to_string_w, write_string_to_buffer, WideChar};
+use cstr::{input_text_arg_to_string_w, write_string_to_buffer, WideChar};
 use definitions::{Integer, SQL_NTS_ISIZE};
 
 /// atlas_sql_test_connection returns true if a connection can be established
@@ -24,7 +24,7 @@ pub unsafe extern "C" fn atlas_sql_test_connection(
     buffer_in_len: usize,
     buffer_out_len: *mut Integer,
 ) -> bool {
-    let conn_str = unsafe { input_text_to_string_w(connection_string, SQL_NTS_ISIZE) };
+    let conn_str = unsafe { input_text_arg_to_string_w(connection_string, SQL_NTS_ISIZE) };
     if let Ok(mut odbc_uri) = ODBCUri::new(conn_str) {
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
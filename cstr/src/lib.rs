@@ -86,35 +86,43 @@ pub unsafe fn input_text_to_string_a(text: *const Char, len: isize) -> String {
 
 ///
 /// input_text_to_string_w converts a u16 cstring to a rust String.
-/// It assumes null termination if the supplied length is negative.
+/// It assumes null termination if the supplied length is negative. It shares the behavior of
+/// input_text_arg_to_string_w for null pointers and counted null terminators.
 ///
 /// # Safety
 /// This converts raw C-pointers to rust Strings, which requires unsafe operations
 ///
-#[allow(clippy::uninit_vec)]
 pub unsafe fn input_text_to_string_w(text: *const WideChar, len: isize) -> String {
-    match len.signum() {
-        -1 => {
-            let mut dst = Vec::new();
-            let mut itr = text;
-            {
-                while *itr != 0 {
-                    dst.push(*itr);
-                    itr = itr.offset(1);
-                }
-            }
-            from_widechar_vec_lossy(dst)
-        }
-        0 => String::new(),
-        1 => {
-            let len = len as usize;
-            let mut dst = Vec::with_capacity(len);
-            dst.set_len(len);
-            copy_nonoverlapping(text, dst.as_mut_ptr(), len);
-            from_widechar_vec_lossy(dst)
+    input_text_arg_to_string_w(text, len)
+}
+
+///
+/// input_text_arg_to_string_w converts a wide string argument of an ODBC API function to a rust
+/// String. A null pointer is treated as the empty string, a negative length (SQL_NTS) means the
+/// string is null terminated, and a non-negative length is the exact number of characters to
+/// read. Null terminators counted in an explicit length are not part of the string.
+///
+/// # Safety
+/// This converts raw C-pointers to rust Strings, which requires unsafe operations
+///
+pub unsafe fn input_text_arg_to_string_w(text: *const WideChar, len: isize) -> String {
+    if text.is_null() {
+        return String::new();
+    }
+    let len = if len < 0 {
+        let mut len = 0;
+        while *text.offset(len) != 0 {
+            len += 1;
         }
-        _ => unreachable!("input_text_to_string_w: len was neither negative, zero, nor positive."),
+        len
+    } else {
+        len
+    };
+    let mut chars = std::slice::from_raw_parts(text, len as usize);
+    while let Some((0, rest)) = chars.split_last() {
+        chars = rest;
     }
+    from_widechar_ref_lossy(chars)
 }
 ///
 /// parse_attribute_string_w converts a null-separated doubly null terminated *Widechar string to a Rust
@@ -211,6 +219,8 @@ pub unsafe fn write_string_to_buffer(
 
 ///
 /// write_wstring_slice_to_buffer writes the input WideChar slice (wstring) to the output buffer, and returns the number of bytes written
+/// When the message is truncated, a surrogate pair is never split: the high surrogate is left for the next write instead.
+/// The null terminator is always written, even when nothing else fits.
 ///
 /// # Safety
 /// This writes to a raw c-pointer, which requires unsafe operations
@@ -220,18 +230,23 @@ pub unsafe fn write_wstring_slice_to_buffer(
     len: isize,
     output_ptr: *mut WideChar,
 ) -> isize {
-    let len = std::cmp::min(message.len(), (len - 1) as usize);
+    if len <= 0 {
+        return 0;
+    }
+    let mut len = std::cmp::min(message.len(), (len - 1) as usize);
+    if len > 0 && len < message.len() && is_high_surrogate(message[len - 1]) {
+        len -= 1;
+    }
+    unsafe {
+        copy_nonoverlapping(message[..len].as_ptr(), output_ptr, len);
+        *output_ptr.add(len) = 0;
+    }
 
-    if len == 0 {
-        0
-    } else {
-        unsafe {
-            copy_nonoverlapping(message[..len].as_ptr(), output_ptr, len);
-            *output_ptr.add(len) = 0;
-        }
+    (len + 1) as isize
+}
 
-        (len + 1) as isize
-    }
+fn is_high_surrogate(c: WideChar) -> bool {
+    (0xD800..=0xDBFF).contains(&u32::from(c))
 }
 
 ///
@@ -381,6 +396,20 @@ mod test {
         assert_eq!(len, std::cmp::min(input.len() + 1, buffer.len()) as isize);
     }
 
+    #[test]
+    fn test_write_wstring_slice_to_buffer_with_room_for_the_null_terminator_only() {
+        let input = &to_widechar_vec("test")[..];
+        let mut buffer = [1; 1];
+        let len = unsafe {
+            write_wstring_slice_to_buffer(input, buffer.len() as isize, buffer.as_mut_ptr())
+        };
+        assert_eq!(1, len);
+        assert_eq!(0, buffer[0]);
+
+        let len = unsafe { write_wstring_slice_to_buffer(input, 0, buffer.as_mut_ptr()) };
+        assert_eq!(0, len);
+    }
+
     #[test]
     fn test_write_wstring_slice_to_buffer_constrained_space() {
         let expected = "te\0";
@@ -405,6 +434,30 @@ mod test {
         assert_eq!(len, std::cmp::min(input.len() + 1, buffer.len()) as isize);
     }
 
+    #[test]
+    #[cfg(not(feature = "utf32"))]
+    fn test_write_wstring_slice_to_buffer_does_not_split_surrogate_pairs() {
+        let input = &to_widechar_vec("a😀b")[..];
+        assert_eq!(4, input.len());
+        let mut buffer = [0; 3];
+        let len = unsafe {
+            write_wstring_slice_to_buffer(input, buffer.len() as isize, buffer.as_mut_ptr())
+        };
+        assert_eq!(2, len);
+        assert_eq!("a", from_widechar_ref_lossy(&buffer[..1]));
+
+        // Only the null terminator fits when the pair starts the message.
+        let len = unsafe { write_wstring_slice_to_buffer(&input[1..], 2, buffer.as_mut_ptr()) };
+        assert_eq!(1, len);
+        assert_eq!(0, buffer[0]);
+
+        let len = unsafe {
+            write_wstring_slice_to_buffer(&input[1..], buffer.len() as isize, buffer.as_mut_ptr())
+        };
+        assert_eq!(3, len);
+        assert_eq!("😀", from_widechar_ref_lossy(&buffer[..2]));
+    }
+
     #[test]
     fn test_write_string_slice_to_buffer_with_enough_space() {
         let expected = "test\0";
@@ -490,4 +543,58 @@ mod test {
         let test = unsafe { input_text_to_string_a(test, 0) };
         assert_eq!(expected, test);
     }
+
+    #[test]
+    fn test_input_text_arg_to_string_w_nts() {
+        let test = to_widechar_vec("test\0ignored");
+        let test = unsafe { input_text_arg_to_string_w(test.as_ptr(), -3) };
+        assert_eq!("test", test);
+    }
+
+    #[test]
+    fn test_input_text_arg_to_string_w_null_pointer_is_empty() {
+        assert_eq!("", unsafe {
+            input_text_arg_to_string_w(std::ptr::null(), -3)
+        });
+        assert_eq!("", unsafe {
+            input_text_arg_to_string_w(std::ptr::null(), 4)
+        });
+    }
+
+    #[test]
+    fn test_input_text_arg_to_string_w_exact_length() {
+        // The buffer is not null terminated, so nothing past the length may be read.
+        let test = to_widechar_vec("testing");
+        let test = unsafe { input_text_arg_to_string_w(test.as_ptr(), 4) };
+        assert_eq!("test", test);
+    }
+
+    #[test]
+    fn test_input_text_arg_to_string_w_length_including_terminator() {
+        let test = to_widechar_vec("test\0");
+        let test = unsafe { input_text_arg_to_string_w(test.as_ptr(), 5) };
+        assert_eq!("test", test);
+    }
+
+    #[test]
+    fn test_input_text_arg_to_string_w_zero_length() {
+        let test = to_widechar_vec("test");
+        let test = unsafe { input_text_arg_to_string_w(test.as_ptr(), 0) };
+        assert_eq!("", test);
+    }
+
+    #[test]
+    fn test_input_text_arg_to_string_w_embedded_content() {
+        // Delimiters and characters outside the BMP must survive both an explicit length and
+        // SQL_NTS.
+        let expected = "URI={mongodb://h/?a=b;c=d};PWD=p\u{e9}\u{1f600}";
+        let len = to_widechar_vec(expected).len() as isize;
+        let test = to_widechar_vec(&format!("{expected};trailing\0"));
+        assert_eq!(expected, unsafe {
+            input_text_arg_to_string_w(test.as_ptr(), len)
+        });
+        assert_eq!(format!("{expected};trailing"), unsafe {
+            input_text_arg_to_string_w(test.as_ptr(), -3)
+        });
+    }
 }
@@ -103,6 +103,26 @@ impl From<AttrCpMatch> for Pointer {
     }
 }
 
+/// Whether statements are committed automatically.
+///
+/// Possible values for the `SQL_ATTR_AUTOCOMMIT` attribute set with `SQLSetConnectAttr`.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive)]
+pub enum AttrAutoCommit {
+    /// The application must explicitly commit or roll back transactions with `SQLEndTran`.
+    SQL_AUTOCOMMIT_OFF = 0,
+    /// Each statement is committed immediately after it is executed. This is the default.
+    SQL_AUTOCOMMIT_ON = 1,
+}
+
+/// Autocommit is on by default
+impl Default for AttrAutoCommit {
+    fn default() -> Self {
+        AttrAutoCommit::SQL_AUTOCOMMIT_ON
+    }
+}
+
 const SQL_ATTR_ASYNC_ENABLE: i32 = 4;
 const SQL_ATTR_METADATA_ID: i32 = 10014;
 
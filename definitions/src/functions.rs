@@ -792,6 +792,41 @@ extern "system" {
         fk_table_name_length: SmallInt,
     ) -> SqlReturn;
 
+    /// Retrieves the optimal set of columns that uniquely identifies a row in the table, or the
+    /// columns that are automatically updated when any value in the row is updated.
+    ///
+    /// # Returns
+    /// `SUCCESS`, `SUCCESS_WITH_INFO`, `ERROR`, `INVALID_HANDLE`, or `SQL_STILL_EXECUTING`.
+    pub fn SQLSpecialColumnsW(
+        statement_handle: HStmt,
+        identifier_type: SmallInt,
+        catalog_name: *const WChar,
+        catalog_name_length: SmallInt,
+        schema_name: *const WChar,
+        schema_name_length: SmallInt,
+        table_name: *const WChar,
+        table_name_length: SmallInt,
+        scope: SmallInt,
+        nullable: SmallInt,
+    ) -> SqlReturn;
+
+    /// Retrieves a list of statistics about a single table and the indexes associated with the
+    /// table.
+    ///
+    /// # Returns
+    /// `SUCCESS`, `SUCCESS_WITH_INFO`, `ERROR`, `INVALID_HANDLE`, or `SQL_STILL_EXECUTING`.
+    pub fn SQLStatisticsW(
+        statement_handle: HStmt,
+        catalog_name: *const WChar,
+        catalog_name_length: SmallInt,
+        schema_name: *const WChar,
+        schema_name_length: SmallInt,
+        table_name: *const WChar,
+        table_name_length: SmallInt,
+        unique: SmallInt,
+        reserved: SmallInt,
+    ) -> SqlReturn;
+
     /// Returns the result descriptor for one column in the result set — column name, type, column
     /// size, decimal digits, and nullability.
     ///
@@ -76,6 +76,7 @@ pub type RetCode = i16;
 
 // Diag constants
 pub const SQL_ROW_NUMBER_UNKNOWN: isize = -2;
+pub const SQL_COLUMN_NUMBER_UNKNOWN: i32 = -2;
 
 // flags for null-terminated string
 pub const SQL_NTS: Integer = -3;
@@ -98,7 +99,9 @@ pub enum FreeStmtOption {
     /// option has no effect for the application. `SQLCloseCursor` can also be called to close a
     /// cursor.
     SQL_CLOSE = 0,
-    // SQL_DROP = 1, is deprecated in favour of SQLFreeHandle
+    /// Frees the StatementHandle. Deprecated in ODBC 3.x in favour of `SQLFreeHandle` with a
+    /// `HandleType` of `SQL_HANDLE_STMT`, but still issued by many applications.
+    SQL_DROP = 1,
     /// Sets the `SQL_DESC_COUNT` field of the ARD to 0, releasing all column buffers bound by
     /// `SQLBindCol` for the given StatementHandle. This does not unbind the bookmark column; to do
     /// that, the `SQL_DESC_DATA_PTR` field of the ARD for the bookmark column is set to NULL.
@@ -286,6 +289,51 @@ pub enum DynamicDiagnosticIdentifier {
     SQL_DIAG_UPDATE_WHERE = 82,
 }
 
+/// `IdentifierType` for `SQLSpecialColumns`
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive)]
+#[repr(i16)]
+pub enum IdentifierType {
+    SQL_BEST_ROWID = 1,
+    SQL_ROWVER = 2,
+}
+
+// SCOPE and PSEUDO_COLUMN values returned by `SQLSpecialColumns`
+pub const SQL_SCOPE_CURROW: SmallInt = 0;
+pub const SQL_SCOPE_TRANSACTION: SmallInt = 1;
+pub const SQL_SCOPE_SESSION: SmallInt = 2;
+pub const SQL_PC_UNKNOWN: SmallInt = 0;
+pub const SQL_PC_NOT_PSEUDO: SmallInt = 1;
+pub const SQL_PC_PSEUDO: SmallInt = 2;
+
+// Values of the SQL_DESC_UNNAMED descriptor field
+pub const SQL_NAMED: SmallInt = 0;
+pub const SQL_UNNAMED: SmallInt = 1;
+
+/// `Unique` argument of `SQLStatistics`
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive)]
+#[repr(i16)]
+pub enum IndexUniqueness {
+    SQL_INDEX_UNIQUE = 0,
+    SQL_INDEX_ALL = 1,
+}
+
+/// `Reserved` argument of `SQLStatistics`, the accuracy of the CARDINALITY and PAGES columns
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive)]
+#[repr(i16)]
+pub enum StatisticsAccuracy {
+    SQL_QUICK = 0,
+    SQL_ENSURE = 1,
+}
+
+// TYPE values returned by `SQLStatistics`
+pub const SQL_TABLE_STAT: SmallInt = 0;
+pub const SQL_INDEX_CLUSTERED: SmallInt = 1;
+pub const SQL_INDEX_HASHED: SmallInt = 2;
+pub const SQL_INDEX_OTHER: SmallInt = 3;
+
 #[allow(non_camel_case_types)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive)]
 #[repr(i16)]
@@ -1,5 +1,6 @@
 use mongodb::{
     bson::{doc, Bson, Document},
+    options::{Collation, CreateCollectionOptions},
     Client, Database, IndexModel,
 };
 use serde::{Deserialize, Serialize};
@@ -59,6 +60,13 @@ struct TestDataEntry {
     //
     // See the docs for more details on possible options.
     indexes: Option<Vec<IndexModel>>,
+
+    // collation specifies the default collation of the collection for this
+    // test entry. Optional. Can only be provided for collections, not views.
+    //
+    // Example:
+    //   collation: {locale: "en", strength: 2}
+    collation: Option<Collation>,
 }
 
 type Result<T> = std::result::Result<T, DataLoaderError>;
@@ -182,6 +190,13 @@ async fn load_test_data(client: Client, test_data: Vec<TestData>) -> Result<()>
             let db = client.database(entry.db.as_str());
 
             if let Some(c) = entry.collection {
+                if let Some(collation) = entry.collation {
+                    let options = CreateCollectionOptions::builder()
+                        .collation(collation)
+                        .build();
+                    db.create_collection(c.as_str(), options).await?;
+                }
+
                 let collection = db.collection::<Bson>(c.as_str());
 
                 if let Some(docs) = entry.docs {
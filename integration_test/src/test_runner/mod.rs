@@ -116,6 +116,8 @@ pub struct TestEntry {
     pub expected_precision: Option<Vec<Value>>,
     pub expected_scale: Option<Vec<Value>>,
     pub expected_nullability: Option<Vec<Value>>,
+    pub expected_base_table_name: Option<Vec<Value>>,
+    pub expected_base_column_name: Option<Vec<Value>>,
 }
 
 #[derive(Debug, Error, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -685,6 +687,20 @@ fn validate_result_set_metadata(entry: &TestEntry, column_count: usize, stmt: HS
         Desc::SQL_DESC_NULLABLE,
         &entry.expected_nullability,
     )?;
+    validate_result_set_metadata_helper(
+        stmt,
+        column_count,
+        entry.description.clone(),
+        Desc::SQL_DESC_BASE_TABLE_NAME,
+        &entry.expected_base_table_name,
+    )?;
+    validate_result_set_metadata_helper(
+        stmt,
+        column_count,
+        entry.description.clone(),
+        Desc::SQL_DESC_BASE_COLUMN_NAME,
+        &entry.expected_base_column_name,
+    )?;
     Ok(())
 }
 
@@ -33,6 +33,8 @@ pub fn generate_baseline_test_file(entry: &TestEntry, stmt: HStmt) -> Result<()>
     let mut expected_precision: Vec<Value> = vec![];
     let mut expected_scale: Vec<Value> = vec![];
     let mut expected_nullability: Vec<Value> = vec![];
+    let mut expected_base_table_name: Vec<Value> = vec![];
+    let mut expected_base_column_name: Vec<Value> = vec![];
 
     for i in 1..(column_count + 1) {
         let catalog_name = get_column_attribute(stmt, i, Desc::SQL_DESC_CATALOG_NAME, &STRING_VAL)?;
@@ -71,6 +73,14 @@ pub fn generate_baseline_test_file(entry: &TestEntry, stmt: HStmt) -> Result<()>
 
         let nullability = get_column_attribute(stmt, i, Desc::SQL_DESC_NULLABLE, &NUMBER_VAL)?;
         expected_nullability.push(nullability);
+
+        let base_table_name =
+            get_column_attribute(stmt, i, Desc::SQL_DESC_BASE_TABLE_NAME, &STRING_VAL)?;
+        expected_base_table_name.push(base_table_name);
+
+        let base_column_name =
+            get_column_attribute(stmt, i, Desc::SQL_DESC_BASE_COLUMN_NAME, &STRING_VAL)?;
+        expected_base_column_name.push(base_column_name);
     }
 
     // 2. Get result set data
@@ -107,6 +117,8 @@ pub fn generate_baseline_test_file(entry: &TestEntry, stmt: HStmt) -> Result<()>
         expected_precision: Some(expected_precision),
         expected_scale: Some(expected_scale),
         expected_nullability: Some(expected_nullability),
+        expected_base_table_name: Some(expected_base_table_name),
+        expected_base_column_name: Some(expected_base_column_name),
     };
 
     // 4. Write the TestEntry to a file
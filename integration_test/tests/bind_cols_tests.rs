@@ -12,8 +12,8 @@ mod integration {
     };
     use definitions::{
         AttrOdbcVersion, CDataType, FetchOrientation, FreeStmtOption, Handle, HandleType, Integer,
-        Len, Pointer, SQLFetchScroll, SQLFreeStmt, SQLSetStmtAttrW, SmallInt, SqlReturn,
-        StatementAttribute, ULen,
+        Len, Pointer, SQLBindCol, SQLFetchScroll, SQLFreeStmt, SQLSetStmtAttrW, SmallInt,
+        SqlReturn, StatementAttribute, ULen,
     };
 
     // The `_id` field is an int that is used across multiple tests.
@@ -181,4 +181,98 @@ mod integration {
             }
         }
     }
+
+    /// This test checks that a statement can be re-executed after closing its
+    /// cursor, and that the column bindings survive SQLFreeStmt(SQL_CLOSE).
+    /// After allocating a statement handle, the flow is:
+    ///     - SQLExecDirectW(<query>)
+    ///     - SQLBindCol
+    ///     - SQLFetchScroll
+    ///     - SQLFreeStmt(SQL_CLOSE)
+    ///     - SQLBindCol (rebind only the second column)
+    ///     - SQLExecDirectW(<query>)
+    ///     - SQLFetchScroll
+    #[test]
+    fn test_close_rebind_and_re_execute() {
+        let (_, _, stmt_handle) =
+            default_setup_connect_and_alloc_stmt(AttrOdbcVersion::SQL_OV_ODBC3);
+
+        unsafe {
+            exec_direct_default_query(stmt_handle);
+
+            let id_buffer = &mut [0u8; ID_TRANSFER_OCTET_LEN];
+            let id_indicator = &mut [0isize; 1] as *mut Len;
+            let a_buffer = &mut [0u8; A_TRANSFER_OCTET_LEN];
+            let a_indicator = &mut [0isize; 1] as *mut Len;
+
+            bind_cols(
+                stmt_handle,
+                vec![
+                    (
+                        CDataType::SQL_C_SLONG,
+                        id_buffer as *mut u8 as Pointer,
+                        ID_TRANSFER_OCTET_LEN as Len,
+                        id_indicator,
+                    ),
+                    (
+                        CDataType::SQL_C_SBIGINT,
+                        a_buffer as *mut u8 as Pointer,
+                        A_TRANSFER_OCTET_LEN as Len,
+                        a_indicator,
+                    ),
+                ],
+            );
+
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLFetchScroll(stmt_handle, FetchOrientation::SQL_FETCH_NEXT as SmallInt, 0),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            assert_eq!(0, *(id_buffer as *mut _ as *mut i32));
+            assert_eq!(42, *(a_buffer as *mut _ as *mut i64));
+
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLFreeStmt(stmt_handle, FreeStmtOption::SQL_CLOSE as SmallInt),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+
+            // Rebind the second column to a new buffer. The first column keeps
+            // the binding it had before the cursor was closed.
+            *id_buffer = [0u8; ID_TRANSFER_OCTET_LEN];
+            *a_buffer = [0u8; A_TRANSFER_OCTET_LEN];
+            let rebound_a_buffer = &mut [0u8; A_TRANSFER_OCTET_LEN];
+            let rebound_a_indicator = &mut [0isize; 1] as *mut Len;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLBindCol(
+                    stmt_handle,
+                    2,
+                    CDataType::SQL_C_SBIGINT as SmallInt,
+                    rebound_a_buffer as *mut u8 as Pointer,
+                    A_TRANSFER_OCTET_LEN as Len,
+                    rebound_a_indicator,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+
+            exec_direct_default_query(stmt_handle);
+
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLFetchScroll(stmt_handle, FetchOrientation::SQL_FETCH_NEXT as SmallInt, 0),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            assert_eq!(0, *(id_buffer as *mut _ as *mut i32));
+            assert_eq!(ID_TRANSFER_OCTET_LEN as isize, *id_indicator);
+            assert_eq!(42, *(rebound_a_buffer as *mut _ as *mut i64));
+            assert_eq!(A_TRANSFER_OCTET_LEN as isize, *rebound_a_indicator);
+            // The original buffer for the second column is no longer bound.
+            assert_eq!([0u8; A_TRANSFER_OCTET_LEN], *a_buffer);
+        }
+    }
 }
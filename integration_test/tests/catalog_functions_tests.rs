@@ -0,0 +1,339 @@
+#![allow(
+    clippy::ptr_as_ptr,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+mod common;
+
+mod integration {
+    use crate::common::{
+        allocate_env, connect_and_allocate_statement, disconnect_and_close_handles,
+        get_column_attributes, get_sql_diagnostics, BUFFER_LENGTH,
+    };
+    use definitions::{
+        AttrOdbcVersion, CDataType, HStmt, Handle, HandleType, IdentifierType, IndexUniqueness,
+        Nullability, Pointer, SQLColumnsW, SQLFetch, SQLGetData, SQLSetStmtAttrW,
+        SQLSpecialColumnsW, SQLStatisticsW, SqlBool, SqlReturn, StatementAttribute,
+        StatisticsAccuracy, SQL_NTS, SQL_PC_NOT_PSEUDO, SQL_SCOPE_SESSION, SQL_TABLE_STAT,
+    };
+    use std::ptr::null_mut;
+
+    fn get_string_data(stmt: HStmt, col_num: u16) -> String {
+        let output_buffer = &mut [0u16; (BUFFER_LENGTH as usize - 1)];
+        let str_len_ptr = &mut 0;
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt,
+                    col_num,
+                    CDataType::SQL_C_WCHAR as i16,
+                    output_buffer.as_mut_ptr() as Pointer,
+                    (BUFFER_LENGTH as usize * std::mem::size_of::<u16>()) as isize,
+                    str_len_ptr,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+            );
+        }
+        cstr::from_widechar_ref_lossy(&output_buffer[..*str_len_ptr as usize / 2])
+    }
+
+    fn get_int_data(stmt: HStmt, col_num: u16) -> i32 {
+        let value = &mut 0i32;
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt,
+                    col_num,
+                    CDataType::SQL_C_SLONG as i16,
+                    value as *mut i32 as Pointer,
+                    0,
+                    null_mut(),
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+            );
+        }
+        *value
+    }
+
+    fn special_columns(stmt: HStmt, identifier_type: IdentifierType, catalog: &str, table: &str) {
+        let catalog = cstr::to_widechar_vec(catalog);
+        let table = cstr::to_widechar_vec(table);
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSpecialColumnsW(
+                    stmt,
+                    identifier_type as i16,
+                    catalog.as_ptr(),
+                    SQL_NTS as i16,
+                    null_mut(),
+                    0,
+                    table.as_ptr(),
+                    SQL_NTS as i16,
+                    SQL_SCOPE_SESSION,
+                    Nullability::SQL_NULLABLE as i16,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+            );
+        }
+    }
+
+    #[test]
+    fn special_columns_best_rowid() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        special_columns(
+            stmt_handle,
+            IdentifierType::SQL_BEST_ROWID,
+            "integration_test",
+            "example",
+        );
+        get_column_attributes(stmt_handle as Handle, 8);
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle));
+            // SCOPE
+            assert_eq!(i32::from(SQL_SCOPE_SESSION), get_int_data(stmt_handle, 1));
+            // COLUMN_NAME
+            assert_eq!("_id", get_string_data(stmt_handle, 2));
+            // PSEUDO_COLUMN
+            assert_eq!(i32::from(SQL_PC_NOT_PSEUDO), get_int_data(stmt_handle, 8));
+            assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt_handle));
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+    }
+
+    #[test]
+    fn special_columns_rowver() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        special_columns(
+            stmt_handle,
+            IdentifierType::SQL_ROWVER,
+            "integration_test",
+            "example",
+        );
+        get_column_attributes(stmt_handle as Handle, 8);
+        unsafe {
+            assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt_handle));
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+    }
+
+    // Calls SQLStatistics on integration_test.example and returns the rows read by read_row, for
+    // the rows of the given TYPE.
+    fn example_statistics_rows<T>(
+        unique: IndexUniqueness,
+        accuracy: StatisticsAccuracy,
+        table_stat: bool,
+        read_row: impl Fn(HStmt) -> T,
+    ) -> Vec<T> {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        let catalog = cstr::to_widechar_vec("integration_test");
+        let table = cstr::to_widechar_vec("example");
+        let mut rows = vec![];
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLStatisticsW(
+                    stmt_handle,
+                    catalog.as_ptr(),
+                    SQL_NTS as i16,
+                    null_mut(),
+                    0,
+                    table.as_ptr(),
+                    SQL_NTS as i16,
+                    unique as i16,
+                    accuracy as i16,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            get_column_attributes(stmt_handle as Handle, 13);
+            while SQLFetch(stmt_handle) == SqlReturn::SUCCESS {
+                if (get_int_data(stmt_handle, 7) == i32::from(SQL_TABLE_STAT)) == table_stat {
+                    rows.push(read_row(stmt_handle));
+                }
+            }
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        rows
+    }
+
+    // Returns (NON_UNIQUE, INDEX_NAME, ORDINAL_POSITION, COLUMN_NAME, ASC_OR_DESC) for every index
+    // row returned by SQLStatistics on integration_test.example.
+    fn example_statistics(unique: IndexUniqueness) -> Vec<(i32, String, i32, String, String)> {
+        example_statistics_rows(
+            unique,
+            StatisticsAccuracy::SQL_QUICK,
+            false,
+            |stmt_handle| {
+                (
+                    get_int_data(stmt_handle, 4),
+                    get_string_data(stmt_handle, 6),
+                    get_int_data(stmt_handle, 8),
+                    get_string_data(stmt_handle, 9),
+                    get_string_data(stmt_handle, 10),
+                )
+            },
+        )
+    }
+
+    #[test]
+    fn statistics_table_row_reports_the_document_count() {
+        for accuracy in [
+            StatisticsAccuracy::SQL_QUICK,
+            StatisticsAccuracy::SQL_ENSURE,
+        ] {
+            assert_eq!(
+                vec![("example".to_string(), 3)],
+                example_statistics_rows(
+                    IndexUniqueness::SQL_INDEX_ALL,
+                    accuracy,
+                    true,
+                    |stmt_handle| (
+                        get_string_data(stmt_handle, 3),
+                        get_int_data(stmt_handle, 11)
+                    )
+                ),
+                "{accuracy:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn statistics_all_indexes() {
+        assert_eq!(
+            vec![
+                (0, "_id_".to_string(), 1, "_id".to_string(), "A".to_string()),
+                (
+                    0,
+                    "_id_1_b_1".to_string(),
+                    1,
+                    "_id".to_string(),
+                    "A".to_string()
+                ),
+                (
+                    0,
+                    "_id_1_b_1".to_string(),
+                    2,
+                    "b".to_string(),
+                    "A".to_string()
+                ),
+                (1, "b_1".to_string(), 1, "b".to_string(), "A".to_string()),
+                (
+                    1,
+                    "b_1__id_-1".to_string(),
+                    1,
+                    "b".to_string(),
+                    "A".to_string()
+                ),
+                (
+                    1,
+                    "b_1__id_-1".to_string(),
+                    2,
+                    "_id".to_string(),
+                    "D".to_string()
+                ),
+            ],
+            example_statistics(IndexUniqueness::SQL_INDEX_ALL)
+        );
+    }
+
+    #[test]
+    fn statistics_unique_indexes() {
+        assert_eq!(
+            vec![
+                (0, "_id_".to_string(), 1, "_id".to_string(), "A".to_string()),
+                (
+                    0,
+                    "_id_1_b_1".to_string(),
+                    1,
+                    "_id".to_string(),
+                    "A".to_string()
+                ),
+                (
+                    0,
+                    "_id_1_b_1".to_string(),
+                    2,
+                    "b".to_string(),
+                    "A".to_string()
+                ),
+            ],
+            example_statistics(IndexUniqueness::SQL_INDEX_UNIQUE)
+        );
+    }
+
+    // Returns (TABLE_NAME, COLUMN_NAME) for every row returned by SQLColumns for the _id column of
+    // the sales%2022 table of integration_test_2, with the given SQL_ATTR_METADATA_ID.
+    fn sales_2022_columns(metadata_id: SqlBool) -> Vec<(String, String)> {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        let catalog = cstr::to_widechar_vec("integration_test_2");
+        let table = cstr::to_widechar_vec("sales%2022");
+        let column = cstr::to_widechar_vec("_id");
+        let mut rows = vec![];
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetStmtAttrW(
+                    stmt_handle,
+                    StatementAttribute::SQL_ATTR_METADATA_ID as i32,
+                    metadata_id as i32 as Pointer,
+                    0,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLColumnsW(
+                    stmt_handle,
+                    catalog.as_ptr(),
+                    SQL_NTS as i16,
+                    null_mut(),
+                    0,
+                    table.as_ptr(),
+                    SQL_NTS as i16,
+                    column.as_ptr(),
+                    SQL_NTS as i16,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            while SQLFetch(stmt_handle) == SqlReturn::SUCCESS {
+                rows.push((
+                    get_string_data(stmt_handle, 3),
+                    get_string_data(stmt_handle, 4),
+                ));
+            }
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        rows
+    }
+
+    #[test]
+    fn columns_table_name_is_a_pattern_without_metadata_id() {
+        assert_eq!(
+            vec![
+                ("sales%2022".to_string(), "_id".to_string()),
+                ("sales_q4_2022".to_string(), "_id".to_string()),
+            ],
+            sales_2022_columns(SqlBool::SQL_FALSE)
+        );
+    }
+
+    #[test]
+    fn columns_table_name_is_an_identifier_with_metadata_id() {
+        assert_eq!(
+            vec![("sales%2022".to_string(), "_id".to_string())],
+            sales_2022_columns(SqlBool::SQL_TRUE)
+        );
+    }
+}
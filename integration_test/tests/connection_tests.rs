@@ -8,12 +8,19 @@ mod common;
 
 mod integration {
     use crate::common::{
-        allocate_env, connect_and_allocate_statement, connect_with_conn_string,
-        disconnect_and_close_handles,
+        allocate_env, allocate_statement, connect_and_allocate_statement, connect_with_conn_string,
+        disconnect_and_close_handles, get_sql_diagnostics, Error,
     };
     use constants::DRIVER_NAME;
+    #[cfg(feature = "test_hooks")]
+    use constants::SQL_ATTR_MONGODB_GET_MORE_COUNT;
     use cstr::{to_char_ptr, to_widechar_ptr, WideChar};
-    use definitions::{AttrOdbcVersion, SQLExecDirectW, SqlReturn};
+    use definitions::{
+        AttrOdbcVersion, CDataType, ConnectionAttribute, FreeStmtOption, HDbc, HStmt, Handle,
+        HandleType, InfoType, Pointer, SQLAllocHandle, SQLBrowseConnectW, SQLDescribeColW,
+        SQLExecDirectW, SQLFetch, SQLFreeStmt, SQLGetData, SQLGetInfoW, SQLGetStmtAttrW,
+        SQLNumResultCols, SQLSetConnectAttrW, SqlDataType, SqlReturn, Timestamp, ULen, SQL_NTS,
+    };
     use lazy_static::lazy_static;
     use logger::Logger;
     use regex::Regex;
@@ -73,6 +80,1450 @@ mod integration {
         let _ = unsafe { Box::from_raw(env_handle) };
     }
 
+    #[test]
+    fn unsupported_compressor_falls_back_to_uncompressed() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str =
+            crate::common::generate_uri_with_default_connection_string("compressors=lz4");
+        match connect_with_conn_string(env_handle, Some(conn_str)) {
+            // The connection succeeds, but the downgrade is reported as a warning
+            Err(Error::DriverConnect(sql_return, diagnostics)) => {
+                assert_eq!("SUCCESS_WITH_INFO", sql_return);
+                assert!(
+                    diagnostics.contains("falling back to uncompressed"),
+                    "unexpected diagnostics: {diagnostics}"
+                );
+            }
+            // The windows DM already turns SUCCESS into SUCCESS_WITH_INFO, which the
+            // helper treats as a successful connection
+            Ok(_) => assert!(cfg!(windows), "expected SUCCESS_WITH_INFO"),
+            Err(e) => panic!("connection should succeed with info: {e}"),
+        }
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn unknown_keyword_is_ignored_with_info() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}NOT_A_KEYWORD=1;",
+            crate::common::generate_default_connection_str()
+        );
+        match connect_with_conn_string(env_handle, Some(conn_str)) {
+            Err(Error::DriverConnect(sql_return, diagnostics)) => {
+                assert_eq!("SUCCESS_WITH_INFO", sql_return);
+                assert!(
+                    diagnostics.contains("unknown keyword 'not_a_keyword' was ignored"),
+                    "unexpected diagnostics: {diagnostics}"
+                );
+            }
+            Ok(_) => assert!(cfg!(windows), "expected SUCCESS_WITH_INFO"),
+            Err(e) => panic!("connection should succeed with info: {e}"),
+        }
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn slow_query_reports_warning() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}SLOW_QUERY_MS=0;",
+            crate::common::generate_default_connection_str()
+        );
+        let conn_handle = connect_with_conn_string(env_handle, Some(conn_str)).unwrap();
+        let stmt_handle = allocate_statement(conn_handle).unwrap();
+        let mut query: Vec<WideChar> =
+            cstr::to_widechar_vec("SELECT *\n  FROM   integration_test.foo");
+        query.push(0);
+        unsafe {
+            // Any query takes longer than 0ms, so it is always reported as slow
+            assert_eq!(
+                SqlReturn::SUCCESS_WITH_INFO,
+                SQLExecDirectW(stmt_handle, query.as_ptr(), SQL_NTS)
+            );
+        }
+        let diagnostics = get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle);
+        assert!(
+            diagnostics.contains("exceeding the slow query threshold of 0ms"),
+            "unexpected diagnostics: {diagnostics}"
+        );
+        // The statement is normalized to a single line
+        assert!(
+            diagnostics.ends_with("SELECT * FROM integration_test.foo"),
+            "unexpected diagnostics: {diagnostics}"
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    fn exec_direct(stmt_handle: HStmt, query: &str) -> SqlReturn {
+        let mut query: Vec<WideChar> = cstr::to_widechar_vec(query);
+        query.push(0);
+        unsafe { SQLExecDirectW(stmt_handle, query.as_ptr(), SQL_NTS) }
+    }
+
+    #[test]
+    fn limit_without_order_by_reports_warning() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        assert_eq!(
+            SqlReturn::SUCCESS_WITH_INFO,
+            exec_direct(stmt_handle, "SELECT * FROM integration_test.foo LIMIT 2")
+        );
+        let diagnostics = get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle);
+        assert!(
+            diagnostics.contains("LIMIT without ORDER BY returns an arbitrary"),
+            "unexpected diagnostics: {diagnostics}"
+        );
+        assert_eq!(SqlReturn::SUCCESS, unsafe {
+            SQLFreeStmt(stmt_handle, FreeStmtOption::SQL_CLOSE as i16)
+        });
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT * FROM integration_test.foo ORDER BY foo.a LIMIT 2"
+            )
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn set_time_zone_changes_returned_timestamps() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, "SET TIMEZONE = '-05:00'"),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        assert_eq!(SqlReturn::SUCCESS, unsafe {
+            SQLFreeStmt(stmt_handle, FreeStmtOption::SQL_CLOSE as i16)
+        });
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT startdate FROM integration_test.class WHERE _id = 0"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        // The start date is midnight UTC, which is the evening before five hours west of UTC.
+        let mut timestamp = Timestamp::default();
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle));
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt_handle,
+                    1,
+                    CDataType::SQL_C_TYPE_TIMESTAMP as i16,
+                    &mut timestamp as *mut Timestamp as Pointer,
+                    std::mem::size_of::<Timestamp>() as isize,
+                    &mut 0,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+        }
+        assert_eq!(
+            (1999, 12, 31, 19, 0, 0),
+            (
+                timestamp.year,
+                timestamp.month,
+                timestamp.day,
+                timestamp.hour,
+                timestamp.minute,
+                timestamp.second
+            )
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn unknown_set_statement_is_ignored_with_warning() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, "SET NAMES utf8"),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        assert_eq!(
+            SqlReturn::SUCCESS_WITH_INFO,
+            exec_direct(stmt_handle, "SET ANSI_NULLS ON")
+        );
+        let diagnostics = get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle);
+        assert!(
+            diagnostics.contains("ANSI_NULLS is not supported"),
+            "unexpected diagnostics: {diagnostics}"
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn character_literal_of_the_maximum_length_is_accepted() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        let mut max_lengths = [0u32; 2];
+        for (info_type, max_length) in [
+            InfoType::SQL_MAX_BINARY_LITERAL_LEN,
+            InfoType::SQL_MAX_CHAR_LITERAL_LEN,
+        ]
+        .into_iter()
+        .zip(max_lengths.iter_mut())
+        {
+            assert_eq!(SqlReturn::SUCCESS, unsafe {
+                SQLGetInfoW(
+                    conn_handle as HDbc,
+                    info_type as u16,
+                    max_length as *mut u32 as Pointer,
+                    std::mem::size_of::<u32>() as i16,
+                    &mut 0,
+                )
+            });
+        }
+        assert_eq!(max_lengths[0], max_lengths[1]);
+        let max_length = max_lengths[1] as usize;
+        assert!(max_length > 0);
+
+        let literal = "x".repeat(max_length);
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, &format!("SELECT '{literal}' AS s")),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut buffer = vec![0u8; max_length + 1];
+        let mut length = 0;
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle));
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt_handle,
+                    1,
+                    CDataType::SQL_C_CHAR as i16,
+                    buffer.as_mut_ptr() as Pointer,
+                    buffer.len() as isize,
+                    &mut length,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+        }
+        assert_eq!(max_length as isize, length);
+        assert_eq!(literal.as_bytes(), &buffer[..max_length]);
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn explain_analyze_returns_execution_stats() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "EXPLAIN ANALYZE SELECT * FROM integration_test.foo"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        unsafe {
+            for (col, expected_name) in ["nReturned", "totalDocsExamined", "executionTimeMillis"]
+                .iter()
+                .enumerate()
+            {
+                let name_buffer = &mut [0 as WideChar; 32];
+                let name_length = &mut 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLDescribeColW(
+                        stmt_handle,
+                        col as u16 + 1,
+                        name_buffer.as_mut_ptr(),
+                        name_buffer.len() as i16,
+                        name_length,
+                        &mut 0,
+                        &mut 0,
+                        &mut 0,
+                        &mut 0,
+                    )
+                );
+                assert_eq!(
+                    *expected_name,
+                    cstr::from_widechar_ref_lossy(&name_buffer[..*name_length as usize])
+                );
+            }
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle));
+            let n_returned = &mut -1i64;
+            let indicator = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt_handle,
+                    1,
+                    CDataType::SQL_C_SBIGINT as i16,
+                    n_returned as *mut i64 as Pointer,
+                    0,
+                    indicator,
+                )
+            );
+            assert!(*n_returned >= 0);
+            assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt_handle));
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // SQL_ROW_UPDATES is N: keyset-driven cursors fall back to forward-only cursors, whose rows
+    // are read from the result of the query, so a row updated by another client after the query
+    // ran is returned unchanged and is not reported as updated.
+    #[test]
+    fn row_updates_by_others_are_not_detected() {
+        use definitions::{RowStatus, SQLSetStmtAttrW, StatementAttribute};
+        use mongodb::{
+            bson::{doc, Document},
+            Client,
+        };
+
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        let row_updates = &mut [0 as WideChar; 2];
+        assert_eq!(SqlReturn::SUCCESS, unsafe {
+            SQLGetInfoW(
+                conn_handle as HDbc,
+                InfoType::SQL_ROW_UPDATES as u16,
+                row_updates.as_mut_ptr() as Pointer,
+                std::mem::size_of_val(row_updates) as i16,
+                &mut 0,
+            )
+        });
+        assert_eq!("N", cstr::from_widechar_ref_lossy(&row_updates[..1]));
+
+        let row_status = &mut 0u16;
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS_WITH_INFO,
+                SQLSetStmtAttrW(
+                    stmt_handle,
+                    StatementAttribute::SQL_ATTR_CURSOR_TYPE as i32,
+                    definitions::CursorType::SQL_CURSOR_KEYSET_DRIVEN as usize as Pointer,
+                    0,
+                )
+            );
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetStmtAttrW(
+                    stmt_handle,
+                    StatementAttribute::SQL_ATTR_ROW_STATUS_PTR as i32,
+                    row_status as *mut u16 as Pointer,
+                    0,
+                )
+            );
+        }
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT v FROM integration_test_2.row_updates ORDER BY _id"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+
+        // Update the second row through a separate client after the first row is fetched.
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let set_second_row = |v: i32| {
+            runtime.block_on(async {
+                Client::with_uri_str(format!(
+                    "mongodb://localhost:{}",
+                    std::env::var("MDB_TEST_LOCAL_PORT").expect("MDB_TEST_LOCAL_PORT is not set")
+                ))
+                .await
+                .unwrap()
+                .database("integration_test_2")
+                .collection::<Document>("row_updates")
+                .update_one(doc! {"_id": 1}, doc! {"$set": {"v": v}}, None)
+                .await
+                .unwrap();
+            })
+        };
+        let mut values = vec![];
+        for row in 0..2 {
+            assert_eq!(SqlReturn::SUCCESS, unsafe { SQLFetch(stmt_handle) });
+            assert_eq!(RowStatus::SQL_ROW_SUCCESS as u16, *row_status);
+            let value = &mut 0i32;
+            assert_eq!(SqlReturn::SUCCESS, unsafe {
+                SQLGetData(
+                    stmt_handle,
+                    1,
+                    CDataType::SQL_C_SLONG as i16,
+                    value as *mut i32 as Pointer,
+                    0,
+                    &mut 0,
+                )
+            });
+            values.push(*value);
+            if row == 0 {
+                set_second_row(2);
+            }
+        }
+        set_second_row(1);
+        assert_eq!(vec![1, 1], values);
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // TAIL opens a change stream, and SQLFetch blocks until the next change is made to the
+    // collection. Change streams require a replica set, which the local test deployment is not.
+    #[test]
+    #[ignore = "change streams require a replica set"]
+    fn tail_returns_change_events() {
+        use definitions::{SQLSetStmtAttrW, StatementAttribute};
+        use mongodb::{bson::doc, Client};
+
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        unsafe {
+            let mut catalog = cstr::to_widechar_vec("integration_test");
+            catalog.push(0);
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetConnectAttrW(
+                    conn_handle,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    catalog.as_mut_ptr() as Pointer,
+                    SQL_NTS as i32,
+                )
+            );
+            // Fail rather than hang if the event never arrives.
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetStmtAttrW(
+                    stmt_handle,
+                    StatementAttribute::SQL_ATTR_QUERY_TIMEOUT as i32,
+                    30 as Pointer,
+                    0,
+                )
+            );
+        }
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, "TAIL change_stream_test"),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+
+        // Insert a document through a separate client once the change stream is open.
+        let uri = format!(
+            "mongodb://{}:{}@{}",
+            std::env::var("ADF_TEST_LOCAL_USER").unwrap(),
+            std::env::var("ADF_TEST_LOCAL_PWD").unwrap(),
+            std::env::var("ADF_TEST_LOCAL_HOST").unwrap(),
+        );
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async {
+                Client::with_uri_str(uri)
+                    .await
+                    .unwrap()
+                    .database("integration_test")
+                    .collection("change_stream_test")
+                    .insert_one(doc! {"tailed": true}, None)
+                    .await
+                    .unwrap();
+            });
+
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLFetch(stmt_handle),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            for (col, expected) in [(1, "insert"), (4, "\"tailed\":true")] {
+                let buffer = &mut [0 as WideChar; 200];
+                let indicator = &mut 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle,
+                        col,
+                        CDataType::SQL_C_WCHAR as i16,
+                        buffer.as_mut_ptr() as Pointer,
+                        std::mem::size_of_val(buffer) as isize,
+                        indicator,
+                    )
+                );
+                let value = cstr::from_widechar_ref_lossy(
+                    &buffer[..*indicator as usize / std::mem::size_of::<WideChar>()],
+                );
+                assert!(value.contains(expected), "{value}");
+            }
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // The data source is read-only, so a CREATE INDEX over more columns than
+    // SQL_MAX_COLUMNS_IN_INDEX is rejected with a diagnostic like any other DDL.
+    #[test]
+    fn create_index_exceeding_max_columns_fails_cleanly() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        let columns = (0..=constants::MAX_COLUMNS_IN_INDEX)
+            .map(|i| format!("c{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        assert_eq!(
+            SqlReturn::ERROR,
+            exec_direct(
+                stmt_handle,
+                &format!("CREATE INDEX too_wide ON integration_test.foo ({columns})")
+            )
+        );
+        let diagnostics = get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle);
+        assert!(!diagnostics.is_empty());
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn limit_without_order_by_warning_can_be_disabled() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}WARN_UNORDERED_LIMIT=0;",
+            crate::common::generate_default_connection_str()
+        );
+        let conn_handle = connect_with_conn_string(env_handle, Some(conn_str)).unwrap();
+        let stmt_handle = allocate_statement(conn_handle).unwrap();
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, "SELECT * FROM integration_test.foo LIMIT 2")
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn row_as_json_returns_each_row_as_a_json_document() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}ROW_AS_JSON=1;",
+            crate::common::generate_default_connection_str()
+        );
+        let conn_handle = connect_with_conn_string(env_handle, Some(conn_str)).unwrap();
+        let stmt_handle = allocate_statement(conn_handle).unwrap();
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT * FROM integration_test.foo ORDER BY _id"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut rows = vec![];
+        unsafe {
+            let column_count = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLNumResultCols(stmt_handle, column_count)
+            );
+            assert_eq!(1, *column_count);
+            let name_buffer = &mut [0 as WideChar; 32];
+            let name_length = &mut 0;
+            let data_type = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLDescribeColW(
+                    stmt_handle,
+                    1,
+                    name_buffer.as_mut_ptr(),
+                    name_buffer.len() as i16,
+                    name_length,
+                    data_type,
+                    &mut 0,
+                    &mut 0,
+                    &mut 0,
+                )
+            );
+            assert_eq!(
+                "json",
+                cstr::from_widechar_ref_lossy(&name_buffer[..*name_length as usize])
+            );
+            assert_eq!(SqlDataType::SQL_WLONGVARCHAR as i16, *data_type);
+            while SQLFetch(stmt_handle) == SqlReturn::SUCCESS {
+                let buffer = &mut [0 as WideChar; 256];
+                let indicator = &mut 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle,
+                        1,
+                        CDataType::SQL_C_WCHAR as i16,
+                        buffer.as_mut_ptr() as Pointer,
+                        std::mem::size_of_val(buffer) as isize,
+                        indicator,
+                    ),
+                    "{}",
+                    get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+                );
+                let json = cstr::from_widechar_ref_lossy(&buffer[..*indicator as usize / 2]);
+                rows.push(serde_json::from_str::<serde_json::Value>(&json).unwrap());
+            }
+        }
+        assert_eq!(
+            vec![
+                serde_json::json!({"_id": 0, "a": 42}),
+                serde_json::json!({"_id": 1, "a": 13}),
+                serde_json::json!({"_id": 2, "a": 100}),
+            ],
+            rows
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // count_rows_matching_john connects with the given connection string attributes and returns
+    // the number of students whose name equals 'john'. The only such student is named John.
+    fn count_rows_matching_john(attributes: &str) -> usize {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}{attributes}",
+            crate::common::generate_default_connection_str()
+        );
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, Some(conn_str));
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT _id FROM integration_test.class WHERE name = 'john'"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut count = 0;
+        while unsafe { SQLFetch(stmt_handle) } == SqlReturn::SUCCESS {
+            count += 1;
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+        count
+    }
+
+    #[test]
+    fn collation_makes_string_comparisons_case_insensitive() {
+        assert_eq!(0, count_rows_matching_john(""));
+        assert_eq!(
+            1,
+            count_rows_matching_john(r#"COLLATION={"locale":"en","strength":1};"#)
+        );
+    }
+
+    #[test]
+    fn invalid_collation_is_reported() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}COLLATION={{\"strength\":1}};",
+            crate::common::generate_default_connection_str()
+        );
+        match connect_with_conn_string(env_handle, Some(conn_str)) {
+            Err(Error::DriverConnect(sql_return, diagnostics)) => {
+                assert_eq!("ERROR", sql_return);
+                assert!(
+                    diagnostics.contains("collation must be a JSON collation document")
+                        && diagnostics.contains("missing field `locale`"),
+                    "unexpected diagnostics: {diagnostics}"
+                );
+            }
+            result => panic!("connection should fail: {result:?}"),
+        }
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn direct_connection_connects_to_the_given_host() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}directConnection=true;",
+            crate::common::generate_default_connection_str()
+        );
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, Some(conn_str));
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, "SELECT * FROM integration_test.foo"),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // select_int_values connects with the given connection string attributes and returns the
+    // first column of the result set of the query, read as integers.
+    fn select_int_values(attributes: &str, query: &str) -> Vec<i32> {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}{attributes}",
+            crate::common::generate_default_connection_str()
+        );
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, Some(conn_str));
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, query),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut values = vec![];
+        while unsafe { SQLFetch(stmt_handle) } == SqlReturn::SUCCESS {
+            let value = &mut 0i32;
+            assert_eq!(SqlReturn::SUCCESS, unsafe {
+                SQLGetData(
+                    stmt_handle,
+                    1,
+                    CDataType::SQL_C_SLONG as i16,
+                    value as *mut i32 as Pointer,
+                    0,
+                    &mut 0,
+                )
+            });
+            values.push(*value);
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+        values
+    }
+
+    // assert_find_matches_aggregate asserts that a query returns the expected rows both when it
+    // may run as a find command and when it runs as the $sql aggregation.
+    fn assert_find_matches_aggregate(query: &str, expected: Vec<i32>) {
+        assert_eq!(expected, select_int_values("", query), "{query}");
+        assert_eq!(
+            expected,
+            select_int_values("FORCE_AGGREGATE=0;", query),
+            "{query}"
+        );
+    }
+
+    #[test]
+    fn simple_query_returns_the_same_rows_with_find_and_aggregate() {
+        assert_find_matches_aggregate(
+            "SELECT a FROM integration_test.foo WHERE a > 20 ORDER BY a DESC",
+            vec![100, 42],
+        );
+        assert_eq!(
+            vec![100, 42],
+            select_int_values(
+                "FORCE_AGGREGATE=1;",
+                "SELECT a FROM integration_test.foo WHERE a > 20 ORDER BY a DESC"
+            )
+        );
+    }
+
+    #[test]
+    fn null_and_missing_fields_match_the_same_rows_with_find_and_aggregate() {
+        assert_find_matches_aggregate(
+            "SELECT _id, a FROM integration_test.null_and_missing WHERE a <> 1",
+            vec![],
+        );
+        assert_find_matches_aggregate(
+            "SELECT _id, a FROM integration_test.null_and_missing WHERE a IS NULL ORDER BY _id",
+            vec![1, 2],
+        );
+        assert_find_matches_aggregate(
+            "SELECT _id, a FROM integration_test.null_and_missing WHERE a IS NOT NULL",
+            vec![0],
+        );
+    }
+
+    #[test]
+    fn arrays_match_the_same_rows_with_find_and_aggregate() {
+        // find would match the array field of the documents whose array holds a null.
+        assert_find_matches_aggregate(
+            "SELECT _id, array FROM integration_test.types_other WHERE array IS NULL",
+            vec![],
+        );
+    }
+
+    #[test]
+    fn mixed_types_match_the_same_rows_with_find_and_aggregate() {
+        assert_find_matches_aggregate(
+            "SELECT _id, a FROM integration_test.anyof_collection WHERE a > 3 ORDER BY _id",
+            vec![1, 2],
+        );
+    }
+
+    #[test]
+    fn collated_collection_matches_the_same_rows_with_find_and_aggregate() {
+        // The names collection has a case-insensitive default collation.
+        let query =
+            "SELECT _id, name FROM integration_test_2.names WHERE name = 'john' ORDER BY _id";
+        assert_eq!(
+            select_int_values("", query),
+            select_int_values("FORCE_AGGREGATE=0;", query)
+        );
+    }
+
+    #[test]
+    fn common_table_expression_returns_the_rows_of_the_inlined_query() {
+        assert_eq!(
+            select_int_values(
+                "",
+                "SELECT a FROM (SELECT a FROM integration_test.foo WHERE a > 20) AS t ORDER BY a"
+            ),
+            select_int_values(
+                "",
+                "WITH t AS (SELECT a FROM integration_test.foo WHERE a > 20) \
+                 SELECT a FROM t ORDER BY a"
+            )
+        );
+    }
+
+    #[test]
+    fn null_safe_comparisons_match_null_and_missing_values() {
+        let select = |condition: &str| {
+            select_int_values(
+                "",
+                &format!(
+                    "SELECT _id FROM integration_test.null_and_missing WHERE {condition} ORDER BY _id"
+                ),
+            )
+        };
+        assert_eq!(Vec::<i32>::new(), select("a <> 1"));
+        assert_eq!(vec![1, 2], select("a IS DISTINCT FROM 1"));
+        assert_eq!(vec![0], select("a <=> 1"));
+        assert_eq!(vec![1, 2], select("a IS NOT DISTINCT FROM NULL"));
+    }
+
+    #[test]
+    fn grouped_query_runs_as_aggregate() {
+        let query = "SELECT a FROM integration_test.foo GROUP BY a ORDER BY a";
+        assert_eq!(
+            vec![13, 42, 100],
+            select_int_values("FORCE_AGGREGATE=0;", query)
+        );
+    }
+
+    #[test]
+    fn invalid_force_aggregate_is_reported() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}FORCE_AGGREGATE=yes;",
+            crate::common::generate_default_connection_str()
+        );
+        match connect_with_conn_string(env_handle, Some(conn_str)) {
+            Err(Error::DriverConnect(sql_return, diagnostics)) => {
+                assert_eq!("ERROR", sql_return);
+                assert!(
+                    diagnostics.contains("force_aggregate must be 0 or 1, found 'yes'"),
+                    "unexpected diagnostics: {diagnostics}"
+                );
+            }
+            result => panic!("connection should fail: {result:?}"),
+        }
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // fetch_staples connects with the given connection string attributes, fetches every row of
+    // the staples collection and returns the number of rows and of getMore commands run.
+    #[cfg(feature = "test_hooks")]
+    fn fetch_staples(attributes: &str) -> (usize, ULen) {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}{attributes}",
+            crate::common::generate_default_connection_str()
+        );
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, Some(conn_str));
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(stmt_handle, "SELECT `Customer Name` FROM tdvt.staples"),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut rows = 0;
+        while unsafe { SQLFetch(stmt_handle) } == SqlReturn::SUCCESS {
+            rows += 1;
+        }
+        let mut get_more_count: ULen = 0;
+        assert_eq!(SqlReturn::SUCCESS, unsafe {
+            SQLGetStmtAttrW(
+                stmt_handle,
+                SQL_ATTR_MONGODB_GET_MORE_COUNT,
+                &mut get_more_count as *mut ULen as Pointer,
+                0,
+                std::ptr::null_mut(),
+            )
+        });
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+        (rows, get_more_count)
+    }
+
+    // The getMore commands are only counted by a driver built with its test_hooks feature.
+    #[cfg(feature = "test_hooks")]
+    #[test]
+    fn batch_size_sets_the_number_of_get_more_commands() {
+        let (rows, small_batches) = fetch_staples("batchSize=500;");
+        assert_eq!(54860, rows);
+        // every batch after the first is fetched by a getMore
+        assert_eq!(rows.div_ceil(500) - 1, small_batches);
+        let (rows, large_batches) = fetch_staples("batchSize=5000;");
+        assert_eq!(54860, rows);
+        assert_eq!(rows.div_ceil(5000) - 1, large_batches);
+        // 0 leaves the batch size to the server
+        let (rows, _) = fetch_staples("batchSize=0;");
+        assert_eq!(54860, rows);
+    }
+
+    #[test]
+    fn invalid_batch_size_is_reported() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}batchSize=-1;",
+            crate::common::generate_default_connection_str()
+        );
+        match connect_with_conn_string(env_handle, Some(conn_str)) {
+            Err(Error::DriverConnect(sql_return, diagnostics)) => {
+                assert_eq!("ERROR", sql_return);
+                assert!(
+                    diagnostics.contains("batchSize must be a non-negative integer, found '-1'"),
+                    "unexpected diagnostics: {diagnostics}"
+                );
+            }
+            result => panic!("connection should fail: {result:?}"),
+        }
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // The connection strings of the two ways a query runs: as the $sql aggregation, which is the
+    // default, or as a find command when FORCE_AGGREGATE=0 allows it.
+    fn hint_connection_strings() -> [Option<String>; 2] {
+        [
+            None,
+            Some(format!(
+                "{}FORCE_AGGREGATE=0;",
+                crate::common::generate_default_connection_str()
+            )),
+        ]
+    }
+
+    // select_class_with_hint runs a query of the class collection with the given index hint, and
+    // returns the result of the execution with its diagnostics.
+    fn select_class_with_hint(hint: &str, conn_str: Option<String>) -> (SqlReturn, String) {
+        use constants::SQL_ATTR_MONGODB_HINT;
+        use definitions::SQLSetStmtAttrW;
+
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, conn_str);
+        unsafe {
+            let mut catalog = cstr::to_widechar_vec("integration_test");
+            catalog.push(0);
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetConnectAttrW(
+                    conn_handle,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    catalog.as_mut_ptr() as Pointer,
+                    SQL_NTS as i32,
+                )
+            );
+            let mut hint = cstr::to_widechar_vec(hint);
+            hint.push(0);
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetStmtAttrW(
+                    stmt_handle,
+                    SQL_ATTR_MONGODB_HINT,
+                    hint.as_mut_ptr() as Pointer,
+                    SQL_NTS as i32,
+                )
+            );
+        }
+        let sql_return = exec_direct(stmt_handle, "SELECT _id FROM class WHERE _id > 1");
+        let diagnostics = get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle);
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+        (sql_return, diagnostics)
+    }
+
+    #[test]
+    fn hint_forces_an_existing_index() {
+        for conn_str in hint_connection_strings() {
+            for hint in ["_id_", r#"{"_id": 1}"#] {
+                let (sql_return, diagnostics) = select_class_with_hint(hint, conn_str.clone());
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    sql_return,
+                    "{hint} with {conn_str:?}: {diagnostics}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hint_of_a_missing_index_is_reported() {
+        for conn_str in hint_connection_strings() {
+            for (hint, expected) in [
+                (
+                    "no_such_index",
+                    r#"No index matches the hint "no_such_index""#,
+                ),
+                (r#"{"a": 1}"#, r#"No index matches the hint { "a": 1 }"#),
+            ] {
+                let (sql_return, diagnostics) = select_class_with_hint(hint, conn_str.clone());
+                assert_eq!(SqlReturn::ERROR, sql_return, "{hint} with {conn_str:?}");
+                assert!(
+                    diagnostics.contains(expected),
+                    "unexpected diagnostics for {hint} with {conn_str:?}: {diagnostics}"
+                );
+            }
+        }
+    }
+
+    // count_students_where returns the number of students matching the filter, after setting the
+    // session time zone if one is given. Three students start on 2000-01-01, one on 2000-02-01
+    // and one on 2000-03-01, all at midnight UTC.
+    fn count_students_where(filter: &str, time_zone: Option<&str>) -> usize {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        if let Some(time_zone) = time_zone {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                exec_direct(stmt_handle, &format!("SET TIMEZONE = '{time_zone}'")),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            assert_eq!(SqlReturn::SUCCESS, unsafe {
+                SQLFreeStmt(stmt_handle, FreeStmtOption::SQL_CLOSE as i16)
+            });
+        }
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                &format!("SELECT _id FROM integration_test.class WHERE {filter}")
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut count = 0;
+        while unsafe { SQLFetch(stmt_handle) } == SqlReturn::SUCCESS {
+            count += 1;
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+        count
+    }
+
+    #[test]
+    fn date_escape_filters_a_date_column() {
+        assert_eq!(
+            3,
+            count_students_where("startdate = {d '2000-01-01'}", None)
+        );
+        assert_eq!(
+            2,
+            count_students_where("startdate > {d '2000-01-01'}", None)
+        );
+    }
+
+    #[test]
+    fn time_escape_filters_a_date_column() {
+        // A time is on the current date, after every start date.
+        assert_eq!(5, count_students_where("startdate < {t '00:00:00'}", None));
+        assert_eq!(0, count_students_where("startdate > {t '23:59:59'}", None));
+    }
+
+    #[test]
+    fn timestamp_escape_filters_a_date_column() {
+        assert_eq!(
+            1,
+            count_students_where("startdate = {ts '2000-02-01 00:00:00'}", None)
+        );
+        assert_eq!(
+            4,
+            count_students_where("startdate < {ts '2000-02-01 00:00:00.001'}", None)
+        );
+    }
+
+    #[test]
+    fn datetime_escapes_are_local_times_of_the_session_time_zone() {
+        // Midnight UTC is the evening before five hours west of UTC.
+        assert_eq!(
+            3,
+            count_students_where("startdate = {ts '1999-12-31 19:00:00'}", Some("-05:00"))
+        );
+        assert_eq!(
+            0,
+            count_students_where("startdate = {d '2000-01-01'}", Some("-05:00"))
+        );
+    }
+
+    #[test]
+    fn objectid_timestamp_selects_and_filters_by_creation_time() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        // The ObjectId 000000000000000000000001 was created at the epoch.
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT OBJECTID_TIMESTAMP(objectId) AS created FROM integration_test.types_other \
+                 WHERE OBJECTID_TIMESTAMP(objectId) < {d '1970-01-02'}"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut timestamp = Timestamp::default();
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle));
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt_handle,
+                    1,
+                    CDataType::SQL_C_TYPE_TIMESTAMP as i16,
+                    &mut timestamp as *mut Timestamp as Pointer,
+                    std::mem::size_of::<Timestamp>() as isize,
+                    &mut 0,
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt_handle));
+        }
+        assert_eq!(
+            (1970, 1, 1, 0, 0, 0),
+            (
+                timestamp.year,
+                timestamp.month,
+                timestamp.day,
+                timestamp.hour,
+                timestamp.minute,
+                timestamp.second
+            )
+        );
+        assert_eq!(SqlReturn::SUCCESS, unsafe {
+            SQLFreeStmt(stmt_handle, FreeStmtOption::SQL_CLOSE as i16)
+        });
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT _id FROM integration_test.types_other \
+                 WHERE OBJECTID_TIMESTAMP(objectId) >= {d '1970-01-02'}"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        assert_eq!(SqlReturn::NO_DATA, unsafe { SQLFetch(stmt_handle) });
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn densify_fills_the_gaps_of_a_time_series() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        // The readings are taken at 00:00, 01:00 and 04:00, the hours between are interpolated.
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT ts, v FROM integration_test_2.readings \
+                 DENSIFY(ts, INTERVAL '1' HOUR) FILL(v, LINEAR)"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        let mut rows = vec![];
+        unsafe {
+            while SQLFetch(stmt_handle) == SqlReturn::SUCCESS {
+                let mut timestamp = Timestamp::default();
+                let mut value = 0i32;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle,
+                        1,
+                        CDataType::SQL_C_TYPE_TIMESTAMP as i16,
+                        &mut timestamp as *mut Timestamp as Pointer,
+                        std::mem::size_of::<Timestamp>() as isize,
+                        &mut 0,
+                    ),
+                    "{}",
+                    get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+                );
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle,
+                        2,
+                        CDataType::SQL_C_SLONG as i16,
+                        &mut value as *mut i32 as Pointer,
+                        std::mem::size_of::<i32>() as isize,
+                        &mut 0,
+                    ),
+                    "{}",
+                    get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+                );
+                rows.push((timestamp.day, timestamp.hour, value));
+            }
+        }
+        assert_eq!(
+            vec![(1, 0, 1), (1, 1, 2), (1, 2, 3), (1, 3, 4), (1, 4, 5)],
+            rows
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    #[test]
+    fn densify_sorts_and_limits_the_rows_once_the_gaps_are_filled() {
+        // The last three hours are 02:00 and 03:00, which are interpolated, and 04:00.
+        assert_eq!(
+            vec![5, 4, 3],
+            select_int_values(
+                "",
+                "SELECT v, ts FROM integration_test_2.readings ORDER BY ts DESC LIMIT 3 \
+                 DENSIFY(ts, INTERVAL '1' HOUR) FILL(v, LINEAR)"
+            )
+        );
+    }
+
+    #[test]
+    fn rollup_returns_subtotal_and_grand_total_rows() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            exec_direct(
+                stmt_handle,
+                "SELECT enrolled, startdate, COUNT(*) AS n FROM integration_test.class \
+                 GROUP BY ROLLUP(enrolled, startdate) ORDER BY grouping_id, n"
+            ),
+            "{}",
+            get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+        );
+        // The columns are looked up by name, the result set is not in select list order.
+        let column = |expected_name: &str| {
+            (1..=4)
+                .find(|&col| {
+                    let name_buffer = &mut [0 as WideChar; 32];
+                    let name_length = &mut 0;
+                    assert_eq!(SqlReturn::SUCCESS, unsafe {
+                        SQLDescribeColW(
+                            stmt_handle,
+                            col,
+                            name_buffer.as_mut_ptr(),
+                            name_buffer.len() as i16,
+                            name_length,
+                            &mut 0,
+                            &mut 0,
+                            &mut 0,
+                            &mut 0,
+                        )
+                    });
+                    cstr::from_widechar_ref_lossy(&name_buffer[..*name_length as usize])
+                        == expected_name
+                })
+                .unwrap_or_else(|| panic!("no {expected_name} column"))
+        };
+        let (enrolled_col, grouping_id_col, n_col) =
+            (column("enrolled"), column("grouping_id"), column("n"));
+        let mut rows = vec![];
+        while unsafe { SQLFetch(stmt_handle) } == SqlReturn::SUCCESS {
+            let (enrolled, grouping_id, n) = (&mut 0u8, &mut -1i64, &mut -1i64);
+            let (enrolled_indicator, indicator) = (&mut 0, &mut 0);
+            unsafe {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle,
+                        enrolled_col,
+                        CDataType::SQL_C_BIT as i16,
+                        enrolled as *mut u8 as Pointer,
+                        0,
+                        enrolled_indicator,
+                    )
+                );
+                for (col, value) in [(grouping_id_col, &mut *grouping_id), (n_col, &mut *n)] {
+                    assert_eq!(
+                        SqlReturn::SUCCESS,
+                        SQLGetData(
+                            stmt_handle,
+                            col,
+                            CDataType::SQL_C_SBIGINT as i16,
+                            value as *mut i64 as Pointer,
+                            0,
+                            indicator,
+                        )
+                    );
+                }
+            }
+            rows.push((
+                *grouping_id,
+                *n,
+                *enrolled_indicator == definitions::SQL_NULL_DATA,
+            ));
+        }
+        // One row per enrolled and start date, a subtotal per enrolled value, and the grand total
+        // where enrolled is NULL.
+        assert_eq!(
+            vec![
+                (0, 1, false),
+                (0, 1, false),
+                (0, 1, false),
+                (0, 2, false),
+                (1, 2, false),
+                (1, 3, false),
+                (3, 5, true),
+            ],
+            rows
+        );
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
+    // browse_connect calls SQLBrowseConnectW with the given attributes and returns its result
+    // along with the output connection string.
+    unsafe fn browse_connect(conn_handle: HDbc, attributes: &str) -> (SqlReturn, String) {
+        let mut in_connection_string = cstr::to_widechar_vec(attributes);
+        in_connection_string.push(0);
+        let out_connection_string = &mut [0 as WideChar; 1024];
+        let out_length = &mut 0;
+        let sql_return = SQLBrowseConnectW(
+            conn_handle,
+            in_connection_string.as_ptr(),
+            SQL_NTS as i16,
+            out_connection_string.as_mut_ptr(),
+            out_connection_string.len() as i16,
+            out_length,
+        );
+        (
+            sql_return,
+            cstr::from_widechar_ref_lossy(&out_connection_string[..*out_length as usize]),
+        )
+    }
+
+    #[test]
+    fn browse_connect_requests_credentials_then_connects() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let user_name = std::env::var("ADF_TEST_LOCAL_USER").unwrap();
+        let password = std::env::var("ADF_TEST_LOCAL_PWD").unwrap();
+        let host = std::env::var("ADF_TEST_LOCAL_HOST").unwrap();
+        unsafe {
+            let mut conn_handle: Handle = std::ptr::null_mut();
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLAllocHandle(
+                    HandleType::SQL_HANDLE_DBC as i16,
+                    env_handle as Handle,
+                    &mut conn_handle,
+                )
+            );
+            let conn_handle = conn_handle as HDbc;
+
+            let (sql_return, browse_result) = browse_connect(
+                conn_handle,
+                &format!("Driver={{{DRIVER_NAME}}};SERVER={host};DATABASE=integration_test"),
+            );
+            assert_eq!(SqlReturn::NEED_DATA, sql_return);
+            assert!(
+                browse_result.starts_with("UID:Login ID=?;PWD:Password=?;*"),
+                "{browse_result}"
+            );
+
+            let (sql_return, connection_string) =
+                browse_connect(conn_handle, &format!("UID={user_name};PWD={password}"));
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                sql_return,
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_DBC, conn_handle as Handle)
+            );
+            assert!(connection_string.ends_with(&format!("UID={user_name};PWD={password}")));
+
+            let stmt_handle = allocate_statement(conn_handle).unwrap();
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                exec_direct(stmt_handle, "SELECT * FROM foo"),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+            disconnect_and_close_handles(conn_handle, stmt_handle);
+            let _ = Box::from_raw(env_handle);
+        }
+    }
+
+    #[test]
+    fn switch_current_catalog() {
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let (conn_handle, stmt_handle) = connect_and_allocate_statement(env_handle, None);
+        // example_2 only exists in integration_test_2
+        let mut query: Vec<WideChar> = cstr::to_widechar_vec("SELECT * FROM example_2");
+        query.push(0);
+        unsafe {
+            let current_db = to_widechar_ptr("integration_test_2");
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetConnectAttrW(
+                    conn_handle,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    current_db.0 as *mut _,
+                    SQL_NTS
+                ),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_DBC, conn_handle as Handle)
+            );
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLExecDirectW(stmt_handle, query.as_ptr(), SQL_NTS),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+
+            // A database that does not exist is rejected and the current catalog is unchanged
+            let missing_db = to_widechar_ptr("no_such_database");
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLSetConnectAttrW(
+                    conn_handle,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    missing_db.0 as *mut _,
+                    SQL_NTS
+                )
+            );
+            assert!(
+                get_sql_diagnostics(HandleType::SQL_HANDLE_DBC, conn_handle as Handle)
+                    .contains("Invalid catalog name"),
+            );
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLExecDirectW(stmt_handle, query.as_ptr(), SQL_NTS),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt_handle as Handle)
+            );
+        }
+        disconnect_and_close_handles(conn_handle, stmt_handle);
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
+
     /**
      * The following tests require a DSN called "ADF_Test" to be configured on the machine running the tests.
      */
@@ -11,11 +11,12 @@ mod integration {
 
     use crate::common::{
         allocate_env, allocate_statement, connect_with_conn_string,
-        disconnect_and_free_dbc_and_env_handles, get_sql_diagnostics,
+        disconnect_and_free_dbc_and_env_handles, generate_default_connection_str,
+        get_sql_diagnostics,
     };
     use definitions::{
-        AttrOdbcVersion, FreeStmtOption, Handle, HandleType, SQLExecDirectW, SQLFreeHandle,
-        SQLFreeStmt, SqlReturn, SQL_NTS,
+        AttrOdbcVersion, DriverConnectOption, FreeStmtOption, Handle, HandleType, SQLDisconnect,
+        SQLDriverConnectW, SQLExecDirectW, SQLFreeHandle, SQLFreeStmt, SqlReturn, SQL_NTS,
     };
 
     #[test]
@@ -88,4 +89,44 @@ mod integration {
 
         disconnect_and_free_dbc_and_env_handles(env, dbc);
     }
+
+    // Disconnecting with an open cursor leaves the connection handle ready to connect again.
+    #[test]
+    fn sql_disconnect_allows_reconnecting() {
+        let env = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let dbc = connect_with_conn_string(env, None)
+            .expect("Failed to connect with default connection string");
+        let stmt = allocate_statement(dbc).expect("Failed to allocate statement");
+        let query = b"SELECT * FROM integration_test.foo\0".map(u16::from);
+        let mut conn_str = cstr::to_widechar_vec(&generate_default_connection_str());
+        conn_str.push(0);
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLExecDirectW(stmt, query.as_ptr(), SQL_NTS)
+            );
+            assert_eq!(SqlReturn::SUCCESS, SQLDisconnect(dbc));
+            assert!(matches!(
+                SQLDriverConnectW(
+                    dbc,
+                    ptr::null_mut(),
+                    conn_str.as_ptr(),
+                    SQL_NTS as i16,
+                    ptr::null_mut(),
+                    0,
+                    &mut 0,
+                    DriverConnectOption::SQL_DRIVER_NO_PROMPT as u16,
+                ),
+                SqlReturn::SUCCESS | SqlReturn::SUCCESS_WITH_INFO
+            ));
+            let stmt = allocate_statement(dbc).expect("Failed to allocate statement");
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLExecDirectW(stmt, query.as_ptr(), SQL_NTS),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+            );
+        }
+        disconnect_and_free_dbc_and_env_handles(env, dbc);
+    }
 }
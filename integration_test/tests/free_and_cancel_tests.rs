@@ -61,6 +61,33 @@ mod integration {
         }
     }
 
+    /// This test checks that the deprecated SQLFreeStmt(SQL_DROP) frees the
+    /// statement handle the same way SQLFreeHandle(SQL_HANDLE_STMT) does.
+    /// After allocating a statement handle, the flow is:
+    ///     - SQLExecDirectW(<query>)
+    ///     - SQLFreeStmt(SQL_DROP)
+    ///     - SQLDisconnect
+    ///     - SQLFreeHandle(SQL_HANDLE_DBC)
+    ///     - SQLFreeHandle(SQL_HANDLE_ENV)
+    #[test]
+    fn test_free_stmt_drop() {
+        let (env_handle, conn_handle, stmt_handle) =
+            default_setup_connect_and_alloc_stmt(AttrOdbcVersion::SQL_OV_ODBC3);
+
+        unsafe {
+            exec_direct_default_query(stmt_handle);
+
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLFreeStmt(stmt_handle, FreeStmtOption::SQL_DROP as i16),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_DBC, conn_handle as Handle)
+            );
+
+            disconnect_and_free_dbc_and_env_handles(env_handle, conn_handle);
+        }
+    }
+
     /// This test is inspired by the SSIS Preview Data data retrieval flow.
     /// It is altered to be more general than that specific flow, with a focus
     /// on canceling the query after getting some data. This flow depends on
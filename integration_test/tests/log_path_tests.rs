@@ -0,0 +1,44 @@
+#![allow(
+    clippy::ptr_as_ptr,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+mod common;
+
+// The logger is global to the process, so these tests live in their own test binary to keep
+// the log path they set from affecting the other connection tests.
+mod integration {
+    use crate::common::{
+        allocate_env, connect_with_conn_string, disconnect_and_free_dbc_and_env_handles,
+        generate_default_connection_str,
+    };
+    use definitions::AttrOdbcVersion;
+    use std::{fs, thread, time};
+
+    #[test]
+    fn log_path_and_level() {
+        let log_dir = std::env::temp_dir().join("mongo_odbc_log_path_test");
+        fs::remove_dir_all(&log_dir).unwrap_or_default();
+
+        let env_handle = allocate_env(AttrOdbcVersion::SQL_OV_ODBC3);
+        let conn_str = format!(
+            "{}LOGLEVEL=debug;LOGPATH={};",
+            generate_default_connection_str(),
+            log_dir.display()
+        );
+        let conn_handle = connect_with_conn_string(env_handle, Some(conn_str)).unwrap();
+        disconnect_and_free_dbc_and_env_handles(env_handle, conn_handle);
+
+        // Wait a little to allow the logger to flush
+        log::logger().flush();
+        thread::sleep(time::Duration::from_secs(1));
+
+        let log_content = fs::read_to_string(log_dir.join("mongo_odbc.log")).unwrap();
+        assert!(
+            log_content.contains("SQLDriverConnectW"),
+            "the connect event was not logged: {log_content}"
+        );
+        fs::remove_dir_all(log_dir).unwrap_or_default();
+    }
+}
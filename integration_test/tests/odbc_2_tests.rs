@@ -66,7 +66,7 @@ mod integration {
         let _ = unsafe { Box::from_raw(env_handle) };
     }
 
-    const EXPECTED_DATATYPES: [SqlDataType; 23] = [
+    const EXPECTED_DATATYPES: [SqlDataType; 24] = [
         SqlDataType::SQL_WVARCHAR,
         SqlDataType::SQL_BIT,
         SqlDataType::SQL_BIGINT,
@@ -84,16 +84,17 @@ mod integration {
         SqlDataType::SQL_UNKNOWN_TYPE,
         SqlDataType::SQL_UNKNOWN_TYPE,
         SqlDataType::SQL_UNKNOWN_TYPE,
-        SqlDataType::SQL_UNKNOWN_TYPE,
         SqlDataType::SQL_INTEGER,
         SqlDataType::SQL_DOUBLE,
         SqlDataType::SQL_TIMESTAMP,
+        SqlDataType::SQL_TIMESTAMP,
         SqlDataType::SQL_VARCHAR,
         SqlDataType::SQL_TYPE_TIMESTAMP,
+        SqlDataType::SQL_TYPE_TIMESTAMP,
     ];
 
     /// call SQLGetTypeInfo to verify the correct types are returned. For all types,
-    /// we should get both date types back. For date, we should get the specific date type
+    /// we should get both date and timestamp types back. For date, we should get the specific date type
     /// we expect back.
     #[test]
     fn test_type_listing() {
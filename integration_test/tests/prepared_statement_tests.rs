@@ -196,4 +196,64 @@ mod integration {
         }
         let _ = unsafe { Box::from_raw(env_handle) };
     }
+
+    #[test]
+    fn test_reprepare_replaces_prepared_statement() {
+        let (env_handle, dbc, stmt) =
+            default_setup_connect_and_alloc_stmt(AttrOdbcVersion::SQL_OV_ODBC3);
+
+        unsafe {
+            let mut query: Vec<WideChar> = cstr::to_widechar_vec("select * from example");
+            query.push(0);
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLPrepareW(stmt as HStmt, query.as_ptr(), SQL_NTS),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+            );
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLExecute(stmt as HStmt),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+            );
+
+            // Preparing again replaces the previous statement and its result set, even if it
+            // was never fetched from.
+            let mut query: Vec<WideChar> =
+                cstr::to_widechar_vec("select * from example where _id = 1");
+            query.push(0);
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLPrepareW(stmt as HStmt, query.as_ptr(), SQL_NTS),
+                "{}",
+                get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+            );
+
+            get_column_attributes(stmt as Handle, 2);
+
+            // The new statement must be executed before fetching.
+            assert_eq!(SqlReturn::ERROR, SQLFetch(stmt as HStmt));
+
+            // Executing twice runs the same prepared statement each time.
+            for _ in 0..2 {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLExecute(stmt as HStmt),
+                    "{}",
+                    get_sql_diagnostics(HandleType::SQL_HANDLE_STMT, stmt as Handle)
+                );
+
+                fetch_and_get_data(
+                    stmt as Handle,
+                    Some(1),
+                    vec![SqlReturn::SUCCESS; 2],
+                    vec![CDataType::SQL_C_SLONG, CDataType::SQL_C_WCHAR],
+                );
+            }
+
+            disconnect_and_close_handles(dbc, stmt);
+        }
+        let _ = unsafe { Box::from_raw(env_handle) };
+    }
 }
@@ -14,7 +14,10 @@ use log4rs::{
     Handle,
 };
 use shared_sql_utils::driver_settings::DriverSettings;
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
 const LOG_FILE_SIZE: u64 = 1024 * 500;
 
@@ -39,7 +42,13 @@ lazy_static! {
                 if let Ok(appender) = Logger::file_appender(log_dir_str) {
                     let level_filter = Logger::level_filter_from_string(driver_settings.log_level);
                     match Logger::init_logger(appender, level_filter) {
-                        Ok(handle) => Some(Logger { handle, log_dir }),
+                        Ok(handle) => Some(Logger {
+                            handle,
+                            log_dir: Mutex::new(LogDir {
+                                path: log_dir,
+                                from_connection: false,
+                            }),
+                        }),
                         Err(_) => None,
                     }
                 } else {
@@ -59,31 +68,81 @@ lazy_static! {
     };
 }
 
+#[derive(Debug)]
+struct LogDir {
+    path: PathBuf,
+    // from_connection is true once a connection string has set the log directory.
+    from_connection: bool,
+}
+
 #[derive(Debug)]
 pub struct Logger {
     handle: Handle,
-    log_dir: PathBuf,
+    log_dir: Mutex<LogDir>,
 }
 
 impl Logger {
     /// Update the logger log level.
     /// This change will affect all logging even already opened connections.
     pub fn set_log_level(level_filter: String) {
-        if LOGGER.is_some() {
-            let logger = LOGGER.as_ref().unwrap();
+        if let Some(logger) = LOGGER.as_ref() {
             let level_filter = Self::level_filter_from_string(level_filter);
-            if let Some(log_dir) = logger.log_dir.to_str() {
+            let log_dir = logger.log_dir.lock().unwrap();
+            logger.reconfigure(&log_dir.path, level_filter);
+        }
+    }
+
+    /// Update the directory the logs are written to, keeping the current log level.
+    /// The logger is global to the process, so only the first connection setting a log directory
+    /// is honored; this keeps concurrent connections from moving each other's logs. Setting the
+    /// same directory again is a no-op, while a different directory is ignored and reported as
+    /// an error.
+    pub fn set_log_dir(log_dir: PathBuf) -> Result<(), String> {
+        let logger = match LOGGER.as_ref() {
+            Some(logger) => logger,
+            None => return Ok(()),
+        };
+        let mut current = logger.log_dir.lock().unwrap();
+        if current.from_connection {
+            return if current.path == log_dir {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Ignoring log path '{}', logs are already written to '{}'",
+                    log_dir.display(),
+                    current.path.display()
+                ))
+            };
+        }
+        std::fs::create_dir_all(&log_dir)
+            .map_err(|e| format!("Unable to use log path '{}': {e}", log_dir.display()))?;
+        if !logger.reconfigure(&log_dir, log::max_level()) {
+            return Err(format!(
+                "Unable to write logs to log path '{}'",
+                log_dir.display()
+            ));
+        }
+        current.path = log_dir;
+        current.from_connection = true;
+        Ok(())
+    }
+
+    /// Replace the logger configuration with one writing to the given directory at the given
+    /// level. Returns false if the configuration could not be built, leaving the logger unchanged.
+    fn reconfigure(&self, log_dir: &Path, level_filter: LevelFilter) -> bool {
+        if let Some(log_dir) = log_dir.to_str() {
+            log::logger().flush();
+            if let Ok(appender) = Logger::file_appender(log_dir) {
+                let config = Config::builder()
+                    .appender(Appender::builder().build("logfile", Box::new(appender)))
+                    .build(Root::builder().appender("logfile").build(level_filter))
+                    .unwrap();
+                self.handle.set_config(config);
                 log::logger().flush();
-                if let Ok(appender) = Logger::file_appender(log_dir) {
-                    let config = Config::builder()
-                        .appender(Appender::builder().build("logfile", Box::new(appender)))
-                        .build(Root::builder().appender("logfile").build(level_filter))
-                        .unwrap();
-                    logger.handle.set_config(config);
-                    log::logger().flush();
-                }
+                return true;
             }
         }
+        false
     }
 
     /// Convert a String value into the corresponding LevelFilter.
@@ -223,6 +282,29 @@ mod driver {
         assert!(!log_file.contains("info3"));
         assert!(!log_file.contains("debug3"));
 
-        fs::remove_file(tmp_log).unwrap()
+        fs::remove_file(tmp_log).unwrap();
+
+        // The log directory can be moved once, keeping the current log level
+        let custom_dir = log_dir.join("mongo_odbc_custom_logs");
+        let custom_log = custom_dir.join("mongo_odbc.log");
+        fs::remove_file(&custom_log).unwrap_or_default();
+        Logger::set_log_dir(custom_dir.clone()).unwrap();
+
+        info!("info4");
+        error!("error4");
+
+        log_file = fs::read_to_string(&custom_log).unwrap();
+        assert!(log_file.contains("error4"));
+        assert!(!log_file.contains("info4"));
+
+        // Setting the same directory again is fine, but a different one is ignored
+        Logger::set_log_dir(custom_dir.clone()).unwrap();
+        assert!(Logger::set_log_dir(log_dir.join("mongo_odbc_other_logs")).is_err());
+
+        error!("error5");
+        log_file = fs::read_to_string(&custom_log).unwrap();
+        assert!(log_file.contains("error5"));
+
+        fs::remove_dir_all(custom_dir).unwrap()
     }
 }
@@ -15,9 +15,9 @@ mod unit {
     use bson::doc;
     use cstr::{input_text_to_string_w, WideChar};
     use definitions::{
-        BindType, CDataType, Len, Nullability,
+        BindType, CDataType, Date, Len, Nullability,
         RowStatus::{SQL_ROW_NOROW, SQL_ROW_SUCCESS},
-        SmallInt, SqlReturn, ULen, USmallInt, WChar, SQL_NTS_ISIZE,
+        SmallInt, SqlReturn, Time, ULen, USmallInt, WChar, SQL_NTS_ISIZE,
     };
     use mongo_odbc_core::{
         json_schema::{
@@ -28,6 +28,7 @@ mod unit {
         MongoColMetadata, MongoStatement, TypeMode,
     };
     use std::collections::HashMap;
+    use std::mem::size_of;
     use std::ptr::null_mut;
 
     // TODO: SQL-2010: Create test coverage for error handling when column binding with rowsets
@@ -341,14 +342,14 @@ mod unit {
                 )
             );
 
-            // Free memory and set row_bind_offset_ptr to null. Set row_bind_type to an invalid number.
+            // Free memory and set row_bind_offset_ptr to null. Use row-wise binding.
             let _ = Box::from_raw(s.attributes.write().unwrap().row_bind_offset_ptr as *mut WChar);
             s.attributes.write().unwrap().row_bind_offset_ptr = null_mut();
             s.attributes.write().unwrap().row_bind_type = 10;
 
-            // Assert that SQLBindCol returns an error because row_bind_type is not 0 (i.e., BindType::SQL_BIND_BY_COLUMN).
+            // Assert that SQLBindCol succeeds since row-wise binding is supported.
             assert_eq!(
-                SqlReturn::ERROR,
+                SqlReturn::SUCCESS,
                 SQLBindCol(
                     stmt as *mut _,
                     1,
@@ -792,6 +793,235 @@ mod unit {
         }
     }
 
+    #[test]
+    fn test_row_wise_binding() {
+        // A row of bound columns, as an application using row-wise binding would lay it out.
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct Row {
+            num: i32,
+            num_indicator: Len,
+            word: [WideChar; 5],
+            word_indicator: Len,
+        }
+
+        // Set up MongoHandle
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Allocated));
+        let stmt: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+
+        unsafe {
+            // Get Statement
+            let s = (*stmt).as_statement().unwrap();
+
+            let rows = &mut [Row {
+                num: 0,
+                num_indicator: 0,
+                word: [0; 5],
+                word_indicator: 0,
+            }; 3];
+
+            // The bound buffers are the fields of the first row; the buffers of the following rows
+            // are found by adding the size of Row.
+            *s.bound_cols.write().unwrap() = Some(map! {
+                1 => BoundColInfo {
+                    target_type: CDataType::SQL_C_SLONG as SmallInt,
+                    target_buffer: &mut rows[0].num as *mut i32 as *mut _,
+                    buffer_length: size_of::<i32>() as Len,
+                    length_or_indicator: &mut rows[0].num_indicator,
+                },
+                2 => BoundColInfo {
+                    target_type: CDataType::SQL_C_WCHAR as SmallInt,
+                    target_buffer: rows[0].word.as_mut_ptr() as *mut _,
+                    buffer_length: size_of::<[WideChar; 5]>() as Len,
+                    length_or_indicator: &mut rows[0].word_indicator,
+                },
+            });
+
+            s.attributes.write().unwrap().row_bind_offset_ptr = null_mut();
+            s.attributes.write().unwrap().row_array_size = 3;
+            s.attributes.write().unwrap().row_bind_type = size_of::<Row>();
+
+            // create a mongo query with data that corresponds to the bound columns.
+            *s.mongo_statement.write().unwrap() =
+                Some(Box::new(create_mongo_query_for_bind_col_fetching_tests()));
+
+            // assert that SQLFetch is successful. We are fetching the first 3 rows in the result set.
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt as *mut _));
+
+            for (row, (num, word)) in rows.iter().zip([(10, "aaaa"), (20, "bbbb"), (30, "cccc")]) {
+                assert_eq!(num, row.num);
+                assert_eq!(size_of::<i32>() as Len, row.num_indicator);
+                assert_eq!(
+                    word,
+                    input_text_to_string_w(row.word.as_ptr(), SQL_NTS_ISIZE)
+                );
+                assert_eq!(
+                    (word.len() * size_of::<WideChar>()) as Len,
+                    row.word_indicator
+                );
+            }
+
+            // assert that SQLFetch is successful. We are fetching the last row in the result set,
+            // which only overwrites the first row.
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt as *mut _));
+            assert_eq!(40, rows[0].num);
+            assert_eq!(
+                "dddd",
+                input_text_to_string_w(rows[0].word.as_ptr(), SQL_NTS_ISIZE)
+            );
+            assert_eq!(20, rows[1].num);
+            assert_eq!(30, rows[2].num);
+
+            // assert that another fetch returns NO_DATA
+            assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt as *mut _));
+        }
+    }
+
+    // Buffers too small for the data get the truncated data, the full length of the data in the
+    // indicator, and a 01004 warning.
+    #[test]
+    fn test_fetching_into_undersized_buffers() {
+        for (target_type, expected) in [
+            // Character data is null-terminated.
+            (CDataType::SQL_C_CHAR, b"abcd\0"),
+            (CDataType::SQL_C_BINARY, b"abcde"),
+        ] {
+            let env = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+            let conn = &mut MongoHandle::Connection(Connection::with_state(
+                env,
+                ConnectionState::Allocated,
+            ));
+            let stmt: *mut _ =
+                &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+
+            unsafe {
+                let s = (*stmt).as_statement().unwrap();
+                *s.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                    vec![doc! {"test": {"value": "abcdefghijklmnopqrst"}}],
+                    vec![MongoColMetadata::new(
+                        "",
+                        "test".to_string(),
+                        "value".to_string(),
+                        Schema::Atomic(Atomic::Scalar(BsonTypeName::String)),
+                        Nullability::SQL_NO_NULLS,
+                        TypeMode::Simple,
+                        None,
+                    )],
+                )));
+
+                let buffer = &mut [0u8; 5];
+                let indicator = &mut 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLBindCol(
+                        stmt as *mut _,
+                        1,
+                        target_type as SmallInt,
+                        buffer.as_mut_ptr() as *mut _,
+                        buffer.len() as Len,
+                        indicator,
+                    )
+                );
+
+                assert_eq!(SqlReturn::SUCCESS_WITH_INFO, SQLFetch(stmt as *mut _));
+                assert_eq!(20, *indicator, "{target_type:?}");
+                assert_eq!(expected, buffer, "{target_type:?}");
+                let errors = s.errors.read().unwrap();
+                assert_eq!(1, errors.len(), "{target_type:?}");
+                assert_eq!("01004", errors[0].get_sql_state().odbc_3_state);
+            }
+        }
+    }
+
+    // A date column bound as SQL_C_TYPE_DATE drops the time of day, and bound as SQL_C_TYPE_TIME
+    // drops the date. Both lose information from this datetime, so the fetch warns with 01S07.
+    #[test]
+    fn test_binding_datetime_as_date_and_time() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Allocated));
+        let stmt: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+
+        unsafe {
+            let s = (*stmt).as_statement().unwrap();
+            // 2014-11-28T12:00:09.123Z
+            let datetime = bson::DateTime::from_millis(1_417_176_009_123);
+            *s.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                vec![doc! {"test": {"d": datetime, "t": datetime}}],
+                ["d", "t"]
+                    .into_iter()
+                    .map(|name| {
+                        MongoColMetadata::new(
+                            "",
+                            "test".to_string(),
+                            name.to_string(),
+                            Schema::Atomic(Atomic::Scalar(BsonTypeName::Date)),
+                            Nullability::SQL_NO_NULLS,
+                            TypeMode::Simple,
+                            None,
+                        )
+                    })
+                    .collect(),
+            )));
+
+            let date = &mut Date::default();
+            let date_indicator = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLBindCol(
+                    stmt as *mut _,
+                    1,
+                    CDataType::SQL_C_TYPE_DATE as SmallInt,
+                    date as *mut Date as *mut _,
+                    size_of::<Date>() as Len,
+                    date_indicator,
+                )
+            );
+            let time = &mut Time::default();
+            let time_indicator = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLBindCol(
+                    stmt as *mut _,
+                    2,
+                    CDataType::SQL_C_TYPE_TIME as SmallInt,
+                    time as *mut Time as *mut _,
+                    size_of::<Time>() as Len,
+                    time_indicator,
+                )
+            );
+
+            assert_eq!(SqlReturn::SUCCESS_WITH_INFO, SQLFetch(stmt as *mut _));
+            assert_eq!(
+                Date {
+                    year: 2014,
+                    month: 11,
+                    day: 28
+                },
+                *date
+            );
+            assert_eq!(size_of::<Date>() as Len, *date_indicator);
+            assert_eq!(
+                Time {
+                    hour: 12,
+                    minute: 0,
+                    second: 9
+                },
+                *time
+            );
+            assert_eq!(size_of::<Time>() as Len, *time_indicator);
+            let errors = s.errors.read().unwrap();
+            assert_eq!(2, errors.len());
+            assert!(errors
+                .iter()
+                .all(|e| e.get_sql_state().odbc_3_state == "01S07"));
+        }
+    }
+
     fn create_mongo_query_for_bind_col_fetching_tests() -> MongoQuery {
         MongoQuery::new(
             vec![
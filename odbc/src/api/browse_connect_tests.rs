@@ -0,0 +1,98 @@
+#![allow(clippy::ptr_as_ptr, clippy::cast_possible_truncation)]
+
+mod unit {
+    use crate::{
+        handles::definitions::{Connection, ConnectionState, MongoHandle},
+        SQLBrowseConnectW,
+    };
+    use cstr::WideChar;
+    use definitions::{SqlReturn, SQL_NTS};
+
+    const BUFFER_LENGTH: i16 = 512;
+
+    // browse_connect calls SQLBrowseConnectW with the given attributes and returns its result
+    // along with the browse result connection string.
+    unsafe fn browse_connect(
+        conn_handle: *mut MongoHandle,
+        attributes: &str,
+    ) -> (SqlReturn, String) {
+        let mut in_connection_string = cstr::to_widechar_vec(attributes);
+        in_connection_string.push(0);
+        let out_connection_string = &mut [0 as WideChar; BUFFER_LENGTH as usize];
+        let out_length = &mut 0;
+        let sql_return = SQLBrowseConnectW(
+            conn_handle as *mut _,
+            in_connection_string.as_ptr(),
+            SQL_NTS as i16,
+            out_connection_string.as_mut_ptr(),
+            BUFFER_LENGTH,
+            out_length,
+        );
+        (
+            sql_return,
+            cstr::from_widechar_ref_lossy(&out_connection_string[..*out_length as usize]),
+        )
+    }
+
+    // Each call returns the attributes that are still missing, and the attributes given so far
+    // are kept by the connection for the next call.
+    #[test]
+    fn missing_attributes_are_requested_until_all_required_are_given() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+                std::ptr::null_mut(),
+                ConnectionState::Allocated,
+            ));
+            assert_eq!(
+                (
+                    SqlReturn::NEED_DATA,
+                    "SERVER:Server=?;UID:Login ID=?;PWD:Password=?;DATABASE:Database=?;\
+                     *TLSCAFILE:TLS CA File=?;\
+                     *TLSALLOWINVALIDCERTIFICATES:TLS Allow Invalid Certificates=?;\
+                     *TLSALLOWINVALIDHOSTNAMES:TLS Allow Invalid Hostnames=?"
+                        .to_string()
+                ),
+                browse_connect(conn_handle, "DRIVER={MongoDB Atlas SQL ODBC Driver}")
+            );
+            assert_eq!(
+                (
+                    SqlReturn::NEED_DATA,
+                    "PWD:Password=?;DATABASE:Database=?;\
+                     *TLSCAFILE:TLS CA File=?;\
+                     *TLSALLOWINVALIDCERTIFICATES:TLS Allow Invalid Certificates=?;\
+                     *TLSALLOWINVALIDHOSTNAMES:TLS Allow Invalid Hostnames=?"
+                        .to_string()
+                ),
+                browse_connect(conn_handle, "SERVER=localhost;UID=me")
+            );
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert_eq!(
+                Some("DRIVER={MongoDB Atlas SQL ODBC Driver};SERVER=localhost;UID=me"),
+                conn.browse_connection_string.read().unwrap().as_deref()
+            );
+            assert_eq!(ConnectionState::Allocated, *conn.state.read().unwrap());
+        }
+    }
+
+    // An invalid connection string is an error and the browsing starts over.
+    #[test]
+    fn invalid_attributes_reset_browsing() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+                std::ptr::null_mut(),
+                ConnectionState::Allocated,
+            ));
+            assert_eq!(
+                SqlReturn::NEED_DATA,
+                browse_connect(conn_handle, "DRIVER={MongoDB Atlas SQL ODBC Driver}").0
+            );
+            assert_eq!(SqlReturn::ERROR, browse_connect(conn_handle, "SERVER").0);
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert_eq!(
+                "08001",
+                conn.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+            assert!(conn.browse_connection_string.read().unwrap().is_none());
+        }
+    }
+}
@@ -6,7 +6,7 @@
 
 use crate::{handles::definitions::*, SQLCancel};
 use definitions::SqlReturn;
-use mongo_odbc_core::MongoConnection;
+use mongo_odbc_core::{MongoConnection, ServerCapabilities};
 use mongodb::Client;
 use std::env;
 
@@ -145,6 +145,16 @@ mod integration {
             client,
             operation_timeout: None,
             uuid_repr: None,
+            retry_reads: true,
+            result_cache: None,
+            collation: None,
+            force_aggregate: false,
+            capabilities: ServerCapabilities::default(),
+            batch_size: None,
+            #[cfg(feature = "test_hooks")]
+            get_more_count: Default::default(),
+            server_warnings: Default::default(),
+            collection_collations: Default::default(),
             runtime,
         };
         *conn_handle.mongo_connection.write().unwrap() = Some(mongo_connection);
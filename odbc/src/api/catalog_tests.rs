@@ -0,0 +1,124 @@
+#![allow(
+    clippy::ptr_as_ptr,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+mod unit {
+    use crate::{
+        handles::definitions::{
+            Connection, ConnectionState, Env, EnvState, MongoHandle, Statement, StatementState,
+        },
+        SQLFetch, SQLSpecialColumnsW, SQLStatisticsW,
+    };
+    use cstr::WideChar;
+    use definitions::{
+        HStmt, IdentifierType, IndexUniqueness, SmallInt, SqlReturn, StatisticsAccuracy, SQL_NTS,
+    };
+    use std::ptr::null;
+
+    const NTS: SmallInt = SQL_NTS as SmallInt;
+
+    // A catalog function looking up a table, called with its catalog and table names.
+    type CatalogFunction = unsafe fn(HStmt, *const WideChar, *const WideChar) -> SqlReturn;
+
+    unsafe fn special_columns(
+        stmt: HStmt,
+        catalog: *const WideChar,
+        table: *const WideChar,
+    ) -> SqlReturn {
+        SQLSpecialColumnsW(
+            stmt,
+            IdentifierType::SQL_BEST_ROWID as SmallInt,
+            catalog,
+            NTS,
+            null(),
+            NTS,
+            table,
+            NTS,
+            0,
+            0,
+        )
+    }
+
+    unsafe fn statistics(
+        stmt: HStmt,
+        catalog: *const WideChar,
+        table: *const WideChar,
+    ) -> SqlReturn {
+        SQLStatisticsW(
+            stmt,
+            catalog,
+            NTS,
+            null(),
+            NTS,
+            table,
+            NTS,
+            IndexUniqueness::SQL_INDEX_ALL as SmallInt,
+            StatisticsAccuracy::SQL_QUICK as SmallInt,
+        )
+    }
+
+    const CATALOG_FUNCTIONS: [(&str, CatalogFunction); 2] = [
+        ("SQLSpecialColumnsW", special_columns),
+        ("SQLStatisticsW", statistics),
+    ];
+
+    // Null catalog and table names are read as empty strings, even when their length is
+    // SQL_NTS. Without a table, the result set is empty.
+    #[test]
+    fn null_names_return_an_empty_result_set() {
+        for (name, catalog_function) in CATALOG_FUNCTIONS {
+            let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+            let conn = &mut MongoHandle::Connection(Connection::with_state(
+                env,
+                ConnectionState::Connected,
+            ));
+            let stmt_handle: *mut _ =
+                &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+            unsafe {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    catalog_function(stmt_handle as *mut _, null(), null()),
+                    "{name}"
+                );
+                assert_eq!(
+                    SqlReturn::NO_DATA,
+                    SQLFetch(stmt_handle as *mut _),
+                    "{name}"
+                );
+            }
+        }
+    }
+
+    // A catalog function called on a connection that is not open fails with 08003 instead of
+    // panicking.
+    #[test]
+    fn not_connected_is_reported() {
+        let catalog = cstr::to_widechar_vec("db\0");
+        let table = cstr::to_widechar_vec("foo\0");
+        for (name, catalog_function) in CATALOG_FUNCTIONS {
+            let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+            let conn = &mut MongoHandle::Connection(Connection::with_state(
+                env,
+                ConnectionState::Allocated,
+            ));
+            let stmt_handle: *mut _ =
+                &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+            unsafe {
+                assert_eq!(
+                    SqlReturn::ERROR,
+                    catalog_function(stmt_handle as *mut _, catalog.as_ptr(), table.as_ptr()),
+                    "{name}"
+                );
+                let errors = (*stmt_handle)
+                    .as_statement()
+                    .unwrap()
+                    .errors
+                    .read()
+                    .unwrap();
+                assert_eq!("08003", errors[0].get_sql_state().odbc_3_state, "{name}");
+            }
+        }
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
     SQLColAttributeW, SQLDescribeColW,
 };
 use definitions::{Desc, Nullability, SmallInt, SqlReturn, WChar};
-use mongo_odbc_core::{MongoFields, SQL_SEARCHABLE};
+use mongo_odbc_core::{MongoFields, MAX_STRING_SIZE, SQL_SEARCHABLE};
 use std::sync::RwLock;
 
 mod unit {
@@ -102,6 +102,8 @@ mod unit {
             Desc::SQL_DESC_AUTO_UNIQUE_VALUE,
             Desc::SQL_DESC_CASE_SENSITIVE,
             Desc::SQL_DESC_COUNT,
+            Desc::SQL_DESC_DATETIME_INTERVAL_CODE,
+            Desc::SQL_DESC_DATETIME_INTERVAL_PRECISION,
             Desc::SQL_DESC_DISPLAY_SIZE,
             Desc::SQL_DESC_FIXED_PREC_SCALE,
             Desc::SQL_DESC_LENGTH,
@@ -227,7 +229,6 @@ mod unit {
 
         for desc in [
             Desc::SQL_DESC_OCTET_LENGTH_PTR,
-            Desc::SQL_DESC_DATETIME_INTERVAL_CODE,
             Desc::SQL_DESC_INDICATOR_PTR,
             Desc::SQL_DESC_DATA_PTR,
             Desc::SQL_DESC_ALLOC_TYPE,
@@ -235,7 +236,6 @@ mod unit {
             Desc::SQL_DESC_ARRAY_STATUS_PTR,
             Desc::SQL_DESC_BIND_OFFSET_PTR,
             Desc::SQL_DESC_BIND_TYPE,
-            Desc::SQL_DESC_DATETIME_INTERVAL_PRECISION,
             Desc::SQL_DESC_MAXIMUM_SCALE,
             Desc::SQL_DESC_MINIMUM_SCALE,
             Desc::SQL_DESC_NUM_PREC_RADIX,
@@ -486,11 +486,17 @@ mod unit {
             (Desc::SQL_DESC_UPDATABLE, 0),
             (Desc::SQL_DESC_COUNT, 18),
             (Desc::SQL_DESC_CASE_SENSITIVE, 1),
+            (Desc::SQL_DESC_DATETIME_INTERVAL_CODE, 0),
+            (Desc::SQL_DESC_DATETIME_INTERVAL_PRECISION, 0),
             (Desc::SQL_DESC_DISPLAY_SIZE, 0),
             (Desc::SQL_DESC_FIXED_PREC_SCALE, 0),
             (Desc::SQL_DESC_LENGTH, 0),
             (Desc::SQL_DESC_NULLABLE, 0),
-            (Desc::SQL_DESC_OCTET_LENGTH, 0),
+            // TABLE_NAME has no max string length, so it reports the largest possible string
+            (
+                Desc::SQL_DESC_OCTET_LENGTH,
+                (MAX_STRING_SIZE as usize * std::mem::size_of::<WChar>()) as isize,
+            ),
             (Desc::SQL_DESC_PRECISION, 0),
             (Desc::SQL_DESC_SCALE, 0),
             (Desc::SQL_DESC_SEARCHABLE, SQL_SEARCHABLE as isize),
@@ -535,6 +541,81 @@ mod unit {
         }
     }
 
+    // every supported descriptor field can be read for every column of a result set
+    #[test]
+    fn test_all_field_attributes_for_all_columns() {
+        let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+            EnvState::ConnectionAllocated,
+        ))));
+        let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+            env as *mut _,
+            ConnectionState::Connected,
+        ))));
+
+        let mut stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+        stmt.mongo_statement = RwLock::new(Some(Box::new(MongoFields::empty())));
+        let mongo_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+        for col_index in 1..=18 {
+            for desc in [
+                Desc::SQL_DESC_BASE_COLUMN_NAME,
+                Desc::SQL_DESC_BASE_TABLE_NAME,
+                Desc::SQL_DESC_CATALOG_NAME,
+                Desc::SQL_DESC_LABEL,
+                Desc::SQL_DESC_LITERAL_PREFIX,
+                Desc::SQL_DESC_LITERAL_SUFFIX,
+                Desc::SQL_DESC_LOCAL_TYPE_NAME,
+                Desc::SQL_DESC_NAME,
+                Desc::SQL_DESC_SCHEMA_NAME,
+                Desc::SQL_DESC_TABLE_NAME,
+                Desc::SQL_DESC_TYPE_NAME,
+                Desc::SQL_DESC_AUTO_UNIQUE_VALUE,
+                Desc::SQL_DESC_CASE_SENSITIVE,
+                Desc::SQL_DESC_CONCISE_TYPE,
+                Desc::SQL_DESC_COUNT,
+                Desc::SQL_DESC_DATETIME_INTERVAL_CODE,
+                Desc::SQL_DESC_DATETIME_INTERVAL_PRECISION,
+                Desc::SQL_DESC_DISPLAY_SIZE,
+                Desc::SQL_DESC_FIXED_PREC_SCALE,
+                Desc::SQL_DESC_LENGTH,
+                Desc::SQL_DESC_NULLABLE,
+                Desc::SQL_DESC_OCTET_LENGTH,
+                Desc::SQL_DESC_PRECISION,
+                Desc::SQL_DESC_SCALE,
+                Desc::SQL_DESC_SEARCHABLE,
+                Desc::SQL_DESC_TYPE,
+                Desc::SQL_DESC_UNNAMED,
+                Desc::SQL_DESC_UNSIGNED,
+                Desc::SQL_DESC_UPDATABLE,
+            ] {
+                unsafe {
+                    let char_buffer: *mut std::ffi::c_void =
+                        Box::into_raw(Box::new([0u8; 200])) as *mut _;
+                    let buffer_length: SmallInt = 200;
+                    let out_length = &mut 0;
+                    let numeric_attr_ptr = &mut 0;
+                    assert_eq!(
+                        SqlReturn::SUCCESS,
+                        SQLColAttributeW(
+                            mongo_handle as *mut _,
+                            col_index,
+                            desc as u16,
+                            char_buffer,
+                            buffer_length,
+                            out_length,
+                            numeric_attr_ptr,
+                        ),
+                        "expected success for {desc:?} on column {col_index}"
+                    );
+                    let _ = Box::from_raw(char_buffer as *mut [u8; 200]);
+                }
+            }
+        }
+        unsafe {
+            let _ = Box::from_raw(conn as *mut WChar);
+            let _ = Box::from_raw(env as *mut WChar);
+        }
+    }
+
     // verify that given a column attribute that doesn't match any enum value, we return an informative error
     #[test]
     fn test_invalid_col_attribute() {
@@ -11,8 +11,11 @@ mod unit {
         util::connection_attribute_to_string,
         SQLGetConnectAttrW, SQLSetConnectAttrW,
     };
+    use constants::{SQL_DD_CP_UTF16, SQL_DD_CP_UTF8, SQL_TXN_READ_UNCOMMITTED};
     use cstr::input_text_to_string_w;
-    use definitions::{ConnectionAttribute, Integer, Pointer, SqlReturn, UInteger};
+    use definitions::{
+        AttrAutoCommit, ConnectionAttribute, Integer, Pointer, SqlReturn, UInteger, SQL_NTS,
+    };
     use std::sync::RwLock;
 
     mod get {
@@ -146,6 +149,37 @@ mod unit {
             actual_value_modifier = modify_numeric_attr,
         );
 
+        test_get_attr!(
+            autocommit_default,
+            attribute = ConnectionAttribute::SQL_ATTR_AUTOCOMMIT as i32,
+            expected_sql_return = SqlReturn::SUCCESS,
+            expected_length = std::mem::size_of::<u32>() as i32,
+            expected_value = AttrAutoCommit::SQL_AUTOCOMMIT_ON as u32,
+            actual_value_modifier = modify_numeric_attr,
+        );
+
+        test_get_attr!(
+            autocommit_off,
+            attribute = ConnectionAttribute::SQL_ATTR_AUTOCOMMIT as i32,
+            expected_sql_return = SqlReturn::SUCCESS,
+            initial_attrs = RwLock::new(ConnectionAttributes {
+                autocommit: AttrAutoCommit::SQL_AUTOCOMMIT_OFF,
+                ..Default::default()
+            }),
+            expected_length = std::mem::size_of::<u32>() as i32,
+            expected_value = AttrAutoCommit::SQL_AUTOCOMMIT_OFF as u32,
+            actual_value_modifier = modify_numeric_attr,
+        );
+
+        test_get_attr!(
+            txn_isolation,
+            attribute = ConnectionAttribute::SQL_ATTR_TXN_ISOLATION as i32,
+            expected_sql_return = SqlReturn::SUCCESS,
+            expected_length = std::mem::size_of::<u32>() as i32,
+            expected_value = SQL_TXN_READ_UNCOMMITTED,
+            actual_value_modifier = modify_numeric_attr,
+        );
+
         test_get_attr!(
             connection_dead,
             attribute = ConnectionAttribute::SQL_ATTR_CONNECTION_DEAD as i32,
@@ -180,6 +214,101 @@ mod unit {
         }
     }
 
+    // Test turning autocommit off and back on.
+    #[test]
+    fn set_autocommit() {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+
+            for autocommit in [
+                AttrAutoCommit::SQL_AUTOCOMMIT_OFF,
+                AttrAutoCommit::SQL_AUTOCOMMIT_ON,
+            ] {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLSetConnectAttrW(
+                        mongo_handle as *mut _,
+                        ConnectionAttribute::SQL_ATTR_AUTOCOMMIT as i32,
+                        autocommit as u32 as Pointer,
+                        0,
+                    )
+                );
+                let conn_handle = (*mongo_handle).as_connection().unwrap();
+                assert_eq!(
+                    autocommit,
+                    conn_handle.attributes.read().unwrap().autocommit
+                );
+            }
+        }
+    }
+
+    // Test that an autocommit value other than on or off is rejected.
+    #[test]
+    fn set_invalid_autocommit() {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLSetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_AUTOCOMMIT as i32,
+                    2usize as Pointer,
+                    0,
+                )
+            );
+            let conn_handle = (*mongo_handle).as_connection().unwrap();
+            assert_eq!(
+                AttrAutoCommit::SQL_AUTOCOMMIT_ON,
+                conn_handle.attributes.read().unwrap().autocommit
+            );
+            assert_eq!(
+                "HY024",
+                conn_handle.errors.read().unwrap()[0]
+                    .get_sql_state()
+                    .odbc_3_state
+            );
+        }
+    }
+
+    // Test that only the read uncommitted isolation level can be set.
+    #[test]
+    fn set_txn_isolation() {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_TXN_ISOLATION as i32,
+                    SQL_TXN_READ_UNCOMMITTED as usize as Pointer,
+                    0,
+                )
+            );
+            // SQL_TXN_SERIALIZABLE
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLSetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_TXN_ISOLATION as i32,
+                    8usize as Pointer,
+                    0,
+                )
+            );
+            let conn_handle = (*mongo_handle).as_connection().unwrap();
+            assert_eq!(
+                "HY024",
+                conn_handle.errors.read().unwrap()[0]
+                    .get_sql_state()
+                    .odbc_3_state
+            );
+        }
+    }
+
     // Test setting the current catalog attribute.
     #[test]
     fn set_current_catalog() {
@@ -205,15 +334,194 @@ mod unit {
         }
     }
 
-    const UNSUPPORTED_ATTRS: [ConnectionAttribute; 18] = [
+    // Test that an empty current catalog is rejected.
+    #[test]
+    fn set_empty_current_catalog() {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+
+            let current_catalog_ptr = cstr::to_widechar_ptr("");
+
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLSetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    current_catalog_ptr.0 as *mut _,
+                    current_catalog_ptr.1.len() as i32
+                )
+            );
+            let conn_handle = (*mongo_handle).as_connection().unwrap();
+            assert_eq!(conn_handle.attributes.read().unwrap().current_catalog, None);
+            assert_eq!(
+                "3D000",
+                conn_handle.errors.read().unwrap()[0]
+                    .get_sql_state()
+                    .odbc_3_state
+            );
+        }
+    }
+
+    // Test that a null current catalog is read as empty, and rejected.
+    #[test]
+    fn set_null_current_catalog() {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLSetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    std::ptr::null_mut(),
+                    SQL_NTS,
+                )
+            );
+            let conn_handle = (*mongo_handle).as_connection().unwrap();
+            assert_eq!(conn_handle.attributes.read().unwrap().current_catalog, None);
+            assert_eq!(
+                "3D000",
+                conn_handle.errors.read().unwrap()[0]
+                    .get_sql_state()
+                    .odbc_3_state
+            );
+        }
+    }
+
+    unsafe fn get_numeric_attr(mongo_handle: *mut MongoHandle, attr: ConnectionAttribute) -> u32 {
+        let mut value: UInteger = u32::MAX;
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            SQLGetConnectAttrW(
+                mongo_handle as *mut _,
+                attr as i32,
+                &mut value as *mut UInteger as Pointer,
+                0,
+                &mut 0,
+            )
+        );
+        value
+    }
+
+    // Test that every attribute that can be set is returned by SQLGetConnectAttrW with the
+    // value it was set to, or the value substituted for it.
+    #[test]
+    fn set_then_get_attrs() {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+
+            for (attr, value, expected_return, expected_value) in [
+                (
+                    ConnectionAttribute::SQL_ATTR_LOGIN_TIMEOUT,
+                    42,
+                    SqlReturn::SUCCESS,
+                    42,
+                ),
+                (
+                    ConnectionAttribute::SQL_ATTR_CONNECTION_TIMEOUT,
+                    0,
+                    SqlReturn::SUCCESS,
+                    0,
+                ),
+                (
+                    ConnectionAttribute::SQL_ATTR_CONNECTION_TIMEOUT,
+                    42,
+                    SqlReturn::SUCCESS_WITH_INFO,
+                    0,
+                ),
+                (
+                    ConnectionAttribute::SQL_ATTR_AUTOCOMMIT,
+                    AttrAutoCommit::SQL_AUTOCOMMIT_OFF as u32,
+                    SqlReturn::SUCCESS,
+                    AttrAutoCommit::SQL_AUTOCOMMIT_OFF as u32,
+                ),
+                (
+                    ConnectionAttribute::SQL_ATTR_TXN_ISOLATION,
+                    SQL_TXN_READ_UNCOMMITTED,
+                    SqlReturn::SUCCESS,
+                    SQL_TXN_READ_UNCOMMITTED,
+                ),
+                (
+                    ConnectionAttribute::SQL_ATTR_APP_WCHAR_TYPE,
+                    SQL_DD_CP_UTF16,
+                    SqlReturn::SUCCESS,
+                    SQL_DD_CP_UTF16,
+                ),
+                (
+                    ConnectionAttribute::SQL_ATTR_APP_WCHAR_TYPE,
+                    SQL_DD_CP_UTF8,
+                    SqlReturn::SUCCESS_WITH_INFO,
+                    SQL_DD_CP_UTF16,
+                ),
+            ] {
+                assert_eq!(
+                    expected_return,
+                    SQLSetConnectAttrW(
+                        mongo_handle as *mut _,
+                        attr as i32,
+                        value as usize as Pointer,
+                        0,
+                    ),
+                    "{attr:?}"
+                );
+                if expected_return == SqlReturn::SUCCESS_WITH_INFO {
+                    let conn_handle = (*mongo_handle).as_connection().unwrap();
+                    assert_eq!(
+                        "01S02",
+                        conn_handle.errors.read().unwrap()[0]
+                            .get_sql_state()
+                            .odbc_3_state
+                    );
+                }
+                assert_eq!(
+                    expected_value,
+                    get_numeric_attr(mongo_handle, attr),
+                    "{attr:?}"
+                );
+            }
+
+            let current_catalog_ptr = cstr::to_widechar_ptr("test");
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    current_catalog_ptr.0 as *mut _,
+                    current_catalog_ptr.1.len() as i32
+                )
+            );
+            let mut buffer = [0 as cstr::WideChar; 10];
+            let out_length = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    buffer.as_mut_ptr() as Pointer,
+                    std::mem::size_of_val(&buffer) as i32,
+                    out_length,
+                )
+            );
+            assert_eq!(
+                "test",
+                input_text_to_string_w(
+                    buffer.as_ptr(),
+                    (*out_length as usize / std::mem::size_of::<cstr::WideChar>()) as isize
+                )
+            );
+        }
+    }
+
+    const UNSUPPORTED_ATTRS: [ConnectionAttribute; 16] = [
         ConnectionAttribute::SQL_ATTR_ASYNC_ENABLE,
         ConnectionAttribute::SQL_ATTR_ACCESS_MODE,
-        ConnectionAttribute::SQL_ATTR_AUTOCOMMIT,
         ConnectionAttribute::SQL_ATTR_TRACE,
         ConnectionAttribute::SQL_ATTR_TRACEFILE,
         ConnectionAttribute::SQL_ATTR_TRANSLATE_LIB,
         ConnectionAttribute::SQL_ATTR_TRANSLATE_OPTION,
-        ConnectionAttribute::SQL_ATTR_TXN_ISOLATION,
         ConnectionAttribute::SQL_ATTR_ODBC_CURSORS,
         ConnectionAttribute::SQL_ATTR_QUIET_MODE,
         ConnectionAttribute::SQL_ATTR_PACKET_SIZE,
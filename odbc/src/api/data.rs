@@ -1,11 +1,12 @@
 use crate::{
     add_diag_with_function,
     errors::ODBCError,
-    handles::definitions::{CachedData, MongoHandle, Statement},
+    handles::definitions::{CachedData, JsonMode, MongoHandle, Statement},
 };
-use bson::{spec::BinarySubtype, Bson, UuidRepresentation};
+use bson::{spec::BinarySubtype, Binary, Bson, Uuid, UuidRepresentation};
 use chrono::{
-    offset::Utc, DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+    offset::Utc, DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime,
+    SecondsFormat, TimeZone, Timelike,
 };
 use cstr::{
     write_binary_slice_to_buffer, write_fixed_data, write_string_slice_to_buffer,
@@ -31,9 +32,13 @@ type Result<T> = std::result::Result<T, ODBCError>;
 
 /// IntoCData is just used for adding methods to bson::Bson.
 trait IntoCData {
-    fn to_json(self, uuid_repr: Option<UuidRepresentation>) -> String;
-    fn to_json_val(self, uuid_repr: Option<UuidRepresentation>) -> Value;
-    fn to_binary(self, uuid_repr: Option<UuidRepresentation>) -> Result<Vec<u8>>;
+    fn to_json(self, uuid_repr: Option<UuidRepresentation>, json_mode: JsonMode) -> String;
+    fn to_json_val(self, uuid_repr: Option<UuidRepresentation>, json_mode: JsonMode) -> Value;
+    fn to_binary(
+        self,
+        uuid_repr: Option<UuidRepresentation>,
+        json_mode: JsonMode,
+    ) -> Result<Vec<u8>>;
     fn to_guid(self, uuid_repr: Option<UuidRepresentation>) -> Result<Vec<u8>>;
     fn to_f64(&self) -> Result<(f64, Option<ODBCError>)>;
     fn to_f32(&self) -> Result<(f32, Option<ODBCError>)>;
@@ -83,44 +88,171 @@ fn from_string(s: &str, conversion_error_type: &'static str) -> Result<f64> {
     f64::from_str(s).map_err(|_| ODBCError::InvalidCharacterValue(conversion_error_type))
 }
 
+// timestamp_to_datetime converts a BSON Timestamp to the datetime of its seconds component. The
+// increment only orders the operations within a second, so it is discarded.
+fn timestamp_to_datetime(ts: &bson::Timestamp) -> bson::DateTime {
+    bson::DateTime::from_millis(i64::from(ts.time) * 1000)
+}
+
+// in_time_zone shifts a datetime into the session time zone set with SET TIMEZONE, so that its
+// date and time fields are those of the local time. Other values are returned unchanged.
+fn in_time_zone(data: Bson, time_zone: Option<FixedOffset>) -> Bson {
+    let Some(offset) = time_zone else {
+        return data;
+    };
+    let millis = match &data {
+        Bson::DateTime(d) => d.timestamp_millis(),
+        Bson::Timestamp(ts) => timestamp_to_datetime(ts).timestamp_millis(),
+        _ => return data,
+    };
+    Bson::DateTime(bson::DateTime::from_millis(
+        millis + i64::from(offset.local_minus_utc()) * 1000,
+    ))
+}
+
+// to_json_in_time_zone formats a value for the character types. With a session time zone set with
+// SET TIMEZONE, a top level datetime or timestamp is formatted in that time zone with its offset,
+// so that it shows the same local time as when it is read as a datetime type.
+fn to_json_in_time_zone(
+    data: Bson,
+    uuid_repr: Option<UuidRepresentation>,
+    json_mode: JsonMode,
+    time_zone: Option<FixedOffset>,
+) -> String {
+    match (data, time_zone) {
+        (Bson::DateTime(d), Some(offset)) => json!({
+            "$date": d
+                .to_chrono()
+                .with_timezone(&offset)
+                .to_rfc3339_opts(SecondsFormat::AutoSi, false)
+        })
+        .to_string(),
+        (Bson::Timestamp(ts), Some(offset)) => timestamp_to_datetime(&ts)
+            .to_chrono()
+            .with_timezone(&offset)
+            .to_rfc3339_opts(SecondsFormat::Secs, false),
+        (data, _) => data.to_json(uuid_repr, json_mode),
+    }
+}
+
+// top_level_json_mode returns the json mode used to format a value that is not nested in a
+// document or array. Only nested values follow the connection's json mode, a top level scalar
+// keeps its relaxed format so that, e.g., a long is still returned as a plain number.
+fn top_level_json_mode(b: &Bson, json_mode: JsonMode) -> JsonMode {
+    match b {
+        Bson::Array(_) | Bson::Document(_) => json_mode,
+        _ => JsonMode::Relaxed,
+    }
+}
+
+// sentinel_name returns the name a top level MinKey or MaxKey is returned as. With the canonical
+// json mode, they are returned as extended JSON like the values nested in documents.
+fn sentinel_name(b: &Bson, json_mode: JsonMode) -> Option<&'static str> {
+    match (b, json_mode) {
+        (Bson::MinKey, JsonMode::Relaxed) => Some("MinKey"),
+        (Bson::MaxKey, JsonMode::Relaxed) => Some("MaxKey"),
+        _ => None,
+    }
+}
+
+// binary_to_uuid returns the UUID stored in a binary of subtype 3 or 4, or None for any other
+// binary. Subtype 4 is always in the standard byte order, while the byte order of the legacy
+// subtype 3 depends on the driver that wrote it, and defaults to the python driver's.
+fn binary_to_uuid(b: &Binary, uuid_repr: Option<UuidRepresentation>) -> Option<Uuid> {
+    match b.subtype {
+        BinarySubtype::Uuid => b.to_uuid().ok(),
+        BinarySubtype::UuidOld => {
+            let legacy_repr = match uuid_repr {
+                None | Some(UuidRepresentation::Standard) => UuidRepresentation::PythonLegacy,
+                Some(repr) => repr,
+            };
+            b.to_uuid_with_representation(legacy_repr).ok()
+        }
+        _ => None,
+    }
+}
+
 impl IntoCData for Bson {
-    fn to_json_val(self, uuid_repr: Option<UuidRepresentation>) -> Value {
+    fn to_json_val(self, uuid_repr: Option<UuidRepresentation>, json_mode: JsonMode) -> Value {
         match self {
-            Bson::Array(v) => {
-                Value::Array(v.into_iter().map(|b| b.to_json_val(uuid_repr)).collect())
-            }
+            Bson::Array(v) => Value::Array(
+                v.into_iter()
+                    .map(|b| b.to_json_val(uuid_repr, json_mode))
+                    .collect(),
+            ),
             Bson::Document(v) => Value::Object(
                 v.into_iter()
-                    .map(|(k, v)| (k, v.to_json_val(uuid_repr)))
+                    .map(|(k, v)| (k, v.to_json_val(uuid_repr, json_mode)))
                     .collect(),
             ),
             Bson::String(s) => Value::String(s),
-            Bson::Binary(b) if b.subtype == BinarySubtype::Uuid => {
-                json!({"$uuid": b.to_uuid().unwrap().to_string()})
-            }
-            Bson::Binary(b) if b.subtype == BinarySubtype::UuidOld => {
-                json!({"$uuid": b.to_uuid_with_representation(uuid_repr.unwrap_or(UuidRepresentation::PythonLegacy)).unwrap().to_string()})
-            }
-            _ => self.into_relaxed_extjson(),
+            Bson::Binary(ref b) => match binary_to_uuid(b, uuid_repr) {
+                Some(uuid) => json!({"$uuid": uuid.to_string()}),
+                None => self.into_relaxed_extjson(),
+            },
+            _ => match json_mode {
+                JsonMode::Relaxed => self.into_relaxed_extjson(),
+                JsonMode::Canonical => self.into_canonical_extjson(),
+            },
         }
     }
-    fn to_json(self, uuid_repr: Option<UuidRepresentation>) -> String {
+    fn to_json(self, uuid_repr: Option<UuidRepresentation>, json_mode: JsonMode) -> String {
+        if let Some(name) = sentinel_name(&self, json_mode) {
+            return name.to_string();
+        }
+        let json_mode = top_level_json_mode(&self, json_mode);
         match self {
             Bson::String(s) => s,
-            _ => self.to_json_val(uuid_repr).to_string(),
+            // A top level Timestamp is formatted as an ISO-8601 timestamp.
+            Bson::Timestamp(ts) => timestamp_to_datetime(&ts)
+                .to_chrono()
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            // A top level UUID is formatted as a canonical GUID string.
+            Bson::Binary(ref b) => match binary_to_uuid(b, uuid_repr) {
+                Some(uuid) => uuid.to_string(),
+                None => self.to_json_val(uuid_repr, json_mode).to_string(),
+            },
+            _ => self.to_json_val(uuid_repr, json_mode).to_string(),
         }
     }
 
-    fn to_binary(self, uuid_repr: Option<UuidRepresentation>) -> Result<Vec<u8>> {
-        Ok(self.to_json(uuid_repr).into_bytes())
+    fn to_binary(
+        self,
+        uuid_repr: Option<UuidRepresentation>,
+        json_mode: JsonMode,
+    ) -> Result<Vec<u8>> {
+        if let Some(name) = sentinel_name(&self, json_mode) {
+            return Ok(name.as_bytes().to_vec());
+        }
+        let json_mode = top_level_json_mode(&self, json_mode);
+        Ok(match self {
+            Bson::String(s) => s,
+            _ => self.to_json_val(uuid_repr, json_mode).to_string(),
+        }
+        .into_bytes())
     }
 
+    // to_guid returns the bytes of the SQLGUID struct for a UUID, whose first three fields are
+    // integers in the native byte order.
     fn to_guid(self, uuid_repr: Option<UuidRepresentation>) -> Result<Vec<u8>> {
         match self {
-            Bson::Binary(b) if b.subtype != BinarySubtype::Uuid => Err(
-                ODBCError::RestrictedDataType("binary with non-uuid subtype", GUID),
-            ),
-            Bson::Binary(_) => Ok(self.to_json(uuid_repr).into_bytes()),
+            Bson::Binary(ref b) => match binary_to_uuid(b, uuid_repr) {
+                Some(uuid) => {
+                    let bytes = uuid.bytes();
+                    let mut guid = Vec::with_capacity(bytes.len());
+                    guid.extend(
+                        u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).to_ne_bytes(),
+                    );
+                    guid.extend(u16::from_be_bytes([bytes[4], bytes[5]]).to_ne_bytes());
+                    guid.extend(u16::from_be_bytes([bytes[6], bytes[7]]).to_ne_bytes());
+                    guid.extend(&bytes[8..]);
+                    Ok(guid)
+                }
+                None => Err(ODBCError::RestrictedDataType(
+                    "binary with non-uuid subtype",
+                    GUID,
+                )),
+            },
             o => Err(ODBCError::RestrictedDataType(o.to_type_str(), GUID)),
         }
     }
@@ -403,6 +535,7 @@ impl IntoCData for Bson {
     fn to_datetime(&self) -> Result<(DateTime<Utc>, Option<ODBCError>)> {
         match self {
             Bson::DateTime(d) => Ok(((*d).into(), None)),
+            Bson::Timestamp(ts) => Ok((timestamp_to_datetime(ts).into(), None)),
             Bson::String(s) => {
                 // using '-' to check if input string contains a date and ':' to check if input string contains a time
                 // parse (or set defaults) for date and time depending on contents of string
@@ -434,6 +567,7 @@ impl IntoCData for Bson {
 
     fn to_date(&self) -> Result<(NaiveDate, Option<ODBCError>)> {
         match self {
+            Bson::Timestamp(ts) => Bson::DateTime(timestamp_to_datetime(ts)).to_date(),
             Bson::DateTime(d) => {
                 let chrono_datetime = (*d).to_chrono();
                 Ok((
@@ -465,6 +599,7 @@ impl IntoCData for Bson {
 
     fn to_time(&self) -> Result<(NaiveTime, Option<ODBCError>)> {
         match self {
+            Bson::Timestamp(ts) => Bson::DateTime(timestamp_to_datetime(ts)).to_time(),
             Bson::DateTime(d) => {
                 let dt_chrono = (*d).to_chrono();
                 Ok((
@@ -812,6 +947,13 @@ pub unsafe fn format_bson_data(
         _ => {}
     }
 
+    let json_mode = (*mongo_handle)
+        .as_statement_connection()
+        .map_or(JsonMode::default(), |conn| *conn.json_mode.read().unwrap());
+    let time_zone = (*mongo_handle)
+        .as_statement_connection()
+        .and_then(|conn| *conn.time_zone.read().unwrap());
+
     let uuid_repr = match (*mongo_handle).as_statement_connection() {
         Some(conn) => match conn.mongo_connection.read() {
             Ok(conn) => {
@@ -831,7 +973,7 @@ pub unsafe fn format_bson_data(
             let data = if target_type == CDataType::SQL_C_GUID {
                 data.to_guid(uuid_repr)
             } else {
-                data.to_binary(uuid_repr)
+                data.to_binary(uuid_repr, json_mode)
             };
             match data {
                 Ok(data) => format_binary(
@@ -853,7 +995,9 @@ pub unsafe fn format_bson_data(
             }
         }
         CDataType::SQL_C_CHAR => {
-            let data = data.to_json(uuid_repr).bytes().collect::<Vec<u8>>();
+            let data = to_json_in_time_zone(data, uuid_repr, json_mode, time_zone)
+                .bytes()
+                .collect::<Vec<u8>>();
             char_data!(
                 mongo_handle,
                 col_num,
@@ -867,7 +1011,8 @@ pub unsafe fn format_bson_data(
             )
         }
         CDataType::SQL_C_WCHAR => {
-            let data = cstr::to_widechar_vec(&data.to_json(uuid_repr));
+            let data =
+                cstr::to_widechar_vec(&to_json_in_time_zone(data, uuid_repr, json_mode, time_zone));
             char_data!(
                 mongo_handle,
                 col_num,
@@ -948,21 +1093,21 @@ pub unsafe fn format_bson_data(
             col_num,
             target_value_ptr,
             str_len_or_ind_ptr,
-            data,
+            in_time_zone(data, time_zone),
         ),
         CDataType::SQL_C_TIME | CDataType::SQL_C_TYPE_TIME => format_time(
             mongo_handle,
             col_num,
             target_value_ptr,
             str_len_or_ind_ptr,
-            data,
+            in_time_zone(data, time_zone),
         ),
         CDataType::SQL_C_DATE | CDataType::SQL_C_TYPE_DATE => format_date(
             mongo_handle,
             col_num,
             target_value_ptr,
             str_len_or_ind_ptr,
-            data,
+            in_time_zone(data, time_zone),
         ),
         other => {
             add_diag_with_function!(
@@ -994,12 +1139,11 @@ unsafe fn set_output_wstring_helper(
     if output_ptr.is_null() || buffer_len == 0 {
         return (0usize, SqlReturn::SUCCESS_WITH_INFO);
     }
-    // TODO SQL-1084: This will currently not work when we need to truncate data that takes more than
-    // two bytes, such as emojis because it's assuming every character is 2 bytes.
-    // Actually, this is not clear now. The spec suggests it may be up to the user to correctly
-    // reassemble parts.
+    // Truncation never splits a surrogate pair, so characters outside the BMP, such as emojis,
+    // are returned whole by one of the chunks. The maximum string length is in characters, and
+    // the buffer also holds the null terminator.
     let num_chars_to_write = match max_string_length {
-        Some(s) => std::cmp::min((s as usize) * size_of::<WideChar>(), buffer_len),
+        Some(s) => std::cmp::min(s as usize + 1, buffer_len),
         None => buffer_len,
     };
     let num_chars_written = write_wstring_slice_to_buffer(
@@ -1094,9 +1238,10 @@ pub mod i16_len {
     use super::*;
     ///
     /// set_output_wstring_as_bytes writes [`message`] to the Pointer [`output_ptr`]. [`buffer_len`] is the
-    /// length of the [`output_ptr`] buffer in characters; the message should be truncated
-    /// if it is longer than the buffer length. The number of *BYTES* written to [`output_ptr`]
-    /// should be stored in [`text_length_ptr`].
+    /// length of the [`output_ptr`] buffer in *BYTES*; the message should be truncated
+    /// if it is longer than the buffer length. The total number of *BYTES* available, excluding
+    /// the null terminator, should be stored in [`text_length_ptr`], even when the message
+    /// is truncated.
     ///
     /// # Safety
     /// This writes to multiple raw C-pointers
@@ -1108,18 +1253,16 @@ pub mod i16_len {
         text_length_ptr: *mut SmallInt,
     ) -> SqlReturn {
         let message = cstr::to_widechar_vec(message);
-        let (len, ret) = set_output_wstring_helper(
+        let (_, ret) = set_output_wstring_helper(
             &message,
             output_ptr.cast::<WideChar>(),
             buffer_len / size_of::<WideChar>(),
             None,
         );
-        // size_of::<WideChar>() will always be 2, so this cast is safe
-        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
         // Only copy the length if the pointer is not null
         ptr_safe_write(
             text_length_ptr,
-            (size_of::<WideChar>() * len)
+            (size_of::<WideChar>() * message.len())
                 .try_into()
                 .expect("Required buffer size exceeds {i16::MAX} on this platform"),
         );
@@ -1210,6 +1353,31 @@ pub mod i32_len {
         ret
     }
 
+    ///
+    /// set_output_wstring writes [`message`] to the *WideChar [`output_ptr`]. [`buffer_len`] is the
+    /// length of the [`output_ptr`] buffer in characters; the message should be truncated
+    /// if it is longer than the buffer length. The total number of characters available, excluding
+    /// the null terminator, should be stored in [`text_length_ptr`], even when the message
+    /// is truncated.
+    ///
+    /// # Safety
+    /// This writes to multiple raw C-pointers
+    ///
+    pub unsafe fn set_output_wstring(
+        message: &str,
+        output_ptr: *mut WideChar,
+        buffer_len: usize,
+        text_length_ptr: *mut Integer,
+    ) -> SqlReturn {
+        let message = cstr::to_widechar_vec(message);
+        let (_, ret) = set_output_wstring_helper(&message, output_ptr, buffer_len, None);
+        ptr_safe_write(
+            text_length_ptr,
+            message.len().try_into().expect("Data too large to fit"),
+        );
+        ret
+    }
+
     ///
     /// set_output_fixed_data writes [`data`], which must be a fixed sized type, to the Pointer [`output_ptr`].
     /// ODBC drivers assume the output buffer is large enough for fixed types, and are allowed to
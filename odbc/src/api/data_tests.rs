@@ -9,7 +9,8 @@ use std::str::FromStr;
 use crate::{
     api::functions::{SQLFetch, SQLMoreResults},
     handles::definitions::{
-        Connection, ConnectionState, Env, EnvState, MongoHandle, Statement, StatementState,
+        Connection, ConnectionState, Env, EnvState, JsonMode, MongoHandle, Statement,
+        StatementState,
     },
     map, set,
 };
@@ -70,8 +71,8 @@ const JS_W_S_STR_VAL: (u16, &str) = (
     JS_W_S_COL,
     "{\"$code\":\"log(\\\"hello\\\" + x + \\\"world\\\")\",\"$scope\":{\"x\":42}}",
 );
-const MAXKEY_STR_VAL: (u16, &str) = (MAXKEY_COL, "{\"$maxKey\":1}");
-const MINKEY_STR_VAL: (u16, &str) = (MINKEY_COL, "{\"$minKey\":1}");
+const MAXKEY_STR_VAL: (u16, &str) = (MAXKEY_COL, "MaxKey");
+const MINKEY_STR_VAL: (u16, &str) = (MINKEY_COL, "MinKey");
 const OID_STR_VAL: (u16, &str) = (OID_COL, "{\"$oid\":\"63448dfed38427a35d534e40\"}");
 const REGEX_STR_VAL: (u16, &str) = (
     REGEX_COL,
@@ -932,13 +933,13 @@ fn sql_get_guid_data(mq: MongoQuery) {
                         .unwrap()[0]
                 ),
             );
-            guid_val_test(
-                GUID_COL,
-                &[
-                    123, 34, 36, 117, 117, 105, 100, 34, 58, 34, 100, 53, 101, 51, 97,
-                ],
-                SqlReturn::SUCCESS,
-            );
+            // d5e3ac31-fd29-49e3-8759-08d116e98b29 as a SQLGUID struct
+            let mut expected_guid = vec![];
+            expected_guid.extend(0xd5e3ac31u32.to_ne_bytes());
+            expected_guid.extend(0xfd29u16.to_ne_bytes());
+            expected_guid.extend(0x49e3u16.to_ne_bytes());
+            expected_guid.extend([0x87, 0x59, 0x08, 0xd1, 0x16, 0xe9, 0x8b, 0x29]);
+            guid_val_test(GUID_COL, &expected_guid, SqlReturn::SUCCESS);
         }
         let _ = Box::from_raw(buffer as *mut WChar);
         let _ = Box::from_raw(conn as *mut WChar);
@@ -1186,6 +1187,137 @@ fn sql_get_binary_data_by_pieces(mq: MongoQuery) {
     }
 }
 
+// sql_get_large_data_in_chunks reads a 10KB string and a 10KB binary value through a 1KB
+// buffer, verifying that each call returns the next chunk and the remaining length until
+// the data is exhausted.
+fn sql_get_large_data_in_chunks(target_type: definitions::CDataType) {
+    use crate::api::functions::SQLGetData;
+    use cstr::{input_text_to_string_a, input_text_to_string_w};
+    use definitions::CDataType;
+    use std::mem::size_of;
+
+    const LARGE_DATA_LEN: usize = 10 * 1024;
+    const BUFFER_LENGTH: usize = 1024;
+
+    let large_str: String = (0..LARGE_DATA_LEN)
+        .map(|i| char::from(b'a' + (i % 26) as u8))
+        .collect();
+    let large_bin: Vec<u8> = (0..LARGE_DATA_LEN).map(|i| (i % 251) as u8).collect();
+    let mq = MongoQuery::new(
+        vec![doc! {"test": {
+            "large_str": large_str.clone(),
+            "large_bin": Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: large_bin.clone(),
+            }),
+        }}],
+        vec![
+            MongoColMetadata::new(
+                "",
+                "test".to_string(),
+                "large_str".to_string(),
+                Schema::Atomic(Atomic::Scalar(BsonTypeName::String)),
+                Nullability::SQL_NO_NULLS,
+                TypeMode::Standard,
+                None,
+            ),
+            MongoColMetadata::new(
+                "",
+                "test".to_string(),
+                "large_bin".to_string(),
+                Schema::Atomic(Atomic::Scalar(BsonTypeName::BinData)),
+                Nullability::SQL_NO_NULLS,
+                TypeMode::Standard,
+                None,
+            ),
+        ],
+    );
+    let (col, expected, char_size) = match target_type {
+        CDataType::SQL_C_CHAR => (1, large_str.into_bytes(), size_of::<u8>()),
+        CDataType::SQL_C_WCHAR => (1, large_str.into_bytes(), size_of::<WideChar>()),
+        // binary data is returned as its extended json representation
+        _ => (
+            2,
+            Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: large_bin,
+            })
+            .into_relaxed_extjson()
+            .to_string()
+            .into_bytes(),
+            0,
+        ),
+    };
+
+    let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+        EnvState::ConnectionAllocated,
+    ))));
+    let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+        env as *mut _,
+        ConnectionState::Connected,
+    ))));
+    let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+    *stmt.mongo_statement.write().unwrap() = Some(Box::new(mq));
+
+    let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+    unsafe {
+        assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+        let buffer: *mut std::ffi::c_void = Box::into_raw(Box::new([0u8; BUFFER_LENGTH])) as *mut _;
+        let out_len_or_ind = &mut 0;
+        // character data is null terminated, so each chunk holds one less character than fits
+        let chunk_len = match char_size {
+            0 => BUFFER_LENGTH,
+            size => BUFFER_LENGTH / size - 1,
+        };
+        let mut actual = vec![];
+        loop {
+            let remaining = expected.len() - actual.len();
+            let sql_return = SQLGetData(
+                stmt_handle as *mut _,
+                col,
+                target_type as i16,
+                buffer,
+                BUFFER_LENGTH as isize,
+                out_len_or_ind,
+            );
+            // the indicator always holds the length of the data not yet retrieved
+            assert_eq!((remaining * char_size.max(1)) as isize, *out_len_or_ind);
+            let read_len = std::cmp::min(chunk_len, remaining);
+            let chunk = match target_type {
+                CDataType::SQL_C_CHAR => {
+                    input_text_to_string_a(buffer as *const _, read_len as isize).into_bytes()
+                }
+                CDataType::SQL_C_WCHAR => {
+                    input_text_to_string_w(buffer as *const _, read_len as isize).into_bytes()
+                }
+                _ => std::slice::from_raw_parts(buffer as *const u8, read_len).to_vec(),
+            };
+            actual.extend(chunk);
+            if remaining > chunk_len {
+                assert_eq!(SqlReturn::SUCCESS_WITH_INFO, sql_return);
+            } else {
+                assert_eq!(SqlReturn::SUCCESS, sql_return);
+                break;
+            }
+        }
+        assert_eq!(expected, actual);
+        assert_eq!(
+            SqlReturn::NO_DATA,
+            SQLGetData(
+                stmt_handle as *mut _,
+                col,
+                target_type as i16,
+                buffer,
+                BUFFER_LENGTH as isize,
+                out_len_or_ind,
+            )
+        );
+        let _ = Box::from_raw(buffer as *mut [u8; BUFFER_LENGTH]);
+        let _ = Box::from_raw(conn as *mut WChar);
+        let _ = Box::from_raw(env as *mut WChar);
+    }
+}
+
 fn sql_get_string_data(mq: MongoQuery) {
     use crate::api::functions::SQLGetData;
     use cstr::input_text_to_string_a;
@@ -3369,6 +3501,12 @@ mod unit_tests {
             errors: RwLock::new(vec![]),
             type_mode: RwLock::new(TypeMode::Simple),
             max_string_length: RwLock::new(Some(6)),
+            slow_query_ms: RwLock::new(None),
+            unordered_limit_warning: RwLock::new(true),
+            row_as_json: RwLock::new(false),
+            browse_connection_string: RwLock::new(None),
+            json_mode: RwLock::new(JsonMode::default()),
+            time_zone: RwLock::new(None),
         })));
 
         // use simple type mode to test string columns for complex types
@@ -3421,8 +3559,8 @@ mod unit_tests {
             str_val_test(ARRAY_COL, 6, "[1,2,");
             str_val_test(JS_COL, 6, "{\"$co");
             str_val_test(JS_W_S_COL, 6, "{\"$co");
-            str_val_test(MINKEY_COL, 6, "{\"$mi");
-            str_val_test(MAXKEY_COL, 6, "{\"$ma");
+            str_val_test(MINKEY_COL, 6, "MinKe");
+            str_val_test(MAXKEY_COL, 6, "MaxKe");
             str_val_test(DOC_COL, 6, "{\"x\":");
             str_val_test(OID_COL, 6, "{\"$oi");
             str_val_test(REGEX_COL, 6, "{\"$re");
@@ -3481,6 +3619,14 @@ mod unit_tests {
         sql_get_binary_data_by_pieces(SIMPLE_BSON_TYPE_MQ.clone());
     }
 
+    #[test]
+    fn sql_get_large_data_in_chunks_test() {
+        use definitions::CDataType;
+        sql_get_large_data_in_chunks(CDataType::SQL_C_CHAR);
+        sql_get_large_data_in_chunks(CDataType::SQL_C_WCHAR);
+        sql_get_large_data_in_chunks(CDataType::SQL_C_BINARY);
+    }
+
     #[test]
     fn sql_get_string_data_test() {
         sql_get_string_data(STANDARD_BSON_TYPE_MQ.clone());
@@ -3546,4 +3692,864 @@ mod unit_tests {
         sql_get_time_data(STANDARD_BSON_TYPE_MQ.clone());
         sql_get_time_data(SIMPLE_BSON_TYPE_MQ.clone());
     }
+
+    #[test]
+    fn sql_get_uuid_subtypes_as_guid_string() {
+        use crate::api::functions::SQLGetData;
+        use cstr::input_text_to_string_w;
+        use definitions::CDataType;
+
+        let uuid_bytes = vec![
+            0xd5, 0xe3, 0xac, 0x31, 0xfd, 0x29, 0x49, 0xe3, 0x87, 0x59, 0x08, 0xd1, 0x16, 0xe9,
+            0x8b, 0x29,
+        ];
+        let binary_metadata = |name: &str| {
+            MongoColMetadata::new(
+                "",
+                "test".to_string(),
+                name.to_string(),
+                Schema::Atomic(Atomic::Scalar(BsonTypeName::BinData)),
+                Nullability::SQL_NO_NULLS,
+                TypeMode::Standard,
+                None,
+            )
+        };
+        let mq = MongoQuery::new(
+            vec![doc! {"test": {
+                "standard": Bson::Binary(Binary {
+                    subtype: BinarySubtype::Uuid,
+                    bytes: uuid_bytes.clone(),
+                }),
+                // Without a uuidRepresentation, legacy UUIDs use the python driver's byte order,
+                // which is the same as the standard one.
+                "legacy": Bson::Binary(Binary {
+                    subtype: BinarySubtype::UuidOld,
+                    bytes: uuid_bytes,
+                }),
+                "generic": Bson::Binary(Binary {
+                    subtype: BinarySubtype::Generic,
+                    bytes: vec![5u8, 6u8, 42u8],
+                }),
+            }}],
+            vec![
+                binary_metadata("standard"),
+                binary_metadata("legacy"),
+                binary_metadata("generic"),
+            ],
+        );
+
+        let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+            EnvState::ConnectionAllocated,
+        ))));
+        let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+            env as *mut _,
+            ConnectionState::Connected,
+        ))));
+        let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+        *stmt.mongo_statement.write().unwrap() = Some(Box::new(mq));
+
+        let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+            let buffer: *mut std::ffi::c_void = Box::into_raw(Box::new([0u8; 200])) as *mut _;
+            let out_len_or_ind = &mut 0;
+            let mut wstr_val_test = |col: u16, expected: &str| {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle as *mut _,
+                        col,
+                        CDataType::SQL_C_WCHAR as i16,
+                        buffer,
+                        200,
+                        out_len_or_ind,
+                    )
+                );
+                assert_eq!(
+                    expected,
+                    input_text_to_string_w(
+                        buffer as *const _,
+                        *out_len_or_ind / std::mem::size_of::<WideChar>() as isize
+                    )
+                );
+            };
+
+            wstr_val_test(1, "d5e3ac31-fd29-49e3-8759-08d116e98b29");
+            wstr_val_test(2, "d5e3ac31-fd29-49e3-8759-08d116e98b29");
+            // Other binary subtypes are not UUIDs.
+            wstr_val_test(3, BIN_STR_VAL.1);
+
+            let _ = Box::from_raw(buffer as *mut WChar);
+            let _ = Box::from_raw(conn as *mut WChar);
+            let _ = Box::from_raw(env as *mut WChar);
+        }
+    }
+
+    // With simple types disabled, documents are only exposed as strings when the flatten=json
+    // option selects TypeMode::NestedJson. The data is the same extended JSON in both modes.
+    #[test]
+    fn sql_get_nested_document_as_json() {
+        use crate::api::functions::{SQLDescribeColW, SQLGetData};
+        use cstr::input_text_to_string_w;
+        use definitions::{CDataType, SqlDataType};
+
+        for (type_mode, expected_type) in [
+            (TypeMode::Standard, SqlDataType::SQL_UNKNOWN_TYPE),
+            (TypeMode::NestedJson, SqlDataType::SQL_WVARCHAR),
+        ] {
+            let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+                EnvState::ConnectionAllocated,
+            ))));
+            let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+                env as *mut _,
+                ConnectionState::Connected,
+            ))));
+            let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                vec![doc! {"test": {"doc": {"x": 42i32, "y": 42i32}}}],
+                vec![MongoColMetadata::new(
+                    "",
+                    "test".to_string(),
+                    "doc".to_string(),
+                    Schema::Atomic(Atomic::Object(ObjectSchema {
+                        properties: map! {
+                            "x".to_string() => Schema::Atomic(Atomic::Scalar(BsonTypeName::Int)),
+                            "y".to_string() => Schema::Atomic(Atomic::Scalar(BsonTypeName::Int)),
+                        },
+                        required: set! {"x".to_string(), "y".to_string()},
+                        additional_properties: false,
+                    })),
+                    Nullability::SQL_NO_NULLS,
+                    type_mode,
+                    None,
+                )],
+            )));
+
+            let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+            unsafe {
+                let name_buffer = &mut [0 as WideChar; 10];
+                let mut data_type = SqlDataType::SQL_UNKNOWN_TYPE;
+                let mut nullable = 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLDescribeColW(
+                        stmt_handle as *mut _,
+                        1,
+                        name_buffer.as_mut_ptr(),
+                        name_buffer.len() as i16,
+                        &mut 0,
+                        &mut data_type,
+                        &mut 0,
+                        &mut 0,
+                        &mut nullable,
+                    )
+                );
+                assert_eq!(expected_type, data_type);
+
+                assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+                let buffer: *mut std::ffi::c_void = Box::into_raw(Box::new([0u8; 200])) as *mut _;
+                let out_len_or_ind = &mut 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle as *mut _,
+                        1,
+                        CDataType::SQL_C_WCHAR as i16,
+                        buffer,
+                        200,
+                        out_len_or_ind,
+                    )
+                );
+                assert_eq!(
+                    DOC_STR_VAL.1,
+                    input_text_to_string_w(
+                        buffer as *const _,
+                        *out_len_or_ind / std::mem::size_of::<WideChar>() as isize
+                    )
+                );
+
+                let _ = Box::from_raw(buffer as *mut WChar);
+                let _ = Box::from_raw(conn as *mut WChar);
+                let _ = Box::from_raw(env as *mut WChar);
+            }
+        }
+    }
+
+    // Values nested in a document follow the connection's json mode, so that Int64, Date and
+    // Decimal128 values keep their exact type in canonical mode. A top level long is always
+    // returned as a plain number.
+    #[test]
+    fn sql_get_nested_json_in_relaxed_and_canonical_modes() {
+        use crate::api::functions::SQLGetData;
+        use definitions::CDataType;
+
+        for (json_mode, expected_doc) in [
+            (
+                JsonMode::Relaxed,
+                "{\"date\":{\"$date\":\"2014-11-28T12:00:09Z\"},\"dec\":{\"$numberDecimal\":\"1.3\"},\"i64\":42}",
+            ),
+            (
+                JsonMode::Canonical,
+                "{\"date\":{\"$date\":{\"$numberLong\":\"1417176009000\"}},\"dec\":{\"$numberDecimal\":\"1.3\"},\"i64\":{\"$numberLong\":\"42\"}}",
+            ),
+        ] {
+            let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+                EnvState::ConnectionAllocated,
+            ))));
+            let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+                env as *mut _,
+                ConnectionState::Connected,
+            ))));
+            unsafe {
+                *(*conn).as_connection().unwrap().json_mode.write().unwrap() = json_mode;
+            }
+            let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                vec![doc! {"test": {
+                    "doc": {
+                        "date": Bson::DateTime(DateTime::from_chrono(*CHRONO_TIME)),
+                        "dec": Bson::Decimal128(bson::Decimal128::from_str("1.3").unwrap()),
+                        "i64": Bson::Int64(42),
+                    },
+                    "i64": Bson::Int64(42),
+                }}],
+                vec![
+                    MongoColMetadata::new(
+                        "",
+                        "test".to_string(),
+                        "doc".to_string(),
+                        Schema::Atomic(Atomic::Object(ObjectSchema {
+                            properties: map! {
+                                "date".to_string() => Schema::Atomic(Atomic::Scalar(BsonTypeName::Date)),
+                                "dec".to_string() => Schema::Atomic(Atomic::Scalar(BsonTypeName::Decimal)),
+                                "i64".to_string() => Schema::Atomic(Atomic::Scalar(BsonTypeName::Long)),
+                            },
+                            required: set! {"date".to_string(), "dec".to_string(), "i64".to_string()},
+                            additional_properties: false,
+                        })),
+                        Nullability::SQL_NO_NULLS,
+                        TypeMode::Simple,
+                        None,
+                    ),
+                    MongoColMetadata::new(
+                        "",
+                        "test".to_string(),
+                        "i64".to_string(),
+                        Schema::Atomic(Atomic::Scalar(BsonTypeName::Long)),
+                        Nullability::SQL_NO_NULLS,
+                        TypeMode::Simple,
+                        None,
+                    ),
+                ],
+            )));
+
+            let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+            unsafe {
+                assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+                let buffer: *mut std::ffi::c_void = Box::into_raw(Box::new([0u8; 200])) as *mut _;
+                let out_len_or_ind = &mut 0;
+                let mut str_val_test = |col: u16, expected: &str| {
+                    assert_eq!(
+                        SqlReturn::SUCCESS,
+                        SQLGetData(
+                            stmt_handle as *mut _,
+                            col,
+                            CDataType::SQL_C_CHAR as i16,
+                            buffer,
+                            200,
+                            out_len_or_ind,
+                        )
+                    );
+                    assert_eq!(
+                        expected,
+                        std::str::from_utf8(std::slice::from_raw_parts(
+                            buffer as *const u8,
+                            *out_len_or_ind as usize
+                        ))
+                        .unwrap()
+                    );
+                };
+
+                str_val_test(1, expected_doc);
+                str_val_test(2, "42");
+
+                let _ = Box::from_raw(buffer as *mut WChar);
+                let _ = Box::from_raw(conn as *mut WChar);
+                let _ = Box::from_raw(env as *mut WChar);
+            }
+        }
+    }
+
+    // A top level MinKey or MaxKey is returned as its name, and as extended JSON in canonical
+    // mode. Nested in a document, it is always extended JSON.
+    #[test]
+    fn sql_get_min_and_max_key_in_relaxed_and_canonical_modes() {
+        use crate::api::functions::SQLGetData;
+        use definitions::CDataType;
+
+        for (json_mode, expected) in [
+            (
+                JsonMode::Relaxed,
+                ["MinKey", "MaxKey", "{\"max\":{\"$maxKey\":1}}"],
+            ),
+            (
+                JsonMode::Canonical,
+                [
+                    "{\"$minKey\":1}",
+                    "{\"$maxKey\":1}",
+                    "{\"max\":{\"$maxKey\":1}}",
+                ],
+            ),
+        ] {
+            let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+                EnvState::ConnectionAllocated,
+            ))));
+            let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+                env as *mut _,
+                ConnectionState::Connected,
+            ))));
+            unsafe {
+                *(*conn).as_connection().unwrap().json_mode.write().unwrap() = json_mode;
+            }
+            let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+            let column = |name: &str, schema| {
+                MongoColMetadata::new(
+                    "",
+                    "test".to_string(),
+                    name.to_string(),
+                    schema,
+                    Nullability::SQL_NO_NULLS,
+                    TypeMode::Standard,
+                    None,
+                )
+            };
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                vec![doc! {"test": {
+                    "min": Bson::MinKey,
+                    "max": Bson::MaxKey,
+                    "doc": {"max": Bson::MaxKey},
+                }}],
+                vec![
+                    column("min", Schema::Atomic(Atomic::Scalar(BsonTypeName::MinKey))),
+                    column("max", Schema::Atomic(Atomic::Scalar(BsonTypeName::MaxKey))),
+                    column(
+                        "doc",
+                        Schema::Atomic(Atomic::Object(ObjectSchema {
+                            properties: map! {
+                                "max".to_string() => Schema::Atomic(Atomic::Scalar(BsonTypeName::MaxKey)),
+                            },
+                            required: set! {"max".to_string()},
+                            additional_properties: false,
+                        })),
+                    ),
+                ],
+            )));
+
+            let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+            unsafe {
+                assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+                let buffer: *mut std::ffi::c_void = Box::into_raw(Box::new([0u8; 200])) as *mut _;
+                let out_len_or_ind = &mut 0;
+                for (col, expected) in (1..).zip(expected) {
+                    assert_eq!(
+                        SqlReturn::SUCCESS,
+                        SQLGetData(
+                            stmt_handle as *mut _,
+                            col,
+                            CDataType::SQL_C_CHAR as i16,
+                            buffer,
+                            200,
+                            out_len_or_ind,
+                        )
+                    );
+                    assert_eq!(
+                        expected,
+                        std::str::from_utf8(std::slice::from_raw_parts(
+                            buffer as *const u8,
+                            *out_len_or_ind as usize
+                        ))
+                        .unwrap()
+                    );
+                }
+
+                let _ = Box::from_raw(buffer as *mut WChar);
+                let _ = Box::from_raw(conn as *mut WChar);
+                let _ = Box::from_raw(env as *mut WChar);
+            }
+        }
+    }
+
+    // Datetimes are returned in the session time zone set with SET TIMEZONE, and in UTC
+    // otherwise.
+    #[test]
+    fn sql_get_timestamp_in_session_time_zone() {
+        use crate::api::functions::SQLGetData;
+        use chrono::FixedOffset;
+        use definitions::CDataType;
+
+        for (time_zone, expected_timestamp, expected_date) in [
+            (
+                None,
+                (2014, 11, 28, 12, 0, 9),
+                Date {
+                    year: 2014,
+                    month: 11,
+                    day: 28,
+                },
+            ),
+            (
+                FixedOffset::east_opt(5 * 3600 + 30 * 60),
+                (2014, 11, 28, 17, 30, 9),
+                Date {
+                    year: 2014,
+                    month: 11,
+                    day: 28,
+                },
+            ),
+            (
+                FixedOffset::west_opt(13 * 3600),
+                (2014, 11, 27, 23, 0, 9),
+                Date {
+                    year: 2014,
+                    month: 11,
+                    day: 27,
+                },
+            ),
+        ] {
+            let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+                EnvState::ConnectionAllocated,
+            ))));
+            let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+                env as *mut _,
+                ConnectionState::Connected,
+            ))));
+            unsafe {
+                *(*conn).as_connection().unwrap().time_zone.write().unwrap() = time_zone;
+            }
+            let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                vec![doc! {"test": {
+                    "datetime": Bson::DateTime(DateTime::from_chrono(*CHRONO_TIME)),
+                    "date": Bson::DateTime(DateTime::from_chrono(*CHRONO_TIME)),
+                }}],
+                ["datetime", "date"]
+                    .into_iter()
+                    .map(|name| {
+                        MongoColMetadata::new(
+                            "",
+                            "test".to_string(),
+                            name.to_string(),
+                            Schema::Atomic(Atomic::Scalar(BsonTypeName::Date)),
+                            Nullability::SQL_NO_NULLS,
+                            TypeMode::Standard,
+                            None,
+                        )
+                    })
+                    .collect(),
+            )));
+
+            let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+            unsafe {
+                assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+                let mut timestamp = Timestamp::default();
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle as *mut _,
+                        1,
+                        CDataType::SQL_C_TYPE_TIMESTAMP as i16,
+                        &mut timestamp as *mut Timestamp as *mut _,
+                        0,
+                        &mut 0,
+                    )
+                );
+                assert_eq!(
+                    expected_timestamp,
+                    (
+                        timestamp.year,
+                        timestamp.month,
+                        timestamp.day,
+                        timestamp.hour,
+                        timestamp.minute,
+                        timestamp.second
+                    )
+                );
+                let mut date = Date::default();
+                // The time part is truncated, so the date is returned with a warning.
+                assert_eq!(
+                    SqlReturn::SUCCESS_WITH_INFO,
+                    SQLGetData(
+                        stmt_handle as *mut _,
+                        2,
+                        CDataType::SQL_C_TYPE_DATE as i16,
+                        &mut date as *mut Date as *mut _,
+                        0,
+                        &mut 0,
+                    )
+                );
+                assert_eq!(expected_date, date);
+
+                let _ = Box::from_raw(conn as *mut WChar);
+                let _ = Box::from_raw(env as *mut WChar);
+            }
+        }
+    }
+
+    // A datetime read as a character type shows the same local time as when it is read as a
+    // timestamp, with the offset of the session time zone.
+    #[test]
+    fn sql_get_datetime_as_timestamp_and_char_in_session_time_zone() {
+        use crate::api::functions::SQLGetData;
+        use chrono::FixedOffset;
+        use definitions::CDataType;
+
+        for (time_zone, expected_timestamp, expected_char) in [
+            (
+                None,
+                (2014, 11, 28, 12, 0, 9),
+                r#"{"$date":"2014-11-28T12:00:09Z"}"#,
+            ),
+            (
+                FixedOffset::east_opt(5 * 3600 + 30 * 60),
+                (2014, 11, 28, 17, 30, 9),
+                r#"{"$date":"2014-11-28T17:30:09+05:30"}"#,
+            ),
+            (
+                FixedOffset::west_opt(13 * 3600),
+                (2014, 11, 27, 23, 0, 9),
+                r#"{"$date":"2014-11-27T23:00:09-13:00"}"#,
+            ),
+        ] {
+            let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+                EnvState::ConnectionAllocated,
+            ))));
+            let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+                env as *mut _,
+                ConnectionState::Connected,
+            ))));
+            unsafe {
+                *(*conn).as_connection().unwrap().time_zone.write().unwrap() = time_zone;
+            }
+            let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                vec![doc! {"test": {
+                    "as_char": Bson::DateTime(DateTime::from_chrono(*CHRONO_TIME)),
+                    "as_timestamp": Bson::DateTime(DateTime::from_chrono(*CHRONO_TIME)),
+                    "as_wchar": Bson::DateTime(DateTime::from_chrono(*CHRONO_TIME)),
+                }}],
+                ["as_char", "as_timestamp", "as_wchar"]
+                    .into_iter()
+                    .map(|name| {
+                        MongoColMetadata::new(
+                            "",
+                            "test".to_string(),
+                            name.to_string(),
+                            Schema::Atomic(Atomic::Scalar(BsonTypeName::Date)),
+                            Nullability::SQL_NO_NULLS,
+                            TypeMode::Standard,
+                            None,
+                        )
+                    })
+                    .collect(),
+            )));
+
+            let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+            unsafe {
+                assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+                let mut char_buffer = [0u8; 64];
+                let mut char_len = 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle as *mut _,
+                        1,
+                        CDataType::SQL_C_CHAR as i16,
+                        char_buffer.as_mut_ptr() as *mut _,
+                        char_buffer.len() as isize,
+                        &mut char_len,
+                    )
+                );
+                assert_eq!(
+                    expected_char,
+                    std::str::from_utf8(&char_buffer[..char_len as usize]).unwrap()
+                );
+                let mut timestamp = Timestamp::default();
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle as *mut _,
+                        2,
+                        CDataType::SQL_C_TYPE_TIMESTAMP as i16,
+                        &mut timestamp as *mut Timestamp as *mut _,
+                        0,
+                        &mut 0,
+                    )
+                );
+                assert_eq!(
+                    expected_timestamp,
+                    (
+                        timestamp.year,
+                        timestamp.month,
+                        timestamp.day,
+                        timestamp.hour,
+                        timestamp.minute,
+                        timestamp.second
+                    )
+                );
+                let mut wchar_buffer = [0 as WideChar; 64];
+                let mut wchar_len = 0;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLGetData(
+                        stmt_handle as *mut _,
+                        3,
+                        CDataType::SQL_C_WCHAR as i16,
+                        wchar_buffer.as_mut_ptr() as *mut _,
+                        std::mem::size_of_val(&wchar_buffer) as isize,
+                        &mut wchar_len,
+                    )
+                );
+                assert_eq!(
+                    expected_char,
+                    cstr::from_widechar_ref_lossy(
+                        &wchar_buffer[..wchar_len as usize / std::mem::size_of::<WideChar>()]
+                    )
+                );
+
+                let _ = Box::from_raw(conn as *mut WChar);
+                let _ = Box::from_raw(env as *mut WChar);
+            }
+        }
+    }
+
+    // Reading a string with astral-plane characters through a buffer that cannot hold a whole
+    // surrogate pair at the end of a chunk must not split the pair across SQLGetData calls.
+    #[test]
+    fn sql_get_wstring_data_does_not_split_surrogate_pairs() {
+        use crate::api::functions::SQLGetData;
+        use definitions::CDataType;
+        use std::mem::size_of;
+
+        let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+            EnvState::ConnectionAllocated,
+        ))));
+        let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+            env as *mut _,
+            ConnectionState::Connected,
+        ))));
+        let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+        *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+            vec![doc! {"test": {"emoji": "a😀😀b"}}],
+            vec![MongoColMetadata::new(
+                "",
+                "test".to_string(),
+                "emoji".to_string(),
+                Schema::Atomic(Atomic::Scalar(BsonTypeName::String)),
+                Nullability::SQL_NO_NULLS,
+                TypeMode::Standard,
+                None,
+            )],
+        )));
+
+        let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+            let buffer = &mut [0 as WideChar; 3];
+            let out_len_or_ind = &mut 0;
+            let mut chunks = vec![];
+            loop {
+                let ret = SQLGetData(
+                    stmt_handle as *mut _,
+                    1,
+                    CDataType::SQL_C_WCHAR as i16,
+                    buffer.as_mut_ptr() as *mut _,
+                    (buffer.len() * size_of::<WideChar>()) as isize,
+                    out_len_or_ind,
+                );
+                if ret == SqlReturn::NO_DATA {
+                    break;
+                }
+                let len = buffer.iter().position(|&c| c == 0).unwrap();
+                chunks.push((
+                    ret,
+                    *out_len_or_ind / size_of::<WideChar>() as isize,
+                    cstr::from_widechar_ref_lossy(&buffer[..len]),
+                ));
+            }
+            assert_eq!(
+                vec![
+                    (SqlReturn::SUCCESS_WITH_INFO, 6, "a".to_string()),
+                    (SqlReturn::SUCCESS_WITH_INFO, 5, "😀".to_string()),
+                    (SqlReturn::SUCCESS_WITH_INFO, 3, "😀".to_string()),
+                    (SqlReturn::SUCCESS, 1, "b".to_string()),
+                ],
+                chunks
+            );
+
+            let _ = Box::from_raw(conn as *mut WChar);
+            let _ = Box::from_raw(env as *mut WChar);
+        }
+    }
+
+    // Characters outside the BMP take two UTF-16 code units, so the length of a string holding them
+    // is reported in bytes of UTF-16, not in characters or bytes of UTF-8.
+    #[test]
+    fn sql_get_wstring_data_with_astral_characters() {
+        use crate::api::functions::SQLGetData;
+        use definitions::CDataType;
+        use std::mem::size_of;
+
+        let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+            EnvState::ConnectionAllocated,
+        ))));
+        let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+            env as *mut _,
+            ConnectionState::Connected,
+        ))));
+        let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+        // An emoji and a CJK Unified Ideographs Extension B character, each 4 bytes of UTF-8.
+        *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+            vec![doc! {"test": {"astral": "😀a𠀀"}}],
+            vec![MongoColMetadata::new(
+                "",
+                "test".to_string(),
+                "astral".to_string(),
+                Schema::Atomic(Atomic::Scalar(BsonTypeName::String)),
+                Nullability::SQL_NO_NULLS,
+                TypeMode::Standard,
+                None,
+            )],
+        )));
+
+        let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _,));
+            let get_data = |buffer: &mut [WideChar], out_len_or_ind: &mut isize| {
+                SQLGetData(
+                    stmt_handle as *mut _,
+                    1,
+                    CDataType::SQL_C_WCHAR as i16,
+                    buffer.as_mut_ptr() as *mut _,
+                    std::mem::size_of_val(buffer) as isize,
+                    out_len_or_ind,
+                )
+            };
+            let out_len_or_ind = &mut 0;
+
+            // A buffer with room for the null terminator only returns nothing of the string, which
+            // is then returned whole by the next call.
+            let buffer = &mut [1 as WideChar; 1];
+            assert_eq!(
+                SqlReturn::SUCCESS_WITH_INFO,
+                get_data(buffer, out_len_or_ind)
+            );
+            assert_eq!(0, buffer[0]);
+            assert_eq!(5 * size_of::<WideChar>() as isize, *out_len_or_ind);
+
+            let buffer = &mut [0 as WideChar; 16];
+            assert_eq!(SqlReturn::SUCCESS, get_data(buffer, out_len_or_ind));
+            assert_eq!(5 * size_of::<WideChar>() as isize, *out_len_or_ind);
+            assert_eq!("😀a𠀀", cstr::from_widechar_ref_lossy(&buffer[..5]));
+            assert_eq!(0, buffer[5]);
+            assert_eq!(SqlReturn::NO_DATA, get_data(buffer, out_len_or_ind));
+
+            let _ = Box::from_raw(conn as *mut WChar);
+            let _ = Box::from_raw(env as *mut WChar);
+        }
+    }
+
+    // A BSON Timestamp is retrieved as the datetime of its seconds component, both as a
+    // timestamp struct and as an ISO-8601 string. The increment is discarded.
+    #[test]
+    fn sql_get_bson_timestamp_data() {
+        use crate::api::functions::SQLGetData;
+        use definitions::CDataType;
+
+        let mq = MongoQuery::new(
+            vec![doc! {"test": {"ts": Bson::Timestamp(bson::Timestamp {
+                time: 1_417_176_009,
+                increment: 7,
+            })}}],
+            vec![MongoColMetadata::new(
+                "",
+                "test".to_string(),
+                "ts".to_string(),
+                Schema::Atomic(Atomic::Scalar(BsonTypeName::Timestamp)),
+                Nullability::SQL_NO_NULLS,
+                TypeMode::Standard,
+                None,
+            )],
+        );
+        let env = Box::into_raw(Box::new(MongoHandle::Env(Env::with_state(
+            EnvState::ConnectionAllocated,
+        ))));
+        let conn = Box::into_raw(Box::new(MongoHandle::Connection(Connection::with_state(
+            env as *mut _,
+            ConnectionState::Connected,
+        ))));
+        let stmt = Statement::with_state(conn as *mut _, StatementState::Allocated);
+        *stmt.mongo_statement.write().unwrap() = Some(Box::new(mq));
+
+        let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+        unsafe {
+            assert_eq!(SqlReturn::SUCCESS, SQLFetch(stmt_handle as *mut _));
+            let buffer: *mut std::ffi::c_void = Box::into_raw(Box::new([0u8; 40])) as *mut _;
+            let out_len_or_ind = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt_handle as *mut _,
+                    1,
+                    CDataType::SQL_C_TYPE_TIMESTAMP as i16,
+                    buffer,
+                    40,
+                    out_len_or_ind,
+                )
+            );
+            assert_eq!(16, *out_len_or_ind);
+            assert_eq!(
+                Timestamp {
+                    year: 2014,
+                    month: 11,
+                    day: 28,
+                    hour: 12,
+                    minute: 0,
+                    second: 9,
+                    fraction: 0,
+                },
+                *(buffer as *const Timestamp)
+            );
+
+            // reset the column so that it can be retrieved again
+            (*stmt_handle)
+                .as_statement()
+                .unwrap()
+                .var_data_cache
+                .write()
+                .unwrap()
+                .as_mut()
+                .unwrap()
+                .remove(&1);
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetData(
+                    stmt_handle as *mut _,
+                    1,
+                    CDataType::SQL_C_CHAR as i16,
+                    buffer,
+                    40,
+                    out_len_or_ind,
+                )
+            );
+            assert_eq!(20, *out_len_or_ind);
+            assert_eq!(
+                "2014-11-28T12:00:09Z",
+                std::str::from_utf8(std::slice::from_raw_parts(buffer as *const u8, 20)).unwrap()
+            );
+
+            let _ = Box::from_raw(buffer as *mut [u8; 40]);
+            let _ = Box::from_raw(conn as *mut WChar);
+            let _ = Box::from_raw(env as *mut WChar);
+        }
+    }
 }
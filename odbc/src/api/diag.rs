@@ -1,10 +1,14 @@
 use crate::{api::data::i16_len, errors::ODBCError};
 use cstr::WideChar;
 use definitions::{
-    AttrOdbcVersion, DiagType, Integer, Pointer, SmallInt, SqlReturn, SQL_ROW_NUMBER_UNKNOWN,
+    AttrOdbcVersion, DiagType, DynamicDiagnosticIdentifier, Integer, Pointer, SmallInt, SqlReturn,
+    SQL_COLUMN_NUMBER_UNKNOWN, SQL_ROW_NUMBER_UNKNOWN,
 };
 use std::ptr::copy_nonoverlapping;
 
+const ISO_9075_ORIGIN: &str = "ISO 9075";
+const ODBC_3_ORIGIN: &str = "ODBC 3.0";
+
 ///
 /// set_sql_statew writes the given sql state to the [`output_ptr`].
 ///
@@ -56,29 +60,89 @@ pub unsafe fn get_diag_recw(
 }
 
 ///
-/// get_stmt_diag_field copies a part of the given ODBC error's diagnostic information
-/// into the provided pointer.
+/// get_stmt_diag_field copies a statement-specific diagnostic field into the provided pointers.
+/// The header fields ignore [`record_number`], while the record fields return NO_DATA if
+/// [`record_number`] does not refer to an existing diagnostic record.
 ///
 /// # Safety
-/// This writes to a raw C-pointer
+/// This writes to multiple raw C-pointers
 ///
-pub unsafe fn get_stmt_diag_field(diag_identifier: DiagType, diag_info_ptr: Pointer) -> SqlReturn {
-    // NOTE: at the moment, this could be merged with get_diag_field. However, as part of SQL-1152,
-    // some functionality will be specific to the statement handle, and thus warrants a separate function
+pub unsafe fn get_stmt_diag_field(
+    errors: &[ODBCError],
+    diag_identifier: DiagType,
+    diag_info_ptr: Pointer,
+    record_number: i16,
+    buffer_length: i16,
+    string_length_ptr: *mut i16,
+) -> SqlReturn {
     match diag_identifier {
         // default to 0, mirroring the behavior in SQLRowCount
-        DiagType::SQL_DIAG_ROW_COUNT => {
+        DiagType::SQL_DIAG_ROW_COUNT | DiagType::SQL_DIAG_CURSOR_ROW_COUNT => {
             i16_len::set_output_fixed_data(&0isize, diag_info_ptr, &mut 0)
         }
-        DiagType::SQL_DIAG_ROW_NUMBER => {
-            // default to unknown, as at the moment statement handels don't update their row number attribute
-            i16_len::set_output_fixed_data(&SQL_ROW_NUMBER_UNKNOWN, diag_info_ptr, &mut 0)
+        // the driver does not parse the statement, so the kind of statement is unknown
+        DiagType::SQL_DIAG_DYNAMIC_FUNCTION => {
+            if buffer_length < 0 {
+                return SqlReturn::ERROR;
+            }
+            i16_len::set_output_wstring_as_bytes(
+                "",
+                diag_info_ptr,
+                buffer_length as usize,
+                string_length_ptr,
+            )
+        }
+        DiagType::SQL_DIAG_DYNAMIC_FUNCTION_CODE => i16_len::set_output_fixed_data(
+            &(DynamicDiagnosticIdentifier::SQL_DIAG_UNKNOWN_STATEMENT as i32),
+            diag_info_ptr,
+            &mut 0,
+        ),
+        DiagType::SQL_DIAG_ROW_NUMBER | DiagType::SQL_DIAG_COLUMN_NUMBER => {
+            if record_number < 1 {
+                return SqlReturn::ERROR;
+            }
+            if errors.len() < record_number as usize {
+                return SqlReturn::NO_DATA;
+            }
+            // default to unknown, as at the moment statement handles don't track which row or
+            // column caused a diagnostic
+            if diag_identifier == DiagType::SQL_DIAG_ROW_NUMBER {
+                i16_len::set_output_fixed_data(&SQL_ROW_NUMBER_UNKNOWN, diag_info_ptr, &mut 0)
+            } else {
+                i16_len::set_output_fixed_data(&SQL_COLUMN_NUMBER_UNKNOWN, diag_info_ptr, &mut 0)
+            }
         }
         // this should not be reachable if match branches here match those in SQLGetDiagFieldW
         _ => SqlReturn::ERROR,
     }
 }
 
+///
+/// class_origin returns the document defining the class portion of the given SQLSTATE.
+///
+fn class_origin(sql_state: &str) -> &'static str {
+    if sql_state.starts_with("IM") {
+        ODBC_3_ORIGIN
+    } else {
+        ISO_9075_ORIGIN
+    }
+}
+
+///
+/// subclass_origin returns the document defining the subclass portion of the given SQLSTATE.
+/// Subclasses in the HY and IM classes, and subclasses beginning with 'S', are defined by ODBC.
+///
+fn subclass_origin(sql_state: &str) -> &'static str {
+    if sql_state.starts_with("HY")
+        || sql_state.starts_with("IM")
+        || sql_state.get(2..).is_some_and(|s| s.starts_with('S'))
+    {
+        ODBC_3_ORIGIN
+    } else {
+        ISO_9075_ORIGIN
+    }
+}
+
 ///
 /// get_diag_field copies a part of the given ODBC error's diagnostic information
 /// into the provided pointers.
@@ -95,53 +159,57 @@ pub unsafe fn get_diag_fieldw(
     buffer_length: i16,
     string_length_ptr: *mut i16,
 ) -> SqlReturn {
-    // NOTE: number is dependent on the list of errors, but is a header, hence separating it from the match
-    if diag_identifier == DiagType::SQL_DIAG_NUMBER {
-        *diag_info_ptr.cast::<usize>() = errors.len();
-        SqlReturn::SUCCESS
-    } else {
-        if buffer_length < 0 || record_number < 1 {
-            return SqlReturn::ERROR;
+    // NOTE: number and return code are header fields, hence separating them from the match
+    match diag_identifier {
+        DiagType::SQL_DIAG_NUMBER => {
+            return i16_len::set_output_fixed_data(
+                &i32::try_from(errors.len()).unwrap_or(i32::MAX),
+                diag_info_ptr,
+                std::ptr::null_mut::<i16>(),
+            )
         }
-        let rec_number = (record_number - 1) as usize;
-        match errors.get(rec_number) {
-            Some(error) => {
-                match diag_identifier {
-                    // NOTE: return code is handled by driver manager; just return success
-                    DiagType::SQL_DIAG_RETURNCODE => SqlReturn::SUCCESS,
-                    DiagType::SQL_DIAG_SQLSTATE => {
-                        let sql_state = match odbc_ver {
-                            AttrOdbcVersion::SQL_OV_ODBC2 => error.get_sql_state().odbc_2_state,
-                            AttrOdbcVersion::SQL_OV_ODBC3 | AttrOdbcVersion::SQL_OV_ODBC3_80 => {
-                                error.get_sql_state().odbc_3_state
-                            }
-                        };
-                        i16_len::set_output_wstring_as_bytes(
-                            sql_state,
-                            diag_info_ptr,
-                            buffer_length as usize,
-                            string_length_ptr,
-                        )
-                    }
-                    DiagType::SQL_DIAG_NATIVE => i16_len::set_output_fixed_data(
-                        &error.get_native_err_code(),
-                        diag_info_ptr,
-                        std::ptr::null_mut::<i16>(),
-                    ),
-                    DiagType::SQL_DIAG_MESSAGE_TEXT => {
-                        let message = format!("{error}");
-                        i16_len::set_output_wstring_as_bytes(
-                            &message,
-                            diag_info_ptr,
-                            buffer_length as usize,
-                            string_length_ptr,
-                        )
-                    }
-                    // this should not be reachable if match branches here mirror those in SQLGetDiagFieldW
-                    _ => SqlReturn::ERROR,
+        // NOTE: return code is handled by driver manager; just return success
+        DiagType::SQL_DIAG_RETURNCODE => return SqlReturn::SUCCESS,
+        _ => {}
+    }
+    if buffer_length < 0 || record_number < 1 {
+        return SqlReturn::ERROR;
+    }
+    let rec_number = (record_number - 1) as usize;
+    match errors.get(rec_number) {
+        Some(error) => {
+            let sql_state = match odbc_ver {
+                AttrOdbcVersion::SQL_OV_ODBC2 => error.get_sql_state().odbc_2_state,
+                AttrOdbcVersion::SQL_OV_ODBC3 | AttrOdbcVersion::SQL_OV_ODBC3_80 => {
+                    error.get_sql_state().odbc_3_state
+                }
+            };
+            let set_string = |value: &str| {
+                i16_len::set_output_wstring_as_bytes(
+                    value,
+                    diag_info_ptr,
+                    buffer_length as usize,
+                    string_length_ptr,
+                )
+            };
+            match diag_identifier {
+                DiagType::SQL_DIAG_SQLSTATE => set_string(sql_state),
+                DiagType::SQL_DIAG_NATIVE => i16_len::set_output_fixed_data(
+                    &error.get_native_err_code(),
+                    diag_info_ptr,
+                    std::ptr::null_mut::<i16>(),
+                ),
+                DiagType::SQL_DIAG_MESSAGE_TEXT => set_string(&format!("{error}")),
+                DiagType::SQL_DIAG_CLASS_ORIGIN => set_string(class_origin(sql_state)),
+                DiagType::SQL_DIAG_SUBCLASS_ORIGIN => set_string(subclass_origin(sql_state)),
+                // connections are not named, and the server name is not tracked per diagnostic
+                DiagType::SQL_DIAG_CONNECTION_NAME | DiagType::SQL_DIAG_SERVER_NAME => {
+                    set_string("")
                 }
+                // this should not be reachable if match branches here mirror those in SQLGetDiagFieldW
+                _ => SqlReturn::ERROR,
             }
-            _ => SqlReturn::NO_DATA,
         }
+        _ => SqlReturn::NO_DATA,
     }
 }
@@ -0,0 +1,74 @@
+#![allow(clippy::ptr_as_ptr)]
+
+mod unit {
+    use crate::{
+        handles::definitions::{
+            Connection, ConnectionState, MongoHandle, Statement, StatementState,
+        },
+        SQLDisconnect,
+    };
+    use definitions::SqlReturn;
+
+    // allocate_statement adds a statement in the given state to the connection, the way
+    // SQLAllocHandle does.
+    unsafe fn allocate_statement(conn_handle: *mut MongoHandle, state: StatementState) {
+        let stmt = Box::into_raw(Box::new(MongoHandle::Statement(Statement::with_state(
+            conn_handle,
+            state,
+        ))));
+        let conn = (*conn_handle).as_connection().unwrap();
+        conn.statements.write().unwrap().insert(stmt);
+        *conn.state.write().unwrap() = ConnectionState::StatementAllocated;
+    }
+
+    // Disconnecting frees the statements still allocated on the connection and leaves the
+    // handle ready for a new connection.
+    #[test]
+    fn disconnect_frees_statements_and_resets_connection() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+                std::ptr::null_mut(),
+                ConnectionState::Connected,
+            ));
+            allocate_statement(conn_handle, StatementState::Allocated);
+            allocate_statement(conn_handle, StatementState::Allocated);
+            assert_eq!(SqlReturn::SUCCESS, SQLDisconnect(conn_handle as *mut _));
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert!(conn.statements.read().unwrap().is_empty());
+            assert!(conn.mongo_connection.read().unwrap().is_none());
+            assert_eq!(ConnectionState::Allocated, *conn.state.read().unwrap());
+        }
+    }
+
+    // A statement that is still executing must be cancelled before disconnecting.
+    #[test]
+    fn disconnect_with_executing_statement_is_function_sequence_error() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+                std::ptr::null_mut(),
+                ConnectionState::Connected,
+            ));
+            allocate_statement(conn_handle, StatementState::Allocated);
+            allocate_statement(conn_handle, StatementState::SynchronousQueryExecuting);
+            assert_eq!(SqlReturn::ERROR, SQLDisconnect(conn_handle as *mut _));
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert_eq!(
+                "HY010",
+                conn.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+            assert_eq!(2, conn.statements.read().unwrap().len());
+            assert_eq!(
+                ConnectionState::StatementAllocated,
+                *conn.state.read().unwrap()
+            );
+
+            // once the statement is done executing, the connection can be disconnected
+            for stmt in conn.statements.read().unwrap().iter() {
+                *(**stmt).as_statement().unwrap().state.write().unwrap() =
+                    StatementState::Allocated;
+            }
+            assert_eq!(SqlReturn::SUCCESS, SQLDisconnect(conn_handle as *mut _));
+            assert!(conn.statements.read().unwrap().is_empty());
+        }
+    }
+}
@@ -0,0 +1,159 @@
+#![allow(clippy::ptr_as_ptr)]
+
+mod unit {
+    use crate::{
+        handles::definitions::{
+            Connection, ConnectionState, Env, EnvState, MongoHandle, Statement, StatementState,
+        },
+        SQLEndTran,
+    };
+    use definitions::{AttrAutoCommit, CompletionType, HandleType, SqlReturn};
+
+    const COMPLETION_TYPES: [CompletionType; 2] =
+        [CompletionType::SQL_COMMIT, CompletionType::SQL_ROLLBACK];
+
+    fn connection_with_autocommit(env: *mut MongoHandle, autocommit: AttrAutoCommit) -> Connection {
+        let conn = Connection::with_state(env, ConnectionState::Connected);
+        conn.attributes.write().unwrap().autocommit = autocommit;
+        conn
+    }
+
+    unsafe fn first_sql_state(handle: *mut MongoHandle) -> String {
+        (*handle).as_connection().map_or_else(
+            || {
+                (*handle).as_env().unwrap().errors.read().unwrap()[0]
+                    .get_sql_state()
+                    .odbc_3_state
+                    .to_string()
+            },
+            |conn| {
+                conn.errors.read().unwrap()[0]
+                    .get_sql_state()
+                    .odbc_3_state
+                    .to_string()
+            },
+        )
+    }
+
+    // Committing or rolling back is a no-op while autocommit is on.
+    #[test]
+    fn end_tran_autocommit_on() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(connection_with_autocommit(
+                std::ptr::null_mut(),
+                AttrAutoCommit::SQL_AUTOCOMMIT_ON,
+            ));
+            for completion_type in COMPLETION_TYPES {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLEndTran(
+                        HandleType::SQL_HANDLE_DBC,
+                        conn_handle as *mut _,
+                        completion_type as i16
+                    )
+                );
+            }
+        }
+    }
+
+    // Transactions are not supported, so an application that turned autocommit off gets HYC00.
+    #[test]
+    fn end_tran_autocommit_off() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(connection_with_autocommit(
+                std::ptr::null_mut(),
+                AttrAutoCommit::SQL_AUTOCOMMIT_OFF,
+            ));
+            for completion_type in COMPLETION_TYPES {
+                assert_eq!(
+                    SqlReturn::ERROR,
+                    SQLEndTran(
+                        HandleType::SQL_HANDLE_DBC,
+                        conn_handle as *mut _,
+                        completion_type as i16
+                    )
+                );
+                assert_eq!("HYC00", first_sql_state(conn_handle));
+            }
+        }
+    }
+
+    // Ending a transaction on an environment applies to all of its connections.
+    #[test]
+    fn end_tran_on_env() {
+        unsafe {
+            let env_handle: *mut _ =
+                &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+            let on_handle: *mut _ = &mut MongoHandle::Connection(connection_with_autocommit(
+                env_handle,
+                AttrAutoCommit::SQL_AUTOCOMMIT_ON,
+            ));
+            let off_handle: *mut _ = &mut MongoHandle::Connection(connection_with_autocommit(
+                env_handle,
+                AttrAutoCommit::SQL_AUTOCOMMIT_OFF,
+            ));
+            let env = (*env_handle).as_env().unwrap();
+            env.connections.write().unwrap().insert(on_handle);
+            for completion_type in COMPLETION_TYPES {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLEndTran(
+                        HandleType::SQL_HANDLE_ENV,
+                        env_handle as *mut _,
+                        completion_type as i16
+                    )
+                );
+            }
+
+            env.connections.write().unwrap().insert(off_handle);
+            for completion_type in COMPLETION_TYPES {
+                assert_eq!(
+                    SqlReturn::ERROR,
+                    SQLEndTran(
+                        HandleType::SQL_HANDLE_ENV,
+                        env_handle as *mut _,
+                        completion_type as i16
+                    )
+                );
+                assert_eq!("HYC00", first_sql_state(env_handle));
+            }
+        }
+    }
+
+    #[test]
+    fn end_tran_invalid_completion_type() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(connection_with_autocommit(
+                std::ptr::null_mut(),
+                AttrAutoCommit::SQL_AUTOCOMMIT_ON,
+            ));
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLEndTran(HandleType::SQL_HANDLE_DBC, conn_handle as *mut _, 2)
+            );
+            assert_eq!("HY012", first_sql_state(conn_handle));
+        }
+    }
+
+    #[test]
+    fn end_tran_invalid_handle_type() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(connection_with_autocommit(
+                std::ptr::null_mut(),
+                AttrAutoCommit::SQL_AUTOCOMMIT_ON,
+            ));
+            let stmt_handle: *mut _ = &mut MongoHandle::Statement(Statement::with_state(
+                conn_handle,
+                StatementState::Allocated,
+            ));
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLEndTran(
+                    HandleType::SQL_HANDLE_STMT,
+                    stmt_handle as *mut _,
+                    CompletionType::SQL_COMMIT as i16
+                )
+            );
+        }
+    }
+}
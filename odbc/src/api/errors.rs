@@ -1,11 +1,14 @@
 use constants::{
-    OdbcState, CONNECTION_NOT_OPEN, FETCH_TYPE_OUT_OF_RANGE, FRACTIONAL_TRUNCATION, GENERAL_ERROR,
+    OdbcState, ACCURACY_OPTION_OUT_OF_RANGE, COLUMN_TYPE_OUT_OF_RANGE, CONNECTION_NOT_OPEN,
+    FETCH_TYPE_OUT_OF_RANGE, FRACTIONAL_TRUNCATION, FUNCTION_SEQUENCE_ERROR, GENERAL_ERROR,
     GENERAL_WARNING, INDICATOR_VARIABLE_REQUIRED, INTEGRAL_TRUNCATION,
-    INVALID_ATTRIBUTE_OR_OPTION_IDENTIFIER, INVALID_ATTR_VALUE, INVALID_CHARACTER_VALUE,
-    INVALID_COLUMN_NUMBER, INVALID_CURSOR_STATE, INVALID_DATETIME_FORMAT, INVALID_DESCRIPTOR_INDEX,
+    INVALID_ATTRIBUTE_OR_OPTION_IDENTIFIER, INVALID_ATTR_VALUE, INVALID_CATALOG_NAME,
+    INVALID_CHARACTER_VALUE, INVALID_COLUMN_NUMBER, INVALID_CONNECTION_STRING_ATTRIBUTE,
+    INVALID_CURSOR_STATE, INVALID_DATETIME_FORMAT, INVALID_DESCRIPTOR_INDEX,
     INVALID_DRIVER_COMPLETION, INVALID_FIELD_DESCRIPTOR, INVALID_INFO_TYPE_VALUE, INVALID_SQL_TYPE,
-    NOT_IMPLEMENTED, NO_DSN_OR_DRIVER, NO_RESULTSET, OPTION_CHANGED, PROGRAM_TYPE_OUT_OF_RANGE,
-    RESTRICTED_DATATYPE, RIGHT_TRUNCATED, VENDOR_IDENTIFIER,
+    INVALID_TRANSACTION_OPERATION_CODE, INVALID_USE_OF_NULL_POINTER, NOT_IMPLEMENTED,
+    NO_DSN_OR_DRIVER, NO_RESULTSET, OPTION_CHANGED, PROGRAM_TYPE_OUT_OF_RANGE, RESTRICTED_DATATYPE,
+    RIGHT_TRUNCATED, UNIQUENESS_OPTION_OUT_OF_RANGE, VENDOR_IDENTIFIER,
 };
 use thiserror::Error;
 
@@ -16,6 +19,23 @@ pub enum ODBCError {
     General(&'static str),
     #[error("[{}][API] {0}", VENDOR_IDENTIFIER)]
     GeneralWarning(String),
+    #[error(
+        "[{}][API] Query took {0}ms, exceeding the slow query threshold of {1}ms: {2}",
+        VENDOR_IDENTIFIER
+    )]
+    SlowQuery(u128, u32, String),
+    #[error(
+        "[{}][API] LIMIT without ORDER BY returns an arbitrary, nondeterministic set of rows",
+        VENDOR_IDENTIFIER
+    )]
+    UnorderedLimit,
+    #[error("[{}][API] The server returned a warning: {0}", VENDOR_IDENTIFIER)]
+    ServerWarning(String),
+    #[error(
+        "[{}][API] The session variable {0} is not supported, the SET statement was ignored",
+        VENDOR_IDENTIFIER
+    )]
+    UnsupportedSessionVariable(String),
     #[error("[{}][API] Caught panic: {0}", VENDOR_IDENTIFIER)]
     Panic(String),
     #[error("[{}][API] The feature {0} is not implemented", VENDOR_IDENTIFIER)]
@@ -71,10 +91,33 @@ pub enum ODBCError {
     InvalidHandleType(&'static str),
     #[error("[{}][API] Invalid value for attribute {0}", VENDOR_IDENTIFIER)]
     InvalidAttrValue(&'static str),
+    #[error(
+        "[{}][API] {0} cannot be a null pointer when SQL_ATTR_METADATA_ID is SQL_TRUE",
+        VENDOR_IDENTIFIER
+    )]
+    NullIdentifierArgument(&'static str),
+    #[error(
+        "[{}][API] Invalid connection string attributes: {0}",
+        VENDOR_IDENTIFIER
+    )]
+    InvalidConnectionStringAttribute(String),
     #[error("[{}][API] Invalid attribute identifier {0}", VENDOR_IDENTIFIER)]
     InvalidAttrIdentifier(i32),
+    #[error(
+        "[{}][API] Invalid catalog name \"{0}\", the database does not exist or is not accessible",
+        VENDOR_IDENTIFIER
+    )]
+    InvalidCatalogName(String),
     #[error("[{}][API] Fetch type out of range {0}", VENDOR_IDENTIFIER)]
     FetchTypeOutOfRange(i16),
+    #[error("[{}][API] Invalid transaction operation code {0}", VENDOR_IDENTIFIER)]
+    InvalidTransactionOperationCode(i16),
+    #[error("[{}][API] Column type out of range {0}", VENDOR_IDENTIFIER)]
+    ColumnTypeOutOfRange(i16),
+    #[error("[{}][API] Uniqueness option type out of range {0}", VENDOR_IDENTIFIER)]
+    UniquenessOptionOutOfRange(i16),
+    #[error("[{}][API] Accuracy option type out of range {0}", VENDOR_IDENTIFIER)]
+    AccuracyOptionOutOfRange(i16),
     #[error("[{}][API] Invalid target type {0}", VENDOR_IDENTIFIER)]
     InvalidTargetType(i16),
     #[error("[{}][API] Invalid driver completion type {0}", VENDOR_IDENTIFIER)]
@@ -135,6 +178,11 @@ pub enum ODBCError {
     RestrictedDataType(&'static str, &'static str),
     #[error("[{}][API] No resultset for statement", VENDOR_IDENTIFIER)]
     NoResultSet,
+    #[error(
+        "[{}][API] A statement on the connection is still executing",
+        VENDOR_IDENTIFIER
+    )]
+    StatementExecuting,
     #[error("Connection not open")]
     ConnectionNotOpen,
     #[error("[{}][Core] {0}", VENDOR_IDENTIFIER)]
@@ -155,11 +203,22 @@ impl ODBCError {
             | ODBCError::UnsupportedInfoTypeRetrieval(_)
             | ODBCError::UnsupportedFieldDescriptor(_) => NOT_IMPLEMENTED,
             ODBCError::General(_) | ODBCError::Panic(_) => GENERAL_ERROR,
-            ODBCError::GeneralWarning(_) => GENERAL_WARNING,
+            ODBCError::GeneralWarning(_)
+            | ODBCError::SlowQuery(_, _, _)
+            | ODBCError::UnorderedLimit
+            | ODBCError::ServerWarning(_)
+            | ODBCError::UnsupportedSessionVariable(_) => GENERAL_WARNING,
             ODBCError::Core(c) => c.get_sql_state(),
             ODBCError::InvalidAttrValue(_) => INVALID_ATTR_VALUE,
+            ODBCError::NullIdentifierArgument(_) => INVALID_USE_OF_NULL_POINTER,
+            ODBCError::InvalidConnectionStringAttribute(_) => INVALID_CONNECTION_STRING_ATTRIBUTE,
             ODBCError::InvalidAttrIdentifier(_) => INVALID_ATTRIBUTE_OR_OPTION_IDENTIFIER,
+            ODBCError::InvalidCatalogName(_) => INVALID_CATALOG_NAME,
             ODBCError::FetchTypeOutOfRange(_) => FETCH_TYPE_OUT_OF_RANGE,
+            ODBCError::InvalidTransactionOperationCode(_) => INVALID_TRANSACTION_OPERATION_CODE,
+            ODBCError::ColumnTypeOutOfRange(_) => COLUMN_TYPE_OUT_OF_RANGE,
+            ODBCError::UniquenessOptionOutOfRange(_) => UNIQUENESS_OPTION_OUT_OF_RANGE,
+            ODBCError::AccuracyOptionOutOfRange(_) => ACCURACY_OPTION_OUT_OF_RANGE,
             ODBCError::InvalidCursorState => INVALID_CURSOR_STATE,
             ODBCError::InvalidHandleType(_) => NOT_IMPLEMENTED,
             ODBCError::InvalidTargetType(_) => PROGRAM_TYPE_OUT_OF_RANGE,
@@ -181,6 +240,7 @@ impl ODBCError {
             ODBCError::InvalidCharacterValue(_) => INVALID_CHARACTER_VALUE,
             ODBCError::IndicatorVariableRequiredButNotSupplied => INDICATOR_VARIABLE_REQUIRED,
             ODBCError::NoResultSet => NO_RESULTSET,
+            ODBCError::StatementExecuting => FUNCTION_SEQUENCE_ERROR,
             ODBCError::UnknownInfoType(_) => INVALID_INFO_TYPE_VALUE,
             ODBCError::ConnectionNotOpen => CONNECTION_NOT_OPEN,
         }
@@ -194,11 +254,22 @@ impl ODBCError {
             ODBCError::Unimplemented(_)
             | ODBCError::General(_)
             | ODBCError::GeneralWarning(_)
+            | ODBCError::SlowQuery(_, _, _)
+            | ODBCError::UnorderedLimit
+            | ODBCError::ServerWarning(_)
+            | ODBCError::UnsupportedSessionVariable(_)
             | ODBCError::Panic(_)
             | ODBCError::UnimplementedDataType(_)
             | ODBCError::InvalidAttrValue(_)
+            | ODBCError::NullIdentifierArgument(_)
+            | ODBCError::InvalidConnectionStringAttribute(_)
             | ODBCError::InvalidAttrIdentifier(_)
+            | ODBCError::InvalidCatalogName(_)
             | ODBCError::FetchTypeOutOfRange(_)
+            | ODBCError::InvalidTransactionOperationCode(_)
+            | ODBCError::ColumnTypeOutOfRange(_)
+            | ODBCError::UniquenessOptionOutOfRange(_)
+            | ODBCError::AccuracyOptionOutOfRange(_)
             | ODBCError::InvalidCursorState
             | ODBCError::InvalidHandleType(_)
             | ODBCError::InvalidTargetType(_)
@@ -225,6 +296,7 @@ impl ODBCError {
             | ODBCError::InvalidCharacterValue(_)
             | ODBCError::InvalidDriverCompletion(_)
             | ODBCError::NoResultSet
+            | ODBCError::StatementExecuting
             | ODBCError::UnsupportedInfoTypeRetrieval(_)
             | ODBCError::ConnectionNotOpen
             | ODBCError::UnknownInfoType(_) => 0,
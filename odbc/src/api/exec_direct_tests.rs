@@ -0,0 +1,140 @@
+#![allow(
+    clippy::ptr_as_ptr,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+mod unit {
+    use crate::{
+        handles::definitions::{
+            Connection, ConnectionState, Env, EnvState, MongoHandle, Statement, StatementState,
+        },
+        SQLExecDirectW, SQLFetch,
+    };
+    use definitions::{Nullability, SqlReturn};
+    use mongo_odbc_core::{
+        json_schema::{
+            simplified::{Atomic, Schema},
+            BsonTypeName,
+        },
+        MongoColMetadata, MongoConnection, ResultCache, ResultKey, ServerCapabilities, TypeMode,
+    };
+    use mongodb::{
+        bson::{doc, Bson},
+        Client,
+    };
+    use std::time::Duration;
+
+    const QUERY: &str = "SELECT a FROM foo";
+
+    // mongo_connection returns a connection to a server that does not exist, whose result cache
+    // answers QUERY on the db database, so that it can be executed without a server.
+    fn mongo_connection() -> MongoConnection {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let client = runtime.block_on(async {
+            Client::with_uri_str("mongodb://localhost:1/?serverSelectionTimeoutMS=1")
+                .await
+                .unwrap()
+        });
+        let cache = ResultCache::new(Duration::from_secs(60));
+        // Statements run with the Simple type mode of a new connection.
+        cache.insert(
+            ResultKey {
+                db: "db".to_string(),
+                query: QUERY.to_string(),
+                type_mode: TypeMode::Simple,
+                max_string_length: None,
+                row_as_json: false,
+            },
+            vec![MongoColMetadata::new(
+                "db",
+                "foo".to_string(),
+                "a".to_string(),
+                Schema::Atomic(Atomic::Scalar(BsonTypeName::Int)),
+                Nullability::SQL_NULLABLE,
+                TypeMode::Standard,
+                None,
+            )],
+            vec![doc! {"foo": {"a": 1}}],
+        );
+        MongoConnection {
+            client,
+            operation_timeout: None,
+            uuid_repr: None,
+            retry_reads: true,
+            result_cache: Some(cache),
+            collation: None,
+            force_aggregate: false,
+            capabilities: ServerCapabilities::default(),
+            batch_size: None,
+            #[cfg(feature = "test_hooks")]
+            get_more_count: Default::default(),
+            server_warnings: Default::default(),
+            collection_collations: Default::default(),
+            runtime,
+        }
+    }
+
+    #[test]
+    fn server_warnings_are_reported_as_diagnostics() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+        let conn_handle = Connection::with_state(env, ConnectionState::Connected);
+        conn_handle.attributes.write().unwrap().current_catalog = Some("db".to_string());
+        *conn_handle.mongo_connection.write().unwrap() = Some(mongo_connection());
+        let conn = &mut MongoHandle::Connection(conn_handle);
+        let stmt: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+        let query = cstr::to_widechar_vec(QUERY);
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLExecDirectW(stmt as *mut _, query.as_ptr(), query.len() as i32)
+            );
+
+            // The monitor of the client collects the warnings of the command replies, by the
+            // statement id in their comment.
+            let push_warning = |statement_id: Bson, warning: &str| {
+                let connection = (*conn).as_connection().unwrap().mongo_connection.read();
+                connection
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .server_warnings
+                    .lock()
+                    .unwrap()
+                    .push(Some(statement_id), warning.to_string());
+            };
+            let statement_id = (*stmt)
+                .as_statement()
+                .unwrap()
+                .statement_id
+                .read()
+                .unwrap()
+                .clone();
+            // A warning left from a previous execution of the statement is not reported.
+            push_warning(statement_id.clone(), "$bar is deprecated");
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLExecDirectW(stmt as *mut _, query.as_ptr(), query.len() as i32)
+            );
+
+            // A warning returned to the commands of the statement is reported once, and one
+            // returned to the commands of another statement is not.
+            push_warning(statement_id, "$foo is deprecated");
+            push_warning(Bson::String("other".to_string()), "$baz is deprecated");
+            assert_eq!(SqlReturn::SUCCESS_WITH_INFO, SQLFetch(stmt as *mut _));
+            let errors = (*stmt).as_statement().unwrap().errors.read().unwrap();
+            assert_eq!(1, errors.len());
+            assert_eq!("01000", errors[0].get_sql_state().odbc_3_state);
+            assert_eq!(
+                "[MongoDB][API] The server returned a warning: $foo is deprecated",
+                errors[0].to_string()
+            );
+            drop(errors);
+            assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt as *mut _));
+        }
+    }
+}
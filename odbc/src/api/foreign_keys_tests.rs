@@ -0,0 +1,107 @@
+#![allow(
+    clippy::ptr_as_ptr,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+use crate::{
+    handles::definitions::{
+        Connection, ConnectionState, Env, EnvState, MongoHandle, Statement, StatementState,
+    },
+    SQLDescribeColW, SQLFetch, SQLForeignKeysW, SQLNumResultCols,
+};
+use cstr::WideChar;
+use definitions::{Nullability, SqlDataType, SqlReturn};
+use std::ptr::null;
+
+const FOREIGN_KEYS_COLUMNS: [(&str, Nullability); 14] = [
+    ("PKTABLE_CAT", Nullability::SQL_NULLABLE),
+    ("PKTABLE_SCHEM", Nullability::SQL_NULLABLE),
+    ("PKTABLE_NAME", Nullability::SQL_NO_NULLS),
+    ("PKCOLUMN_NAME", Nullability::SQL_NO_NULLS),
+    ("FKTABLE_CAT", Nullability::SQL_NULLABLE),
+    ("FKTABLE_SCHEM", Nullability::SQL_NULLABLE),
+    ("FKTABLE_NAME", Nullability::SQL_NO_NULLS),
+    ("FKCOLUMN_NAME", Nullability::SQL_NO_NULLS),
+    ("KEY_SEQ", Nullability::SQL_NO_NULLS),
+    ("UPDATE_RULE", Nullability::SQL_NULLABLE),
+    ("DELETE_RULE", Nullability::SQL_NULLABLE),
+    ("FK_NAME", Nullability::SQL_NULLABLE),
+    ("PK_NAME", Nullability::SQL_NULLABLE),
+    ("DEFERRABILITY", Nullability::SQL_NULLABLE),
+];
+
+mod unit {
+    use super::*;
+
+    // SQLForeignKeys always returns an empty result set since MongoDB has no foreign keys, but
+    // the result set must still be fully described so that clients can continue their metadata
+    // discovery.
+    #[test]
+    fn foreign_keys_empty_result_set() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Connected));
+        let stmt_handle: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLForeignKeysW(
+                    stmt_handle as *mut _,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                )
+            );
+
+            let column_count = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLNumResultCols(stmt_handle as *mut _, column_count)
+            );
+            assert_eq!(14, *column_count);
+
+            for (col_index, (expected_name, expected_nullability)) in
+                FOREIGN_KEYS_COLUMNS.iter().enumerate()
+            {
+                let name_buffer = &mut [0 as WideChar; 20];
+                let out_name_length = &mut 0;
+                let mut data_type = SqlDataType::SQL_UNKNOWN_TYPE;
+                let col_size = &mut 0usize;
+                let decimal_digits = &mut 0i16;
+                let mut nullable = Nullability::SQL_NULLABLE_UNKNOWN as i16;
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    SQLDescribeColW(
+                        stmt_handle as *mut _,
+                        col_index as u16 + 1,
+                        name_buffer.as_mut_ptr(),
+                        name_buffer.len() as i16,
+                        out_name_length,
+                        &mut data_type,
+                        col_size,
+                        decimal_digits,
+                        &mut nullable,
+                    )
+                );
+                assert_eq!(
+                    *expected_name,
+                    cstr::from_widechar_ref_lossy(&name_buffer[..*out_name_length as usize])
+                );
+                assert_eq!(*expected_nullability as i16, nullable);
+            }
+
+            assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt_handle as *mut _));
+        }
+    }
+}
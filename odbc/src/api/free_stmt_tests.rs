@@ -22,7 +22,7 @@ mod unit {
         mock_query::MongoQuery,
         Error, MongoColMetadata, MongoCollections, MongoStatement, TypeMode,
     };
-    use std::ptr::null_mut;
+    use std::{collections::HashMap, ptr::null_mut};
 
     #[test]
     fn test_free_stmt_invalid() {
@@ -32,7 +32,48 @@ mod unit {
         let stmt: *mut _ =
             &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
 
-        unsafe { assert_eq!(SqlReturn::ERROR, SQLFreeStmt(stmt as *mut _, 1)) }
+        unsafe {
+            assert_eq!(SqlReturn::ERROR, SQLFreeStmt(stmt as *mut _, 99));
+            let s = (*stmt).as_statement().unwrap();
+            assert_eq!(
+                "HY092",
+                s.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+        }
+    }
+
+    #[test]
+    fn test_free_stmt_close_keeps_bindings() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Allocated));
+        let stmt: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+
+        unsafe {
+            let s = (*stmt).as_statement().unwrap();
+            let bindings = Some(map! {
+                1 => BoundColInfo {
+                    target_type: 1,
+                    target_buffer: null_mut(),
+                    buffer_length: 1,
+                    length_or_indicator: null_mut(),
+                }
+            });
+            *s.bound_cols.write().unwrap() = bindings.clone();
+            *s.var_data_cache.write().unwrap() = Some(HashMap::new());
+            *s.mongo_statement.write().unwrap() = Some(Box::new(MongoCollections::empty()));
+
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLFreeStmt(stmt as *mut _, FreeStmtOption::SQL_CLOSE as i16)
+            );
+
+            // Closing the cursor discards the data cached for SQLGetData, but the bound columns
+            // remain in place for the next execution of the statement.
+            assert!(s.var_data_cache.read().unwrap().is_none());
+            assert_eq!(bindings, *s.bound_cols.read().unwrap());
+        }
     }
 
     #[test]
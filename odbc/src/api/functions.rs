@@ -10,29 +10,45 @@ use crate::{
     has_odbc_3_behavior, trace_odbc,
 };
 use bson::{doc, Bson};
+use chrono::FixedOffset;
 use constants::*;
 
-use cstr::{input_text_to_string_w, Charset, WideChar};
+use cstr::{input_text_arg_to_string_w, Charset, WideChar};
 
 use definitions::{
-    AllocType, AsyncEnable, AttrConnectionPooling, AttrCpMatch, AttrOdbcVersion, BindType,
-    CDataType, Concurrency, ConnectionAttribute, CursorScrollable, CursorSensitivity, CursorType,
-    Desc, DiagType, DriverConnectOption, EnvironmentAttribute, FetchOrientation, FreeStmtOption,
-    HDbc, HDesc, HEnv, HStmt, HWnd, Handle, HandleType, Integer, Len, NoScan, Pointer, RetCode,
-    RetrieveData, RowStatus, SmallInt, SqlBool, SqlDataType, SqlReturn, StatementAttribute, ULen,
-    USmallInt, UseBookmarks, SQL_NTS,
+    AllocType, AsyncEnable, AttrAutoCommit, AttrConnectionPooling, AttrCpMatch, AttrOdbcVersion,
+    BindType, CDataType, CompletionType, Concurrency, ConnectionAttribute, CursorScrollable,
+    CursorSensitivity, CursorType, Desc, DiagType, DriverConnectOption, EnvironmentAttribute,
+    FetchOrientation, FreeStmtOption, HDbc, HDesc, HEnv, HStmt, HWnd, Handle, HandleType,
+    IdentifierType, IndexUniqueness, Integer, Len, NoScan, Pointer, RetCode, RetrieveData,
+    RowStatus, SmallInt, SqlBool, SqlDataType, SqlReturn, StatementAttribute, StatisticsAccuracy,
+    ULen, USmallInt, UseBookmarks, SQL_NAMED, SQL_NTS, SQL_NTS_ISIZE, SQL_UNNAMED,
 };
 use function_name::named;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use logger::Logger;
 use mongo_odbc_core::{
-    odbc_uri::ODBCUri, Error, MongoColMetadata, MongoCollections, MongoConnection, MongoDatabases,
-    MongoFields, MongoForeignKeys, MongoPrimaryKeys, MongoQuery, MongoStatement, MongoTableTypes,
-    MongoTypesInfo, TypeMode,
+    change_stream_target, expand_grouping_sets, explain_analyze_target, has_unordered_limit,
+    inline_ctes,
+    odbc_uri::{ODBCUri, LOGLEVEL, LOGPATH, SLOW_QUERY_MS},
+    translate_datetime_literals, translate_distinct_from, translate_objectid_timestamp,
+    CommandOptions, Error, MongoChangeStream, MongoColMetadata, MongoCollections, MongoConnection,
+    MongoDatabases, MongoExplain, MongoFields, MongoForeignKeys, MongoPrimaryKeys,
+    MongoProcedureColumns, MongoProcedures, MongoQuery, MongoSet, MongoSpecialColumns,
+    MongoStatement, MongoStatistics, MongoTableTypes, MongoTypesInfo, ResultCache,
+    ServerCapabilities, SessionVariable, TypeMode,
 };
+use mongodb::options::{Collation, Hint};
 use num_traits::FromPrimitive;
 use std::ptr::null_mut;
-use std::{collections::HashMap, mem::size_of, panic, sync::mpsc};
+use std::{
+    collections::HashMap,
+    mem::size_of,
+    panic,
+    path::PathBuf,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
 
 const NULL_HANDLE_ERROR: &str = "handle cannot be null";
 const HANDLE_MUST_BE_ENV_ERROR: &str = "handle must be env";
@@ -349,18 +365,7 @@ pub unsafe extern "C" fn SQLBindCol(
             let mongo_handle = MongoHandleRef::from(hstmt);
             let stmt = must_be_valid!((*mongo_handle).as_statement());
 
-            // Currently, we only support column binding with no offsets.
-            // Make sure that column-wise binding is being used.
-            if stmt.attributes.read().unwrap().row_bind_type != BindType::SQL_BIND_BY_COLUMN as ULen
-            {
-                let mongo_handle = MongoHandleRef::from(hstmt);
-                add_diag_info!(
-                    mongo_handle,
-                    ODBCError::Unimplemented("`row-wise column binding`")
-                );
-                return SqlReturn::ERROR;
-            }
-
+            // Currently, we only support binding with no offsets.
             // Make sure that offsets are not being used in column binding.
             if !stmt
                 .attributes
@@ -477,13 +482,134 @@ pub unsafe extern "C" fn SQLBindParameter(
 #[no_mangle]
 pub unsafe extern "C" fn SQLBrowseConnectW(
     connection_handle: HDbc,
-    _in_connection_string: *const WideChar,
-    _string_length: SmallInt,
-    _out_connection_string: *mut WideChar,
-    _buffer_length: SmallInt,
-    _out_buffer_length: *mut SmallInt,
+    in_connection_string: *const WideChar,
+    string_length: SmallInt,
+    out_connection_string: *mut WideChar,
+    buffer_length: SmallInt,
+    out_buffer_length: *mut SmallInt,
 ) -> SqlReturn {
-    unimpl!(connection_handle);
+    panic_safe_exec_clear_diagnostics!(
+        debug,
+        || {
+            let conn_handle = MongoHandleRef::from(connection_handle);
+            let conn = must_be_valid!((*conn_handle).as_connection());
+            // Each call only carries the attributes requested by the previous one, so they are
+            // accumulated until the connection can be established. Any error ends the browsing.
+            let in_string = input_text_arg_to_string_w(in_connection_string, string_length.into());
+            let odbc_uri_string = match conn.browse_connection_string.write().unwrap().take() {
+                Some(previous) => format!("{previous};{in_string}"),
+                None => in_string,
+            };
+            let odbc_uri = odbc_unwrap!(ODBCUri::new(odbc_uri_string.clone()), conn_handle);
+            if let Some(browse_result) = odbc_uri.browse_result() {
+                *conn.browse_connection_string.write().unwrap() = Some(odbc_uri_string);
+                // A truncated browse result is reported as a diagnostic, more data is still
+                // needed either way.
+                let _ = set_out_connection_string(
+                    conn_handle,
+                    &browse_result,
+                    out_connection_string,
+                    buffer_length,
+                    out_buffer_length,
+                    function_name!(),
+                );
+                return SqlReturn::NEED_DATA;
+            }
+            connect(
+                conn_handle,
+                &odbc_uri_string,
+                out_connection_string,
+                buffer_length,
+                out_buffer_length,
+                function_name!(),
+            )
+        },
+        connection_handle
+    );
+}
+
+// connect establishes the connection described by the connection string, and returns the
+// completed connection string to the application.
+unsafe fn connect(
+    conn_handle: &mut MongoHandle,
+    odbc_uri_string: &str,
+    out_connection_string: *mut WideChar,
+    buffer_length: SmallInt,
+    string_length: *mut SmallInt,
+    function_name: &str,
+) -> SqlReturn {
+    let conn = must_be_valid!(conn_handle.as_connection());
+    let (mongo_connection, warnings) = match sql_driver_connect(conn, odbc_uri_string) {
+        Ok(connected) => connected,
+        Err(error) => {
+            add_diag_with_function!(conn_handle, error, function_name);
+            return SqlReturn::ERROR;
+        }
+    };
+    *conn.mongo_connection.write().unwrap() = Some(mongo_connection);
+    *conn.state.write().unwrap() = ConnectionState::Connected;
+    // Connection warnings (e.g. a compressor downgrade) do not prevent connecting, but
+    // the application is notified through SUCCESS_WITH_INFO.
+    let success = if warnings.is_empty() {
+        SqlReturn::SUCCESS
+    } else {
+        SqlReturn::SUCCESS_WITH_INFO
+    };
+    for warning in warnings {
+        add_diag_with_function!(conn_handle, warning, function_name);
+    }
+    match set_out_connection_string(
+        conn_handle,
+        odbc_uri_string,
+        out_connection_string,
+        buffer_length,
+        string_length,
+        function_name,
+    ) {
+        SqlReturn::SUCCESS => success,
+        sql_return => sql_return,
+    }
+}
+
+// set_out_connection_string writes the connection string to the output buffer, reporting its
+// truncation.
+unsafe fn set_out_connection_string(
+    conn_handle: &mut MongoHandle,
+    connection_string: &str,
+    out_connection_string: *mut WideChar,
+    buffer_length: SmallInt,
+    string_length: *mut SmallInt,
+    function_name: &str,
+) -> SqlReturn {
+    // We know the mysql ODBC driver returns SUCCESS if the out_connection_string is NULL.
+    // We can also just return SUCCESS if the buffer_len is 0. Likely, users are not
+    // expecting to get back a warning when they pass an empty buffer to this, especially
+    // given that we only currently support DriverConnectOption::SQL_DRIVER_NO_PROMPT.
+    if buffer_length <= 0 || out_connection_string.is_null() {
+        ptr_safe_write(
+            string_length,
+            connection_string
+                .len()
+                .try_into()
+                .expect("connection_string.len exceeds i16"),
+        );
+        return SqlReturn::SUCCESS;
+    }
+    let buffer_len = usize::try_from(buffer_length).unwrap();
+    let sql_return = i16_len::set_output_wstring(
+        connection_string,
+        out_connection_string,
+        buffer_len,
+        string_length,
+    );
+    if sql_return == SqlReturn::SUCCESS_WITH_INFO {
+        add_diag_with_function!(
+            conn_handle,
+            ODBCError::OutStringTruncated(buffer_len),
+            function_name
+        );
+    }
+    sql_return
 }
 
 ///
@@ -645,10 +771,18 @@ pub unsafe extern "C" fn SQLColAttributeW(
                         *numeric_attribute_ptr = SqlBool::SQL_FALSE as Len;
                         SqlReturn::SUCCESS
                     }
-                    Desc::SQL_DESC_UNNAMED | Desc::SQL_DESC_UPDATABLE => {
+                    // The data source is read-only, so no column is updatable.
+                    Desc::SQL_DESC_UPDATABLE => {
                         *numeric_attribute_ptr = 0 as Len;
                         SqlReturn::SUCCESS
                     }
+                    Desc::SQL_DESC_UNNAMED => numeric_col_attr(&|x: &MongoColMetadata| {
+                        if x.col_name.is_empty() {
+                            SQL_UNNAMED as Len
+                        } else {
+                            SQL_NAMED as Len
+                        }
+                    }),
                     Desc::SQL_DESC_COUNT => {
                         *numeric_attribute_ptr = isize::try_from(
                             mongo_stmt
@@ -703,8 +837,8 @@ pub unsafe extern "C" fn SQLColAttributeW(
                     }),
                     Desc::SQL_DESC_OCTET_LENGTH | Desc::SQL_COLUMN_LENGTH => {
                         numeric_col_attr(&|x: &MongoColMetadata| {
-                            isize::try_from(x.transfer_octet_length.unwrap_or(0))
-                                .expect("transfer_octet_length exceeds isize on this platform")
+                            isize::try_from(x.octet_length())
+                                .expect("octet length exceeds isize on this platform")
                         })
                     }
                     Desc::SQL_DESC_LENGTH => numeric_col_attr(&|x: &MongoColMetadata| {
@@ -744,11 +878,20 @@ pub unsafe extern "C" fn SQLColAttributeW(
                     Desc::SQL_DESC_TYPE_NAME => {
                         string_col_attr(&|x: &MongoColMetadata| x.type_name.as_ref())
                     }
-                    Desc::SQL_DESC_TYPE | Desc::SQL_DESC_CONCISE_TYPE => {
+                    Desc::SQL_DESC_CONCISE_TYPE => numeric_col_attr(&|x: &MongoColMetadata| {
+                        handle_sql_type(odbc_version, x.sql_type) as Len
+                    }),
+                    // The verbose type, which is SQL_DATETIME for all the datetime types.
+                    Desc::SQL_DESC_TYPE => {
+                        numeric_col_attr(&|x: &MongoColMetadata| x.non_concise_type as Len)
+                    }
+                    Desc::SQL_DESC_DATETIME_INTERVAL_CODE => {
                         numeric_col_attr(&|x: &MongoColMetadata| {
-                            handle_sql_type(odbc_version, x.sql_type) as Len
+                            x.sql_code.map_or(0, |code| code as Len)
                         })
                     }
+                    // There are no interval types, and datetimes have no leading precision.
+                    Desc::SQL_DESC_DATETIME_INTERVAL_PRECISION => numeric_col_attr(&|_| 0),
                     Desc::SQL_DESC_UNSIGNED => {
                         numeric_col_attr(&|x: &MongoColMetadata| x.is_unsigned.into())
                     }
@@ -756,14 +899,12 @@ pub unsafe extern "C" fn SQLColAttributeW(
                         numeric_col_attr(&|_| AllocType::SQL_DESC_ALLOC_AUTO as Len)
                     }
                     desc @ (Desc::SQL_DESC_OCTET_LENGTH_PTR
-                    | Desc::SQL_DESC_DATETIME_INTERVAL_CODE
                     | Desc::SQL_DESC_INDICATOR_PTR
                     | Desc::SQL_DESC_DATA_PTR
                     | Desc::SQL_DESC_ARRAY_SIZE
                     | Desc::SQL_DESC_ARRAY_STATUS_PTR
                     | Desc::SQL_DESC_BIND_OFFSET_PTR
                     | Desc::SQL_DESC_BIND_TYPE
-                    | Desc::SQL_DESC_DATETIME_INTERVAL_PRECISION
                     | Desc::SQL_DESC_MAXIMUM_SCALE
                     | Desc::SQL_DESC_MINIMUM_SCALE
                     | Desc::SQL_DESC_PARAMETER_TYPE
@@ -817,6 +958,40 @@ pub unsafe extern "C" fn SQLColumnPrivilegesW(
     unimpl!(statement_handle);
 }
 
+// catalog_function_arg reads a catalog or pattern value argument of a catalog function. When
+// SQL_ATTR_METADATA_ID is SQL_TRUE the argument is an identifier instead: it cannot be null, the
+// trailing spaces and enclosing quotes are removed, and the search pattern characters of pattern
+// value arguments are escaped so that they match literally. Identifiers are never case folded
+// since MongoDB names are case sensitive.
+unsafe fn catalog_function_arg(
+    text: *const WideChar,
+    len: SmallInt,
+    metadata_id: SqlBool,
+    is_pattern: bool,
+    arg_name: &'static str,
+) -> Result<Option<String>> {
+    if text.is_null() {
+        return match metadata_id {
+            SqlBool::SQL_TRUE => Err(ODBCError::NullIdentifierArgument(arg_name)),
+            SqlBool::SQL_FALSE => Ok(None),
+        };
+    }
+    let arg = input_text_arg_to_string_w(text, len.into());
+    if metadata_id == SqlBool::SQL_FALSE {
+        return Ok(Some(arg));
+    }
+    let arg = arg.trim_end();
+    let identifier = match arg.strip_prefix('`').and_then(|a| a.strip_suffix('`')) {
+        Some(quoted) => quoted.replace("``", "`"),
+        None => arg.to_string(),
+    };
+    Ok(Some(if is_pattern {
+        identifier.replace('%', r"\%").replace('_', r"\_")
+    } else {
+        identifier
+    }))
+}
+
 ///
 /// [`SQLColumnsW`]: https://learn.microsoft.com/en-us/sql/odbc/reference/syntax/SQLColumns-function
 ///
@@ -844,25 +1019,39 @@ pub unsafe extern "C" fn SQLColumnsW(
             let mongo_handle = MongoHandleRef::from(statement_handle);
             let odbc_3_data_types = has_odbc_3_behavior!(mongo_handle);
             let stmt = must_be_valid!((*mongo_handle).as_statement());
-            let catalog_string = input_text_to_string_w(catalog_name, catalog_name_length.into());
-            let catalog = if catalog_name.is_null() || catalog_string.is_empty() {
-                None
-            } else {
-                Some(catalog_string.as_str())
-            };
+            let metadata_id = stmt.attributes.read().unwrap().metadata_id;
+            let catalog = odbc_unwrap!(
+                catalog_function_arg(
+                    catalog_name,
+                    catalog_name_length,
+                    metadata_id,
+                    false,
+                    "CatalogName"
+                ),
+                mongo_handle
+            )
+            .filter(|catalog| !catalog.is_empty());
             // ignore schema
-            let table_string = input_text_to_string_w(table_name, table_name_length.into());
-            let table = if table_name.is_null() {
-                None
-            } else {
-                Some(table_string.as_str())
-            };
-            let column_name_string = input_text_to_string_w(column_name, column_name_length.into());
-            let column = if column_name.is_null() {
-                None
-            } else {
-                Some(column_name_string.as_str())
-            };
+            let table = odbc_unwrap!(
+                catalog_function_arg(
+                    table_name,
+                    table_name_length,
+                    metadata_id,
+                    true,
+                    "TableName"
+                ),
+                mongo_handle
+            );
+            let column = odbc_unwrap!(
+                catalog_function_arg(
+                    column_name,
+                    column_name_length,
+                    metadata_id,
+                    true,
+                    "ColumnName"
+                ),
+                mongo_handle
+            );
             let connection = must_be_valid!((*stmt.connection).as_connection());
             let type_mode = *connection.type_mode.read().unwrap();
             let max_string_length = *connection.max_string_length.read().unwrap();
@@ -881,9 +1070,9 @@ pub unsafe extern "C" fn SQLColumnsW(
                         .try_into()
                         .unwrap_or(i32::MAX),
                 ),
-                catalog,
-                table,
-                column,
+                catalog.as_deref(),
+                table.as_deref(),
+                column.as_deref(),
                 type_mode,
                 max_string_length,
                 odbc_3_data_types,
@@ -1058,7 +1247,8 @@ pub unsafe extern "C" fn SQLDescribeParam(
 ///
 /// [`SQLDisconnect`]: https://learn.microsoft.com/en-us/sql/odbc/reference/syntax/SQLDisconnect-function
 ///
-/// This function is used to disconnect from the data source. It will also free any statements that have not been freed.
+/// This function is used to disconnect from the data source. It will also free any statements that have not been freed,
+/// unless one of them is still executing.
 ///
 /// # Safety
 /// Because this is a C-interface, this is necessarily unsafe
@@ -1072,7 +1262,19 @@ pub unsafe extern "C" fn SQLDisconnect(connection_handle: HDbc) -> SqlReturn {
             let conn_handle = MongoHandleRef::from(connection_handle);
             let conn = must_be_valid!((*conn_handle).as_connection());
 
-            // Close any open cursors on statements and drop all statements
+            // A statement executing on another thread must be cancelled before disconnecting
+            let executing = conn.statements.read().unwrap().iter().any(|stmt| {
+                (**stmt).as_statement().is_some_and(|stmt| {
+                    *stmt.state.read().unwrap() == StatementState::SynchronousQueryExecuting
+                })
+            });
+            if executing {
+                add_diag_info!(conn_handle, ODBCError::StatementExecuting);
+                return SqlReturn::ERROR;
+            }
+
+            // Close any open cursors on statements and drop all statements. Dropping a cursor
+            // which is not exhausted schedules a killCursors, which runs on shutdown below.
             if let Ok(mut stmts) = conn.statements.write() {
                 stmts.iter().for_each(|stmt| {
                     if let Some(stmt) = (*stmt).as_ref() {
@@ -1092,41 +1294,178 @@ pub unsafe extern "C" fn SQLDisconnect(connection_handle: HDbc) -> SqlReturn {
             if let Some(conn) = conn.mongo_connection.write().unwrap().take() {
                 let _ = conn.shutdown();
             }
+            // the connection handle can be connected again
+            *conn.state.write().unwrap() = ConnectionState::Allocated;
             SqlReturn::SUCCESS
         },
         connection_handle
     );
 }
 
-fn sql_driver_connect(conn: &Connection, odbc_uri_string: &str) -> Result<MongoConnection> {
+// parse_collation returns the collation of the collation attribute, a collation document in JSON
+// such as {"locale": "en", "strength": 1}. Braces delimit connection string values, so the
+// braces of the document may have been removed already.
+fn parse_collation(value: &str) -> Result<Collation> {
+    let json = if value.trim_start().starts_with('{') {
+        value.to_string()
+    } else {
+        format!("{{{value}}}")
+    };
+    serde_json::from_str::<serde_json::Value>(&json)
+        .map_err(|e| e.to_string())
+        .and_then(|json| Bson::try_from(json).map_err(|e| e.to_string()))
+        .and_then(|collation| bson::from_bson(collation).map_err(|e| e.to_string()))
+        .map_err(|e| {
+            Error::InvalidUriFormat(format!(
+                "collation must be a JSON collation document such as {{\"locale\": \"en\", \"strength\": 1}}, found '{json}': {e}"
+            ))
+            .into()
+        })
+}
+
+fn sql_driver_connect(
+    conn: &Connection,
+    odbc_uri_string: &str,
+) -> Result<(MongoConnection, Vec<ODBCError>)> {
     let mut odbc_uri = ODBCUri::new(odbc_uri_string.to_string())?;
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .unwrap();
-    let client_options = runtime.block_on(async { odbc_uri.try_into_client_options().await })?;
+    let mut client_options =
+        runtime.block_on(async { odbc_uri.try_into_client_options().await })?;
+    let mut warnings: Vec<ODBCError> = std::mem::take(&mut client_options.warnings)
+        .into_iter()
+        .map(ODBCError::GeneralWarning)
+        .collect();
+    if !client_options.ignored_problems.is_empty() {
+        warnings.push(ODBCError::InvalidConnectionStringAttribute(
+            client_options.ignored_problems.join("; "),
+        ));
+    }
     odbc_uri
         .remove(&["driver", "dsn"])
         .ok_or(ODBCError::MissingDriverOrDSNProperty)?;
 
-    if let Some(log_level) = odbc_uri.remove(&["loglevel"]) {
+    if let Some(log_level) = odbc_uri.remove(&[LOGLEVEL]) {
         // The connection log level takes precedence over the driver log level
         // Update the logger configuration. This will affect all logging from the application.
         Logger::set_log_level(log_level);
     }
 
+    if let Some(log_path) = odbc_uri.remove(&[LOGPATH]) {
+        // The log path can only be set once per process, a different path is reported and ignored
+        if let Err(warning) = Logger::set_log_dir(PathBuf::from(log_path)) {
+            warnings.push(ODBCError::GeneralWarning(warning));
+        }
+    }
+
     if let Some(simple) = odbc_uri.remove(&["simple_types_only"]) {
         if simple.eq("0") {
             *conn.type_mode.write().unwrap() = TypeMode::Standard;
         }
     }
 
+    // flatten=json exposes arrays and documents as extended JSON strings even when simple types
+    // are disabled. Simple types already render them as strings, so it only changes Standard.
+    if let Some(flatten) = odbc_uri.remove(&["flatten"]) {
+        match flatten.to_lowercase().as_str() {
+            "off" => {}
+            "json" => {
+                let mut type_mode = conn.type_mode.write().unwrap();
+                if *type_mode == TypeMode::Standard {
+                    *type_mode = TypeMode::NestedJson;
+                }
+            }
+            _ => {
+                return Err(Error::InvalidUriFormat(format!(
+                    "flatten must be 'off' or 'json', found '{flatten}'"
+                ))
+                .into())
+            }
+        }
+    }
+
     if let Some(enable_max_string_length) = odbc_uri.remove(&["enable_max_string_length"]) {
         if enable_max_string_length.eq("1") {
             *conn.max_string_length.write().unwrap() = Some(constants::DEFAULT_MAX_STRING_LENGTH);
         }
     }
 
+    if let Some(slow_query_ms) = odbc_uri.remove(&[SLOW_QUERY_MS]) {
+        let threshold = slow_query_ms.parse::<u32>().map_err(|_| {
+            Error::InvalidUriFormat(format!(
+                "slow_query_ms must be a non-negative integer, found '{slow_query_ms}'"
+            ))
+        })?;
+        *conn.slow_query_ms.write().unwrap() = Some(threshold);
+    }
+
+    if let Some(warn_unordered_limit) = odbc_uri.remove(&["warn_unordered_limit"]) {
+        if warn_unordered_limit.eq("0") {
+            *conn.unordered_limit_warning.write().unwrap() = false;
+        }
+    }
+
+    if let Some(row_as_json) = odbc_uri.remove(&["row_as_json"]) {
+        if row_as_json.eq("1") {
+            *conn.row_as_json.write().unwrap() = true;
+        }
+    }
+
+    if let Some(json_mode) = odbc_uri.remove(&["jsonmode"]) {
+        *conn.json_mode.write().unwrap() = match json_mode.to_lowercase().as_str() {
+            "relaxed" => JsonMode::Relaxed,
+            "canonical" => JsonMode::Canonical,
+            _ => {
+                return Err(Error::InvalidUriFormat(format!(
+                    "jsonMode must be 'canonical' or 'relaxed', found '{json_mode}'"
+                ))
+                .into())
+            }
+        };
+    }
+
+    // The result cache is opt-in, a TTL of 0 leaves it disabled.
+    let result_cache_ttl = match odbc_uri.remove(&["result_cache_ttl_ms"]) {
+        Some(ttl) => ttl.parse::<u64>().map_err(|_| {
+            Error::InvalidUriFormat(format!(
+                "result_cache_ttl_ms must be a non-negative integer, found '{ttl}'"
+            ))
+        })?,
+        None => 0,
+    };
+
+    let collation = odbc_uri
+        .remove(&["collation"])
+        .map(|collation| parse_collation(&collation))
+        .transpose()?;
+
+    // Queries run as the $sql aggregation unless FORCE_AGGREGATE=0 lets simple queries run as
+    // find commands.
+    let force_aggregate = match odbc_uri.remove(&["force_aggregate"]).as_deref() {
+        None | Some("1") => true,
+        Some("0") => false,
+        Some(force_aggregate) => {
+            return Err(Error::InvalidUriFormat(format!(
+                "force_aggregate must be 0 or 1, found '{force_aggregate}'"
+            ))
+            .into())
+        }
+    };
+
+    // A batch size of 0 leaves it to the server.
+    let batch_size = odbc_uri
+        .remove(&["batchsize"])
+        .map(|batch_size| {
+            batch_size.parse::<u32>().map_err(|_| {
+                Error::InvalidUriFormat(format!(
+                    "batchSize must be a non-negative integer, found '{batch_size}'"
+                ))
+            })
+        })
+        .transpose()?;
+
     let mut conn_attrs = conn.attributes.write().unwrap();
     let database = if conn_attrs.current_catalog.is_some() {
         conn_attrs.current_catalog.as_deref().map(|s| s.to_string())
@@ -1137,10 +1476,7 @@ fn sql_driver_connect(conn: &Connection, odbc_uri_string: &str) -> Result<MongoC
     };
     let connection_timeout = conn_attrs.connection_timeout;
     let login_timeout = conn_attrs.login_timeout;
-    // ODBCError has an impl From mongo_odbc_core::Error, but that does not
-    // create an impl From Result<T, mongo_odbc_core::Error> to Result<T, ODBCError>
-    // hence this bizarre Ok(func?) pattern.
-    Ok(mongo_odbc_core::MongoConnection::connect(
+    let mut mongo_connection = mongo_odbc_core::MongoConnection::connect(
         client_options,
         database,
         connection_timeout,
@@ -1148,7 +1484,29 @@ fn sql_driver_connect(conn: &Connection, odbc_uri_string: &str) -> Result<MongoC
         *conn.type_mode.read().unwrap(),
         *conn.max_string_length.read().unwrap(),
         Some(runtime),
-    )?)
+    )?;
+    if result_cache_ttl > 0 {
+        mongo_connection.result_cache =
+            Some(ResultCache::new(Duration::from_millis(result_cache_ttl)));
+    }
+    mongo_connection.collation = collation;
+    mongo_connection.force_aggregate = force_aggregate;
+    mongo_connection.batch_size = batch_size;
+    // The features the server lacks are left out rather than failing the queries that use them.
+    warnings.extend(
+        mongo_connection
+            .capabilities
+            .unsupported_features()
+            .into_iter()
+            .map(ODBCError::GeneralWarning),
+    );
+    warnings.extend(
+        mongo_connection
+            .take_server_warnings(None)
+            .into_iter()
+            .map(ODBCError::ServerWarning),
+    );
+    Ok((mongo_connection, warnings))
 }
 
 ///
@@ -1207,35 +1565,16 @@ pub unsafe extern "C" fn SQLDriverConnectW(
                 }
             }
 
-            let conn = must_be_valid!((*conn_handle).as_connection());
             let odbc_uri_string =
-                input_text_to_string_w(in_connection_string, string_length_1.into());
-            let mongo_connection =
-                odbc_unwrap!(sql_driver_connect(conn, &odbc_uri_string), conn_handle);
-            *conn.mongo_connection.write().unwrap() = Some(mongo_connection);
-            // We know the mysql ODBC driver returns SUCCESS if the out_connection_string is NULL.
-            // We can also just return SUCCESS if the buffer_len is 0. Likely, users are not
-            // expecting to get back a warning when they pass an empty buffer to this, especially
-            // given that we only currently support DriverConnectOption::SQL_DRIVER_NO_PROMPT.
-            // given that we only currently support DriverConnectOption::NoPrompt.
-            if buffer_length <= 0 || out_connection_string.is_null() {
-                *string_length_2 = odbc_uri_string
-                    .len()
-                    .try_into()
-                    .expect("odbc_uri_string.len exceeds i16");
-                return SqlReturn::SUCCESS;
-            }
-            let buffer_len = usize::try_from(buffer_length).unwrap();
-            let sql_return = i16_len::set_output_wstring(
+                input_text_arg_to_string_w(in_connection_string, string_length_1.into());
+            connect(
+                conn_handle,
                 &odbc_uri_string,
                 out_connection_string,
-                buffer_len,
+                buffer_length,
                 string_length_2,
-            );
-            if sql_return == SqlReturn::SUCCESS_WITH_INFO {
-                add_diag_info!(conn_handle, ODBCError::OutStringTruncated(buffer_len));
-            }
-            sql_return
+                function_name!(),
+            )
         },
         connection_handle
     );
@@ -1275,11 +1614,62 @@ pub unsafe extern "C" fn SQLDriversW(
 #[named]
 #[no_mangle]
 pub unsafe extern "C" fn SQLEndTran(
-    _handle_type: HandleType,
+    handle_type: HandleType,
     handle: Handle,
-    _completion_type: SmallInt,
+    completion_type: SmallInt,
 ) -> SqlReturn {
-    unimpl!(handle);
+    panic_safe_exec_clear_diagnostics!(
+        debug,
+        || {
+            let mongo_handle = MongoHandleRef::from(handle);
+            match sql_end_tran_helper(handle_type, mongo_handle, completion_type) {
+                Ok(()) => SqlReturn::SUCCESS,
+                Err(e) => {
+                    add_diag_info!(mongo_handle, e);
+                    SqlReturn::ERROR
+                }
+            }
+        },
+        handle
+    );
+}
+
+// The driver is read-only, so there is never anything to commit or roll back. Ending a
+// transaction is a no-op as long as autocommit is on for every connection involved. If an
+// application explicitly turned autocommit off, it expects real transactions, which we don't
+// support.
+unsafe fn sql_end_tran_helper(
+    handle_type: HandleType,
+    handle: &MongoHandle,
+    completion_type: SmallInt,
+) -> Result<()> {
+    let _: CompletionType = FromPrimitive::from_i16(completion_type)
+        .ok_or(ODBCError::InvalidTransactionOperationCode(completion_type))?;
+    let autocommit_on = |conn: &Connection| {
+        conn.attributes.read().unwrap().autocommit == AttrAutoCommit::SQL_AUTOCOMMIT_ON
+    };
+    let all_autocommit = match handle_type {
+        HandleType::SQL_HANDLE_DBC => autocommit_on(
+            handle
+                .as_connection()
+                .ok_or(ODBCError::InvalidHandleType(HANDLE_MUST_BE_CONN_ERROR))?,
+        ),
+        HandleType::SQL_HANDLE_ENV => handle
+            .as_env()
+            .ok_or(ODBCError::InvalidHandleType(HANDLE_MUST_BE_ENV_ERROR))?
+            .connections
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|conn| (**conn).as_connection())
+            .all(autocommit_on),
+        _ => return Err(ODBCError::InvalidHandleType(HANDLE_MUST_BE_CONN_ERROR)),
+    };
+    if all_autocommit {
+        Ok(())
+    } else {
+        Err(ODBCError::Unimplemented("`transactions`"))
+    }
 }
 
 ///
@@ -1303,22 +1693,33 @@ pub unsafe extern "C" fn SQLExecDirectW(
             let mongo_handle = MongoHandleRef::from(statement_handle);
             let stmt = must_be_valid!(mongo_handle.as_statement());
             let connection = must_be_valid!((*stmt.connection).as_connection());
+            // the slow query threshold applies to both the translation and the execution
+            let start = Instant::now();
+            let statement_id = stmt.statement_id.read().unwrap().clone();
+            clear_server_warnings(connection, None);
+            clear_server_warnings(connection, Some(&statement_id));
             let mongo_statement = odbc_unwrap!(
-                sql_prepare(statement_text, text_length, connection,),
+                sql_prepare(
+                    statement_text,
+                    text_length,
+                    connection,
+                    stmt.attributes.read().unwrap().query_timeout,
+                ),
                 mongo_handle
             );
+            let prepare_warnings = server_warnings(connection, None);
 
-            *stmt.mongo_statement.write().unwrap() = Some(Box::new(mongo_statement));
+            *stmt.mongo_statement.write().unwrap() = Some(mongo_statement);
 
             // set the statment state to executing so SQLCancel knows to search the op log for hanging queries
             *stmt.state.write().unwrap() = StatementState::SynchronousQueryExecuting;
 
-            odbc_unwrap!(sql_execute(stmt, connection), mongo_handle);
+            let executed = sql_execute(stmt, connection);
 
-            // return the statement state to its original value
+            // return the statement state to its original value, even if the execution failed
             *stmt.state.write().unwrap() = StatementState::Allocated;
-
-            SqlReturn::SUCCESS
+            odbc_unwrap!(executed, mongo_handle);
+            sql_execute_warnings(statement_handle, start, prepare_warnings, function_name!())
         },
         statement_handle
     );
@@ -1339,12 +1740,16 @@ pub unsafe extern "C" fn SQLExecute(statement_handle: HStmt) -> SqlReturn {
             let mongo_handle = MongoHandleRef::from(statement_handle);
             let stmt = must_be_valid!(mongo_handle.as_statement());
             let connection = must_be_valid!((*stmt.connection).as_connection());
+            let start = Instant::now();
+            let statement_id = stmt.statement_id.read().unwrap().clone();
+            clear_server_warnings(connection, Some(&statement_id));
             // set the statment state to executing so SQLCancel knows to search the op log for hanging queries
             *stmt.state.write().unwrap() = StatementState::SynchronousQueryExecuting;
-            odbc_unwrap!(sql_execute(stmt, connection), mongo_handle);
-            // return the statement state to its original value
+            let executed = sql_execute(stmt, connection);
+            // return the statement state to its original value, even if the execution failed
             *stmt.state.write().unwrap() = StatementState::Allocated;
-            SqlReturn::SUCCESS
+            odbc_unwrap!(executed, mongo_handle);
+            sql_execute_warnings(statement_handle, start, vec![], function_name!())
         },
         statement_handle
     );
@@ -1359,12 +1764,20 @@ unsafe fn sql_execute(stmt: &Statement, connection: &Connection) -> Result<bool>
                 Err(_) => unreachable!("Err should be impossible since SQLSetStmtAttrW sets row_array_size to u32::MAX if it's outside of the u32 range"),
             };
 
+            let command_options = {
+                let attributes = stmt.attributes.read().unwrap();
+                CommandOptions {
+                    hint: attributes.hint.as_deref().and_then(parse_hint),
+                    comment: attributes.comment.clone(),
+                }
+            };
+
             stmt.mongo_statement
                 .write()
                 .unwrap()
                 .as_mut()
                 .unwrap()
-                .execute(mongo_connection, stmt_id, rowset_size)
+                .execute(mongo_connection, stmt_id, rowset_size, &command_options)
                 .map_err(|e| e.into())
         } else {
             Err(ODBCError::InvalidCursorState)
@@ -1374,6 +1787,147 @@ unsafe fn sql_execute(stmt: &Statement, connection: &Connection) -> Result<bool>
     mongo_statement
 }
 
+///
+/// sql_execute_warnings reports the warnings of a statement executed since start: those of the SET
+/// statement it applied, of a LIMIT without an ORDER BY, of a slow query, those of preparing it
+/// when it was executed directly, and those the server returned to its commands. It returns
+/// SUCCESS_WITH_INFO if there are any.
+///
+unsafe fn sql_execute_warnings(
+    statement_handle: HStmt,
+    start: Instant,
+    prepare_warnings: Vec<ODBCError>,
+    function_name: &str,
+) -> SqlReturn {
+    let mongo_handle = MongoHandleRef::from(statement_handle);
+    let stmt = must_be_valid!(mongo_handle.as_statement());
+    let connection = must_be_valid!((*stmt.connection).as_connection());
+    let statement_id = stmt.statement_id.read().unwrap().clone();
+    let warnings = [
+        apply_set_statement(stmt, connection),
+        unordered_limit_warning(stmt, connection),
+        slow_query_warning(stmt, connection, start.elapsed()),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(prepare_warnings)
+    .chain(server_warnings(connection, Some(&statement_id)))
+    .collect::<Vec<_>>();
+    if warnings.is_empty() {
+        return SqlReturn::SUCCESS;
+    }
+    for warning in warnings {
+        add_diag_with_function!(mongo_handle, warning, function_name);
+    }
+    SqlReturn::SUCCESS_WITH_INFO
+}
+
+///
+/// apply_set_statement applies the session variable assigned by an executed SET statement to the
+/// connection. TIMEZONE sets the time zone datetimes are returned in, and NAMES is accepted as is
+/// since strings are always UTF-8. Any other variable is ignored with a warning.
+///
+fn apply_set_statement(stmt: &Statement, connection: &Connection) -> Option<ODBCError> {
+    let variable = stmt
+        .mongo_statement
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|s| s.query())
+        .and_then(MongoSet::parse)?
+        .ok()?
+        .variable;
+    match variable {
+        SessionVariable::TimeZone(offset) => {
+            *connection.time_zone.write().unwrap() = Some(offset);
+            None
+        }
+        SessionVariable::Names(_) => None,
+        SessionVariable::Unknown(name) => Some(ODBCError::UnsupportedSessionVariable(name)),
+    }
+}
+
+///
+/// unordered_limit_warning returns a warning if the statement applies a LIMIT without an ORDER BY,
+/// since MongoDB returns the documents in no particular order. The warning can be turned off with
+/// the WARN_UNORDERED_LIMIT=0 connection attribute.
+///
+fn unordered_limit_warning(stmt: &Statement, connection: &Connection) -> Option<ODBCError> {
+    if !*connection.unordered_limit_warning.read().unwrap() {
+        return None;
+    }
+    stmt.mongo_statement
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|s| s.query())
+        .filter(|q| has_unordered_limit(q))
+        .map(|_| ODBCError::UnorderedLimit)
+}
+
+///
+/// slow_query_warning returns a warning if [`elapsed`] exceeds the SLOW_QUERY_MS threshold of
+/// the connection. The statement text is normalized to a single line, and the warning is also
+/// logged so slow queries can be found after the fact.
+///
+fn slow_query_warning(
+    stmt: &Statement,
+    connection: &Connection,
+    elapsed: Duration,
+) -> Option<ODBCError> {
+    let threshold = (*connection.slow_query_ms.read().unwrap())?;
+    if elapsed <= Duration::from_millis(u64::from(threshold)) {
+        return None;
+    }
+    let query = stmt
+        .mongo_statement
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|s| {
+            s.query()
+                .map(|q| q.split_whitespace().collect::<Vec<_>>().join(" "))
+        })
+        .unwrap_or_default();
+    let warning = ODBCError::SlowQuery(elapsed.as_millis(), threshold, query);
+    warn!("{warning}");
+    Some(warning)
+}
+
+///
+/// server_warnings returns the warnings the server returned to the commands of the statement since
+/// they were last reported, such as the aggregate of an execution or the getMores of a fetch. The
+/// statement id is the one in the comment of the statement's commands, and None returns the
+/// warnings of the commands run outside of an execution, like getting the schema of a prepared
+/// query.
+///
+fn server_warnings(connection: &Connection, statement_id: Option<&Bson>) -> Vec<ODBCError> {
+    connection
+        .mongo_connection
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|mongo_connection| {
+            mongo_connection
+                .take_server_warnings(statement_id)
+                .into_iter()
+                .map(ODBCError::ServerWarning)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+///
+/// clear_server_warnings discards the warnings the server returned to the commands of the
+/// statement before it is prepared or executed, which were left unreported by a previous
+/// execution, so that only the warnings of the new execution are reported.
+///
+fn clear_server_warnings(connection: &Connection, statement_id: Option<&Bson>) {
+    if let Some(mongo_connection) = connection.mongo_connection.read().unwrap().as_ref() {
+        mongo_connection.clear_server_warnings(statement_id);
+    }
+}
+
 ///
 /// [`SQLFetch`]: https://learn.microsoft.com/en-us/sql/odbc/reference/syntax/SQLFetch-function
 ///
@@ -1503,6 +2057,7 @@ unsafe fn sql_fetch_helper(statement_handle: HStmt, function_name: &str) -> SqlR
                     index,
                     row_status_buffer,
                     bound_cols,
+                    stmt.attributes.read().unwrap().row_bind_type,
                     function_name,
                 );
 
@@ -1546,6 +2101,15 @@ unsafe fn sql_fetch_helper(statement_handle: HStmt, function_name: &str) -> SqlR
         *stmt.attributes.write().unwrap().rows_fetched_ptr = fetched_rows;
     }
 
+    let statement_id = stmt.statement_id.read().unwrap().clone();
+    for warning in server_warnings(
+        must_be_valid!((*stmt.connection).as_connection()),
+        Some(&statement_id),
+    ) {
+        add_diag_with_function!(mongo_handle, warning, function_name.to_string());
+        encountered_success_with_info = true;
+    }
+
     // Only return ERROR if every row that does not have status SQL_ROW_NOROW causes an error.
     if row_error_count == fetched_rows {
         SqlReturn::ERROR
@@ -1569,6 +2133,7 @@ unsafe fn sql_fetch_bound_buffers(
     index: ULen,
     row_status_buffer: *mut USmallInt,
     bound_cols: &HashMap<USmallInt, BoundColInfo>,
+    row_bind_type: ULen,
     function_name: &str,
 ) -> (bool, bool) {
     let mongo_handle_for_sql_get_data_helper = MongoHandleRef::from(statement_handle);
@@ -1577,14 +2142,23 @@ unsafe fn sql_fetch_bound_buffers(
     let mut encountered_success_with_info_getting_data = false;
 
     for (col, bound_col_info) in bound_cols.iter() {
+        // With column-wise binding, every bound column is an array of buffers. With row-wise
+        // binding, the row_bind_type is the size of the structure holding a row, and the bound
+        // buffers are the fields of the first structure in an array of structures.
+        let (target_stride, len_ind_stride) =
+            if row_bind_type == BindType::SQL_BIND_BY_COLUMN as ULen {
+                (bound_col_info.buffer_length as ULen, size_of::<Len>())
+            } else {
+                (row_bind_type, row_bind_type)
+            };
+
         // Set target_buffer to the correct buffer in the array of buffers
-        let target_buffer = (bound_col_info.target_buffer as ULen
-            + (index * (bound_col_info.buffer_length as ULen)))
-            as Pointer;
+        let target_buffer =
+            (bound_col_info.target_buffer as ULen + (index * target_stride)) as Pointer;
 
         // Set length/indicator buffer to the correct buffer in the array of buffers
         let len_ind_buffer =
-            (bound_col_info.length_or_indicator as ULen + (index * size_of::<isize>())) as *mut Len;
+            (bound_col_info.length_or_indicator as ULen + (index * len_ind_stride)) as *mut Len;
 
         let sql_return = sql_get_data_helper(
             mongo_handle_for_sql_get_data_helper,
@@ -1795,6 +2369,11 @@ fn sql_free_handle(handle_type: HandleType, handle: *mut MongoHandle) -> Result<
 #[named]
 #[no_mangle]
 pub unsafe extern "C" fn SQLFreeStmt(statement_handle: HStmt, option: SmallInt) -> SqlReturn {
+    // SQL_DROP frees the handle itself, so it must not go through the panic-safe wrapper below,
+    // which traces against the handle after the function returns.
+    if FromPrimitive::from_i16(option) == Some(FreeStmtOption::SQL_DROP) {
+        return SQLFreeHandle(HandleType::SQL_HANDLE_STMT, statement_handle as Handle);
+    }
     panic_safe_exec_clear_diagnostics!(
         debug,
         || {
@@ -1802,8 +2381,10 @@ pub unsafe extern "C" fn SQLFreeStmt(statement_handle: HStmt, option: SmallInt)
             let stmt = must_be_valid!((*mongo_handle).as_statement());
 
             match FromPrimitive::from_i16(option) {
-                // Drop all pending results from the cursor and close the cursor.
+                // Drop all pending results from the cursor and close the cursor. Column
+                // bindings are kept so the statement can be re-executed with them.
                 Some(FreeStmtOption::SQL_CLOSE) => {
+                    *stmt.var_data_cache.write().unwrap() = None;
                     let mut mongo_statement = stmt.mongo_statement.write().unwrap();
                     match mongo_statement.as_mut() {
                         // No-op when the mongo_statement is not set. This is typically an
@@ -1823,7 +2404,15 @@ pub unsafe extern "C" fn SQLFreeStmt(statement_handle: HStmt, option: SmallInt)
                 }
                 // We do not implement SQLBindParameter, so this is a no-op.
                 Some(FreeStmtOption::SQL_RESET_PARAMS) => SqlReturn::SUCCESS,
-                _ => SqlReturn::ERROR,
+                // SQL_DROP is handled before entering this closure.
+                Some(FreeStmtOption::SQL_DROP) => unreachable!(),
+                None => {
+                    add_diag_info!(
+                        mongo_handle,
+                        ODBCError::InvalidAttrIdentifier(option.into())
+                    );
+                    SqlReturn::ERROR
+                }
             }
         },
         statement_handle
@@ -1915,6 +2504,19 @@ unsafe fn sql_get_connect_attrw_helper(
                 let connection_timeout = attributes.connection_timeout.unwrap_or(0);
                 i32_len::set_output_fixed_data(&connection_timeout, value_ptr, string_length_ptr)
             }
+            ConnectionAttribute::SQL_ATTR_AUTOCOMMIT => i32_len::set_output_fixed_data(
+                &(attributes.autocommit as u32),
+                value_ptr,
+                string_length_ptr,
+            ),
+            ConnectionAttribute::SQL_ATTR_TXN_ISOLATION => i32_len::set_output_fixed_data(
+                &SQL_TXN_READ_UNCOMMITTED,
+                value_ptr,
+                string_length_ptr,
+            ),
+            ConnectionAttribute::SQL_ATTR_APP_WCHAR_TYPE => {
+                i32_len::set_output_fixed_data(&SQL_DD_CP_UTF16, value_ptr, string_length_ptr)
+            }
             _ => {
                 err = Some(ODBCError::UnsupportedConnectionAttribute(
                     connection_attribute_to_string(attribute),
@@ -2156,17 +2758,34 @@ pub unsafe extern "C" fn SQLGetDiagFieldW(
                 Some(diag_identifier) => {
                     match diag_identifier {
                         // some diagnostics are statement specific; return error if another handle is passed
-                        DiagType::SQL_DIAG_ROW_COUNT | DiagType::SQL_DIAG_ROW_NUMBER => {
+                        DiagType::SQL_DIAG_ROW_COUNT
+                        | DiagType::SQL_DIAG_CURSOR_ROW_COUNT
+                        | DiagType::SQL_DIAG_DYNAMIC_FUNCTION
+                        | DiagType::SQL_DIAG_DYNAMIC_FUNCTION_CODE
+                        | DiagType::SQL_DIAG_ROW_NUMBER
+                        | DiagType::SQL_DIAG_COLUMN_NUMBER => {
                             if _handle_type != HandleType::SQL_HANDLE_STMT {
                                 return SqlReturn::ERROR;
                             }
-                            get_stmt_diag_field(diag_identifier, diag_info_ptr)
+                            let stmt = must_be_stmt!(mongo_handle);
+                            get_stmt_diag_field(
+                                &stmt.errors.read().unwrap(),
+                                diag_identifier,
+                                diag_info_ptr,
+                                record_number,
+                                buffer_length,
+                                string_length_ptr,
+                            )
                         }
                         DiagType::SQL_DIAG_NUMBER
                         | DiagType::SQL_DIAG_MESSAGE_TEXT
                         | DiagType::SQL_DIAG_NATIVE
                         | DiagType::SQL_DIAG_SQLSTATE
-                        | DiagType::SQL_DIAG_RETURNCODE => match _handle_type {
+                        | DiagType::SQL_DIAG_RETURNCODE
+                        | DiagType::SQL_DIAG_CLASS_ORIGIN
+                        | DiagType::SQL_DIAG_SUBCLASS_ORIGIN
+                        | DiagType::SQL_DIAG_CONNECTION_NAME
+                        | DiagType::SQL_DIAG_SERVER_NAME => match _handle_type {
                             HandleType::SQL_HANDLE_ENV => {
                                 let env = must_be_env!(mongo_handle);
                                 get_error(&env.errors.read().unwrap(), diag_identifier)
@@ -2184,11 +2803,9 @@ pub unsafe extern "C" fn SQLGetDiagFieldW(
                                 get_error(&desc.errors.read().unwrap(), diag_identifier)
                             }
                         },
-                        // TODO: SQL-1152: Implement additional diag types
-                        // this condition should only occur if the _diag_identifier is not in the spec
-                        _ => SqlReturn::ERROR,
                     }
                 }
+                // this condition should only occur if the _diag_identifier is not in the spec
                 None => SqlReturn::ERROR,
             }
         },
@@ -2392,9 +3009,9 @@ macro_rules! sql_get_info_helper {
                 ),
                 InfoType::SQL_DRIVER_ODBC_VER => {
                     // This driver supports version 3.8.
-                    i16_len::set_output_wstring(
+                    i16_len::set_output_wstring_as_bytes(
                         ODBC_VERSION,
-                        info_value_ptr.cast::<WideChar>(),
+                        info_value_ptr,
                         buffer_length as usize,
                         string_length_ptr,
                     )
@@ -2442,6 +3059,15 @@ macro_rules! sql_get_info_helper {
                     // the result is NULL. The return value indicates that.
                     i16_len::set_output_fixed_data(&SQL_CB_NULL, info_value_ptr, string_length_ptr)
                 }
+                // MongoDB field and collection names are case-sensitive and stored as given,
+                // whether they are quoted or not.
+                InfoType::SQL_IDENTIFIER_CASE | InfoType::SQL_QUOTED_IDENTIFIER_CASE => {
+                    i16_len::set_output_fixed_data(
+                        &SQL_IC_SENSITIVE,
+                        info_value_ptr,
+                        string_length_ptr,
+                    )
+                }
                 InfoType::SQL_IDENTIFIER_QUOTE_CHAR => {
                     // MongoSQL supports ` and " as identifier delimiters. The "
                     // character is the SQL-92 standard, but we instead return `
@@ -2577,6 +3203,17 @@ macro_rules! sql_get_info_helper {
                         string_length_ptr,
                     )
                 }
+                InfoType::SQL_DATETIME_LITERALS => {
+                    // DATE, TIME and TIMESTAMP literals are translated to CAST by the driver, as
+                    // are the {d}, {t} and {ts} escape clauses.
+                    const DATETIME_LITERALS: u32 =
+                        SQL_DL_SQL92_DATE | SQL_DL_SQL92_TIME | SQL_DL_SQL92_TIMESTAMP;
+                    i16_len::set_output_fixed_data(
+                        &DATETIME_LITERALS,
+                        info_value_ptr,
+                        string_length_ptr,
+                    )
+                }
                 InfoType::SQL_CONVERT_BIGINT
                 | InfoType::SQL_CONVERT_DECIMAL
                 | InfoType::SQL_CONVERT_DOUBLE
@@ -2705,6 +3342,39 @@ macro_rules! sql_get_info_helper {
                     // columns allowed in a GROUP BY, ORDER BY, or SELECT clause.
                     i16_len::set_output_fixed_data(&MAX_COLUMNS_U16_ZERO, info_value_ptr, string_length_ptr)
                 }
+                // Indexes cannot be created through the driver, but these are the limits of the
+                // indexes reported by SQLStatistics.
+                InfoType::SQL_MAX_COLUMNS_IN_INDEX => i16_len::set_output_fixed_data(
+                    &MAX_COLUMNS_IN_INDEX,
+                    info_value_ptr,
+                    string_length_ptr,
+                ),
+                InfoType::SQL_MAX_INDEX_SIZE => i16_len::set_output_fixed_data(
+                    &MAX_INDEX_SIZE,
+                    info_value_ptr,
+                    string_length_ptr,
+                ),
+                InfoType::SQL_MAX_CATALOG_NAME_LEN => i16_len::set_output_fixed_data(
+                    &MAX_CATALOG_NAME_LEN,
+                    info_value_ptr,
+                    string_length_ptr,
+                ),
+                InfoType::SQL_MAX_BINARY_LITERAL_LEN | InfoType::SQL_MAX_CHAR_LITERAL_LEN => {
+                    i16_len::set_output_fixed_data(
+                        &MAX_LITERAL_LEN,
+                        info_value_ptr,
+                        string_length_ptr,
+                    )
+                }
+                InfoType::SQL_MAX_COLUMN_NAME_LEN => {
+                    // Field names are only bounded by the maximum document size.
+                    i16_len::set_output_fixed_data(&0u16, info_value_ptr, string_length_ptr)
+                }
+                InfoType::SQL_MAX_TABLES_IN_SELECT => {
+                    // MongoSQL does not limit the number of collections joined in a FROM
+                    // clause; queries are only bounded by the server's pipeline limits.
+                    i16_len::set_output_fixed_data(&0u16, info_value_ptr, string_length_ptr)
+                }
 
                 InfoType::SQL_TIMEDATE_ADD_INTERVALS | InfoType::SQL_TIMEDATE_DIFF_INTERVALS => {
                     // Note that MongoSQL does not support TIMEDATE_ADD or
@@ -2800,8 +3470,7 @@ macro_rules! sql_get_info_helper {
                         string_length_ptr,
                     )
                 }
-                InfoType::SQL_DEFAULT_TXN_ISOLATION
-                | InfoType::SQL_DTC_TRANSITION_COST
+                InfoType::SQL_DTC_TRANSITION_COST
                 | InfoType::SQL_BOOKMARK_PERSISTENCE
                 | InfoType::SQL_POS_OPERATIONS
                 | InfoType::SQL_STATIC_SENSITIVITY
@@ -2812,13 +3481,13 @@ macro_rules! sql_get_info_helper {
                         string_length_ptr,
                     )
                 }
-                // Setting this to 10, which is our default for the number of workers in the mongo driver's connection pool.
-                InfoType::SQL_MAX_CONCURRENT_ACTIVITIES => {
-                    i16_len::set_output_fixed_data(&10, info_value_ptr, string_length_ptr)
+                // Statements do not hold a pooled connection between server round trips, so any
+                // number of them can be active on a connection, and any number of connections can
+                // be opened.
+                InfoType::SQL_MAX_CONCURRENT_ACTIVITIES | InfoType::SQL_MAX_DRIVER_CONNECTIONS => {
+                    i16_len::set_output_fixed_data(&0u16, info_value_ptr, string_length_ptr)
                 }
                 InfoType::SQL_FORWARD_ONLY_CURSOR_ATTRIBUTES1
-                | InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES1
-                | InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES1
                 | InfoType::SQL_STATIC_CURSOR_ATTRIBUTES1 => {
                     i16_len::set_output_fixed_data(
                         &SQL_CA1_NEXT,
@@ -2827,8 +3496,6 @@ macro_rules! sql_get_info_helper {
                     )
                 }
                 InfoType::SQL_FORWARD_ONLY_CURSOR_ATTRIBUTES2
-                | InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES2
-                | InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES2
                 | InfoType::SQL_STATIC_CURSOR_ATTRIBUTES2 => {
                     i16_len::set_output_fixed_data(
                         &MONGO_CA2_SUPPORT,
@@ -2836,6 +3503,30 @@ macro_rules! sql_get_info_helper {
                         string_length_ptr,
                     )
                 }
+                // Keyset-driven and dynamic cursors are not supported; SQLSetStmtAttrW changes
+                // SQL_ATTR_CURSOR_TYPE to SQL_CURSOR_FORWARD_ONLY. Report no capabilities so
+                // applications don't request them.
+                InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES1
+                | InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES2
+                | InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES1
+                | InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES2 => {
+                    i16_len::set_output_fixed_data(&0u32, info_value_ptr, string_length_ptr)
+                }
+                // Without keyset-driven cursors, rows are read once from the result set cursor and
+                // changes made by others after the query ran are not detected.
+                InfoType::SQL_ROW_UPDATES => i16_len::set_output_wstring_as_bytes(
+                    ROW_UPDATES_INFO_N,
+                    info_value_ptr,
+                    buffer_length as usize,
+                    string_length_ptr,
+                ),
+                InfoType::SQL_FETCH_DIRECTION => {
+                    i16_len::set_output_fixed_data(
+                        &MONGO_FD_SUPPORT,
+                        info_value_ptr,
+                        string_length_ptr,
+                    )
+                }
                 InfoType::SQL_SCROLL_OPTIONS => {
                     i16_len::set_output_fixed_data(
                         &MONGO_SO_SUPPORT,
@@ -2851,9 +3542,9 @@ macro_rules! sql_get_info_helper {
                         string_length_ptr,
                     )
                 }
-                InfoType::SQL_TXN_ISOLATION_OPTION => {
+                InfoType::SQL_TXN_ISOLATION_OPTION | InfoType::SQL_DEFAULT_TXN_ISOLATION => {
                     i16_len::set_output_fixed_data(
-                        &SQL_TXN_SERIALIZABLE,
+                        &SQL_TXN_READ_UNCOMMITTED,
                         info_value_ptr,
                         string_length_ptr,
                     )
@@ -2887,6 +3578,31 @@ macro_rules! sql_get_info_helper {
                         string_length_ptr,
                     )
                 }
+                InfoType::SQL_INTEGRITY => {
+                    // MongoDB has no referential integrity constraints, so the Integrity
+                    // Enhancement Facility is not supported.
+                    i16_len::set_output_wstring_as_bytes(
+                        INTEGRITY_INFO_N,
+                        info_value_ptr,
+                        buffer_length as usize,
+                        string_length_ptr,
+                    )
+                }
+                // Both the X/Open CLI and ISO 92 CLI require parameter binding, which this driver
+                // does not support (SQLBindParameter returns HYC00), so no level is claimed.
+                InfoType::SQL_STANDARD_CLI_CONFORMANCE => {
+                    i16_len::set_output_fixed_data(&0u32, info_value_ptr, string_length_ptr)
+                }
+                // The data source is read-only, so neither ALTER TABLE nor CREATE/DROP INDEX
+                // is supported in any form.
+                InfoType::SQL_ALTER_TABLE | InfoType::SQL_DDL_INDEX => {
+                    i16_len::set_output_fixed_data(&0u32, info_value_ptr, string_length_ptr)
+                }
+                // Cursors are read-only and SQLSetPos is not supported, so WHERE CURRENT OF and
+                // SELECT FOR UPDATE are not either.
+                InfoType::SQL_POSITIONED_STATEMENTS => {
+                    i16_len::set_output_fixed_data(&0u32, info_value_ptr, string_length_ptr)
+                }
                 _ => {
                     err = Some(ODBCError::UnsupportedInfoTypeRetrieval(
                         info_type.to_string(),
@@ -2903,6 +3619,9 @@ macro_rules! sql_get_info_helper {
         }
     };
 
+    if sql_return == SqlReturn::SUCCESS_WITH_INFO && err.is_none() {
+        err = Some(ODBCError::OutStringTruncated(buffer_length as usize));
+    }
     if let Some(error) = err {
         add_diag_with_function!(conn_handle, error, "SQLGetInfoW");
     }
@@ -2954,7 +3673,7 @@ pub unsafe extern "C" fn SQLGetStmtAttrW(
     handle: HStmt,
     attribute: Integer,
     value_ptr: Pointer,
-    _buffer_length: Integer,
+    buffer_length: Integer,
     string_length_ptr: *mut Integer,
 ) -> SqlReturn {
     panic_safe_exec_clear_diagnostics!(
@@ -2969,6 +3688,37 @@ pub unsafe extern "C" fn SQLGetStmtAttrW(
                 Some(valid_attr) => {
                     sql_get_stmt_attrw_helper(stmt_handle, valid_attr, value_ptr, string_length_ptr)
                 }
+                None if [SQL_ATTR_MONGODB_HINT, SQL_ATTR_MONGODB_COMMENT].contains(&attribute) => {
+                    let stmt = must_be_valid!(stmt_handle.as_statement());
+                    let attributes = stmt.attributes.read().unwrap();
+                    let value = if attribute == SQL_ATTR_MONGODB_HINT {
+                        &attributes.hint
+                    } else {
+                        &attributes.comment
+                    };
+                    i32_len::set_output_wstring_as_bytes(
+                        value.as_deref().unwrap_or_default(),
+                        value_ptr,
+                        buffer_length as usize,
+                        string_length_ptr,
+                    )
+                }
+                #[cfg(feature = "test_hooks")]
+                None if attribute == SQL_ATTR_MONGODB_GET_MORE_COUNT => {
+                    let stmt = must_be_valid!(stmt_handle.as_statement());
+                    let get_more_count = if stmt.connection.is_null() {
+                        0
+                    } else {
+                        must_be_valid!((*stmt.connection).as_connection())
+                            .mongo_connection
+                            .read()
+                            .unwrap()
+                            .as_ref()
+                            .map_or(0, MongoConnection::get_more_count)
+                    };
+                    *value_ptr.cast::<ULen>() = ULen::try_from(get_more_count).unwrap_or(ULen::MAX);
+                    SqlReturn::SUCCESS
+                }
                 None => {
                     add_diag_info!(stmt_handle, ODBCError::InvalidAttrIdentifier(attribute));
                     SqlReturn::ERROR
@@ -3148,9 +3898,7 @@ unsafe fn sql_get_stmt_attrw_helper(
                 SqlReturn::SUCCESS
             }
             StatementAttribute::SQL_ATTR_METADATA_ID => {
-                // False means that we treat arguments to catalog functions as case sensitive. This
-                // is a _requirement_ for mongodb where FOO and foo are distinct database names.
-                *value_ptr.cast::<ULen>() = SqlBool::SQL_FALSE as ULen;
+                *value_ptr.cast::<ULen>() = stmt.attributes.read().unwrap().metadata_id as ULen;
                 SqlReturn::SUCCESS
             }
             // leave SQL_GET_BOOKMARK as unsupported since it is for ODBC < 3.0 drivers
@@ -3192,13 +3940,20 @@ pub unsafe extern "C" fn SQLGetTypeInfoW(handle: HStmt, data_type: SmallInt) ->
                 Some(sql_data_type) => {
                     let sql_data_type = handle_sql_type(odbc_version, sql_data_type);
                     let stmt = must_be_valid!((*mongo_handle).as_statement());
-                    let type_mode = if stmt.connection.is_null() {
-                        TypeMode::Standard
+                    let (type_mode, capabilities) = if stmt.connection.is_null() {
+                        (TypeMode::Standard, ServerCapabilities::default())
                     } else {
                         let connection = must_be_valid!((*stmt.connection).as_connection());
-                        *connection.type_mode.read().unwrap()
+                        let capabilities = connection
+                            .mongo_connection
+                            .read()
+                            .unwrap()
+                            .as_ref()
+                            .map(|mongo_connection| mongo_connection.capabilities.clone())
+                            .unwrap_or_default();
+                        (*connection.type_mode.read().unwrap(), capabilities)
                     };
-                    let types_info = MongoTypesInfo::new(sql_data_type, type_mode);
+                    let types_info = MongoTypesInfo::new(sql_data_type, type_mode, capabilities);
                     *stmt.mongo_statement.write().unwrap() = Some(Box::new(types_info));
                     SqlReturn::SUCCESS
                 }
@@ -3240,13 +3995,67 @@ pub unsafe extern "C" fn SQLMoreResults(_handle: HStmt) -> SqlReturn {
 #[no_mangle]
 pub unsafe extern "C" fn SQLNativeSqlW(
     connection_handle: HDbc,
-    _in_statement_text: *const WideChar,
-    _in_statement_len: Integer,
-    _out_statement_text: *mut WideChar,
-    _buffer_len: Integer,
-    _out_statement_len: *mut Integer,
+    in_statement_text: *const WideChar,
+    in_statement_len: Integer,
+    out_statement_text: *mut WideChar,
+    buffer_len: Integer,
+    out_statement_len: *mut Integer,
 ) -> SqlReturn {
-    unimpl!(connection_handle);
+    panic_safe_exec_clear_diagnostics!(
+        debug,
+        || {
+            let conn_handle = MongoHandleRef::from(connection_handle);
+            let time_zone = *must_be_valid!((*conn_handle).as_connection())
+                .time_zone
+                .read()
+                .unwrap();
+            let query = input_text_arg_to_string_w(
+                in_statement_text,
+                in_statement_len
+                    .try_into()
+                    .expect("i32 exceeded max isize on this platform"),
+            );
+            let native_query = match native_sql(&query, time_zone) {
+                Ok(native_query) => native_query,
+                Err(e) => {
+                    add_diag_info!(conn_handle, e);
+                    return SqlReturn::ERROR;
+                }
+            };
+            let buffer_len = usize::try_from(buffer_len).unwrap_or_default();
+            let sql_return = i32_len::set_output_wstring(
+                &native_query,
+                out_statement_text,
+                buffer_len,
+                out_statement_len,
+            );
+            // Without an output buffer, only the length of the statement is requested.
+            if out_statement_text.is_null() {
+                return SqlReturn::SUCCESS;
+            }
+            if sql_return == SqlReturn::SUCCESS_WITH_INFO {
+                add_diag_info!(conn_handle, ODBCError::OutStringTruncated(buffer_len));
+            }
+            sql_return
+        },
+        connection_handle
+    );
+}
+
+///
+/// native_sql returns the text of the statement the driver sends to the server for [`query`]. The
+/// server translates MongoSQL to an aggregation pipeline itself, so only the trailing semicolon
+/// is removed, the common table expressions of a WITH clause are inlined as derived tables, the
+/// datetime literals, which MongoSQL builds with CAST, are translated as local times of the
+/// session [`time_zone`], OBJECTID_TIMESTAMP calls are translated to timestamp casts, the
+/// null-safe comparisons IS [NOT] DISTINCT FROM and <=> are translated to COALESCE, and ROLLUP
+/// and CUBE are expanded to the union of their grouping sets.
+///
+fn native_sql(query: &str, time_zone: Option<FixedOffset>) -> Result<String> {
+    let query = inline_ctes(query.strip_suffix(';').unwrap_or(query))?;
+    let query = translate_datetime_literals(&query, time_zone)?;
+    let query = translate_distinct_from(&translate_objectid_timestamp(&query));
+    Ok(expand_grouping_sets(&query)?)
 }
 
 ///
@@ -3336,12 +4145,25 @@ pub unsafe extern "C" fn SQLPrepareW(
             let mongo_handle = MongoHandleRef::from(statement_handle);
             let stmt = must_be_valid!(mongo_handle.as_statement());
             let connection = must_be_valid!((*stmt.connection).as_connection());
+            clear_server_warnings(connection, None);
             let mongo_statement = odbc_unwrap!(
-                sql_prepare(statement_text, text_length, connection,),
+                sql_prepare(
+                    statement_text,
+                    text_length,
+                    connection,
+                    stmt.attributes.read().unwrap().query_timeout,
+                ),
                 mongo_handle
             );
 
-            *stmt.mongo_statement.write().unwrap() = Some(Box::new(mongo_statement));
+            *stmt.mongo_statement.write().unwrap() = Some(mongo_statement);
+            let warnings = server_warnings(connection, None);
+            if !warnings.is_empty() {
+                for warning in warnings {
+                    add_diag_info!(mongo_handle, warning);
+                }
+                return SqlReturn::SUCCESS_WITH_INFO;
+            }
             SqlReturn::SUCCESS
         },
         statement_handle
@@ -3352,23 +4174,42 @@ fn sql_prepare(
     statement_text: *const WideChar,
     text_length: Integer,
     connection: &Connection,
-) -> Result<MongoQuery> {
-    let mut query = unsafe {
-        input_text_to_string_w(
-            statement_text,
-            text_length
-                .try_into()
-                .expect("i32 exceeded max isize on this platform"),
-        )
-    };
-    query = query.strip_suffix(';').unwrap_or(&query).to_string();
+    query_timeout: ULen,
+) -> Result<Box<dyn MongoStatement>> {
+    let query = native_sql(
+        &unsafe {
+            input_text_arg_to_string_w(
+                statement_text,
+                text_length
+                    .try_into()
+                    .expect("i32 exceeded max isize on this platform"),
+            )
+        },
+        *connection.time_zone.read().unwrap(),
+    )?;
     let mongo_statement = {
         let type_mode = *connection.type_mode.read().unwrap();
         let max_string_length = *connection.max_string_length.read().unwrap();
+        let row_as_json = *connection.row_as_json.read().unwrap();
         let attributes = connection.attributes.read().unwrap();
         let timeout = attributes.connection_timeout;
         let current_db = attributes.current_catalog.as_ref().cloned();
-        if let Some(mongo_connection) = connection.mongo_connection.read().unwrap().as_ref() {
+        if let Some(target) = explain_analyze_target(&query) {
+            // EXPLAIN ANALYZE has a fixed result set, so there is nothing to translate.
+            MongoExplain::prepare(current_db, &query, target)
+                .map(|explain| Box::new(explain) as Box<dyn MongoStatement>)
+                .map_err(|e| e.into())
+        } else if let Some(collection) = change_stream_target(&query) {
+            // TAIL statements read a change stream rather than running a query.
+            MongoChangeStream::prepare(current_db, &query, collection, query_timeout as u64)
+                .map(|stream| Box::new(stream) as Box<dyn MongoStatement>)
+                .map_err(|e| e.into())
+        } else if let Some(set) = MongoSet::parse(&query) {
+            // SET statements are applied by the driver when executed, see apply_set_statement.
+            set.map(|set| Box::new(set) as Box<dyn MongoStatement>)
+                .map_err(|e| e.into())
+        } else if let Some(mongo_connection) = connection.mongo_connection.read().unwrap().as_ref()
+        {
             MongoQuery::prepare(
                 mongo_connection,
                 current_db,
@@ -3376,7 +4217,9 @@ fn sql_prepare(
                 &query,
                 type_mode,
                 max_string_length,
+                row_as_json,
             )
+            .map(|query| Box::new(query) as Box<dyn MongoStatement>)
             .map_err(|e| e.into())
         } else {
             Err(ODBCError::InvalidCursorState)
@@ -3439,7 +4282,18 @@ pub unsafe extern "C" fn SQLProcedureColumnsW(
     _column_name: *const WideChar,
     _column_name_length: SmallInt,
 ) -> SqlReturn {
-    unsupported_function!(statement_handle)
+    panic_safe_exec_clear_diagnostics!(
+        debug,
+        || {
+            let mongo_handle = MongoHandleRef::from(statement_handle);
+            let stmt = must_be_valid!((*mongo_handle).as_statement());
+            let max_string_length = stmt.get_max_string_length();
+            let mongo_statement = MongoProcedureColumns::empty(max_string_length);
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(mongo_statement));
+            SqlReturn::SUCCESS
+        },
+        statement_handle
+    );
 }
 
 ///
@@ -3461,7 +4315,18 @@ pub unsafe extern "C" fn SQLProceduresW(
     _proc_name: *const WideChar,
     _proc_name_length: SmallInt,
 ) -> SqlReturn {
-    unsupported_function!(statement_handle)
+    panic_safe_exec_clear_diagnostics!(
+        debug,
+        || {
+            let mongo_handle = MongoHandleRef::from(statement_handle);
+            let stmt = must_be_valid!((*mongo_handle).as_statement());
+            let max_string_length = stmt.get_max_string_length();
+            let mongo_statement = MongoProcedures::empty(max_string_length);
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(mongo_statement));
+            SqlReturn::SUCCESS
+        },
+        statement_handle
+    );
 }
 
 ///
@@ -3557,23 +4422,75 @@ unsafe fn set_connect_attrw_helper(
                 conn.attributes.write().unwrap().login_timeout = Some(value_ptr as u32);
                 SqlReturn::SUCCESS
             }
-            ConnectionAttribute::SQL_ATTR_APP_WCHAR_TYPE => SqlReturn::SUCCESS,
+            // Wide characters are always UTF-16, any other encoding is substituted.
+            ConnectionAttribute::SQL_ATTR_APP_WCHAR_TYPE => {
+                if value_ptr as usize == SQL_DD_CP_UTF16 as usize {
+                    SqlReturn::SUCCESS
+                } else {
+                    err = Some(ODBCError::OptionValueChanged(
+                        "SQL_ATTR_APP_WCHAR_TYPE",
+                        "SQL_DD_CP_UTF16",
+                    ));
+                    SqlReturn::SUCCESS_WITH_INFO
+                }
+            }
+            ConnectionAttribute::SQL_ATTR_AUTOCOMMIT => {
+                match FromPrimitive::from_usize(value_ptr as usize) {
+                    Some(autocommit) => {
+                        conn.attributes.write().unwrap().autocommit = autocommit;
+                        SqlReturn::SUCCESS
+                    }
+                    None => {
+                        err = Some(ODBCError::InvalidAttrValue("SQL_ATTR_AUTOCOMMIT"));
+                        SqlReturn::ERROR
+                    }
+                }
+            }
+            // Read uncommitted is the only isolation level, so it is the only value accepted.
+            ConnectionAttribute::SQL_ATTR_TXN_ISOLATION => {
+                if value_ptr as usize == SQL_TXN_READ_UNCOMMITTED as usize {
+                    SqlReturn::SUCCESS
+                } else {
+                    err = Some(ODBCError::InvalidAttrValue("SQL_ATTR_TXN_ISOLATION"));
+                    SqlReturn::ERROR
+                }
+            }
             ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG => {
-                let current_db = input_text_to_string_w(
+                let current_db = input_text_arg_to_string_w(
                     value_ptr as *const _,
                     SQL_NTS
                         .try_into()
                         .expect("i32 exceeded max isize on this platform"),
                 );
-                conn.attributes.write().unwrap().current_catalog = Some(current_db);
-                SqlReturn::SUCCESS
+                // Once connected, the database must exist for subsequent queries to target it.
+                // Before connecting, the database is validated by SQLDriverConnectW.
+                let exists = match conn.mongo_connection.read().unwrap().as_ref() {
+                    _ if current_db.is_empty() => Ok(false),
+                    Some(mongo_connection) => mongo_connection.database_exists(&current_db),
+                    None => Ok(true),
+                };
+                match exists {
+                    Ok(true) => {
+                        conn.attributes.write().unwrap().current_catalog = Some(current_db);
+                        SqlReturn::SUCCESS
+                    }
+                    Ok(false) => {
+                        err = Some(ODBCError::InvalidCatalogName(current_db));
+                        SqlReturn::ERROR
+                    }
+                    Err(e) => {
+                        err = Some(e.into());
+                        SqlReturn::ERROR
+                    }
+                }
             }
             // we use 0 (no timeout throughout the driver); only allow the user to set this value if they are setting to 0
             ConnectionAttribute::SQL_ATTR_CONNECTION_TIMEOUT => match (value_ptr as u32) == 0 {
                 true => SqlReturn::SUCCESS,
                 false => {
-                    conn_handle.add_diag_info(ODBCError::GeneralWarning(
-                        "Driver only accepts 0 for SQL_ATTR_CONNECTION_TIMEOUT".into(),
+                    err = Some(ODBCError::OptionValueChanged(
+                        "SQL_ATTR_CONNECTION_TIMEOUT",
+                        "0",
                     ));
                     SqlReturn::SUCCESS_WITH_INFO
                 }
@@ -3792,7 +4709,7 @@ pub unsafe extern "C" fn SQLSetStmtAttrW(
     hstmt: HStmt,
     attr: Integer,
     value: Pointer,
-    _str_length: Integer,
+    str_length: Integer,
 ) -> SqlReturn {
     panic_safe_exec_clear_diagnostics!(
         debug,
@@ -3801,6 +4718,9 @@ pub unsafe extern "C" fn SQLSetStmtAttrW(
 
             match FromPrimitive::from_i32(attr) {
                 Some(valid_attr) => sql_set_stmt_attrw_helper(stmt_handle, valid_attr, value),
+                None if [SQL_ATTR_MONGODB_HINT, SQL_ATTR_MONGODB_COMMENT].contains(&attr) => {
+                    sql_set_driver_stmt_attrw_helper(stmt_handle, attr, value, str_length)
+                }
                 None => {
                     add_diag_info!(stmt_handle, ODBCError::InvalidAttrIdentifier(attr));
                     SqlReturn::ERROR
@@ -3811,6 +4731,55 @@ pub unsafe extern "C" fn SQLSetStmtAttrW(
     );
 }
 
+// parse_hint returns the index hinted by the value of SQL_ATTR_MONGODB_HINT: a key document in
+// extended JSON, or an index name. Invalid key documents return None.
+fn parse_hint(value: &str) -> Option<Hint> {
+    if !value.trim_start().starts_with('{') {
+        return Some(Hint::Name(value.to_string()));
+    }
+    let json = serde_json::from_str::<serde_json::Value>(value).ok()?;
+    match Bson::try_from(json).ok()? {
+        Bson::Document(keys) if !keys.is_empty() => Some(Hint::Keys(keys)),
+        _ => None,
+    }
+}
+
+// The driver-specific statement attributes are wide strings, a null or empty value unsets them.
+unsafe fn sql_set_driver_stmt_attrw_helper(
+    stmt_handle: &mut MongoHandle,
+    attribute: Integer,
+    value_ptr: Pointer,
+    str_length: Integer,
+) -> SqlReturn {
+    let stmt = must_be_valid!(stmt_handle.as_statement());
+    // the length is in bytes, unless the value is null-terminated
+    let length = if str_length == SQL_NTS {
+        SQL_NTS_ISIZE
+    } else {
+        isize::try_from(str_length as usize / size_of::<WideChar>())
+            .expect("i32 exceeded max isize on this platform")
+    };
+    let value = input_text_arg_to_string_w(value_ptr as *const _, length);
+    let value = (!value.is_empty()).then_some(value);
+    if attribute == SQL_ATTR_MONGODB_HINT {
+        if value
+            .as_deref()
+            .is_some_and(|hint| parse_hint(hint).is_none())
+        {
+            add_diag_with_function!(
+                stmt_handle,
+                ODBCError::InvalidAttrValue("SQL_ATTR_MONGODB_HINT"),
+                "SQLSetStmtAttrW"
+            );
+            return SqlReturn::ERROR;
+        }
+        stmt.attributes.write().unwrap().hint = value;
+    } else {
+        stmt.attributes.write().unwrap().comment = value;
+    }
+    SqlReturn::SUCCESS
+}
+
 unsafe fn sql_set_stmt_attrw_helper(
     stmt_handle: &mut MongoHandle,
     attribute: StatementAttribute,
@@ -3958,12 +4927,9 @@ unsafe fn sql_set_stmt_attrw_helper(
 
         }
         StatementAttribute::SQL_ATTR_ROW_BIND_TYPE => {
-            if value_ptr as ULen != BindType::SQL_BIND_BY_COLUMN as ULen{
-                add_diag_with_function!(stmt_handle,ODBCError::Unimplemented("`row-wise column binding`"), "SQLSetStmtAttrW");
-                return SqlReturn::ERROR;
-            }
-
-            stmt.attributes.write().unwrap().row_bind_type = BindType::SQL_BIND_BY_COLUMN as ULen;
+            // Either SQL_BIND_BY_COLUMN or, for row-wise binding, the size of the structure
+            // holding a row of bound columns.
+            stmt.attributes.write().unwrap().row_bind_type = value_ptr as ULen;
             SqlReturn::SUCCESS
         }
         StatementAttribute::SQL_ATTR_ROW_NUMBER => {
@@ -4017,7 +4983,16 @@ unsafe fn sql_set_stmt_attrw_helper(
             SqlReturn::ERROR
         }
         StatementAttribute::SQL_ATTR_METADATA_ID => {
-            todo!()
+            match FromPrimitive::from_i32(value_ptr as i32) {
+                Some(metadata_id) => {
+                    stmt.attributes.write().unwrap().metadata_id = metadata_id;
+                    SqlReturn::SUCCESS
+                }
+                None => {
+                    add_diag_with_function!(stmt_handle, ODBCError::InvalidAttrValue("SQL_ATTR_METADATA_ID"), "SQLSetStmtAttrW");
+                    SqlReturn::ERROR
+                }
+            }
         }
         // leave SQL_GET_BOOKMARK as unsupported since it is for ODBC < 3.0 drivers
         StatementAttribute::SQL_GET_BOOKMARK
@@ -4034,6 +5009,20 @@ unsafe fn sql_set_stmt_attrw_helper(
     }
 }
 
+///
+/// with_mongo_connection runs a catalog function on the MongoDB connection of a connection handle.
+/// It fails with ConnectionNotOpen if the handle is not connected.
+///
+fn with_mongo_connection<T>(
+    connection: &Connection,
+    catalog_function: impl FnOnce(&MongoConnection) -> mongo_odbc_core::Result<T>,
+) -> Result<T> {
+    match connection.mongo_connection.read().unwrap().as_ref() {
+        Some(mongo_connection) => Ok(catalog_function(mongo_connection)?),
+        None => Err(ODBCError::ConnectionNotOpen),
+    }
+}
+
 ///
 /// [`SQLSpecialColumnsW`]: https://learn.microsoft.com/en-us/sql/odbc/reference/syntax/SQLSpecialColumns-function
 ///
@@ -4046,17 +5035,78 @@ unsafe fn sql_set_stmt_attrw_helper(
 #[no_mangle]
 pub unsafe extern "C" fn SQLSpecialColumnsW(
     statement_handle: HStmt,
-    _identifier_type: SmallInt,
-    _catalog_name: *const WideChar,
-    _catalog_name_length: SmallInt,
+    identifier_type: SmallInt,
+    catalog_name: *const WideChar,
+    catalog_name_length: SmallInt,
     _schema_name: *const WideChar,
     _schema_name_length: SmallInt,
-    _table_name: *const WideChar,
-    _table_name_length: SmallInt,
+    table_name: *const WideChar,
+    table_name_length: SmallInt,
     _scope: SmallInt,
     _nullable: SmallInt,
 ) -> SqlReturn {
-    unimpl!(statement_handle);
+    panic_safe_exec_clear_diagnostics!(
+        debug,
+        || {
+            let mongo_handle = MongoHandleRef::from(statement_handle);
+            let odbc_3_data_types = has_odbc_3_behavior!(mongo_handle);
+            let stmt = must_be_valid!((*mongo_handle).as_statement());
+            let max_string_length = stmt.get_max_string_length();
+            // The scope and nullable arguments are ignored: the _id of a document is valid for
+            // the whole session and can never be null.
+            let mongo_statement = match FromPrimitive::from_i16(identifier_type) {
+                Some(IdentifierType::SQL_BEST_ROWID) => {
+                    let connection = must_be_valid!((*stmt.connection).as_connection());
+                    let catalog_string =
+                        input_text_arg_to_string_w(catalog_name, catalog_name_length.into());
+                    // ignore schema
+                    let table = input_text_arg_to_string_w(table_name, table_name_length.into());
+                    // Without a catalog argument, the table is looked up in the current catalog
+                    let catalog = if catalog_string.is_empty() {
+                        connection
+                            .attributes
+                            .read()
+                            .unwrap()
+                            .current_catalog
+                            .clone()
+                    } else {
+                        Some(catalog_string)
+                    };
+                    match catalog {
+                        Some(catalog) if !table.is_empty() => {
+                            let type_mode = *connection.type_mode.read().unwrap();
+                            odbc_unwrap!(
+                                with_mongo_connection(connection, |mongo_connection| {
+                                    MongoSpecialColumns::best_rowid(
+                                        mongo_connection,
+                                        &catalog,
+                                        &table,
+                                        type_mode,
+                                        max_string_length,
+                                        odbc_3_data_types,
+                                    )
+                                }),
+                                mongo_handle
+                            )
+                        }
+                        _ => MongoSpecialColumns::empty(max_string_length),
+                    }
+                }
+                // No column is automatically updated when a document is modified
+                Some(IdentifierType::SQL_ROWVER) => MongoSpecialColumns::empty(max_string_length),
+                None => {
+                    add_diag_info!(
+                        mongo_handle,
+                        ODBCError::ColumnTypeOutOfRange(identifier_type)
+                    );
+                    return SqlReturn::ERROR;
+                }
+            };
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(mongo_statement));
+            SqlReturn::SUCCESS
+        },
+        statement_handle
+    );
 }
 
 ///
@@ -4069,16 +5119,72 @@ pub unsafe extern "C" fn SQLSpecialColumnsW(
 #[no_mangle]
 pub unsafe extern "C" fn SQLStatisticsW(
     statement_handle: HStmt,
-    _catalog_name: *const WideChar,
-    _catalog_name_length: SmallInt,
+    catalog_name: *const WideChar,
+    catalog_name_length: SmallInt,
     _schema_name: *const WideChar,
     _schema_name_length: SmallInt,
-    _table_name: *const WideChar,
-    _table_name_length: SmallInt,
-    _unique: SmallInt,
-    _reserved: SmallInt,
+    table_name: *const WideChar,
+    table_name_length: SmallInt,
+    unique: SmallInt,
+    reserved: SmallInt,
 ) -> SqlReturn {
-    unsupported_function!(statement_handle)
+    panic_safe_exec_clear_diagnostics!(
+        debug,
+        || {
+            let mongo_handle = MongoHandleRef::from(statement_handle);
+            let stmt = must_be_valid!((*mongo_handle).as_statement());
+            let unique_only = match FromPrimitive::from_i16(unique) {
+                Some(IndexUniqueness::SQL_INDEX_UNIQUE) => true,
+                Some(IndexUniqueness::SQL_INDEX_ALL) => false,
+                None => {
+                    add_diag_info!(mongo_handle, ODBCError::UniquenessOptionOutOfRange(unique));
+                    return SqlReturn::ERROR;
+                }
+            };
+            let exact_cardinality = match FromPrimitive::from_i16(reserved) {
+                Some(StatisticsAccuracy::SQL_ENSURE) => true,
+                Some(StatisticsAccuracy::SQL_QUICK) => false,
+                None => {
+                    add_diag_info!(mongo_handle, ODBCError::AccuracyOptionOutOfRange(reserved));
+                    return SqlReturn::ERROR;
+                }
+            };
+            let connection = must_be_valid!((*stmt.connection).as_connection());
+            let catalog_string =
+                input_text_arg_to_string_w(catalog_name, catalog_name_length.into());
+            // ignore schema
+            let table = input_text_arg_to_string_w(table_name, table_name_length.into());
+            // Without a catalog argument, the table is looked up in the current catalog
+            let catalog = if catalog_string.is_empty() {
+                connection
+                    .attributes
+                    .read()
+                    .unwrap()
+                    .current_catalog
+                    .clone()
+            } else {
+                Some(catalog_string)
+            };
+            let mongo_statement = match catalog {
+                Some(catalog) if !table.is_empty() => odbc_unwrap!(
+                    with_mongo_connection(connection, |mongo_connection| {
+                        MongoStatistics::list_indexes(
+                            mongo_connection,
+                            &catalog,
+                            &table,
+                            unique_only,
+                            exact_cardinality,
+                        )
+                    }),
+                    mongo_handle
+                ),
+                _ => MongoStatistics::empty(),
+            };
+            *stmt.mongo_statement.write().unwrap() = Some(Box::new(mongo_statement));
+            SqlReturn::SUCCESS
+        },
+        statement_handle
+    );
 }
 
 ///
@@ -4173,10 +5279,26 @@ pub unsafe extern "C" fn SQLTablesW(
             let mongo_handle = MongoHandleRef::from(statement_handle);
             let odbc_behavior = has_odbc_3_behavior!(mongo_handle);
             let stmt = must_be_valid!((*mongo_handle).as_statement());
-            let catalog = input_text_to_string_w(catalog_name, name_length_1.into());
-            let schema = input_text_to_string_w(schema_name, name_length_2.into());
-            let table = input_text_to_string_w(table_name, name_length_3.into());
-            let table_t = input_text_to_string_w(table_type, name_length_4.into());
+            let metadata_id = stmt.attributes.read().unwrap().metadata_id;
+            // The catalog is only a pattern value argument with ODBC 3 behavior.
+            let catalog = odbc_unwrap!(
+                catalog_function_arg(
+                    catalog_name,
+                    name_length_1,
+                    metadata_id,
+                    odbc_behavior,
+                    "CatalogName"
+                ),
+                mongo_handle
+            )
+            .unwrap_or_default();
+            let schema = input_text_arg_to_string_w(schema_name, name_length_2.into());
+            let table = odbc_unwrap!(
+                catalog_function_arg(table_name, name_length_3, metadata_id, true, "TableName"),
+                mongo_handle
+            )
+            .unwrap_or_default();
+            let table_t = input_text_arg_to_string_w(table_type, name_length_4.into());
             let connection = (*stmt.connection).as_connection().unwrap();
             let max_string_length = *connection.max_string_length.read().unwrap();
             let mongo_statement = sql_tables(
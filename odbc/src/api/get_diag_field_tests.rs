@@ -37,6 +37,41 @@ mod unit {
         }
     }
 
+    fn validate_string_diag_field(
+        handle_type: HandleType,
+        handle: *mut MongoHandle,
+        diag_identifier: i16,
+        expected_value: &str,
+    ) {
+        use cstr::WideChar;
+        use std::mem::size_of;
+        let buffer = &mut [0 as WideChar; 20];
+        let string_length_ptr = &mut 0;
+
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetDiagFieldW(
+                    handle_type,
+                    handle as *mut _,
+                    1,
+                    diag_identifier,
+                    buffer.as_mut_ptr() as *mut c_void,
+                    (buffer.len() * size_of::<WideChar>()) as i16,
+                    string_length_ptr
+                )
+            );
+            assert_eq!(
+                expected_value,
+                cstr::from_widechar_ref_lossy(&buffer[..expected_value.len()])
+            );
+            assert_eq!(
+                (expected_value.len() * size_of::<WideChar>()) as i16,
+                *string_length_ptr
+            );
+        }
+    }
+
     fn validate_message_text(handle_type: HandleType, handle: *mut MongoHandle) {
         use cstr::WideChar;
         use std::mem::size_of;
@@ -146,6 +181,14 @@ mod unit {
             // SQL_DIAG_NUMBER
             validate_integer_diag_field(*handle_type, *handle, 2, 1);
             validate_return_code(*handle_type, *handle);
+            // SQL_DIAG_CLASS_ORIGIN
+            validate_string_diag_field(*handle_type, *handle, 8, "ISO 9075");
+            // SQL_DIAG_SUBCLASS_ORIGIN
+            validate_string_diag_field(*handle_type, *handle, 9, "ODBC 3.0");
+            // SQL_DIAG_CONNECTION_NAME
+            validate_string_diag_field(*handle_type, *handle, 10, "");
+            // SQL_DIAG_SERVER_NAME
+            validate_string_diag_field(*handle_type, *handle, 11, "");
 
             //statement only
             if *handle_type == HandleType::SQL_HANDLE_STMT {
@@ -153,6 +196,29 @@ mod unit {
                 validate_integer_diag_field(*handle_type, *handle, -1248, -2i64);
                 // SQL_DIAG_ROW_COUNT
                 validate_integer_diag_field(*handle_type, *handle, 3, 0i64);
+                // SQL_DIAG_CURSOR_ROW_COUNT
+                validate_integer_diag_field(*handle_type, *handle, -1249, 0i64);
+                // SQL_DIAG_DYNAMIC_FUNCTION_CODE
+                validate_integer_diag_field(*handle_type, *handle, 12, 0i64);
+                // SQL_DIAG_DYNAMIC_FUNCTION
+                validate_string_diag_field(*handle_type, *handle, 7, "");
+                // SQL_DIAG_COLUMN_NUMBER
+                let column_number = &mut 0i32;
+                unsafe {
+                    assert_eq!(
+                        SqlReturn::SUCCESS,
+                        SQLGetDiagFieldW(
+                            *handle_type,
+                            *handle as *mut _,
+                            1,
+                            -1247,
+                            column_number as *mut _ as *mut c_void,
+                            0,
+                            &mut 0
+                        )
+                    );
+                }
+                assert_eq!(-2, *column_number);
             }
         });
     }
@@ -214,6 +280,65 @@ mod unit {
         }
     }
 
+    #[test]
+    fn test_origins() {
+        let env_handle: *mut _ = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+        unsafe {
+            // 01004 is defined by ISO 9075
+            (*env_handle).add_diag_info(ODBCError::OutStringTruncated(1));
+        }
+        // SQL_DIAG_CLASS_ORIGIN
+        validate_string_diag_field(HandleType::SQL_HANDLE_ENV, env_handle, 8, "ISO 9075");
+        // SQL_DIAG_SUBCLASS_ORIGIN
+        validate_string_diag_field(HandleType::SQL_HANDLE_ENV, env_handle, 9, "ISO 9075");
+    }
+
+    #[test]
+    fn test_stmt_record_out_of_range() {
+        let env_handle: *mut _ = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+        let conn_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+            env_handle,
+            ConnectionState::Allocated,
+        ));
+        let stmt_handle: *mut _ = &mut MongoHandle::Statement(Statement::with_state(
+            conn_handle,
+            StatementState::Allocated,
+        ));
+        unsafe {
+            (*stmt_handle).add_diag_info(ODBCError::Unimplemented("SQLDrivers"));
+            // record fields return NO_DATA past the last record
+            for diag_identifier in [-1248, -1247, 8, 9] {
+                assert_eq!(
+                    SqlReturn::NO_DATA,
+                    SQLGetDiagFieldW(
+                        HandleType::SQL_HANDLE_STMT,
+                        stmt_handle as *mut _,
+                        2,
+                        diag_identifier,
+                        (&mut [0u8; 20]) as *mut _ as *mut c_void,
+                        20,
+                        &mut 0
+                    )
+                );
+            }
+            // header fields ignore the record number
+            let number = &mut 0i32;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetDiagFieldW(
+                    HandleType::SQL_HANDLE_STMT,
+                    stmt_handle as *mut _,
+                    0,
+                    2, // DiagType::SQL_DIAG_NUMBER
+                    number as *mut _ as *mut c_void,
+                    0,
+                    &mut 0
+                )
+            );
+            assert_eq!(1, *number);
+        }
+    }
+
     #[test]
     fn test_invalid_ops() {
         use cstr::WideChar;
@@ -286,7 +411,18 @@ mod unit {
                     &mut 0
                 )
             );
-            // make a call for an unimplemented diagnostic type
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLGetDiagFieldW(
+                    HandleType::SQL_HANDLE_ENV,
+                    env_handle as *mut _,
+                    1,
+                    -1247, // DiagType::SQL_DIAG_COLUMN_NUMBER
+                    (&mut 0) as *mut _ as *mut c_void,
+                    10,
+                    &mut 0
+                )
+            );
             assert_eq!(
                 SqlReturn::ERROR,
                 SQLGetDiagFieldW(
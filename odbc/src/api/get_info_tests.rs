@@ -6,7 +6,7 @@
 
 use crate::{
     handles::definitions::{Connection, ConnectionState, MongoHandle},
-    SQLGetInfoW,
+    SQLGetInfoW, SQLSetConnectAttrW,
 };
 use constants::*;
 use cstr::{input_text_to_string_w, WideChar};
@@ -102,10 +102,6 @@ unsafe fn modify_string_value(value_ptr: Pointer, out_length: usize) -> String {
     )
 }
 
-unsafe fn modify_string_value_from_runes(value_ptr: Pointer, out_length: usize) -> String {
-    input_text_to_string_w(value_ptr as *const _, out_length as isize)
-}
-
 unsafe fn modify_u32_value(value_ptr: Pointer, _: usize) -> u32 {
     *(value_ptr as *mut UInteger)
 }
@@ -145,12 +141,78 @@ mod unit {
         driver_odbc_ver,
         info_type = InfoType::SQL_DRIVER_ODBC_VER as u16,
         expected_sql_return = SqlReturn::SUCCESS,
-        buffer_length = 6,
-        expected_length = 5,
+        buffer_length = 6 * size_of::<WideChar>() as i16,
+        expected_length = 5 * size_of::<WideChar>() as i16,
         expected_value = ODBC_VERSION,
-        actual_value_modifier = modify_string_value_from_runes,
+        actual_value_modifier = modify_string_value,
     );
 
+    // When the buffer is too small, the value is truncated, the total length is still
+    // reported, and a 01004 warning is added to the connection.
+    fn truncated_string_info(info_type: InfoType, expected_value: &str) {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+
+            let value_ptr: *mut std::ffi::c_void = Box::into_raw(Box::new([0u8; 40])) as *mut _;
+            let out_length = &mut 0;
+            // room for three characters and the null terminator
+            let buffer_length = 4 * size_of::<WideChar>() as i16;
+
+            assert_eq!(
+                SqlReturn::SUCCESS_WITH_INFO,
+                SQLGetInfoW(
+                    mongo_handle as *mut _,
+                    info_type as u16,
+                    value_ptr,
+                    buffer_length,
+                    out_length,
+                )
+            );
+            assert_eq!(
+                (expected_value.len() * size_of::<WideChar>()) as i16,
+                *out_length
+            );
+            assert_eq!(
+                &expected_value[..3],
+                modify_string_value(value_ptr, 3 * size_of::<WideChar>())
+            );
+            let errors = (*mongo_handle)
+                .as_connection()
+                .unwrap()
+                .errors
+                .read()
+                .unwrap();
+            assert_eq!(1, errors.len());
+            assert_eq!(RIGHT_TRUNCATED, errors[0].get_sql_state());
+
+            let _ = Box::from_raw(value_ptr as *mut [u8; 40]);
+        }
+    }
+
+    #[test]
+    fn driver_ver_truncated() {
+        truncated_string_info(InfoType::SQL_DRIVER_VER, DRIVER_ODBC_VERSION.as_str());
+    }
+
+    #[test]
+    fn driver_odbc_ver_truncated() {
+        truncated_string_info(InfoType::SQL_DRIVER_ODBC_VER, ODBC_VERSION);
+    }
+
+    #[test]
+    fn driver_ver_format() {
+        // SQL_DRIVER_VER must be of the form ##.##.####
+        let version: Vec<&str> = DRIVER_ODBC_VERSION.split('.').collect();
+        assert_eq!(3, version.len());
+        assert_eq!(2, version[0].len());
+        assert_eq!(2, version[1].len());
+        assert_eq!(4, version[2].len());
+        assert!(version
+            .iter()
+            .all(|part| part.chars().all(|c| c.is_ascii_digit())));
+    }
+
     test_get_info!(
         search_pattern_escape,
         info_type = InfoType::SQL_SEARCH_PATTERN_ESCAPE as u16,
@@ -182,6 +244,24 @@ mod unit {
         actual_value_modifier = modify_u16_value,
     );
 
+    test_get_info!(
+        identifier_case,
+        info_type = InfoType::SQL_IDENTIFIER_CASE as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = SQL_IC_SENSITIVE,
+        actual_value_modifier = modify_u16_value,
+    );
+
+    test_get_info!(
+        quoted_identifier_case,
+        info_type = InfoType::SQL_QUOTED_IDENTIFIER_CASE as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = SQL_IC_SENSITIVE,
+        actual_value_modifier = modify_u16_value,
+    );
+
     test_get_info!(
         identifier_quote_char,
         info_type = InfoType::SQL_IDENTIFIER_QUOTE_CHAR as u16,
@@ -300,6 +380,15 @@ mod unit {
         actual_value_modifier = modify_u32_value,
     );
 
+    test_get_info!(
+        datetime_literals,
+        info_type = InfoType::SQL_DATETIME_LITERALS as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = SQL_DL_SQL92_DATE | SQL_DL_SQL92_TIME | SQL_DL_SQL92_TIMESTAMP,
+        actual_value_modifier = modify_u32_value,
+    );
+
     test_get_info!(
         dtc_transition_cost,
         info_type = InfoType::SQL_DTC_TRANSITION_COST as u16,
@@ -313,9 +402,36 @@ mod unit {
         max_concurrent_activities,
         info_type = InfoType::SQL_MAX_CONCURRENT_ACTIVITIES as u16,
         expected_sql_return = SqlReturn::SUCCESS,
-        expected_length = std::mem::size_of::<u32>() as i16,
-        expected_value = 10u32,
-        actual_value_modifier = modify_u32_value,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = 0u16,
+        actual_value_modifier = modify_u16_value,
+    );
+
+    test_get_info!(
+        max_driver_connections,
+        info_type = InfoType::SQL_MAX_DRIVER_CONNECTIONS as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = 0u16,
+        actual_value_modifier = modify_u16_value,
+    );
+
+    test_get_info!(
+        max_catalog_name_len,
+        info_type = InfoType::SQL_MAX_CATALOG_NAME_LEN as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = MAX_CATALOG_NAME_LEN,
+        actual_value_modifier = modify_u16_value,
+    );
+
+    test_get_info!(
+        max_column_name_len,
+        info_type = InfoType::SQL_MAX_COLUMN_NAME_LEN as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = 0u16,
+        actual_value_modifier = modify_u16_value,
     );
 
     test_get_info!(
@@ -336,40 +452,34 @@ mod unit {
         actual_value_modifier = modify_u32_value,
     );
 
-    test_get_info!(
+    test_get_info_expect_u32_zero!(
         sql_keyset_cursor_attributes1,
-        info_type = InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES1 as u16,
-        expected_sql_return = SqlReturn::SUCCESS,
-        expected_length = std::mem::size_of::<u32>() as i16,
-        expected_value = SQL_CA1_NEXT,
-        actual_value_modifier = modify_u32_value,
+        info_type = InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES1 as u16
     );
 
-    test_get_info!(
+    test_get_info_expect_u32_zero!(
         sql_keyset_cursor_attributes2,
-        info_type = InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES2 as u16,
-        expected_sql_return = SqlReturn::SUCCESS,
-        expected_length = std::mem::size_of::<u32>() as i16,
-        expected_value = MONGO_CA2_SUPPORT,
-        actual_value_modifier = modify_u32_value,
+        info_type = InfoType::SQL_KEYSET_CURSOR_ATTRIBUTES2 as u16
     );
 
-    test_get_info!(
+    test_get_info_expect_u32_zero!(
         sql_dynamic_cursor_attributes1,
-        info_type = InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES1 as u16,
-        expected_sql_return = SqlReturn::SUCCESS,
-        expected_length = std::mem::size_of::<u32>() as i16,
-        expected_value = SQL_CA1_NEXT,
-        actual_value_modifier = modify_u32_value,
+        info_type = InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES1 as u16
     );
 
-    test_get_info!(
+    test_get_info_expect_u32_zero!(
         sql_dynamic_cursor_attributes2,
-        info_type = InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES2 as u16,
+        info_type = InfoType::SQL_DYNAMIC_CURSOR_ATTRIBUTES2 as u16
+    );
+
+    test_get_info!(
+        sql_row_updates,
+        info_type = InfoType::SQL_ROW_UPDATES as u16,
         expected_sql_return = SqlReturn::SUCCESS,
-        expected_length = std::mem::size_of::<u32>() as i16,
-        expected_value = MONGO_CA2_SUPPORT,
-        actual_value_modifier = modify_u32_value,
+        buffer_length = 2 * size_of::<WideChar>() as i16,
+        expected_length = size_of::<WideChar>() as i16,
+        expected_value = "N",
+        actual_value_modifier = modify_string_value,
     );
 
     test_get_info!(
@@ -399,6 +509,15 @@ mod unit {
         actual_value_modifier = modify_u32_value,
     );
 
+    test_get_info!(
+        sql_fetch_direction,
+        info_type = InfoType::SQL_FETCH_DIRECTION as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = SQL_FD_FETCH_NEXT,
+        actual_value_modifier = modify_u32_value,
+    );
+
     test_get_info!(
         sql_bookmark_persistence,
         info_type = InfoType::SQL_BOOKMARK_PERSISTENCE as u16,
@@ -418,12 +537,48 @@ mod unit {
         actual_value_modifier = modify_string_value,
     );
 
+    test_get_info!(
+        sql_scroll_concurrency,
+        info_type = InfoType::SQL_SCROLL_CONCURRENCY as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = SQL_SCCO_READ_ONLY,
+        actual_value_modifier = modify_u32_value,
+    );
+
+    test_get_info!(
+        sql_lock_types,
+        info_type = InfoType::SQL_LOCK_TYPES as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = SQL_LCK_NO_CHANGE,
+        actual_value_modifier = modify_u32_value,
+    );
+
+    test_get_info!(
+        sql_positioned_statements,
+        info_type = InfoType::SQL_POSITIONED_STATEMENTS as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = 0u32,
+        actual_value_modifier = modify_u32_value,
+    );
+
     test_get_info!(
         sql_txn_isolation_option,
         info_type = InfoType::SQL_TXN_ISOLATION_OPTION as u16,
         expected_sql_return = SqlReturn::SUCCESS,
         expected_length = std::mem::size_of::<u32>() as i16,
-        expected_value = SQL_TXN_SERIALIZABLE,
+        expected_value = SQL_TXN_READ_UNCOMMITTED,
+        actual_value_modifier = modify_u32_value,
+    );
+
+    test_get_info!(
+        sql_default_txn_isolation,
+        info_type = InfoType::SQL_DEFAULT_TXN_ISOLATION as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = SQL_TXN_READ_UNCOMMITTED,
         actual_value_modifier = modify_u32_value,
     );
 
@@ -468,6 +623,48 @@ mod unit {
         }
     }
 
+    // get_database_name returns the SQL_DATABASE_NAME of the connection.
+    unsafe fn get_database_name(mongo_handle: *mut MongoHandle) -> String {
+        let value = &mut [0 as WideChar; 40];
+        let out_length = &mut 0;
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            SQLGetInfoW(
+                mongo_handle as *mut _,
+                InfoType::SQL_DATABASE_NAME as u16,
+                value.as_mut_ptr() as Pointer,
+                std::mem::size_of_val(value) as SmallInt,
+                out_length,
+            )
+        );
+        modify_string_value(value.as_mut_ptr() as Pointer, *out_length as usize)
+    }
+
+    // SQL_DATABASE_NAME is the current catalog at the time of the call, not the database the
+    // connection was opened with.
+    #[test]
+    fn sql_database_name_follows_current_catalog() {
+        unsafe {
+            let conn = Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected);
+            conn.attributes.write().unwrap().current_catalog = Some("test".to_string());
+            let mongo_handle: *mut _ = &mut MongoHandle::Connection(conn);
+            assert_eq!("test", get_database_name(mongo_handle));
+
+            let mut catalog = cstr::to_widechar_vec("other");
+            catalog.push(0);
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetConnectAttrW(
+                    mongo_handle as *mut _,
+                    ConnectionAttribute::SQL_ATTR_CURRENT_CATALOG as i32,
+                    catalog.as_mut_ptr() as Pointer,
+                    SQL_NTS as Integer,
+                )
+            );
+            assert_eq!("other", get_database_name(mongo_handle));
+        }
+    }
+
     test_get_info_expect_u32_sql_all!(
         convert_big_int,
         info_type = InfoType::SQL_CONVERT_BIGINT as u16
@@ -661,6 +858,51 @@ mod unit {
         actual_value_modifier = modify_u16_value,
     );
 
+    test_get_info!(
+        max_columns_in_index,
+        info_type = InfoType::SQL_MAX_COLUMNS_IN_INDEX as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = 32u16,
+        actual_value_modifier = modify_u16_value,
+    );
+
+    test_get_info!(
+        max_index_size,
+        info_type = InfoType::SQL_MAX_INDEX_SIZE as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = 1024u32,
+        actual_value_modifier = modify_u32_value,
+    );
+
+    test_get_info!(
+        max_binary_literal_len,
+        info_type = InfoType::SQL_MAX_BINARY_LITERAL_LEN as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = MAX_LITERAL_LEN,
+        actual_value_modifier = modify_u32_value,
+    );
+
+    test_get_info!(
+        max_char_literal_len,
+        info_type = InfoType::SQL_MAX_CHAR_LITERAL_LEN as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value = MAX_LITERAL_LEN,
+        actual_value_modifier = modify_u32_value,
+    );
+
+    test_get_info!(
+        max_tables_in_select,
+        info_type = InfoType::SQL_MAX_TABLES_IN_SELECT as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u16>() as i16,
+        expected_value = 0u16,
+        actual_value_modifier = modify_u16_value,
+    );
+
     test_get_info!(
         timedata_add_intervals,
         info_type = InfoType::SQL_TIMEDATE_ADD_INTERVALS as u16,
@@ -780,4 +1022,174 @@ mod unit {
         expected_value = u16::MAX,
         actual_value_modifier = modify_u16_value,
     );
+
+    test_get_info!(
+        integrity,
+        info_type = InfoType::SQL_INTEGRITY as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        buffer_length = 2 * size_of::<WideChar>() as i16,
+        expected_length = size_of::<WideChar>() as i16,
+        expected_value = "N",
+        actual_value_modifier = modify_string_value,
+    );
+
+    test_get_info_expect_u32_zero!(alter_table, info_type = InfoType::SQL_ALTER_TABLE as u16);
+
+    test_get_info_expect_u32_zero!(ddl_index, info_type = InfoType::SQL_DDL_INDEX as u16);
+
+    test_get_info_expect_u32_zero!(
+        standard_cli_conformance,
+        info_type = InfoType::SQL_STANDARD_CLI_CONFORMANCE as u16
+    );
+
+    test_get_info!(
+        oj_capabilities,
+        info_type = InfoType::SQL_OJ_CAPABILITIES as u16,
+        expected_sql_return = SqlReturn::SUCCESS,
+        expected_length = std::mem::size_of::<u32>() as i16,
+        expected_value =
+            SQL_OJ_LEFT | SQL_OJ_NOT_ORDERED | SQL_OJ_INNER | SQL_OJ_ALL_COMPARISON_OPS,
+        actual_value_modifier = modify_u32_value,
+    );
+
+    unsafe fn get_u32_info(info_type: InfoType) -> u32 {
+        let mongo_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+            std::ptr::null_mut(),
+            ConnectionState::Connected,
+        ));
+        let value = &mut 0u32;
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            SQLGetInfoW(
+                mongo_handle as *mut _,
+                info_type as u16,
+                value as *mut u32 as Pointer,
+                0,
+                std::ptr::null_mut(),
+            )
+        );
+        *value
+    }
+
+    // The CLI conformance levels require parameter binding, so no level may be claimed while
+    // SQLBindParameter is unsupported.
+    #[test]
+    fn standard_cli_conformance_consistent_with_supported_functions() {
+        use crate::{
+            handles::definitions::{Statement, StatementState},
+            SQLBindParameter,
+        };
+        unsafe {
+            let conn = &mut MongoHandle::Connection(Connection::with_state(
+                std::ptr::null_mut(),
+                ConnectionState::Connected,
+            ));
+            let stmt_handle: *mut _ =
+                &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+            let bind_parameter_supported = SQLBindParameter(
+                stmt_handle as *mut _,
+                1,
+                0,
+                0,
+                0,
+                0,
+                0,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+            ) != SqlReturn::ERROR;
+            let conformance = get_u32_info(InfoType::SQL_STANDARD_CLI_CONFORMANCE);
+            assert!(
+                bind_parameter_supported
+                    || conformance & (SQL_SCC_XOPEN_CLI_VERSION1 | SQL_SCC_ISO92_CLI) == 0
+            );
+        }
+    }
+
+    // SQL_OJ_CAPABILITIES must not advertise outer joins that SQL_SQL92_RELATIONAL_JOIN_OPERATORS
+    // reports as unsupported.
+    #[test]
+    fn oj_capabilities_consistent_with_join_operators() {
+        unsafe {
+            let oj_capabilities = get_u32_info(InfoType::SQL_OJ_CAPABILITIES);
+            let join_operators = get_u32_info(InfoType::SQL_SQL92_RELATIONAL_JOIN_OPERATORS);
+            assert_eq!(
+                oj_capabilities & SQL_OJ_LEFT != 0,
+                join_operators & SQL_SRJO_LEFT_OUTER_JOIN != 0
+            );
+            assert_eq!(
+                oj_capabilities & SQL_OJ_RIGHT != 0,
+                join_operators & SQL_SRJO_RIGHT_OUTER_JOIN != 0
+            );
+            assert_eq!(
+                oj_capabilities & SQL_OJ_INNER != 0,
+                join_operators & SQL_SRJO_INNER_JOIN != 0
+            );
+            assert_eq!(0, oj_capabilities & SQL_OJ_FULL);
+        }
+    }
+
+    // Every direction SQL_FETCH_DIRECTION advertises must be accepted by SQLFetchScroll, and every
+    // other direction must be rejected with HY106.
+    #[test]
+    fn fetch_direction_consistent_with_fetch_scroll() {
+        use crate::{
+            handles::definitions::{Env, EnvState, Statement, StatementState},
+            SQLFetchScroll,
+        };
+        use bson::doc;
+        use mongo_odbc_core::{
+            json_schema::{
+                simplified::{Atomic, Schema},
+                BsonTypeName,
+            },
+            mock_query::MongoQuery,
+            MongoColMetadata, TypeMode,
+        };
+        unsafe {
+            let fetch_direction = get_u32_info(InfoType::SQL_FETCH_DIRECTION);
+            for (orientation, direction) in [
+                (FetchOrientation::SQL_FETCH_NEXT, SQL_FD_FETCH_NEXT),
+                (FetchOrientation::SQL_FETCH_FIRST, SQL_FD_FETCH_FIRST),
+                (FetchOrientation::SQL_FETCH_LAST, SQL_FD_FETCH_LAST),
+                (FetchOrientation::SQL_FETCH_PRIOR, SQL_FD_FETCH_PRIOR),
+                (FetchOrientation::SQL_FETCH_ABSOLUTE, SQL_FD_FETCH_ABSOLUTE),
+                (FetchOrientation::SQL_FETCH_RELATIVE, SQL_FD_FETCH_RELATIVE),
+            ] {
+                let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+                let conn = &mut MongoHandle::Connection(Connection::with_state(
+                    env,
+                    ConnectionState::Connected,
+                ));
+                let stmt = Statement::with_state(conn, StatementState::Allocated);
+                *stmt.mongo_statement.write().unwrap() = Some(Box::new(MongoQuery::new(
+                    vec![doc! {"test": {"x": 1}}, doc! {"test": {"x": 2}}],
+                    vec![MongoColMetadata::new(
+                        "",
+                        "test".to_string(),
+                        "x".to_string(),
+                        Schema::Atomic(Atomic::Scalar(BsonTypeName::Int)),
+                        Nullability::SQL_NO_NULLS,
+                        TypeMode::Simple,
+                        None,
+                    )],
+                )));
+                let stmt_handle: *mut _ = &mut MongoHandle::Statement(stmt);
+
+                let ret = SQLFetchScroll(stmt_handle as *mut _, orientation as SmallInt, 1);
+                if fetch_direction & direction != 0 {
+                    assert_eq!(SqlReturn::SUCCESS, ret, "{orientation:?}");
+                } else {
+                    assert_eq!(SqlReturn::ERROR, ret, "{orientation:?}");
+                    let errors = (*stmt_handle)
+                        .as_statement()
+                        .unwrap()
+                        .errors
+                        .read()
+                        .unwrap();
+                    assert_eq!("HY106", errors[0].get_sql_state().odbc_3_state);
+                }
+            }
+        }
+    }
 }
@@ -5,8 +5,12 @@ pub use functions::*;
 #[cfg(test)]
 mod bind_col_tests;
 #[cfg(test)]
+mod browse_connect_tests;
+#[cfg(test)]
 mod cancel_tests;
 #[cfg(test)]
+mod catalog_tests;
+#[cfg(test)]
 mod col_attr_describe_tests;
 #[cfg(test)]
 mod connect_attr_tests;
@@ -14,8 +18,16 @@ pub(crate) mod data;
 #[cfg(test)]
 mod data_tests;
 #[cfg(test)]
+mod disconnect_tests;
+#[cfg(test)]
+mod end_tran_tests;
+#[cfg(test)]
 mod env_attr_tests;
 #[cfg(test)]
+mod exec_direct_tests;
+#[cfg(test)]
+mod foreign_keys_tests;
+#[cfg(test)]
 mod free_stmt_tests;
 #[cfg(test)]
 mod get_diag_field_tests;
@@ -26,8 +38,12 @@ mod get_info_tests;
 #[cfg(test)]
 mod get_type_info_tests;
 #[cfg(test)]
+mod native_sql_tests;
+#[cfg(test)]
 mod panic_safe_exec_tests;
 #[cfg(test)]
+mod procedures_tests;
+#[cfg(test)]
 mod stmt_attr_tests;
 pub(crate) mod util;
 
@@ -0,0 +1,251 @@
+#![allow(
+    clippy::ptr_as_ptr,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+mod unit {
+    use crate::{
+        handles::definitions::{Connection, ConnectionState, MongoHandle},
+        SQLNativeSqlW,
+    };
+    use cstr::WideChar;
+    use definitions::{SqlReturn, SQL_NTS};
+
+    const QUERY: &str = "SELECT a, b FROM foo WHERE a > 1";
+
+    unsafe fn native_sql(
+        query: &str,
+        buffer: &mut [WideChar],
+        out_length: &mut i32,
+    ) -> (*mut MongoHandle, SqlReturn) {
+        let conn_handle: *mut _ = Box::into_raw(Box::new(MongoHandle::Connection(
+            Connection::with_state(std::ptr::null_mut(), ConnectionState::Connected),
+        )));
+        let query = cstr::to_widechar_vec(query);
+        let sql_return = SQLNativeSqlW(
+            conn_handle as *mut _,
+            query.as_ptr(),
+            query.len() as i32,
+            buffer.as_mut_ptr(),
+            buffer.len() as i32,
+            out_length,
+        );
+        (conn_handle, sql_return)
+    }
+
+    #[test]
+    fn simple_select() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 100];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(QUERY, buffer, out_length);
+            assert_eq!(SqlReturn::SUCCESS, sql_return);
+            assert_eq!(QUERY.len() as i32, *out_length);
+            assert_eq!(
+                QUERY,
+                cstr::from_widechar_ref_lossy(&buffer[..*out_length as usize])
+            );
+            // The output is null terminated.
+            assert_eq!(0, buffer[*out_length as usize]);
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn trailing_semicolon_is_removed() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 100];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(&format!("{QUERY};"), buffer, out_length);
+            assert_eq!(SqlReturn::SUCCESS, sql_return);
+            assert_eq!(
+                QUERY,
+                cstr::from_widechar_ref_lossy(&buffer[..*out_length as usize])
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn datetime_escape_clauses_are_translated() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 200];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(
+                "SELECT * FROM foo WHERE d = {d '2020-01-31'} AND ts < {ts '2020-01-31 13:14:15.5'}",
+                buffer,
+                out_length,
+            );
+            assert_eq!(SqlReturn::SUCCESS, sql_return);
+            assert_eq!(
+                "SELECT * FROM foo WHERE d = CAST('2020-01-31T00:00:00.000Z' AS TIMESTAMP) \
+                 AND ts < CAST('2020-01-31T13:14:15.500Z' AS TIMESTAMP)",
+                cstr::from_widechar_ref_lossy(&buffer[..*out_length as usize])
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn invalid_datetime_literal_is_an_invalid_datetime_format() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 100];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(
+                "SELECT * FROM foo WHERE d = {d '2020-13-01'}",
+                buffer,
+                out_length,
+            );
+            assert_eq!(SqlReturn::ERROR, sql_return);
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert_eq!(
+                "22007",
+                conn.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn rollup_is_expanded_to_its_grouping_sets() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 300];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(
+                "SELECT a, COUNT(*) AS n FROM foo GROUP BY ROLLUP(a);",
+                buffer,
+                out_length,
+            );
+            assert_eq!(SqlReturn::SUCCESS, sql_return);
+            assert_eq!(
+                "SELECT a, COUNT(*) AS n, 0 AS grouping_id FROM foo GROUP BY a \
+                 UNION ALL SELECT NULL AS a, COUNT(*) AS n, 1 AS grouping_id FROM foo",
+                cstr::from_widechar_ref_lossy(&buffer[..*out_length as usize])
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn unsupported_grouping_is_a_syntax_error() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 100];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(
+                "SELECT a FROM foo GROUP BY GROUPING SETS ((a), ())",
+                buffer,
+                out_length,
+            );
+            assert_eq!(SqlReturn::ERROR, sql_return);
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert_eq!(
+                "42000",
+                conn.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn common_table_expressions_are_inlined() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 100];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(
+                "WITH t AS (SELECT a FROM foo) SELECT a FROM t",
+                buffer,
+                out_length,
+            );
+            assert_eq!(SqlReturn::SUCCESS, sql_return);
+            assert_eq!(
+                "SELECT a FROM (SELECT a FROM foo) AS t",
+                cstr::from_widechar_ref_lossy(&buffer[..*out_length as usize])
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn recursive_common_table_expression_is_a_syntax_error() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 100];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(
+                "WITH RECURSIVE t AS (SELECT 1 AS n UNION ALL SELECT n + 1 FROM t) SELECT n FROM t",
+                buffer,
+                out_length,
+            );
+            assert_eq!(SqlReturn::ERROR, sql_return);
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert_eq!(
+                "42000",
+                conn.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn null_safe_comparisons_are_translated() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 100];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(
+                "SELECT a FROM foo WHERE a IS NOT DISTINCT FROM NULL",
+                buffer,
+                out_length,
+            );
+            assert_eq!(SqlReturn::SUCCESS, sql_return);
+            assert_eq!(
+                "SELECT a FROM foo WHERE (a IS NULL)",
+                cstr::from_widechar_ref_lossy(&buffer[..*out_length as usize])
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn truncated_output_reports_total_length() {
+        unsafe {
+            let buffer = &mut [0 as WideChar; 7];
+            let out_length = &mut 0;
+            let (conn_handle, sql_return) = native_sql(QUERY, buffer, out_length);
+            assert_eq!(SqlReturn::SUCCESS_WITH_INFO, sql_return);
+            assert_eq!(QUERY.len() as i32, *out_length);
+            assert_eq!("SELECT", cstr::from_widechar_ref_lossy(&buffer[..6]));
+            assert_eq!(0, buffer[6]);
+            let conn = (*conn_handle).as_connection().unwrap();
+            assert_eq!(
+                "01004",
+                conn.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+            let _ = Box::from_raw(conn_handle);
+        }
+    }
+
+    #[test]
+    fn length_only_with_null_terminated_input() {
+        unsafe {
+            let conn_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+                std::ptr::null_mut(),
+                ConnectionState::Connected,
+            ));
+            let mut query = cstr::to_widechar_vec(QUERY);
+            query.push(0);
+            let out_length = &mut 0;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLNativeSqlW(
+                    conn_handle as *mut _,
+                    query.as_ptr(),
+                    SQL_NTS,
+                    std::ptr::null_mut(),
+                    0,
+                    out_length,
+                )
+            );
+            assert_eq!(QUERY.len() as i32, *out_length);
+        }
+    }
+}
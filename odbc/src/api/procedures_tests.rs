@@ -0,0 +1,143 @@
+#![allow(
+    clippy::ptr_as_ptr,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+
+use crate::{
+    handles::definitions::{
+        Connection, ConnectionState, Env, EnvState, MongoHandle, Statement, StatementState,
+    },
+    SQLDescribeColW, SQLFetch, SQLNumResultCols, SQLProcedureColumnsW, SQLProceduresW,
+};
+use cstr::WideChar;
+use definitions::{HStmt, Nullability, SqlDataType, SqlReturn};
+use std::ptr::null;
+
+const PROCEDURES_COLUMNS: [(&str, Nullability); 8] = [
+    ("PROCEDURE_CAT", Nullability::SQL_NULLABLE),
+    ("PROCEDURE_SCHEM", Nullability::SQL_NULLABLE),
+    ("PROCEDURE_NAME", Nullability::SQL_NO_NULLS),
+    ("NUM_INPUT_PARAMS", Nullability::SQL_NULLABLE),
+    ("NUM_OUTPUT_PARAMS", Nullability::SQL_NULLABLE),
+    ("NUM_RESULT_SETS", Nullability::SQL_NULLABLE),
+    ("REMARKS", Nullability::SQL_NULLABLE),
+    ("PROCEDURE_TYPE", Nullability::SQL_NULLABLE),
+];
+
+const PROCEDURE_COLUMNS_COLUMNS: [(&str, Nullability); 19] = [
+    ("PROCEDURE_CAT", Nullability::SQL_NULLABLE),
+    ("PROCEDURE_SCHEM", Nullability::SQL_NULLABLE),
+    ("PROCEDURE_NAME", Nullability::SQL_NO_NULLS),
+    ("COLUMN_NAME", Nullability::SQL_NO_NULLS),
+    ("COLUMN_TYPE", Nullability::SQL_NO_NULLS),
+    ("DATA_TYPE", Nullability::SQL_NO_NULLS),
+    ("TYPE_NAME", Nullability::SQL_NO_NULLS),
+    ("COLUMN_SIZE", Nullability::SQL_NULLABLE),
+    ("BUFFER_LENGTH", Nullability::SQL_NULLABLE),
+    ("DECIMAL_DIGITS", Nullability::SQL_NULLABLE),
+    ("NUM_PREC_RADIX", Nullability::SQL_NULLABLE),
+    ("NULLABLE", Nullability::SQL_NO_NULLS),
+    ("REMARKS", Nullability::SQL_NULLABLE),
+    ("COLUMN_DEF", Nullability::SQL_NULLABLE),
+    ("SQL_DATA_TYPE", Nullability::SQL_NO_NULLS),
+    ("SQL_DATETIME_SUB", Nullability::SQL_NULLABLE),
+    ("CHAR_OCTET_LENGTH", Nullability::SQL_NULLABLE),
+    ("ORDINAL_POSITION", Nullability::SQL_NO_NULLS),
+    ("IS_NULLABLE", Nullability::SQL_NULLABLE),
+];
+
+mod unit {
+    use super::*;
+
+    // assert_empty_result_set checks that the result set of the statement has the expected
+    // columns and no rows.
+    unsafe fn assert_empty_result_set(
+        stmt_handle: HStmt,
+        expected_columns: &[(&str, Nullability)],
+    ) {
+        let column_count = &mut 0;
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            SQLNumResultCols(stmt_handle, column_count)
+        );
+        assert_eq!(expected_columns.len() as i16, *column_count);
+
+        for (col_index, (expected_name, expected_nullability)) in
+            expected_columns.iter().enumerate()
+        {
+            let name_buffer = &mut [0 as WideChar; 20];
+            let out_name_length = &mut 0;
+            let mut data_type = SqlDataType::SQL_UNKNOWN_TYPE;
+            let col_size = &mut 0usize;
+            let decimal_digits = &mut 0i16;
+            let mut nullable = Nullability::SQL_NULLABLE_UNKNOWN as i16;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLDescribeColW(
+                    stmt_handle,
+                    col_index as u16 + 1,
+                    name_buffer.as_mut_ptr(),
+                    name_buffer.len() as i16,
+                    out_name_length,
+                    &mut data_type,
+                    col_size,
+                    decimal_digits,
+                    &mut nullable,
+                )
+            );
+            assert_eq!(
+                *expected_name,
+                cstr::from_widechar_ref_lossy(&name_buffer[..*out_name_length as usize])
+            );
+            assert_eq!(*expected_nullability as i16, nullable);
+        }
+
+        assert_eq!(SqlReturn::NO_DATA, SQLFetch(stmt_handle));
+    }
+
+    // MongoDB has no stored procedures, so SQLProcedures and SQLProcedureColumns always return
+    // empty result sets, which tools enumerating the procedures of a data source expect rather
+    // than an error.
+    #[test]
+    fn procedures_empty_result_set() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Connected));
+        let stmt_handle: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLProceduresW(stmt_handle as *mut _, null(), 0, null(), 0, null(), 0)
+            );
+            assert_empty_result_set(stmt_handle as *mut _, &PROCEDURES_COLUMNS);
+        }
+    }
+
+    #[test]
+    fn procedure_columns_empty_result_set() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Connected));
+        let stmt_handle: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLProcedureColumnsW(
+                    stmt_handle as *mut _,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                    null(),
+                    0,
+                )
+            );
+            assert_empty_result_set(stmt_handle as *mut _, &PROCEDURE_COLUMNS_COLUMNS);
+        }
+    }
+}
@@ -5,12 +5,19 @@
 )]
 
 use crate::{
-    handles::definitions::{MongoHandle, Statement, StatementState},
-    map, SQLGetStmtAttrW, SQLSetStmtAttrW,
+    handles::definitions::{
+        Connection, ConnectionState, Env, EnvState, MongoHandle, Statement, StatementState,
+    },
+    map, SQLColumnsW, SQLGetStmtAttrW, SQLSetStmtAttrW, SQLTablesW,
 };
+#[cfg(feature = "test_hooks")]
+use constants::SQL_ATTR_MONGODB_GET_MORE_COUNT;
+use constants::{SQL_ATTR_MONGODB_COMMENT, SQL_ATTR_MONGODB_HINT};
+use cstr::WideChar;
 use definitions::{
-    AsyncEnable, BindType, CursorScrollable, CursorSensitivity, CursorType, HStmt, Integer, NoScan,
-    Pointer, RetrieveData, SqlBool, SqlReturn, StatementAttribute, ULen, USmallInt, UseBookmarks,
+    AsyncEnable, BindType, Concurrency, CursorScrollable, CursorSensitivity, CursorType, HStmt,
+    Integer, NoScan, Pointer, RetrieveData, SqlBool, SqlReturn, StatementAttribute, ULen,
+    USmallInt, UseBookmarks, SQL_NTS,
 };
 use std::{collections::BTreeMap, mem::size_of};
 
@@ -208,6 +215,17 @@ mod unit {
             },
             CursorType::SQL_CURSOR_FORWARD_ONLY as usize,
         );
+        get_set_stmt_attr(
+            stmt_handle,
+            StatementAttribute::SQL_ATTR_CONCURRENCY,
+            map! {
+                Concurrency::SQL_CONCUR_READ_ONLY as i32 => SqlReturn::SUCCESS,
+                Concurrency::SQL_CONCUR_LOCK as i32 => SqlReturn::SUCCESS_WITH_INFO,
+                Concurrency::SQL_CONCUR_ROWVER as i32 => SqlReturn::SUCCESS_WITH_INFO,
+                Concurrency::SQL_CONCUR_VALUES as i32 => SqlReturn::SUCCESS_WITH_INFO,
+            },
+            Concurrency::SQL_CONCUR_READ_ONLY as usize,
+        );
         get_set_stmt_attr(
             stmt_handle,
             StatementAttribute::SQL_ATTR_MAX_LENGTH,
@@ -225,6 +243,15 @@ mod unit {
             },
             0,
         );
+        get_set_stmt_attr(
+            stmt_handle,
+            StatementAttribute::SQL_ATTR_METADATA_ID,
+            map! {
+                SqlBool::SQL_TRUE as i32 => SqlReturn::SUCCESS,
+                SqlBool::SQL_FALSE as i32 => SqlReturn::SUCCESS,
+            },
+            SqlBool::SQL_FALSE as usize,
+        );
         get_set_stmt_attr(
             stmt_handle,
             StatementAttribute::SQL_ATTR_NOSCAN,
@@ -256,7 +283,7 @@ mod unit {
             StatementAttribute::SQL_ATTR_ROW_BIND_TYPE,
             map! {
                 BindType::SQL_BIND_BY_COLUMN as i32 => SqlReturn::SUCCESS,
-                10 => SqlReturn::ERROR // Any number besides 0
+                10 => SqlReturn::SUCCESS // The size of a row for row-wise binding
             },
             BindType::SQL_BIND_BY_COLUMN as usize,
         );
@@ -299,6 +326,15 @@ mod unit {
             },
             1,
         );
+        // SQL_ROWSET_SIZE is the ODBC 2 name of SQL_ATTR_ROW_ARRAY_SIZE, set to 10 above.
+        get_set_stmt_attr(
+            stmt_handle,
+            StatementAttribute::SQL_ROWSET_SIZE,
+            map! {
+                5 => SqlReturn::SUCCESS, // Any number within the u32 range.
+            },
+            10,
+        );
         get_set_stmt_attr(
             stmt_handle,
             StatementAttribute::SQL_ATTR_USE_BOOKMARKS,
@@ -412,4 +448,241 @@ mod unit {
             size_of::<*mut USmallInt>(),
         );
     }
+
+    // Attributes the driver does not support are errors with SQLSTATE HYC00 in both
+    // SQLGetStmtAttr and SQLSetStmtAttr.
+    #[test]
+    fn test_unsupported_attributes_are_not_implemented() {
+        unsafe {
+            for attribute in [
+                StatementAttribute::SQL_GET_BOOKMARK,
+                StatementAttribute::SQL_ATTR_SAMPLE_SIZE,
+                StatementAttribute::SQL_ATTR_DYNAMIC_COLUMNS,
+                StatementAttribute::SQL_ATTR_TYPE_EXCEPTION_BEHAVIOR,
+                StatementAttribute::SQL_ATTR_LENGTH_EXCEPTION_BEHAVIOR,
+            ] {
+                let stmt_handle: *mut _ = &mut MongoHandle::Statement(Statement::with_state(
+                    std::ptr::null_mut(),
+                    StatementState::Allocated,
+                ));
+                let attr_buffer = &mut 0_usize;
+                assert_eq!(
+                    SqlReturn::ERROR,
+                    SQLGetStmtAttrW(
+                        stmt_handle as *mut _,
+                        attribute as i32,
+                        attr_buffer as *mut usize as Pointer,
+                        0,
+                        &mut 0
+                    ),
+                    "{attribute:?}"
+                );
+                let stmt = (*stmt_handle).as_statement().unwrap();
+                assert_eq!(
+                    "HYC00",
+                    stmt.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+                );
+                assert_eq!(
+                    SqlReturn::ERROR,
+                    SQLSetStmtAttrW(stmt_handle as HStmt, attribute as i32, 1 as Pointer, 0),
+                    "{attribute:?}"
+                );
+                assert_eq!(
+                    "HYC00",
+                    stmt.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+                );
+            }
+        }
+    }
+
+    unsafe fn set_string_attr(
+        stmt_handle: *mut MongoHandle,
+        attribute: i32,
+        value: &str,
+    ) -> SqlReturn {
+        let value = cstr::to_widechar_vec(value);
+        SQLSetStmtAttrW(
+            stmt_handle as *mut _,
+            attribute,
+            value.as_ptr() as Pointer,
+            (value.len() * size_of::<WideChar>()) as Integer,
+        )
+    }
+
+    unsafe fn get_string_attr(stmt_handle: *mut MongoHandle, attribute: i32) -> String {
+        let buffer = &mut [0 as WideChar; 64];
+        let string_length = &mut 0;
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            SQLGetStmtAttrW(
+                stmt_handle as *mut _,
+                attribute,
+                buffer.as_mut_ptr() as Pointer,
+                (buffer.len() * size_of::<WideChar>()) as Integer,
+                string_length,
+            )
+        );
+        cstr::from_widechar_ref_lossy(&buffer[..*string_length as usize / size_of::<WideChar>()])
+    }
+
+    // The hint and comment driver-specific attributes are kept on the statement and attached to
+    // the command run when it is executed.
+    #[test]
+    fn test_hint_and_comment_attributes() {
+        let stmt_handle: *mut _ = &mut MongoHandle::Statement(Statement::with_state(
+            std::ptr::null_mut(),
+            StatementState::Allocated,
+        ));
+        unsafe {
+            assert_eq!("", get_string_attr(stmt_handle, SQL_ATTR_MONGODB_HINT));
+            for hint in ["a_1", r#"{"a": 1, "b": -1}"#] {
+                assert_eq!(
+                    SqlReturn::SUCCESS,
+                    set_string_attr(stmt_handle, SQL_ATTR_MONGODB_HINT, hint)
+                );
+                assert_eq!(hint, get_string_attr(stmt_handle, SQL_ATTR_MONGODB_HINT));
+            }
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                set_string_attr(stmt_handle, SQL_ATTR_MONGODB_COMMENT, "dashboard")
+            );
+            assert_eq!(
+                "dashboard",
+                get_string_attr(stmt_handle, SQL_ATTR_MONGODB_COMMENT)
+            );
+
+            // an invalid key document leaves the previous hint
+            for hint in ["{a: 1", "{}"] {
+                assert_eq!(
+                    SqlReturn::ERROR,
+                    set_string_attr(stmt_handle, SQL_ATTR_MONGODB_HINT, hint)
+                );
+            }
+            let stmt = (*stmt_handle).as_statement().unwrap();
+            assert_eq!(
+                "HY024",
+                stmt.errors.read().unwrap()[0].get_sql_state().odbc_3_state
+            );
+            assert_eq!(
+                Some(r#"{"a": 1, "b": -1}"#.to_string()),
+                stmt.attributes.read().unwrap().hint
+            );
+
+            // a null value unsets the attribute
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetStmtAttrW(
+                    stmt_handle as *mut _,
+                    SQL_ATTR_MONGODB_HINT,
+                    std::ptr::null_mut(),
+                    0
+                )
+            );
+            assert_eq!(None, stmt.attributes.read().unwrap().hint);
+        }
+    }
+
+    // The getMore count is read-only, and 0 until the connection runs a query.
+    #[cfg(feature = "test_hooks")]
+    #[test]
+    fn test_get_more_count_attribute() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Connected));
+        let stmt_handle: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+        unsafe {
+            let mut get_more_count: ULen = 1;
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLGetStmtAttrW(
+                    stmt_handle as *mut _,
+                    SQL_ATTR_MONGODB_GET_MORE_COUNT,
+                    &mut get_more_count as *mut ULen as Pointer,
+                    0,
+                    std::ptr::null_mut(),
+                )
+            );
+            assert_eq!(0, get_more_count);
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLSetStmtAttrW(
+                    stmt_handle as *mut _,
+                    SQL_ATTR_MONGODB_GET_MORE_COUNT,
+                    2 as Pointer,
+                    0
+                )
+            );
+            assert_eq!(
+                "HY092",
+                (*stmt_handle)
+                    .as_statement()
+                    .unwrap()
+                    .errors
+                    .read()
+                    .unwrap()[0]
+                    .get_sql_state()
+                    .odbc_3_state
+            );
+        }
+    }
+
+    // With SQL_ATTR_METADATA_ID set, catalog function arguments are identifiers and cannot be
+    // null. The error is raised before the server is reached.
+    #[test]
+    fn test_metadata_id_rejects_null_catalog_function_arguments() {
+        let env = &mut MongoHandle::Env(Env::with_state(EnvState::ConnectionAllocated));
+        let conn =
+            &mut MongoHandle::Connection(Connection::with_state(env, ConnectionState::Connected));
+        let stmt_handle: *mut _ =
+            &mut MongoHandle::Statement(Statement::with_state(conn, StatementState::Allocated));
+        let catalog = cstr::to_widechar_vec("db");
+        unsafe {
+            assert_eq!(
+                SqlReturn::SUCCESS,
+                SQLSetStmtAttrW(
+                    stmt_handle as HStmt,
+                    StatementAttribute::SQL_ATTR_METADATA_ID as i32,
+                    SqlBool::SQL_TRUE as i32 as Pointer,
+                    0
+                )
+            );
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLColumnsW(
+                    stmt_handle as HStmt,
+                    catalog.as_ptr(),
+                    SQL_NTS as i16,
+                    std::ptr::null(),
+                    0,
+                    std::ptr::null(),
+                    0,
+                    std::ptr::null(),
+                    0,
+                )
+            );
+            assert_eq!(
+                SqlReturn::ERROR,
+                SQLTablesW(
+                    stmt_handle as HStmt,
+                    std::ptr::null(),
+                    0,
+                    std::ptr::null(),
+                    0,
+                    catalog.as_ptr(),
+                    SQL_NTS as i16,
+                    std::ptr::null(),
+                    0,
+                )
+            );
+            let stmt = (*stmt_handle).as_statement().unwrap();
+            let errors = stmt.errors.read().unwrap();
+            assert_eq!(1, errors.len());
+            assert_eq!("HY009", errors[0].get_sql_state().odbc_3_state);
+            assert_eq!(
+                "[MongoDB][API] CatalogName cannot be a null pointer when SQL_ATTR_METADATA_ID is SQL_TRUE",
+                errors[0].to_string()
+            );
+        }
+    }
 }
@@ -1,10 +1,12 @@
 use crate::api::errors::ODBCError;
 use bson::{Bson, Uuid};
+use chrono::FixedOffset;
 use cstr::{Charset, WideChar};
 use definitions::{
-    AsyncEnable, AttrConnectionPooling, AttrCpMatch, AttrOdbcVersion, BindType, Concurrency,
-    CursorScrollable, CursorSensitivity, CursorType, HDbc, HDesc, HEnv, HStmt, Handle, Len, NoScan,
-    Pointer, RetrieveData, SimulateCursor, SmallInt, SqlBool, ULen, USmallInt, UseBookmarks,
+    AsyncEnable, AttrAutoCommit, AttrConnectionPooling, AttrCpMatch, AttrOdbcVersion, BindType,
+    Concurrency, CursorScrollable, CursorSensitivity, CursorType, HDbc, HDesc, HEnv, HStmt, Handle,
+    Len, NoScan, Pointer, RetrieveData, SimulateCursor, SmallInt, SqlBool, ULen, USmallInt,
+    UseBookmarks,
 };
 use mongo_odbc_core::TypeMode;
 use std::{
@@ -284,6 +286,30 @@ pub struct Connection {
     pub type_mode: RwLock<TypeMode>,
     // max_string_length is the maximum character length of string data.
     pub max_string_length: RwLock<Option<u16>>,
+    // slow_query_ms is the number of milliseconds after which a query is reported as slow.
+    pub slow_query_ms: RwLock<Option<u32>>,
+    // unordered_limit_warning indicates if a LIMIT without ORDER BY is reported as a warning.
+    pub unordered_limit_warning: RwLock<bool>,
+    // row_as_json indicates if query results are returned as a single JSON column per row.
+    pub row_as_json: RwLock<bool>,
+    // browse_connection_string accumulates the attributes given to SQLBrowseConnect until the
+    // connection is established.
+    pub browse_connection_string: RwLock<Option<String>>,
+    // json_mode is the extended JSON format of values nested in documents and arrays that are
+    // returned as JSON strings.
+    pub json_mode: RwLock<JsonMode>,
+    // time_zone is the session time zone set with a SET TIMEZONE statement. Datetimes are
+    // returned in UTC when it is not set.
+    pub time_zone: RwLock<Option<FixedOffset>>,
+}
+
+/// JsonMode selects between the relaxed and canonical formats of extended JSON. The canonical
+/// format preserves the exact BSON type of every value, so it round-trips without loss.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum JsonMode {
+    #[default]
+    Relaxed,
+    Canonical,
 }
 
 #[derive(Debug, Default)]
@@ -299,6 +325,9 @@ pub struct ConnectionAttributes {
     // to wait for any operation on a connection to timeout (other than
     // initial login).
     pub connection_timeout: Option<u32>,
+    // SQL_ATTR_AUTOCOMMIT: whether statements are committed automatically.
+    // The driver is read-only, so this only changes the behavior of SQLEndTran.
+    pub autocommit: AttrAutoCommit,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -322,6 +351,12 @@ impl Connection {
             errors: RwLock::new(vec![]),
             type_mode: RwLock::new(TypeMode::Simple),
             max_string_length: RwLock::new(None),
+            slow_query_ms: RwLock::new(None),
+            unordered_limit_warning: RwLock::new(true),
+            row_as_json: RwLock::new(false),
+            browse_connection_string: RwLock::new(None),
+            json_mode: RwLock::new(JsonMode::default()),
+            time_zone: RwLock::new(None),
         }
     }
 }
@@ -385,6 +420,7 @@ pub struct StatementAttributes {
     pub imp_param_desc: *mut MongoHandle,
     pub max_length: ULen,
     pub max_rows: ULen,
+    pub metadata_id: SqlBool,
     pub no_scan: NoScan,
     pub param_bind_offset_ptr: *mut ULen,
     pub param_bind_type: ULen,
@@ -404,6 +440,10 @@ pub struct StatementAttributes {
     pub rows_fetched_ptr: *mut ULen,
     pub simulate_cursor: ULen,
     pub use_bookmarks: UseBookmarks,
+    // SQL_ATTR_MONGODB_HINT: the index name or key document hinted to the server.
+    pub hint: Option<String>,
+    // SQL_ATTR_MONGODB_COMMENT: the comment attached to the commands of the statement.
+    pub comment: Option<String>,
 }
 
 impl Drop for StatementAttributes {
@@ -477,6 +517,7 @@ impl Statement {
                 ))),
                 max_length: 0,
                 max_rows: 0,
+                metadata_id: SqlBool::SQL_FALSE,
                 no_scan: NoScan::SQL_NOSCAN_OFF,
                 param_bind_offset_ptr: null_mut(),
                 param_bind_type: BindType::SQL_BIND_BY_COLUMN as usize,
@@ -496,6 +537,8 @@ impl Statement {
                 rows_fetched_ptr: null_mut(),
                 simulate_cursor: SimulateCursor::NonUnique as usize,
                 use_bookmarks: UseBookmarks::Off,
+                hint: None,
+                comment: None,
             }),
             errors: RwLock::new(vec![]),
             mongo_statement: RwLock::new(None),
@@ -4,8 +4,10 @@
     clippy::cast_possible_wrap
 )]
 
-use crate::{handles::definitions::*, has_odbc_3_behavior, SQLAllocHandle, SQLFreeHandle};
-use definitions::{AttrOdbcVersion, Handle, HandleType, SqlReturn};
+use crate::{
+    handles::definitions::*, has_odbc_3_behavior, SQLAllocHandle, SQLFreeHandle, SQLFreeStmt,
+};
+use definitions::{AttrOdbcVersion, FreeStmtOption, HStmt, Handle, HandleType, SqlReturn};
 
 #[test]
 fn test_env_alloc_free() {
@@ -151,6 +153,59 @@ fn test_statement_alloc_free() {
     }
 }
 
+#[test]
+fn test_statement_alloc_free_stmt_drop() {
+    unsafe {
+        let env_handle: *mut _ = &mut MongoHandle::Env(Env::with_state(EnvState::Allocated));
+
+        let conn_handle: *mut _ = &mut MongoHandle::Connection(Connection::with_state(
+            env_handle,
+            ConnectionState::Allocated,
+        ));
+
+        let mut handle: *mut _ = &mut MongoHandle::Statement(Statement::with_state(
+            std::ptr::null_mut(),
+            StatementState::Allocated,
+        ));
+        let handle_ptr: *mut _ = &mut handle;
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            SQLAllocHandle(
+                HandleType::SQL_HANDLE_STMT,
+                conn_handle as *mut _,
+                std::mem::transmute::<*mut *mut MongoHandle, *mut Handle>(handle_ptr),
+            )
+        );
+        // SQLFreeStmt(SQL_DROP) frees the handle exactly like SQLFreeHandle.
+        assert_eq!(
+            SqlReturn::SUCCESS,
+            SQLFreeStmt(
+                std::mem::transmute::<*mut MongoHandle, HStmt>(handle),
+                FreeStmtOption::SQL_DROP as i16,
+            )
+        );
+        assert_eq!(
+            0,
+            (*conn_handle)
+                .as_connection()
+                .unwrap()
+                .statements
+                .read()
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            ConnectionState::Connected,
+            *(*conn_handle)
+                .as_connection()
+                .unwrap()
+                .state
+                .read()
+                .unwrap()
+        );
+    }
+}
+
 #[test]
 fn test_descriptor_alloc_free() {
     unsafe {